@@ -3,6 +3,7 @@
 //! Parse ICS calendar files and convert events to dungeon elements.
 
 pub mod parser;
+mod timezone;
 pub mod types;
 
 pub use parser::{group_by_date, parse_ics_content, parse_ics_file};
@@ -1,5 +1,6 @@
 //! ICS calendar file parser.
 
+use super::timezone::utc_offset_minutes;
 use super::types::{CalendarError, EventCategory, EventData};
 use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use std::collections::BTreeMap;
@@ -18,7 +19,7 @@ pub fn parse_ics_content(content: &str, days: u32) -> Result<Vec<EventData>, Cal
     let mut events = Vec::new();
     let mut current_event: Option<EventBuilder> = None;
 
-    for line in content.lines() {
+    for line in unfold_lines(content) {
         let line = line.trim();
 
         if line.starts_with("BEGIN:VEVENT") {
@@ -47,6 +48,23 @@ pub fn parse_ics_content(content: &str, days: u32) -> Result<Vec<EventData>, Cal
     Ok(events)
 }
 
+/// Unfold RFC 5545 content lines.
+///
+/// Long lines are folded by inserting a line break followed by a single
+/// space or tab; continuation lines are joined back onto the previous line.
+fn unfold_lines(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(previous)) => previous.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    lines
+}
+
 /// Group events by date for room generation.
 pub fn group_by_date(events: Vec<EventData>) -> BTreeMap<NaiveDate, Vec<EventData>> {
     let mut grouped: BTreeMap<NaiveDate, Vec<EventData>> = BTreeMap::new();
@@ -77,13 +95,13 @@ impl EventBuilder {
     }
 
     fn parse_line(&mut self, line: &str) {
-        if let Some((key, value)) = line.split_once(':') {
-            let key = key.split(';').next().unwrap_or(key);
+        if let Some((key, value)) = split_property(line) {
+            let (key, params) = key.split_once(';').unwrap_or((key, ""));
 
             match key {
                 "UID" => self.uid = Some(value.to_string()),
-                "DTSTART" => self.dtstart = parse_datetime(value),
-                "DTEND" => self.dtend = parse_datetime(value),
+                "DTSTART" => self.dtstart = parse_datetime_with_params(value, params),
+                "DTEND" => self.dtend = parse_datetime_with_params(value, params),
                 "SUMMARY" => self.summary = Some(unescape_ics(value)),
                 "DESCRIPTION" => self.description = Some(unescape_ics(value)),
                 "LOCATION" => self.location = Some(unescape_ics(value)),
@@ -114,6 +132,57 @@ impl EventBuilder {
     }
 }
 
+/// Split a content line into its name (with parameters) and value.
+///
+/// Parameter values may be quoted and contain colons, e.g.
+/// `DTSTART;TZID="(UTC-05:00) Eastern Time":20260115T090000`.
+fn split_property(line: &str) -> Option<(&str, &str)> {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return Some((&line[..i], &line[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parse a DTSTART/DTEND value, applying its TZID or VALUE=DATE parameters.
+///
+/// Unknown timezones fall back to treating the time as UTC.
+fn parse_datetime_with_params(value: &str, params: &str) -> Option<DateTime<Utc>> {
+    let mut tzid = None;
+    let mut all_day = false;
+
+    for param in params.split(';') {
+        if let Some((name, param_value)) = param.split_once('=') {
+            if name.eq_ignore_ascii_case("TZID") {
+                tzid = Some(param_value);
+            } else if name.eq_ignore_ascii_case("VALUE") {
+                all_day = param_value.eq_ignore_ascii_case("DATE");
+            }
+        }
+    }
+
+    // All-day events keep their calendar date whatever the timezone
+    if all_day {
+        let date = NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()?;
+        return Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?));
+    }
+
+    if let Some(tzid) = tzid.filter(|_| !value.ends_with('Z')) {
+        if let Ok(local) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+            if let Some(offset) = utc_offset_minutes(tzid, &local) {
+                let utc = local - Duration::minutes(offset as i64);
+                return Some(Utc.from_utc_datetime(&utc));
+            }
+        }
+    }
+
+    parse_datetime(value)
+}
+
 /// Parse ICS datetime formats.
 fn parse_datetime(value: &str) -> Option<DateTime<Utc>> {
     // Handle UTC format: 20240115T100000Z
@@ -169,6 +238,76 @@ mod tests {
         assert_eq!(dt.format("%Y-%m-%d").to_string(), "2024-01-15");
     }
 
+    #[test]
+    fn test_parse_datetime_with_tzid() {
+        let dt = parse_datetime_with_params("20260115T090000", "TZID=America/New_York").unwrap();
+        assert_eq!(dt.format("%Y-%m-%d %H:%M").to_string(), "2026-01-15 14:00");
+    }
+
+    #[test]
+    fn test_parse_datetime_with_unknown_tzid_falls_back_to_utc() {
+        let dt = parse_datetime_with_params("20260115T090000", "TZID=Mars/Base").unwrap();
+        assert_eq!(dt.format("%Y-%m-%d %H:%M").to_string(), "2026-01-15 09:00");
+    }
+
+    #[test]
+    fn test_parse_datetime_utc_ignores_tzid() {
+        let dt = parse_datetime_with_params("20260115T090000Z", "TZID=Asia/Tokyo").unwrap();
+        assert_eq!(dt.format("%Y-%m-%d %H:%M").to_string(), "2026-01-15 09:00");
+    }
+
+    #[test]
+    fn test_parse_datetime_all_day() {
+        let dt = parse_datetime_with_params("20260601", "VALUE=DATE").unwrap();
+        assert_eq!(dt.format("%Y-%m-%d %H:%M").to_string(), "2026-06-01 00:00");
+    }
+
+    #[test]
+    fn test_split_property_quoted_param() {
+        let (key, value) =
+            split_property("DTSTART;TZID=\"(UTC-05:00) Eastern Time\":20260115T090000").unwrap();
+        assert_eq!(key, "DTSTART;TZID=\"(UTC-05:00) Eastern Time\"");
+        assert_eq!(value, "20260115T090000");
+    }
+
+    #[test]
+    fn test_parse_tzid_event() {
+        let ics = [
+            "BEGIN:VCALENDAR",
+            "BEGIN:VEVENT",
+            "UID:tz-1",
+            "DTSTART;TZID=America/Los_Angeles:20260601T200000",
+            "DTEND;TZID=America/Los_Angeles:20260601T210000",
+            "SUMMARY:Late sync",
+            "END:VEVENT",
+            "END:VCALENDAR",
+        ]
+        .join("\r\n");
+
+        let events = parse_ics_content(&ics, 3650).unwrap();
+        // 20:00 PDT is 03:00 UTC the next day
+        assert_eq!(events[0].start.format("%Y-%m-%d %H:%M").to_string(), "2026-06-02 03:00");
+        assert_eq!(events[0].duration_minutes, 60);
+    }
+
+    #[test]
+    fn test_parse_all_day_event() {
+        let ics = [
+            "BEGIN:VCALENDAR",
+            "BEGIN:VEVENT",
+            "UID:allday-1",
+            "DTSTART;VALUE=DATE:20260601",
+            "DTEND;VALUE=DATE:20260602",
+            "SUMMARY:Offsite",
+            "END:VEVENT",
+            "END:VCALENDAR",
+        ]
+        .join("\r\n");
+
+        let events = parse_ics_content(&ics, 3650).unwrap();
+        assert_eq!(events[0].date_naive(), NaiveDate::from_ymd_opt(2026, 6, 1).unwrap());
+    }
+
     #[test]
     fn test_unescape_ics() {
         assert_eq!(unescape_ics("Hello\\nWorld"), "Hello\nWorld");
@@ -194,6 +333,47 @@ END:VCALENDAR"#;
         assert_eq!(events[0].duration_minutes, 60);
     }
 
+    #[test]
+    fn test_unfold_lines() {
+        let lines = unfold_lines("SUMMARY:Quarterly\r\n  planning\r\nUID:1\r\n");
+        assert_eq!(lines, vec!["SUMMARY:Quarterly planning", "UID:1"]);
+    }
+
+    #[test]
+    fn test_unfold_lines_with_tab() {
+        let lines = unfold_lines("DESCRIPTION:one\n\ttwo");
+        assert_eq!(lines, vec!["DESCRIPTION:onetwo"]);
+    }
+
+    #[test]
+    fn test_parse_folded_description() {
+        let ics = [
+            "BEGIN:VCALENDAR",
+            "BEGIN:VEVENT",
+            "UID:folded-1",
+            "DTSTART:20260601T100000Z",
+            "DTEND:20260601T110000Z",
+            "SUMMARY:Architecture review for the new billing pipeline and",
+            "  its rollout plan",
+            "DESCRIPTION:Agenda:\\n1. Current state of the ledger service\\n2. Migr",
+            " ation steps\\, risks\\, and owners",
+            "END:VEVENT",
+            "END:VCALENDAR",
+        ]
+        .join("\r\n");
+
+        let events = parse_ics_content(&ics, 3650).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].summary,
+            "Architecture review for the new billing pipeline and its rollout plan"
+        );
+        assert_eq!(
+            events[0].description.as_deref(),
+            Some("Agenda:\n1. Current state of the ledger service\n2. Migration steps, risks, and owners")
+        );
+    }
+
     #[test]
     fn test_event_category_detection() {
         assert_eq!(
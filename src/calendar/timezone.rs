@@ -0,0 +1,229 @@
+//! Timezone offsets for ICS `TZID` parameters.
+//!
+//! Uses a table of common zones with their standard offsets and daylight
+//! saving rules, so we don't need a full timezone database.
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Weekday};
+
+/// Daylight saving rule for a zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DstRule {
+    /// No daylight saving.
+    None,
+    /// Second Sunday in March to first Sunday in November.
+    NorthAmerica,
+    /// Last Sunday in March to last Sunday in October.
+    Europe,
+    /// First Sunday in October to first Sunday in April.
+    Australia,
+    /// Last Sunday in September to first Sunday in April.
+    NewZealand,
+}
+
+/// Common zones: (TZID, standard offset in minutes, DST rule).
+/// Includes the Windows zone names Outlook writes into exports.
+const ZONES: &[(&str, i32, DstRule)] = &[
+    ("UTC", 0, DstRule::None),
+    ("Etc/UTC", 0, DstRule::None),
+    ("GMT", 0, DstRule::None),
+    ("America/New_York", -300, DstRule::NorthAmerica),
+    ("America/Toronto", -300, DstRule::NorthAmerica),
+    ("America/Detroit", -300, DstRule::NorthAmerica),
+    ("US/Eastern", -300, DstRule::NorthAmerica),
+    ("Eastern Standard Time", -300, DstRule::NorthAmerica),
+    ("America/Chicago", -360, DstRule::NorthAmerica),
+    ("US/Central", -360, DstRule::NorthAmerica),
+    ("Central Standard Time", -360, DstRule::NorthAmerica),
+    ("America/Denver", -420, DstRule::NorthAmerica),
+    ("US/Mountain", -420, DstRule::NorthAmerica),
+    ("Mountain Standard Time", -420, DstRule::NorthAmerica),
+    ("America/Phoenix", -420, DstRule::None),
+    ("America/Los_Angeles", -480, DstRule::NorthAmerica),
+    ("America/Vancouver", -480, DstRule::NorthAmerica),
+    ("US/Pacific", -480, DstRule::NorthAmerica),
+    ("Pacific Standard Time", -480, DstRule::NorthAmerica),
+    ("America/Anchorage", -540, DstRule::NorthAmerica),
+    ("Pacific/Honolulu", -600, DstRule::None),
+    ("America/Sao_Paulo", -180, DstRule::None),
+    ("Europe/London", 0, DstRule::Europe),
+    ("Europe/Dublin", 0, DstRule::Europe),
+    ("Europe/Lisbon", 0, DstRule::Europe),
+    ("GMT Standard Time", 0, DstRule::Europe),
+    ("Europe/Paris", 60, DstRule::Europe),
+    ("Europe/Berlin", 60, DstRule::Europe),
+    ("Europe/Madrid", 60, DstRule::Europe),
+    ("Europe/Rome", 60, DstRule::Europe),
+    ("Europe/Amsterdam", 60, DstRule::Europe),
+    ("Europe/Brussels", 60, DstRule::Europe),
+    ("Europe/Vienna", 60, DstRule::Europe),
+    ("Europe/Zurich", 60, DstRule::Europe),
+    ("Europe/Stockholm", 60, DstRule::Europe),
+    ("Europe/Oslo", 60, DstRule::Europe),
+    ("Europe/Copenhagen", 60, DstRule::Europe),
+    ("Europe/Prague", 60, DstRule::Europe),
+    ("Europe/Warsaw", 60, DstRule::Europe),
+    ("W. Europe Standard Time", 60, DstRule::Europe),
+    ("Central Europe Standard Time", 60, DstRule::Europe),
+    ("Romance Standard Time", 60, DstRule::Europe),
+    ("Europe/Athens", 120, DstRule::Europe),
+    ("Europe/Helsinki", 120, DstRule::Europe),
+    ("Europe/Kyiv", 120, DstRule::Europe),
+    ("Europe/Kiev", 120, DstRule::Europe),
+    ("Europe/Istanbul", 180, DstRule::None),
+    ("Europe/Moscow", 180, DstRule::None),
+    ("Asia/Dubai", 240, DstRule::None),
+    ("Asia/Kolkata", 330, DstRule::None),
+    ("Asia/Calcutta", 330, DstRule::None),
+    ("India Standard Time", 330, DstRule::None),
+    ("Asia/Singapore", 480, DstRule::None),
+    ("Asia/Shanghai", 480, DstRule::None),
+    ("Asia/Hong_Kong", 480, DstRule::None),
+    ("Asia/Tokyo", 540, DstRule::None),
+    ("Tokyo Standard Time", 540, DstRule::None),
+    ("Asia/Seoul", 540, DstRule::None),
+    ("Australia/Brisbane", 600, DstRule::None),
+    ("Australia/Sydney", 600, DstRule::Australia),
+    ("Australia/Melbourne", 600, DstRule::Australia),
+    ("AUS Eastern Standard Time", 600, DstRule::Australia),
+    ("Pacific/Auckland", 720, DstRule::NewZealand),
+];
+
+/// UTC offset in minutes for a local time in the given zone.
+///
+/// Returns None for unknown zones. Outlook-style names such as
+/// `(UTC-05:00) Eastern Time` fall back to the offset in the name.
+pub fn utc_offset_minutes(tzid: &str, local: &NaiveDateTime) -> Option<i32> {
+    let tzid = tzid.trim_matches('"');
+
+    if let Some(&(_, standard, rule)) = ZONES.iter().find(|(name, _, _)| *name == tzid) {
+        let dst = if in_dst(rule, local) { 60 } else { 0 };
+        return Some(standard + dst);
+    }
+
+    parse_utc_label(tzid)
+}
+
+/// Parse an offset from a label like `(UTC+05:30) Chennai` or `GMT-0800`.
+fn parse_utc_label(tzid: &str) -> Option<i32> {
+    let start = tzid.find("UTC").or_else(|| tzid.find("GMT"))? + 3;
+    let rest = &tzid[start..];
+
+    let sign = match rest.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+
+    let digits: String = rest[1..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == ':')
+        .filter(|c| c.is_ascii_digit())
+        .collect();
+
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().ok()?, 0),
+        3 | 4 => {
+            let split = digits.len() - 2;
+            (digits[..split].parse::<i32>().ok()?, digits[split..].parse::<i32>().ok()?)
+        }
+        _ => return None,
+    };
+
+    Some(sign * (hours * 60 + minutes))
+}
+
+/// Check whether a local time falls inside daylight saving time.
+fn in_dst(rule: DstRule, local: &NaiveDateTime) -> bool {
+    let year = local.year();
+    let date = local.date();
+
+    match rule {
+        DstRule::None => false,
+        DstRule::NorthAmerica => {
+            match (nth_sunday(year, 3, 2), nth_sunday(year, 11, 1)) {
+                (Some(start), Some(end)) => date >= start && date < end,
+                _ => false,
+            }
+        }
+        DstRule::Europe => match (last_sunday(year, 3), last_sunday(year, 10)) {
+            (Some(start), Some(end)) => date >= start && date < end,
+            _ => false,
+        },
+        DstRule::Australia => match (nth_sunday(year, 4, 1), nth_sunday(year, 10, 1)) {
+            (Some(end), Some(start)) => date < end || date >= start,
+            _ => false,
+        },
+        DstRule::NewZealand => match (nth_sunday(year, 4, 1), last_sunday(year, 9)) {
+            (Some(end), Some(start)) => date < end || date >= start,
+            _ => false,
+        },
+    }
+}
+
+/// The nth Sunday of a month.
+fn nth_sunday(year: i32, month: u32, n: u8) -> Option<NaiveDate> {
+    NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Sun, n)
+}
+
+/// The last Sunday of a month.
+fn last_sunday(year: i32, month: u32) -> Option<NaiveDate> {
+    let last_day = (28..=31)
+        .rev()
+        .find_map(|day| NaiveDate::from_ymd_opt(year, month, day))?;
+    let back = last_day.weekday().num_days_from_sunday() as i64;
+    Some(last_day - chrono::Duration::days(back))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S").unwrap()
+    }
+
+    #[test]
+    fn test_new_york_standard_and_daylight() {
+        assert_eq!(utc_offset_minutes("America/New_York", &local("20260115T090000")), Some(-300));
+        assert_eq!(utc_offset_minutes("America/New_York", &local("20260715T090000")), Some(-240));
+    }
+
+    #[test]
+    fn test_north_america_dst_boundaries() {
+        // 2026: DST runs from March 8 to November 1
+        assert_eq!(utc_offset_minutes("America/Chicago", &local("20260307T120000")), Some(-360));
+        assert_eq!(utc_offset_minutes("America/Chicago", &local("20260308T120000")), Some(-300));
+        assert_eq!(utc_offset_minutes("America/Chicago", &local("20261101T120000")), Some(-360));
+    }
+
+    #[test]
+    fn test_europe_dst() {
+        // 2026: DST runs from March 29 to October 25
+        assert_eq!(utc_offset_minutes("Europe/Berlin", &local("20260328T120000")), Some(60));
+        assert_eq!(utc_offset_minutes("Europe/Berlin", &local("20260329T120000")), Some(120));
+        assert_eq!(utc_offset_minutes("Europe/London", &local("20261026T120000")), Some(0));
+    }
+
+    #[test]
+    fn test_southern_hemisphere_dst() {
+        assert_eq!(utc_offset_minutes("Australia/Sydney", &local("20260115T120000")), Some(660));
+        assert_eq!(utc_offset_minutes("Australia/Sydney", &local("20260615T120000")), Some(600));
+    }
+
+    #[test]
+    fn test_windows_zone_name() {
+        assert_eq!(utc_offset_minutes("Pacific Standard Time", &local("20260115T120000")), Some(-480));
+    }
+
+    #[test]
+    fn test_utc_label_fallback() {
+        let dt = local("20260115T120000");
+        assert_eq!(utc_offset_minutes("(UTC+05:30) Chennai, Kolkata", &dt), Some(330));
+        assert_eq!(utc_offset_minutes("GMT-0800", &dt), Some(-480));
+    }
+
+    #[test]
+    fn test_unknown_zone() {
+        assert_eq!(utc_offset_minutes("Mars/Olympus_Mons", &local("20260115T120000")), None);
+    }
+}
@@ -34,13 +34,13 @@ pub enum EventCategory {
     /// Regular meeting - normal room
     #[default]
     Meeting,
-    /// 1:1 or small sync - sanctuary (easier)
+    /// 1:1 or small sync - treasure room (items)
     OneOnOne,
     /// Large meeting or all-hands - boss room
     AllHands,
     /// Focus time block - treasure room (items)
     FocusTime,
-    /// Break or lunch - healing zone
+    /// Break or lunch - sanctuary (healing)
     Break,
 }
 
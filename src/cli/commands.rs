@@ -2,6 +2,7 @@
 
 use std::io;
 use std::path::Path;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
@@ -9,21 +10,208 @@ use crossterm::ExecutableCommand;
 use ratatui::prelude::*;
 
 use crate::calendar::parse_ics_file;
+use crate::config::load_settings;
 use crate::email::{ImapConfig, parse_mbox_file, fetch_emails};
 use crate::entity::PlayerClass;
-use crate::game::{load_game, save_exists, save_game, GameState, load_run_history};
-use crate::git::parse_repository;
-use crate::ui::App;
+use crate::game::{
+    dungeon_key, list_saves, load_game_from, load_progression, save_game_to, save_progression, save_run_history, slot_exists,
+    suggested_difficulty, Difficulty, GameState, load_run_history, Progression, RunRecord, VictoryCondition,
+};
+use crate::git::{attach_diff_snippets, parse_repository, parse_repository_range, pseudonymize, CommitWindow};
+use crate::ui::{App, EventSink, SeedBrowser, SpeedrunTimer};
 use crate::weather::{fetch_weather, fetch_weather_by_city};
-use crate::world::{generate_dungeon_from_calendar, generate_dungeon_from_email, generate_dungeon_from_weather};
+use crate::world::{
+    generate_dungeon_from_calendar_smoothed, generate_dungeon_from_email, generate_dungeon_from_weather,
+    generate_dungeon_with_options, seed_candidates, DungeonSummary, GenerationOptions, MAX_DIFFICULTY_STARS,
+    SEED_CANDIDATES,
+};
+
+/// How the player's class is picked for a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassChoice {
+    /// Detect from the data source.
+    Auto,
+    /// Play this class.
+    Fixed(PlayerClass),
+    /// Roll among unlocked classes from the run's seed.
+    Random,
+}
+
+/// Turn a class choice into the class to play, rolling if asked.
+/// None means auto-detect.
+fn resolve_class(choice: ClassChoice, seed: u64) -> Option<PlayerClass> {
+    match choice {
+        ClassChoice::Auto => None,
+        ClassChoice::Fixed(class) => Some(class),
+        ClassChoice::Random => {
+            let prog = load_progression().unwrap_or_else(|_| Progression::new());
+            let class = prog.roll_class(seed);
+            println!("Rolled class: {:?}", class);
+            Some(class)
+        }
+    }
+}
+
+/// How the run's difficulty is picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifficultyChoice {
+    /// Play at this difficulty.
+    Fixed(Difficulty),
+    /// Suggest one from recent runs.
+    Auto,
+}
+
+/// Turn a difficulty choice into the difficulty to play.
+fn resolve_difficulty(choice: DifficultyChoice) -> Difficulty {
+    match choice {
+        DifficultyChoice::Fixed(difficulty) => difficulty,
+        DifficultyChoice::Auto => {
+            let history = load_run_history().unwrap_or_default();
+            let difficulty = suggested_difficulty(&history);
+            println!("Difficulty: {} (from your recent runs)", difficulty.name());
+            difficulty
+        }
+    }
+}
+
+/// Whether a new run starts in the tutorial room: forced on or off by
+/// the flags, otherwise until the player's first victory.
+pub fn wants_tutorial(force: bool, skip: bool) -> bool {
+    if skip {
+        return false;
+    }
+    force || load_progression().unwrap_or_else(|_| Progression::new()).needs_tutorial()
+}
+
+/// Wrap a game in an app configured from display settings.
+fn new_app(state: GameState) -> App {
+    let mut app = App::new(state);
+    let settings = load_settings();
+    let display = settings.display;
+    app.auto_advance = settings.gameplay.auto_advance;
+    app.anonymize_authors = display.anonymize_authors;
+    app.color_by_author = display.color_by_author;
+    app.peek_next_room = display.peek_next_room;
+    app.low_hp_warning = display.low_hp_warning;
+    app.debug = app.state.debug;
+    app.progression = load_progression().ok();
+    if display.fog_memory {
+        app.fog_memory = Some(display.fog_fade_turns);
+    }
+    if display.speedrun_timer {
+        let best = load_progression().ok().and_then(|prog| prog.best_time_ms);
+        // A continued run picks its clock up from the save
+        let best = best.map(Duration::from_millis);
+        app.speedrun = Some(SpeedrunTimer::resume(best, &app.state.speedrun));
+    }
+    app
+}
+
+/// Raise inventory capacity by any purchased upgrade levels.
+fn apply_inventory_upgrade(state: &mut GameState) {
+    if let Ok(prog) = load_progression() {
+        state.player.max_inventory += prog.upgrades.bonus_inventory();
+    }
+}
+
+/// Append a finished or quit run to history and award its essence.
+/// Quitting earns partial essence for rooms cleared so far.
+fn record_run(app: &App) {
+    let state = &app.state;
+    if !state.game_over && !app.quit {
+        return;
+    }
+    // Debug runs prove nothing, so they earn and record nothing
+    if state.debug {
+        return;
+    }
+
+    if let Ok(mut prog) = load_progression() {
+        if let Some(earned) = settle_run(&mut prog, app) {
+            println!(
+                "Run abandoned. You keep {} essence for {} rooms cleared.",
+                earned,
+                state.rooms_played()
+            );
+        }
+        let _ = save_progression(&prog);
+    }
+
+    let _ = save_run_history(RunRecord::from_state(state));
+}
+
+/// Apply a finished or quit run to progression. A finished run counts
+/// towards records; a quit run only keeps partial essence for the rooms
+/// cleared, which is returned.
+pub fn settle_run(prog: &mut Progression, app: &App) -> Option<u32> {
+    let state = &app.state;
+    let rooms = state.rooms_played() as u32;
+    let abandoned = if state.game_over {
+        prog.complete_run(state.victory, state.enemies_killed, rooms, state.turn, state.total_lines);
+        if let Some(timer) = app.speedrun.as_ref().filter(|_| state.victory) {
+            let splits: Vec<u64> = timer.splits().iter().map(|s| s.as_millis() as u64).collect();
+            let dungeon = dungeon_key(state.seed, state.world.rooms.len());
+            prog.record_speedrun(&dungeon, timer.elapsed().as_millis() as u64, &splits);
+        }
+        None
+    } else {
+        Some(prog.abandon_run(rooms))
+    };
+    prog.essence += state.bonus_essence();
+    prog.record_authors(&state.author_tally());
+    abandoned
+}
+
+/// Warn if the run couldn't be saved. Called once the terminal is
+/// restored, so the warning stays on screen.
+fn report_save(saved: Result<()>, slot: &str) {
+    if let Err(e) = saved {
+        eprintln!("Warning: could not save to slot '{}': {:#}", slot, e);
+    }
+}
+
+/// Show the seed browser in its own terminal session.
+fn browse_seeds(candidates: Vec<DungeonSummary>) -> Result<Option<u64>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    stdout.execute(EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let choice = SeedBrowser::new(candidates).run(&mut terminal);
+
+    disable_raw_mode()?;
+    io::stdout().execute(LeaveAlternateScreen)?;
+    choice.context("Seed browser failed")
+}
 
 /// Start a new game.
-pub fn play(git_path: &Path, days: u32, seed: Option<u64>, class: Option<PlayerClass>) -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+pub fn play(
+    git_path: &Path,
+    window: CommitWindow,
+    seed: Option<u64>,
+    class: ClassChoice,
+    goal: VictoryCondition,
+    frustration: bool,
+    difficulty: DifficultyChoice,
+    generation: &GenerationOptions,
+    slot: &str,
+    debug: bool,
+    emit_events: Option<&Path>,
+    choose_seed: bool,
+    as_author: Option<&str>,
+) -> Result<()> {
     // Parse git repository
-    let commits = parse_repository(git_path, days)
-        .context("Failed to parse git repository")?;
+    let mut commits = match window {
+        CommitWindow::Days(days) => parse_repository(git_path, days),
+        CommitWindow::Range { since, until } => parse_repository_range(git_path, since, until),
+    }
+    .context("Failed to parse git repository")?;
+    if generation.diff_lore {
+        attach_diff_snippets(git_path, &mut commits).context("Failed to read commit diffs")?;
+    }
 
-    println!("Found {} commits over {} days", commits.len(), days);
+    println!("Found {} commits over {}", commits.len(), window);
     println!("Generating dungeon...");
 
     // Generate seed if not provided
@@ -35,8 +223,67 @@ pub fn play(git_path: &Path, days: u32, seed: Option<u64>, class: Option<PlayerC
             .as_secs()
     });
 
-    // Create game state with optional class (auto-detects if None)
-    let state = GameState::new_with_class(commits, seed, class, git_path.to_path_buf());
+    let gameplay = load_settings().gameplay;
+    let generation = GenerationOptions {
+        max_enemies_per_room: Some(gameplay.max_enemies_per_room),
+        simple_layout: gameplay.simple_layout,
+        conflict_markers: gameplay.conflict_markers,
+        squash_aware: gameplay.squash_aware,
+        ..generation.clone()
+    };
+
+    // Let the player pick among nearby seeds, keeping this one on Esc
+    let seed = if choose_seed {
+        let candidates = seed_candidates(&commits, seed, SEED_CANDIDATES, &generation);
+        browse_seeds(candidates)?.unwrap_or(seed)
+    } else {
+        seed
+    };
+    let class = match (resolve_class(class, seed), as_author) {
+        // Play in one author's style instead of the whole team's
+        (None, Some(author)) => {
+            if !commits.iter().any(|c| c.author.eq_ignore_ascii_case(author)) {
+                anyhow::bail!("No commits by {} over {}", author, window);
+            }
+            let class = PlayerClass::detect_for_author(&commits, author);
+            println!("Playing as {}: {:?}", author, class);
+            Some(class)
+        }
+        (class, _) => class,
+    };
+
+    // Built as a closure so the same run can be restarted from game over
+    let git_path_buf = git_path.to_path_buf();
+    let difficulty = resolve_difficulty(difficulty);
+    let new_run = move |seed: u64| {
+        // Create game state with optional class (auto-detects if None)
+        let mut state = GameState::new_with_options(commits.clone(), seed, class, git_path_buf.clone(), &generation);
+        apply_inventory_upgrade(&mut state);
+        state.set_goal(goal);
+        state.permadeath = gameplay.permadeath;
+        state.repopulate_on_revisit = gameplay.repopulate_on_revisit;
+        state.energy_costs = gameplay.energy_costs;
+        state.pacifist_exits = gameplay.pacifist_exits;
+        state.heal_mode = gameplay.heal_mode;
+        state.guard_turns = gameplay.guard_turns;
+        state.clear_rewards = gameplay.clear_rewards;
+        state.damage_variance = gameplay.damage_variance;
+        state.xp_multiplier = gameplay.xp_multiplier;
+        state.entry_grace_turns = gameplay.entry_grace;
+        if frustration {
+            state.world.apply_frustration();
+        }
+        state.apply_difficulty(difficulty);
+        if debug {
+            state.enable_debug();
+        }
+        state
+    };
+    let state = new_run(seed);
+    let event_sink = emit_events
+        .map(EventSink::open)
+        .transpose()
+        .context("Failed to open event stream")?;
 
     println!("Created {} rooms", state.world.rooms.len());
     println!("Starting game...");
@@ -48,18 +295,34 @@ pub fn play(git_path: &Path, days: u32, seed: Option<u64>, class: Option<PlayerC
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Run game
-    let mut app = App::new(state);
-    let result = app.run(&mut terminal);
+    // Run game, starting over as often as the player asks
+    let mut app = new_app(state);
+    app.new_run = Some(Box::new(new_run));
+    app.event_sink = event_sink;
+    let result = loop {
+        let result = app.run(&mut terminal);
+        match app.restart_seed.take() {
+            Some(seed) if result.is_ok() => {
+                record_run(&app);
+                app.restart(seed);
+                app.progression = load_progression().ok();
+            }
+            _ => break result,
+        }
+    };
 
     // Save if game ended properly
-    if !app.quit {
-        let _ = save_game(&app.state);
-    }
+    let saved = if !app.quit {
+        save_game_to(&app.state, slot)
+    } else {
+        Ok(())
+    };
 
     // Restore terminal
     disable_raw_mode()?;
     io::stdout().execute(LeaveAlternateScreen)?;
+    report_save(saved, slot);
+    record_run(&app);
 
     result.context("Game error")?;
 
@@ -73,7 +336,7 @@ pub fn play(git_path: &Path, days: u32, seed: Option<u64>, class: Option<PlayerC
 }
 
 /// Start a new game from calendar data.
-pub fn play_calendar(calendar_path: &Path, days: u32, seed: Option<u64>, class: Option<PlayerClass>) -> Result<()> {
+pub fn play_calendar(calendar_path: &Path, days: u32, seed: Option<u64>, class: ClassChoice, goal: VictoryCondition, slot: &str) -> Result<()> {
     // Parse calendar file
     let events = parse_ics_file(calendar_path, days)
         .context("Failed to parse calendar file")?;
@@ -89,12 +352,26 @@ pub fn play_calendar(calendar_path: &Path, days: u32, seed: Option<u64>, class:
             .unwrap()
             .as_secs()
     });
+    let class = resolve_class(class, seed);
 
     // Generate world from calendar
-    let world = generate_dungeon_from_calendar(&events, seed);
+    let gameplay = load_settings().gameplay;
+    let world = generate_dungeon_from_calendar_smoothed(&events, seed, gameplay.calendar_smoothing);
 
     // Create game state
-    let state = GameState::new_from_world(world, seed, class, calendar_path.to_path_buf());
+    let mut state = GameState::new_from_world(world, seed, class, calendar_path.to_path_buf());
+    apply_inventory_upgrade(&mut state);
+    state.set_goal(goal);
+    state.permadeath = gameplay.permadeath;
+    state.repopulate_on_revisit = gameplay.repopulate_on_revisit;
+    state.energy_costs = gameplay.energy_costs;
+    state.pacifist_exits = gameplay.pacifist_exits;
+    state.heal_mode = gameplay.heal_mode;
+    state.guard_turns = gameplay.guard_turns;
+    state.clear_rewards = gameplay.clear_rewards;
+    state.damage_variance = gameplay.damage_variance;
+    state.xp_multiplier = gameplay.xp_multiplier;
+    state.entry_grace_turns = gameplay.entry_grace;
 
     println!("Created {} rooms", state.world.rooms.len());
     println!("Starting game...");
@@ -107,17 +384,21 @@ pub fn play_calendar(calendar_path: &Path, days: u32, seed: Option<u64>, class:
     let mut terminal = Terminal::new(backend)?;
 
     // Run game
-    let mut app = App::new(state);
+    let mut app = new_app(state);
     let result = app.run(&mut terminal);
 
     // Save if game ended properly
-    if !app.quit {
-        let _ = save_game(&app.state);
-    }
+    let saved = if !app.quit {
+        save_game_to(&app.state, slot)
+    } else {
+        Ok(())
+    };
 
     // Restore terminal
     disable_raw_mode()?;
     io::stdout().execute(LeaveAlternateScreen)?;
+    report_save(saved, slot);
+    record_run(&app);
 
     result.context("Game error")?;
 
@@ -131,13 +412,17 @@ pub fn play_calendar(calendar_path: &Path, days: u32, seed: Option<u64>, class:
 }
 
 /// Continue a saved game.
-pub fn continue_game() -> Result<()> {
-    if !save_exists() {
-        println!("No saved game found. Start a new game with 'penumbra play'");
+pub fn continue_game(slot: &str) -> Result<()> {
+    if !slot_exists(slot) {
+        println!("No saved game in slot '{}'. Start a new game with 'penumbra play'", slot);
+        let saves = list_saves().unwrap_or_default();
+        if !saves.is_empty() {
+            println!("Saved slots: {}", saves.join(", "));
+        }
         return Ok(());
     }
 
-    let state = load_game().context("Failed to load save file")?;
+    let state = load_game_from(slot).context("Failed to load save file")?;
     println!("Loading saved game (Turn {})...", state.turn);
 
     // Setup terminal
@@ -148,17 +433,21 @@ pub fn continue_game() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Run game
-    let mut app = App::new(state);
+    let mut app = new_app(state);
     let result = app.run(&mut terminal);
 
     // Save progress
-    if !app.quit && !app.state.game_over {
-        let _ = save_game(&app.state);
-    }
+    let saved = if !app.quit && !app.state.game_over {
+        save_game_to(&app.state, slot)
+    } else {
+        Ok(())
+    };
 
     // Restore terminal
     disable_raw_mode()?;
     io::stdout().execute(LeaveAlternateScreen)?;
+    report_save(saved, slot);
+    record_run(&app);
 
     result.context("Game error")?;
 
@@ -166,7 +455,7 @@ pub fn continue_game() -> Result<()> {
 }
 
 /// Start a new game from mbox email file.
-pub fn play_email(email_path: &Path, seed: Option<u64>, class: Option<PlayerClass>) -> Result<()> {
+pub fn play_email(email_path: &Path, seed: Option<u64>, class: ClassChoice, goal: VictoryCondition, slot: &str) -> Result<()> {
     // Parse email file
     let emails = parse_mbox_file(email_path)
         .context("Failed to parse mbox file")?;
@@ -182,12 +471,26 @@ pub fn play_email(email_path: &Path, seed: Option<u64>, class: Option<PlayerClas
             .unwrap()
             .as_secs()
     });
+    let class = resolve_class(class, seed);
 
     // Generate world from emails
     let world = generate_dungeon_from_email(&emails, seed);
 
     // Create game state
-    let state = GameState::new_from_world(world, seed, class, email_path.to_path_buf());
+    let mut state = GameState::new_from_world(world, seed, class, email_path.to_path_buf());
+    apply_inventory_upgrade(&mut state);
+    state.set_goal(goal);
+    let gameplay = load_settings().gameplay;
+    state.permadeath = gameplay.permadeath;
+    state.repopulate_on_revisit = gameplay.repopulate_on_revisit;
+    state.energy_costs = gameplay.energy_costs;
+    state.pacifist_exits = gameplay.pacifist_exits;
+    state.heal_mode = gameplay.heal_mode;
+    state.guard_turns = gameplay.guard_turns;
+    state.clear_rewards = gameplay.clear_rewards;
+    state.damage_variance = gameplay.damage_variance;
+    state.xp_multiplier = gameplay.xp_multiplier;
+    state.entry_grace_turns = gameplay.entry_grace;
 
     println!("Created {} rooms", state.world.rooms.len());
     println!("Starting game...");
@@ -200,17 +503,21 @@ pub fn play_email(email_path: &Path, seed: Option<u64>, class: Option<PlayerClas
     let mut terminal = Terminal::new(backend)?;
 
     // Run game
-    let mut app = App::new(state);
+    let mut app = new_app(state);
     let result = app.run(&mut terminal);
 
     // Save if game ended properly
-    if !app.quit {
-        let _ = save_game(&app.state);
-    }
+    let saved = if !app.quit {
+        save_game_to(&app.state, slot)
+    } else {
+        Ok(())
+    };
 
     // Restore terminal
     disable_raw_mode()?;
     io::stdout().execute(LeaveAlternateScreen)?;
+    report_save(saved, slot);
+    record_run(&app);
 
     result.context("Game error")?;
 
@@ -224,7 +531,7 @@ pub fn play_email(email_path: &Path, seed: Option<u64>, class: Option<PlayerClas
 }
 
 /// Start a new game from IMAP email server.
-pub fn play_imap(config: &ImapConfig, limit: usize, seed: Option<u64>, class: Option<PlayerClass>) -> Result<()> {
+pub fn play_imap(config: &ImapConfig, limit: usize, seed: Option<u64>, class: ClassChoice, goal: VictoryCondition, slot: &str) -> Result<()> {
     // Prompt for password if not provided
     let mut config = config.clone();
     if config.password.is_empty() {
@@ -252,13 +559,27 @@ pub fn play_imap(config: &ImapConfig, limit: usize, seed: Option<u64>, class: Op
             .unwrap()
             .as_secs()
     });
+    let class = resolve_class(class, seed);
 
     // Generate world from emails
     let world = generate_dungeon_from_email(&emails, seed);
 
     // Create game state (use host as source path)
     let source_path = std::path::PathBuf::from(&config.host);
-    let state = GameState::new_from_world(world, seed, class, source_path);
+    let mut state = GameState::new_from_world(world, seed, class, source_path);
+    apply_inventory_upgrade(&mut state);
+    state.set_goal(goal);
+    let gameplay = load_settings().gameplay;
+    state.permadeath = gameplay.permadeath;
+    state.repopulate_on_revisit = gameplay.repopulate_on_revisit;
+    state.energy_costs = gameplay.energy_costs;
+    state.pacifist_exits = gameplay.pacifist_exits;
+    state.heal_mode = gameplay.heal_mode;
+    state.guard_turns = gameplay.guard_turns;
+    state.clear_rewards = gameplay.clear_rewards;
+    state.damage_variance = gameplay.damage_variance;
+    state.xp_multiplier = gameplay.xp_multiplier;
+    state.entry_grace_turns = gameplay.entry_grace;
 
     println!("Created {} rooms", state.world.rooms.len());
     println!("Starting game...");
@@ -271,17 +592,21 @@ pub fn play_imap(config: &ImapConfig, limit: usize, seed: Option<u64>, class: Op
     let mut terminal = Terminal::new(backend)?;
 
     // Run game
-    let mut app = App::new(state);
+    let mut app = new_app(state);
     let result = app.run(&mut terminal);
 
     // Save if game ended properly
-    if !app.quit {
-        let _ = save_game(&app.state);
-    }
+    let saved = if !app.quit {
+        save_game_to(&app.state, slot)
+    } else {
+        Ok(())
+    };
 
     // Restore terminal
     disable_raw_mode()?;
     io::stdout().execute(LeaveAlternateScreen)?;
+    report_save(saved, slot);
+    record_run(&app);
 
     result.context("Game error")?;
 
@@ -295,29 +620,29 @@ pub fn play_imap(config: &ImapConfig, limit: usize, seed: Option<u64>, class: Op
 }
 
 /// Start a new game from weather data by city name.
-pub fn play_weather_city(city: &str, seed: Option<u64>, class: Option<PlayerClass>) -> Result<()> {
+pub fn play_weather_city(city: &str, seed: Option<u64>, class: ClassChoice, goal: VictoryCondition, slot: &str) -> Result<()> {
     println!("Fetching weather for {}...", city);
 
     // Fetch weather data
     let weather = fetch_weather_by_city(city)
         .context("Failed to fetch weather data")?;
 
-    play_weather_internal(weather, seed, class)
+    play_weather_internal(weather, seed, class, goal, slot)
 }
 
 /// Start a new game from weather data by coordinates.
-pub fn play_weather_coords(lat: f64, lon: f64, seed: Option<u64>, class: Option<PlayerClass>) -> Result<()> {
+pub fn play_weather_coords(lat: f64, lon: f64, seed: Option<u64>, class: ClassChoice, goal: VictoryCondition, slot: &str) -> Result<()> {
     println!("Fetching weather for ({:.2}, {:.2})...", lat, lon);
 
     // Fetch weather data
     let weather = fetch_weather(lat, lon)
         .context("Failed to fetch weather data")?;
 
-    play_weather_internal(weather, seed, class)
+    play_weather_internal(weather, seed, class, goal, slot)
 }
 
 /// Internal function to run game from weather data.
-fn play_weather_internal(weather: crate::weather::WeatherData, seed: Option<u64>, class: Option<PlayerClass>) -> Result<()> {
+fn play_weather_internal(weather: crate::weather::WeatherData, seed: Option<u64>, class: ClassChoice, goal: VictoryCondition, slot: &str) -> Result<()> {
     println!("Weather in {}: {} ({:.1}C, {}% humidity, {:.1} km/h wind)",
         weather.location, weather.description,
         weather.temperature_c, weather.humidity, weather.wind_speed_kph);
@@ -335,13 +660,27 @@ fn play_weather_internal(weather: crate::weather::WeatherData, seed: Option<u64>
             .unwrap()
             .as_secs()
     });
+    let class = resolve_class(class, seed);
 
     // Generate world from weather
     let world = generate_dungeon_from_weather(&weather, seed);
 
     // Create game state (use location as source path)
     let source_path = std::path::PathBuf::from(&weather.location);
-    let state = GameState::new_from_world(world, seed, class, source_path);
+    let mut state = GameState::new_from_world(world, seed, class, source_path);
+    apply_inventory_upgrade(&mut state);
+    state.set_goal(goal);
+    let gameplay = load_settings().gameplay;
+    state.permadeath = gameplay.permadeath;
+    state.repopulate_on_revisit = gameplay.repopulate_on_revisit;
+    state.energy_costs = gameplay.energy_costs;
+    state.pacifist_exits = gameplay.pacifist_exits;
+    state.heal_mode = gameplay.heal_mode;
+    state.guard_turns = gameplay.guard_turns;
+    state.clear_rewards = gameplay.clear_rewards;
+    state.damage_variance = gameplay.damage_variance;
+    state.xp_multiplier = gameplay.xp_multiplier;
+    state.entry_grace_turns = gameplay.entry_grace;
 
     println!("Created {} rooms", state.world.rooms.len());
     println!("Starting game...");
@@ -354,17 +693,21 @@ fn play_weather_internal(weather: crate::weather::WeatherData, seed: Option<u64>
     let mut terminal = Terminal::new(backend)?;
 
     // Run game
-    let mut app = App::new(state);
+    let mut app = new_app(state);
     let result = app.run(&mut terminal);
 
     // Save if game ended properly
-    if !app.quit {
-        let _ = save_game(&app.state);
-    }
+    let saved = if !app.quit {
+        save_game_to(&app.state, slot)
+    } else {
+        Ok(())
+    };
 
     // Restore terminal
     disable_raw_mode()?;
     io::stdout().execute(LeaveAlternateScreen)?;
+    report_save(saved, slot);
+    record_run(&app);
 
     result.context("Game error")?;
 
@@ -377,6 +720,92 @@ fn play_weather_internal(weather: crate::weather::WeatherData, seed: Option<u64>
     Ok(())
 }
 
+/// Print the dungeon a git history would become, one line per room with
+/// a difficulty estimate, without starting a game.
+pub fn inspect(git_path: &Path, window: CommitWindow, seed: Option<u64>, generation: &GenerationOptions) -> Result<()> {
+    let commits = match window {
+        CommitWindow::Days(days) => parse_repository(git_path, days),
+        CommitWindow::Range { since, until } => parse_repository_range(git_path, since, until),
+    }
+    .context("Failed to parse git repository")?;
+
+    let seed = seed.unwrap_or_else(|| {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    });
+
+    let gameplay = load_settings().gameplay;
+    let generation = GenerationOptions {
+        max_enemies_per_room: Some(gameplay.max_enemies_per_room),
+        simple_layout: gameplay.simple_layout,
+        conflict_markers: gameplay.conflict_markers,
+        squash_aware: gameplay.squash_aware,
+        ..generation.clone()
+    };
+    let world = generate_dungeon_with_options(&commits, seed, &generation);
+
+    println!("Found {} commits over {}", commits.len(), window);
+    println!("Seed {} (play this dungeon with --seed {})\n", seed, seed);
+    println!("{:>3}  {:<10}  {:<13}  {:>7}  Difficulty", "#", "Date", "Room", "Enemies");
+    for (i, room) in world.rooms.iter().enumerate() {
+        println!(
+            "{:>3}  {:<10}  {:<13}  {:>7}  {}",
+            i + 1,
+            room.source_date,
+            room.room_type.name(),
+            room.enemies.len(),
+            difficulty_stars(room.estimated_difficulty())
+        );
+    }
+
+    Ok(())
+}
+
+/// A difficulty estimate as filled and empty stars, e.g. "***--".
+fn difficulty_stars(stars: u32) -> String {
+    let filled = stars.min(MAX_DIFFICULTY_STARS) as usize;
+    format!("{}{}", "*".repeat(filled), "-".repeat(MAX_DIFFICULTY_STARS as usize - filled))
+}
+
+/// Show which authors' commits you've defeated the most enemies from.
+pub fn show_leaderboard() -> Result<()> {
+    let prog = load_progression().context("Failed to load progression")?;
+    let ranking = prog.leaderboard();
+
+    if ranking.iter().all(|(_, tally)| tally.defeated == 0) {
+        println!("No enemies defeated yet. Start a game with 'penumbra play'");
+        return Ok(());
+    }
+
+    let anonymize = load_settings().display.anonymize_authors;
+    let name = |author: &str| {
+        if anonymize {
+            pseudonymize(author)
+        } else {
+            author.to_string()
+        }
+    };
+
+    println!("=== Author Leaderboard ===\n");
+    let (top, tally) = ranking[0];
+    println!("Most bugs defeated: {} {}\n", name(top), tally.defeated);
+
+    for (i, (author, tally)) in ranking.iter().take(10).enumerate() {
+        println!(
+            "{:>2}. {:<24} {:>4} defeated of {} spawned",
+            i + 1,
+            name(author),
+            tally.defeated,
+            tally.spawned
+        );
+    }
+
+    Ok(())
+}
+
 /// Show run history.
 pub fn show_history() -> Result<()> {
     let history = load_run_history().context("Failed to load history")?;
@@ -389,7 +818,13 @@ pub fn show_history() -> Result<()> {
     println!("=== Run History ===\n");
 
     for (i, run) in history.iter().rev().take(10).enumerate() {
-        let status = if run.victory { "Victory" } else { "Defeat" };
+        let status = if !run.completed {
+            "Quit"
+        } else if run.victory {
+            "Victory"
+        } else {
+            "Defeat"
+        };
         println!(
             "{}. {} - {} turns, {} rooms, level {}",
             i + 1,
@@ -398,6 +833,10 @@ pub fn show_history() -> Result<()> {
             run.rooms_cleared,
             run.final_level
         );
+        println!("   Goal: {}", run.goal);
+        if let Some(score) = run.endless_score {
+            println!("   Rooms survived: {}", score);
+        }
         if let Some(cause) = &run.death_cause {
             println!("   Cause: {}", cause);
         }
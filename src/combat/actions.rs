@@ -1,5 +1,7 @@
 //! Player actions and costs.
 
+use serde::{Deserialize, Serialize};
+
 use crate::world::Direction;
 
 /// Energy costs for actions.
@@ -9,6 +11,30 @@ pub const DEFEND_COST: i32 = 3;
 pub const USE_ITEM_COST: i32 = 2;
 pub const WAIT_REGEN: i32 = 2;
 
+/// Energy regenerated every turn spent in a Sanctuary.
+pub const SANCTUARY_REGEN: i32 = 5;
+
+/// Energy cost table, overridable from gameplay settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnergyCosts {
+    pub movement: i32,
+    pub attack: i32,
+    pub defend: i32,
+    pub use_item: i32,
+}
+
+impl Default for EnergyCosts {
+    fn default() -> Self {
+        Self {
+            movement: MOVE_COST,
+            attack: ATTACK_COST,
+            defend: DEFEND_COST,
+            use_item: USE_ITEM_COST,
+        }
+    }
+}
+
 /// Actions the player can take.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerAction {
@@ -25,13 +51,18 @@ pub enum PlayerAction {
 }
 
 impl PlayerAction {
-    /// Get the energy cost of this action.
+    /// Get the energy cost of this action with the default cost table.
     pub fn energy_cost(&self) -> i32 {
+        self.energy_cost_with(&EnergyCosts::default())
+    }
+
+    /// Get the energy cost of this action from a cost table.
+    pub fn energy_cost_with(&self, costs: &EnergyCosts) -> i32 {
         match self {
-            PlayerAction::Move(_, _) => MOVE_COST,
-            PlayerAction::Attack(_) => ATTACK_COST,
-            PlayerAction::Defend => DEFEND_COST,
-            PlayerAction::UseItem(_) => USE_ITEM_COST,
+            PlayerAction::Move(_, _) => costs.movement,
+            PlayerAction::Attack(_) => costs.attack,
+            PlayerAction::Defend => costs.defend,
+            PlayerAction::UseItem(_) => costs.use_item,
             PlayerAction::Wait => 0, // Wait costs nothing, gives regen
         }
     }
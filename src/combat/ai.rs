@@ -5,6 +5,16 @@ use std::collections::{HashSet, VecDeque};
 use crate::entity::{Enemy, EnemyType, Player};
 use crate::world::Room;
 
+/// Enemies notice a player they can see within this many steps.
+pub const AGGRO_RADIUS: i32 = 2;
+
+/// Range at which enemies notice a player with the given stealth. Each
+/// point of stealth shaves a step off `AGGRO_RADIUS`, but an enemy always
+/// notices a player right beside it.
+pub fn detection_radius(stealth: i32) -> i32 {
+    (AGGRO_RADIUS - stealth).max(1)
+}
+
 /// Action an enemy can take.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EnemyAction {
@@ -18,6 +28,8 @@ pub enum EnemyAction {
     Split,
     /// Grow damage (TechDebt).
     Grow(i32),
+    /// Take an item from the player (ScopeCreep).
+    Steal,
     /// Do nothing.
     Wait,
 }
@@ -26,6 +38,19 @@ pub enum EnemyAction {
 pub fn decide_action(enemy: &Enemy, player: &Player, room: &Room) -> EnemyAction {
     let dist = manhattan_distance((enemy.x, enemy.y), (player.x, player.y));
 
+    // Scope Creep runs once it has loot, and steals instead of attacking
+    if enemy.enemy_type == EnemyType::ScopeCreep {
+        if enemy.stolen.is_some() {
+            return match flee_step(enemy, (player.x, player.y), room) {
+                Some((dx, dy)) => EnemyAction::Move { dx, dy },
+                None => EnemyAction::Wait,
+            };
+        }
+        if dist == 1 && !player.inventory.is_empty() {
+            return EnemyAction::Steal;
+        }
+    }
+
     // Adjacent to player - attack
     if dist == 1 {
         // Check for special abilities first
@@ -75,8 +100,8 @@ pub fn should_use_special(enemy: &Enemy) -> Option<EnemyAction> {
                 return Some(EnemyAction::Split);
             }
         }
-        EnemyType::Bug => {
-            // Bugs have no special
+        EnemyType::Bug | EnemyType::ScopeCreep | EnemyType::Deletion => {
+            // No specials; Scope Creep's steal depends on the player
         }
     }
     None
@@ -122,6 +147,32 @@ pub fn find_path(from: (i32, i32), to: (i32, i32), room: &Room) -> Option<Vec<(i
     None
 }
 
+/// Pick the step that takes an enemy furthest from the player.
+///
+/// Only steps that increase the distance count. Among equally good steps,
+/// the one heading most directly away from the player wins, so an enemy
+/// level with the player flees along that row rather than sideways.
+/// Returns None when the enemy is cornered.
+pub fn flee_step(enemy: &Enemy, player: (i32, i32), room: &Room) -> Option<(i32, i32)> {
+    let straightness = |(x, y): (i32, i32)| (x - player.0).pow(2) + (y - player.1).pow(2);
+    let current = (manhattan_distance((enemy.x, enemy.y), player), 0);
+    let mut best: Option<((i32, i32), (i32, i32))> = None;
+
+    for (dx, dy) in get_adjacent_deltas() {
+        let next = (enemy.x + dx, enemy.y + dy);
+        if next == player || !room.is_walkable(next.0, next.1) || room.get_enemy_at(next.0, next.1).is_some() {
+            continue;
+        }
+        let score = (manhattan_distance(next, player), straightness(next));
+        let best_score = best.map_or(current, |(_, s)| s);
+        if score.0 > current.0 && score > best_score {
+            best = Some(((dx, dy), score));
+        }
+    }
+
+    best.map(|(step, _)| step)
+}
+
 /// Get adjacent position deltas (cardinal directions).
 pub fn get_adjacent_deltas() -> [(i32, i32); 4] {
     [(0, -1), (0, 1), (-1, 0), (1, 0)]
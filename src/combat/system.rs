@@ -3,6 +3,7 @@
 use rand::Rng;
 
 use crate::entity::{Enemy, Player};
+use crate::rng::GameRng;
 
 /// Result of a combat action.
 #[derive(Debug, Clone)]
@@ -14,8 +15,74 @@ pub struct CombatResult {
     pub message: String,
 }
 
+/// Expected outcome of a player attack, worked out without rolling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttackPreview {
+    /// Chance the attack lands, 0.0 to 1.0.
+    pub hit_chance: f32,
+    /// Least damage a landed hit deals.
+    pub min_damage: i32,
+    /// Most damage a landed hit deals, on a critical.
+    pub max_damage: i32,
+    /// Chance a landed hit is a critical, 0.0 to 1.0.
+    pub crit_chance: f32,
+}
+
+/// Share of player damage a warded boss takes while its conflict markers
+/// stand, in percent.
+pub const WARDED_DAMAGE_PERCENT: i32 = 25;
+
+/// Chance a landed player hit is a critical, doubling its damage.
+pub const CRITICAL_CHANCE: f32 = 0.05;
+
+/// Preview a player attack on an enemy without touching any RNG.
+pub fn preview_attack(player: &Player, enemy: &Enemy) -> AttackPreview {
+    preview_attack_with_variance(player, enemy, 0)
+}
+
+/// Preview a player attack whose damage may land up to `variance`
+/// percent either side of the usual amount.
+///
+/// The bounds match `player_attack_with_variance`: the lowest roll
+/// without a critical, and the highest roll with one, both after any
+/// conflict-marker ward.
+pub fn preview_attack_with_variance(player: &Player, enemy: &Enemy, variance: u32) -> AttackPreview {
+    let base = calculate_damage(player.damage, player.level, false);
+    let spread = base as f32 * variance as f32 / 100.0;
+    let low = ((base as f32 - spread).round() as i32).max(1);
+    let high = ((base as f32 + spread).round() as i32).max(1);
+
+    AttackPreview {
+        hit_chance: calculate_hit_chance(player.focus),
+        min_damage: landed_damage(low, false, enemy.warded),
+        max_damage: landed_damage(high, true, enemy.warded),
+        crit_chance: CRITICAL_CHANCE,
+    }
+}
+
+/// Damage a landed player hit deals after criticals and wards.
+fn landed_damage(base: i32, critical: bool, warded: bool) -> i32 {
+    let damage = if critical { base * 2 } else { base };
+    if warded {
+        (damage * WARDED_DAMAGE_PERCENT / 100).max(1)
+    } else {
+        damage
+    }
+}
+
 /// Resolve a player attack on an enemy.
-pub fn player_attack(player: &Player, enemy: &mut Enemy, rng: &mut impl Rng) -> CombatResult {
+pub fn player_attack(player: &Player, enemy: &mut Enemy, rng: &mut GameRng) -> CombatResult {
+    player_attack_with_variance(player, enemy, rng, 0)
+}
+
+/// Resolve a player attack whose damage may land up to `variance`
+/// percent either side of the usual amount.
+pub fn player_attack_with_variance(
+    player: &Player,
+    enemy: &mut Enemy,
+    rng: &mut GameRng,
+    variance: u32,
+) -> CombatResult {
     let hit_chance = calculate_hit_chance(player.focus);
     let roll: f32 = rng.gen();
     
@@ -29,17 +96,19 @@ pub fn player_attack(player: &Player, enemy: &mut Enemy, rng: &mut impl Rng) ->
         };
     }
 
-    // Check for critical hit (5% chance)
+    // Check for critical hit
     let crit_roll: f32 = rng.gen();
-    let critical = crit_roll < 0.05;
+    let critical = crit_roll < CRITICAL_CHANCE;
     
-    let base_damage = calculate_damage(player.damage, player.level, false);
-    let damage = if critical { base_damage * 2 } else { base_damage };
+    let base_damage = apply_variance(calculate_damage(player.damage, player.level, false), variance, rng);
+    let damage = landed_damage(base_damage, critical, enemy.warded);
     
     let killed = !enemy.take_damage(damage);
     
     let message = if killed {
         format!("You dealt {} damage and killed the {}!", damage, enemy.enemy_type.symbol())
+    } else if enemy.warded {
+        format!("Its conflict markers deflect the blow. You dealt {} damage.", damage)
     } else if critical {
         format!("Critical hit! You dealt {} damage!", damage)
     } else {
@@ -56,7 +125,18 @@ pub fn player_attack(player: &Player, enemy: &mut Enemy, rng: &mut impl Rng) ->
 }
 
 /// Resolve an enemy attack on the player.
-pub fn enemy_attack(enemy: &Enemy, player: &mut Player, rng: &mut impl Rng) -> CombatResult {
+pub fn enemy_attack(enemy: &Enemy, player: &mut Player, rng: &mut GameRng) -> CombatResult {
+    enemy_attack_with_variance(enemy, player, rng, 0)
+}
+
+/// Resolve an enemy attack whose damage may land up to `variance`
+/// percent either side of the usual amount.
+pub fn enemy_attack_with_variance(
+    enemy: &Enemy,
+    player: &mut Player,
+    rng: &mut GameRng,
+    variance: u32,
+) -> CombatResult {
     // Enemies have 80% base hit chance
     let hit_chance = 0.80;
     let roll: f32 = rng.gen();
@@ -71,7 +151,7 @@ pub fn enemy_attack(enemy: &Enemy, player: &mut Player, rng: &mut impl Rng) -> C
         };
     }
 
-    let damage = calculate_damage(enemy.damage, 1, player.defending);
+    let damage = apply_variance(calculate_damage(enemy.damage, 1, player.is_guarding()), variance, rng);
     let killed = !player.take_damage(damage);
     
     let message = if killed {
@@ -89,6 +169,18 @@ pub fn enemy_attack(enemy: &Enemy, player: &mut Player, rng: &mut impl Rng) -> C
     }
 }
 
+/// Spread damage up to `percent` either side, rounding to nearest and
+/// keeping it at least 1. Zero leaves it alone without touching the RNG,
+/// so runs without variance play out exactly as before.
+pub fn apply_variance(damage: i32, percent: u32, rng: &mut GameRng) -> i32 {
+    if percent == 0 {
+        return damage;
+    }
+    let spread = damage as f32 * percent as f32 / 100.0;
+    let offset: f32 = rng.gen_range(-spread..=spread);
+    ((damage as f32 + offset).round() as i32).max(1)
+}
+
 /// Calculate hit chance based on focus stat.
 /// Base 80%, +1% per 10 focus. Min 5%, max 95%.
 pub fn calculate_hit_chance(focus: i32) -> f32 {
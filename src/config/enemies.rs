@@ -0,0 +1,172 @@
+//! Enemy stat overrides for modders.
+//!
+//! An optional `~/.penumbra/enemies.toml` changes enemy stats without
+//! recompiling. Each table is named after an enemy type and may set
+//! `base_hp`, `base_damage` and `symbol`:
+//!
+//! ```toml
+//! [bug]
+//! base_hp = 15
+//! symbol = "b"
+//!
+//! [tech_debt]
+//! base_damage = 6
+//! ```
+//!
+//! Unknown types and keys are ignored, and so are values that don't make
+//! sense (HP below 1, negative damage, symbols that aren't one visible
+//! character). A table with a value of the wrong kind, like
+//! `base_hp = "ten"`, is ignored, and a file that isn't valid TOML is
+//! ignored as a whole.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+
+use crate::entity::EnemyType;
+
+/// Overrides for one enemy type. `None` keeps the built-in value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnemyOverride {
+    pub base_hp: Option<i32>,
+    pub base_damage: Option<i32>,
+    pub symbol: Option<char>,
+}
+
+/// A top-level entry of `enemies.toml`: a table of overrides, or
+/// anything else, which is ignored.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawEntry {
+    Table(RawOverride),
+    Other(IgnoredAny),
+}
+
+/// One table of `enemies.toml`, before validation.
+#[derive(Debug, Deserialize)]
+struct RawOverride {
+    base_hp: Option<i64>,
+    base_damage: Option<i64>,
+    symbol: Option<String>,
+}
+
+impl RawOverride {
+    /// Keep only the values that make sense.
+    fn validate(self) -> EnemyOverride {
+        let symbol = self.symbol.and_then(|s| {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if !c.is_whitespace() && !c.is_control() => Some(c),
+                _ => None,
+            }
+        });
+
+        EnemyOverride {
+            base_hp: self.base_hp.filter(|&hp| hp >= 1).and_then(|hp| i32::try_from(hp).ok()),
+            base_damage: self.base_damage.filter(|&d| d >= 0).and_then(|d| i32::try_from(d).ok()),
+            symbol,
+        }
+    }
+}
+
+/// Enemy stats after applying any overrides.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnemyConfig {
+    overrides: HashMap<EnemyType, EnemyOverride>,
+}
+
+impl EnemyConfig {
+    /// Parse an `enemies.toml`. Invalid TOML gives the built-in stats.
+    pub fn parse(content: &str) -> Self {
+        let Ok(entries) = toml::from_str::<HashMap<String, RawEntry>>(content) else {
+            return Self::default();
+        };
+
+        let overrides = entries
+            .into_iter()
+            .filter_map(|(key, entry)| match entry {
+                RawEntry::Table(raw) => Some((enemy_type_for_key(&key)?, raw.validate())),
+                RawEntry::Other(_) => None,
+            })
+            .collect();
+        Self { overrides }
+    }
+
+    /// Load overrides from a file, or the built-in stats if it can't be read.
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    /// Overrides set for an enemy type, if any.
+    pub fn get(&self, enemy_type: EnemyType) -> Option<&EnemyOverride> {
+        self.overrides.get(&enemy_type)
+    }
+
+    /// Base HP for an enemy type.
+    pub fn base_hp(&self, enemy_type: EnemyType) -> i32 {
+        self.get(enemy_type)
+            .and_then(|o| o.base_hp)
+            .unwrap_or_else(|| enemy_type.base_hp())
+    }
+
+    /// Base damage for an enemy type.
+    pub fn base_damage(&self, enemy_type: EnemyType) -> i32 {
+        self.get(enemy_type)
+            .and_then(|o| o.base_damage)
+            .unwrap_or_else(|| enemy_type.base_damage())
+    }
+
+    /// Map symbol for an enemy type.
+    pub fn symbol(&self, enemy_type: EnemyType) -> char {
+        self.get(enemy_type)
+            .and_then(|o| o.symbol)
+            .unwrap_or_else(|| enemy_type.default_symbol())
+    }
+}
+
+/// Enemy type named by a table in `enemies.toml`.
+fn enemy_type_for_key(key: &str) -> Option<EnemyType> {
+    match key.to_lowercase().as_str() {
+        "bug" => Some(EnemyType::Bug),
+        "regression" => Some(EnemyType::Regression),
+        "tech_debt" | "techdebt" => Some(EnemyType::TechDebt),
+        "merge_conflict" | "mergeconflict" => Some(EnemyType::MergeConflict),
+        "scope_creep" | "scopecreep" => Some(EnemyType::ScopeCreep),
+        "deletion" => Some(EnemyType::Deletion),
+        _ => None,
+    }
+}
+
+/// Get the enemy override file path.
+pub fn enemy_config_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".penumbra")
+        .join("enemies.toml")
+}
+
+/// Load enemy overrides from the config dir, falling back to defaults.
+pub fn load_enemy_config() -> EnemyConfig {
+    EnemyConfig::load_from(&enemy_config_path())
+}
+
+static ENEMY_CONFIG: OnceLock<EnemyConfig> = OnceLock::new();
+
+/// Make `config` the enemy stats for the rest of the process.
+///
+/// Call once at startup, before any enemy is created. Returns false if
+/// stats were already in use, in which case they are kept.
+pub fn install_enemy_config(config: EnemyConfig) -> bool {
+    ENEMY_CONFIG.set(config).is_ok()
+}
+
+/// The enemy stats in use: the installed config, or the built-in stats.
+pub fn enemy_config() -> &'static EnemyConfig {
+    ENEMY_CONFIG.get_or_init(EnemyConfig::default)
+}
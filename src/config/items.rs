@@ -0,0 +1,202 @@
+//! Custom item definitions for modders.
+//!
+//! An optional `~/.penumbra/items.toml` adds items to the loot pool.
+//! `chance` is the share of commit drops replaced by a custom item
+//! (default 0.25). Each `[[item]]` picks one of the existing effects:
+//!
+//! ```toml
+//! chance = 0.3
+//!
+//! [[item]]
+//! name = "Rubber Duck"
+//! type = "Consumable"
+//! effect = { Heal = 15 }
+//! rarity = "Uncommon"
+//! weight = 3
+//!
+//! [[item]]
+//! name = "Stack Trace"
+//! type = "Scroll"
+//! effect = "RevealMap"
+//! ```
+//!
+//! `rarity` defaults to Common and `weight`, the relative chance of this
+//! item among the custom ones, to 1. Items that don't parse, have no
+//! name, have zero weight or heal or restore less than 1 are skipped. A
+//! file that isn't valid TOML is ignored as a whole.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use rand::Rng;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+
+use crate::item::{Item, ItemEffect, ItemType, Rarity};
+use crate::rng::GameRng;
+
+/// Share of drops replaced by custom items when `chance` isn't set.
+pub const DEFAULT_CUSTOM_ITEM_CHANCE: f64 = 0.25;
+
+/// A custom item from `items.toml`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItemDefinition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub item_type: ItemType,
+    pub effect: ItemEffect,
+    #[serde(default = "default_rarity")]
+    pub rarity: Rarity,
+    /// Relative chance among custom items.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_rarity() -> Rarity {
+    Rarity::Common
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn default_chance() -> f64 {
+    DEFAULT_CUSTOM_ITEM_CHANCE
+}
+
+impl ItemDefinition {
+    /// Whether this definition makes a usable item. Heals and energy
+    /// restores of zero or less would do nothing, or drain the player.
+    fn is_valid(&self) -> bool {
+        let restores = match self.effect {
+            ItemEffect::Heal(amount) | ItemEffect::RestoreEnergy(amount) => amount > 0,
+            _ => true,
+        };
+        !self.name.trim().is_empty() && self.weight > 0 && restores
+    }
+
+    /// Create an item from this definition.
+    pub fn to_item(&self) -> Item {
+        Item::new(self.name.clone(), self.item_type, self.effect.clone(), self.rarity)
+    }
+}
+
+/// An `[[item]]` entry: a definition, or anything else, which is skipped.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawItem {
+    Item(ItemDefinition),
+    Other(IgnoredAny),
+}
+
+/// The whole of `items.toml`, before validation.
+#[derive(Debug, Deserialize)]
+struct RawItemsFile {
+    #[serde(default = "default_chance")]
+    chance: f64,
+    #[serde(default, rename = "item")]
+    items: Vec<RawItem>,
+}
+
+/// Custom items mixed into the loot pool.
+#[derive(Debug, Clone)]
+pub struct ItemRegistry {
+    /// Share of drops replaced by a custom item, from 0 to 1.
+    pub chance: f64,
+    items: Vec<ItemDefinition>,
+}
+
+impl Default for ItemRegistry {
+    fn default() -> Self {
+        Self {
+            chance: DEFAULT_CUSTOM_ITEM_CHANCE,
+            items: Vec::new(),
+        }
+    }
+}
+
+impl ItemRegistry {
+    /// Parse an `items.toml`. Invalid TOML gives an empty registry.
+    pub fn parse(content: &str) -> Self {
+        let Ok(file) = toml::from_str::<RawItemsFile>(content) else {
+            return Self::default();
+        };
+
+        let items = file
+            .items
+            .into_iter()
+            .filter_map(|raw| match raw {
+                RawItem::Item(def) if def.is_valid() => Some(def),
+                _ => None,
+            })
+            .collect();
+        let chance = if file.chance.is_nan() { DEFAULT_CUSTOM_ITEM_CHANCE } else { file.chance.clamp(0.0, 1.0) };
+
+        Self { chance, items }
+    }
+
+    /// Load custom items from a file, or none if it can't be read.
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    /// The custom item definitions.
+    pub fn items(&self) -> &[ItemDefinition] {
+        &self.items
+    }
+
+    /// Whether there are no custom items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Roll for a custom item to replace a built-in drop.
+    ///
+    /// An empty registry never draws from `rng`, so seeds build the same
+    /// dungeon as they would without an `items.toml`.
+    pub fn roll(&self, rng: &mut GameRng) -> Option<Item> {
+        if self.is_empty() || !rng.gen_bool(self.chance) {
+            return None;
+        }
+        let total: u64 = self.items.iter().map(|def| def.weight as u64).sum();
+        let mut roll = rng.gen_range(0..total);
+        for def in &self.items {
+            if roll < def.weight as u64 {
+                return Some(def.to_item());
+            }
+            roll -= def.weight as u64;
+        }
+        None
+    }
+}
+
+/// Get the custom item file path.
+pub fn item_registry_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".penumbra")
+        .join("items.toml")
+}
+
+/// Load custom items from the config dir, or none if absent.
+pub fn load_item_registry() -> ItemRegistry {
+    ItemRegistry::load_from(&item_registry_path())
+}
+
+static ITEM_REGISTRY: OnceLock<ItemRegistry> = OnceLock::new();
+
+/// Make `registry` the custom items for the rest of the process.
+///
+/// Call once at startup, before any dungeon is generated. Returns false
+/// if items were already in use, in which case they are kept.
+pub fn install_item_registry(registry: ItemRegistry) -> bool {
+    ITEM_REGISTRY.set(registry).is_ok()
+}
+
+/// The custom items in use: the installed registry, or none.
+pub fn item_registry() -> &'static ItemRegistry {
+    ITEM_REGISTRY.get_or_init(ItemRegistry::default)
+}
@@ -1,5 +1,9 @@
 //! Configuration.
 
+mod enemies;
+mod items;
 mod settings;
 
+pub use enemies::*;
+pub use items::*;
 pub use settings::*;
@@ -4,7 +4,11 @@ use std::fs;
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::combat::EnergyCosts;
+use crate::item::HealMode;
+use crate::world::MAX_ENEMIES_PER_ROOM;
 
 /// Complete application settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +25,38 @@ pub struct DisplaySettings {
     pub color: bool,
     /// Use unicode box-drawing characters.
     pub unicode: bool,
+    /// Show commit authors as stable pseudonyms instead of real names.
+    #[serde(default)]
+    pub anonymize_authors: bool,
+    /// Color enemies by the author of their commit instead of by type.
+    #[serde(default)]
+    pub color_by_author: bool,
+    /// Keep explored tiles on the map after they leave view, fading them
+    /// into the fog over time.
+    #[serde(default)]
+    pub fog_memory: bool,
+    /// Turns for a remembered tile to fade fully into the fog.
+    #[serde(default = "default_fog_fade_turns")]
+    pub fog_fade_turns: u32,
+    /// Show a real-time speedrun timer with room splits.
+    #[serde(default)]
+    pub speedrun_timer: bool,
+    /// Preview the next room's type, enemies and items when standing by
+    /// the exit of a cleared room.
+    #[serde(default)]
+    pub peek_next_room: bool,
+    /// Warn and draw a red border once HP falls below this percent of
+    /// max HP. Zero turns the warning off.
+    #[serde(default = "default_low_hp_warning")]
+    pub low_hp_warning: u32,
+}
+
+fn default_fog_fade_turns() -> u32 {
+    50
+}
+
+fn default_low_hp_warning() -> u32 {
+    25
 }
 
 /// Gameplay-related settings.
@@ -32,6 +68,97 @@ pub struct GameplaySettings {
     pub auto_pickup: bool,
     /// Confirm before attacking.
     pub confirm_attacks: bool,
+    /// End the run on death. When off, the player revives at the room
+    /// entrance a limited number of times.
+    #[serde(default = "default_permadeath")]
+    pub permadeath: bool,
+    /// Respawn a weak enemy in cleared rooms when backtracking into them.
+    /// When off, revisited rooms are safe.
+    #[serde(default)]
+    pub repopulate_on_revisit: bool,
+    /// Allow leaving a room while enemies remain, as long as none of
+    /// them has noticed you. Unnoticed enemies stay put.
+    #[serde(default)]
+    pub pacifist_exits: bool,
+    /// Energy spent per action. Missing entries keep their defaults and
+    /// negative ones count as free.
+    #[serde(default, deserialize_with = "deserialize_energy_costs")]
+    pub energy_costs: EnergyCosts,
+    /// Whether heal items restore fixed HP or a percentage of max HP.
+    #[serde(default)]
+    pub heal_mode: HealMode,
+    /// Most enemies spawned from a room's commits. Small rooms still
+    /// hold fewer, a quarter of their tiles at most.
+    #[serde(default = "default_max_enemies_per_room")]
+    pub max_enemies_per_room: usize,
+    /// Turns Defend halves damage for. Zero keeps the stance only until
+    /// your next action.
+    #[serde(default)]
+    pub guard_turns: u32,
+    /// After clearing a non-boss room, choose between a small heal and a
+    /// chest that may be a mimic.
+    #[serde(default)]
+    pub clear_rewards: bool,
+    /// Plain rectangular rooms with the entrance and exit always in the
+    /// same spots. Merge branches and shortcut doors are left out.
+    #[serde(default)]
+    pub simple_layout: bool,
+    /// Offer to move straight on to the next room once one is cleared,
+    /// instead of walking to the exit.
+    #[serde(default)]
+    pub auto_advance: bool,
+    /// Merge Conflict bosses arrive with conflict markers that must be
+    /// killed before the boss takes full damage.
+    #[serde(default)]
+    pub conflict_markers: bool,
+    /// Let hits land up to this many percent above or below their usual
+    /// damage. Zero keeps every hit the same.
+    #[serde(default)]
+    pub damage_variance: u32,
+    /// Treat commits far larger than the median as squashed work, making
+    /// epic rooms with extra waves of enemies.
+    #[serde(default)]
+    pub squash_aware: bool,
+    /// Multiplier on XP from kills. Above 1.0 levels faster, below slower.
+    #[serde(default = "default_xp_multiplier")]
+    pub xp_multiplier: f32,
+    /// Size calendar rooms from the average intensity of this many days
+    /// either side, so busy stretches ramp up instead of spiking. Zero
+    /// uses each day alone.
+    #[serde(default)]
+    pub calendar_smoothing: usize,
+    /// Enemy turns after entering a room in which enemies move but don't
+    /// attack, so you can take in the layout. Zero turns it off.
+    #[serde(default = "default_entry_grace")]
+    pub entry_grace: u32,
+}
+
+fn default_permadeath() -> bool {
+    true
+}
+
+fn default_entry_grace() -> u32 {
+    1
+}
+
+fn default_xp_multiplier() -> f32 {
+    1.0
+}
+
+fn default_max_enemies_per_room() -> usize {
+    MAX_ENEMIES_PER_ROOM
+}
+
+/// Read an energy cost table, clamping negative costs to zero so no
+/// action refunds energy.
+fn deserialize_energy_costs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<EnergyCosts, D::Error> {
+    let costs = EnergyCosts::deserialize(deserializer)?;
+    Ok(EnergyCosts {
+        movement: costs.movement.max(0),
+        attack: costs.attack.max(0),
+        defend: costs.defend.max(0),
+        use_item: costs.use_item.max(0),
+    })
 }
 
 /// Custom keybindings.
@@ -59,6 +186,13 @@ impl Default for DisplaySettings {
         Self {
             color: true,
             unicode: true,
+            anonymize_authors: false,
+            color_by_author: false,
+            fog_memory: false,
+            fog_fade_turns: default_fog_fade_turns(),
+            speedrun_timer: false,
+            peek_next_room: false,
+            low_hp_warning: default_low_hp_warning(),
         }
     }
 }
@@ -69,6 +203,22 @@ impl Default for GameplaySettings {
             default_days: 30,
             auto_pickup: true,
             confirm_attacks: false,
+            permadeath: true,
+            repopulate_on_revisit: false,
+            pacifist_exits: false,
+            energy_costs: EnergyCosts::default(),
+            heal_mode: HealMode::default(),
+            max_enemies_per_room: default_max_enemies_per_room(),
+            guard_turns: 0,
+            clear_rewards: false,
+            simple_layout: false,
+            auto_advance: false,
+            conflict_markers: false,
+            damage_variance: 0,
+            squash_aware: false,
+            xp_multiplier: default_xp_multiplier(),
+            calendar_smoothing: 0,
+            entry_grace: default_entry_grace(),
         }
     }
 }
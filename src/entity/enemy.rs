@@ -3,6 +3,10 @@
 use serde::{Deserialize, Serialize};
 
 use super::EnemyType;
+use crate::item::Item;
+
+/// Glyph shown for one turn where an enemy fell.
+pub const DEATH_SYMBOL: char = '%';
 
 /// An enemy in the dungeon.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,24 +19,59 @@ pub struct Enemy {
     pub enemy_type: EnemyType,
     pub source_commit: String,
     pub turns_alive: u32,
+    /// Name shown for mini-bosses.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Item taken from the player, dropped on death.
+    #[serde(default)]
+    pub stolen: Option<Item>,
+    /// Whether this enemy has noticed the player.
+    #[serde(default)]
+    pub aggro: bool,
+    /// A conflict marker guarding a merge boss.
+    #[serde(default)]
+    pub conflict_marker: bool,
+    /// Takes reduced damage while its conflict markers stand.
+    #[serde(default)]
+    pub warded: bool,
 }
 
 impl Enemy {
-    /// Create a new enemy of the given type.
+    /// Create a new enemy of the given type, with stats from any
+    /// installed `enemies.toml` overrides.
     pub fn new(enemy_type: EnemyType, x: i32, y: i32, commit_hash: &str) -> Self {
-        let hp = enemy_type.base_hp();
+        let config = crate::config::enemy_config();
+        let hp = config.base_hp(enemy_type);
         Self {
             x,
             y,
             hp,
             max_hp: hp,
-            damage: enemy_type.base_damage(),
+            damage: config.base_damage(enemy_type),
             enemy_type,
             source_commit: commit_hash.to_string(),
             turns_alive: 0,
+            title: None,
+            stolen: None,
+            aggro: false,
+            conflict_marker: false,
+            warded: false,
         }
     }
 
+    /// Promote to a named mini-boss with double HP and extra damage.
+    pub fn promote(&mut self, title: impl Into<String>) {
+        self.hp *= 2;
+        self.max_hp *= 2;
+        self.damage += 2;
+        self.title = Some(title.into());
+    }
+
+    /// Check if this enemy is a mini-boss.
+    pub fn is_mini_boss(&self) -> bool {
+        self.title.is_some()
+    }
+
     /// Take damage, return true if still alive.
     pub fn take_damage(&mut self, amount: i32) -> bool {
         self.hp -= amount.max(1);
@@ -6,6 +6,16 @@ use crate::item::Item;
 
 use super::PlayerClass;
 
+/// Default inventory capacity.
+pub const DEFAULT_MAX_INVENTORY: usize = 10;
+
+/// Number of quick slots, bound to keys 1 to 5.
+pub const QUICK_SLOTS: usize = 5;
+
+fn default_max_inventory() -> usize {
+    DEFAULT_MAX_INVENTORY
+}
+
 /// The player character.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
@@ -19,10 +29,23 @@ pub struct Player {
     pub max_focus: i32,
     pub damage: i32,
     pub inventory: Vec<Item>,
+    /// Inventory capacity.
+    #[serde(default = "default_max_inventory")]
+    pub max_inventory: usize,
     pub class: PlayerClass,
     pub level: u32,
     pub xp: u32,
+    /// Braced by Defend until the player's next action.
     pub defending: bool,
+    /// Enemy phases left on a timed guard from Defend.
+    #[serde(default)]
+    pub guard_turns: u32,
+    /// Inventory indices bound to the quick-slot keys.
+    #[serde(default)]
+    pub quick_slots: [Option<usize>; QUICK_SLOTS],
+    /// Steps shaved off the range at which enemies notice you.
+    #[serde(default)]
+    pub stealth: i32,
 }
 
 impl Player {
@@ -34,6 +57,11 @@ impl Player {
             PlayerClass::InboxKnight => (0, 10, 0),
             PlayerClass::Wanderer => (5, 5, 5),
         };
+        // Wanderers are used to slipping through unnoticed
+        let stealth = match class {
+            PlayerClass::Wanderer => 1,
+            _ => 0,
+        };
 
         Self {
             x: 1,
@@ -46,21 +74,37 @@ impl Player {
             max_focus: 50 + focus_bonus,
             damage: 10 + damage_bonus,
             inventory: Vec::new(),
+            max_inventory: DEFAULT_MAX_INVENTORY,
             class,
             level: 1,
             xp: 0,
             defending: false,
+            guard_turns: 0,
+            quick_slots: [None; QUICK_SLOTS],
+            stealth,
         }
     }
 
+    /// Whether incoming damage is halved, from Defend or a timed guard.
+    pub fn is_guarding(&self) -> bool {
+        self.defending || self.guard_turns > 0
+    }
+
     /// Take damage, return true if still alive.
+    ///
+    /// Guarding halves every hit, so one Defend can soak several
+    /// enemies' attacks in the same phase.
     pub fn take_damage(&mut self, amount: i32) -> bool {
-        let actual = if self.defending { amount / 2 } else { amount };
+        let actual = if self.is_guarding() { amount / 2 } else { amount };
         self.hp -= actual.max(1);
-        self.defending = false;
         self.hp > 0
     }
 
+    /// Count down a timed guard at the end of an enemy phase.
+    pub fn tick_guard(&mut self) {
+        self.guard_turns = self.guard_turns.saturating_sub(1);
+    }
+
     /// Heal the player.
     pub fn heal(&mut self, amount: i32) {
         self.hp = (self.hp + amount).min(self.max_hp);
@@ -96,13 +140,83 @@ impl Player {
         }
     }
 
-    /// Pick up an item (max 10).
+    /// Whether the inventory is at capacity.
+    pub fn inventory_full(&self) -> bool {
+        self.inventory.len() >= self.max_inventory
+    }
+
+    /// Pick up an item, return false if the inventory is full.
     pub fn pickup_item(&mut self, item: Item) -> bool {
-        if self.inventory.len() < 10 {
+        if !self.inventory_full() {
             self.inventory.push(item);
             true
         } else {
             false
         }
     }
+
+    /// Remove an item, keeping quick slots on the same items.
+    ///
+    /// A slot bound to the removed item is cleared, and slots bound to
+    /// later items follow them down.
+    pub fn remove_item(&mut self, index: usize) -> Item {
+        let item = self.inventory.remove(index);
+        for slot in &mut self.quick_slots {
+            *slot = match *slot {
+                Some(i) if i == index => None,
+                Some(i) if i > index => Some(i - 1),
+                other => other,
+            };
+        }
+        item
+    }
+
+    /// Bind a quick slot to an inventory item.
+    ///
+    /// An item sits in one slot at a time, so binding it again moves it.
+    /// Returns false if the slot or item doesn't exist.
+    pub fn assign_quick_slot(&mut self, slot: usize, index: usize) -> bool {
+        if slot >= QUICK_SLOTS || index >= self.inventory.len() {
+            return false;
+        }
+        for bound in &mut self.quick_slots {
+            if *bound == Some(index) {
+                *bound = None;
+            }
+        }
+        self.quick_slots[slot] = Some(index);
+        true
+    }
+
+    /// Inventory index bound to a quick slot, if it still holds an item.
+    pub fn quick_slot(&self, slot: usize) -> Option<usize> {
+        self.quick_slots
+            .get(slot)
+            .copied()
+            .flatten()
+            .filter(|&index| index < self.inventory.len())
+    }
+
+    /// Quick slot an inventory item is bound to, if any.
+    pub fn quick_slot_of(&self, index: usize) -> Option<usize> {
+        self.quick_slots.iter().position(|&bound| bound == Some(index))
+    }
+
+    /// Index of the lowest-rarity item, earliest first on ties.
+    pub fn lowest_rarity_index(&self) -> Option<usize> {
+        self.inventory
+            .iter()
+            .enumerate()
+            .min_by_key(|(i, item)| (item.rarity, *i))
+            .map(|(i, _)| i)
+    }
+
+    /// Drop the lowest-rarity item to make room for a new one.
+    /// Returns the dropped item, or None if the inventory is empty.
+    pub fn swap_lowest_rarity(&mut self, item: Item) -> Option<Item> {
+        let index = self.lowest_rarity_index()?;
+        let dropped = self.remove_item(index);
+        self.inventory.push(item);
+        Some(dropped)
+    }
 }
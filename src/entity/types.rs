@@ -14,6 +14,14 @@ pub enum PlayerClass {
 }
 
 impl PlayerClass {
+    /// Every class, in a fixed order.
+    pub const ALL: [PlayerClass; 4] = [
+        PlayerClass::CodeWarrior,
+        PlayerClass::MeetingSurvivor,
+        PlayerClass::InboxKnight,
+        PlayerClass::Wanderer,
+    ];
+
     /// Auto-detect player class from git patterns.
     ///
     /// - CodeWarrior: >100 commits in period
@@ -60,45 +68,100 @@ impl PlayerClass {
 
         PlayerClass::Wanderer
     }
+
+    /// Like `detect`, but only from one author's commits, so in a shared
+    /// repo you play in that person's style. Authors match ignoring ASCII
+    /// case.
+    pub fn detect_for_author(commits: &[CommitData], author: &str) -> Self {
+        let theirs: Vec<CommitData> = commits
+            .iter()
+            .filter(|c| c.author.eq_ignore_ascii_case(author))
+            .cloned()
+            .collect();
+        Self::detect(&theirs)
+    }
 }
 
 /// Enemy type determines behavior and stats.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EnemyType {
     Bug,
     Regression,
     TechDebt,
     MergeConflict,
+    /// Sprawling commits. Steals an item and runs.
+    ScopeCreep,
+    /// Removed code. Frail but fast, and comes in swarms.
+    Deletion,
 }
 
 impl EnemyType {
-    /// Base HP for this enemy type.
+    /// Every enemy type, in a fixed order.
+    pub const ALL: [EnemyType; 6] = [
+        EnemyType::Bug,
+        EnemyType::Regression,
+        EnemyType::TechDebt,
+        EnemyType::MergeConflict,
+        EnemyType::ScopeCreep,
+        EnemyType::Deletion,
+    ];
+
+    /// Built-in base HP for this enemy type. `enemies.toml` may override
+    /// it; see `EnemyConfig`.
     pub fn base_hp(&self) -> i32 {
         match self {
             EnemyType::Bug => 10,
             EnemyType::Regression => 20,
             EnemyType::TechDebt => 30,
             EnemyType::MergeConflict => 50,
+            EnemyType::ScopeCreep => 15,
+            EnemyType::Deletion => 4,
         }
     }
 
-    /// Base damage for this enemy type.
+    /// Built-in base damage for this enemy type.
     pub fn base_damage(&self) -> i32 {
         match self {
             EnemyType::Bug => 3,
             EnemyType::Regression => 5,
             EnemyType::TechDebt => 4,
             EnemyType::MergeConflict => 8,
+            EnemyType::ScopeCreep => 2,
+            EnemyType::Deletion => 1,
+        }
+    }
+
+    /// XP awarded for killing this enemy type, before any multiplier.
+    pub fn xp_reward(&self) -> u32 {
+        match self {
+            EnemyType::Bug => 10,
+            EnemyType::Regression => 20,
+            EnemyType::TechDebt => 30,
+            EnemyType::MergeConflict => 50,
+            EnemyType::ScopeCreep => 25,
+            EnemyType::Deletion => 5,
         }
     }
 
-    /// ASCII symbol for this enemy.
+    /// ASCII symbol for this enemy, as set in `enemies.toml` if it is.
     pub fn symbol(&self) -> char {
+        crate::config::enemy_config().symbol(*self)
+    }
+
+    /// Built-in ASCII symbol for this enemy.
+    pub fn default_symbol(&self) -> char {
         match self {
             EnemyType::Bug => 'B',
             EnemyType::Regression => 'R',
             EnemyType::TechDebt => 'D',
             EnemyType::MergeConflict => 'M',
+            EnemyType::ScopeCreep => 'S',
+            EnemyType::Deletion => '-',
         }
     }
+
+    /// Whether this enemy moves two steps a turn.
+    pub fn is_fast(&self) -> bool {
+        matches!(self, EnemyType::Deletion)
+    }
 }
@@ -0,0 +1,85 @@
+//! Difficulty levels and adaptive difficulty from run history.
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::Enemy;
+
+use super::RunRecord;
+
+/// Finished runs looked at when suggesting a difficulty.
+pub const RECENT_RUNS: usize = 5;
+
+/// Finished runs needed before the suggestion moves off Normal.
+pub const MIN_RUNS_FOR_SUGGESTION: usize = 3;
+
+/// How tough enemies are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// Enemies have three quarters of their HP and damage.
+    Easy,
+    /// Enemies as generated.
+    #[default]
+    Normal,
+    /// Enemies have a quarter more HP and damage.
+    Hard,
+}
+
+impl Difficulty {
+    /// Enemy HP and damage as a percentage of normal.
+    pub fn enemy_percent(&self) -> i32 {
+        match self {
+            Difficulty::Easy => 75,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 125,
+        }
+    }
+
+    /// Scale an enemy stat, rounding to nearest and keeping it at least 1.
+    pub fn scale(&self, value: i32) -> i32 {
+        ((value * self.enemy_percent() + 50) / 100).max(1)
+    }
+
+    /// Scale an enemy's HP and damage.
+    pub fn apply_to(&self, enemy: &mut Enemy) {
+        enemy.hp = self.scale(enemy.hp);
+        enemy.max_hp = self.scale(enemy.max_hp);
+        enemy.damage = self.scale(enemy.damage);
+    }
+
+    /// Display name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+}
+
+/// Suggest a difficulty from the player's recent runs.
+///
+/// Looks at the last `RECENT_RUNS` finished runs, ignoring ones the
+/// player quit. Winning at most one of them eases off to Easy, and
+/// winning all but at most one ramps up to Hard. Anything else, or fewer
+/// than `MIN_RUNS_FOR_SUGGESTION` finished runs, stays Normal.
+pub fn suggested_difficulty(history: &[RunRecord]) -> Difficulty {
+    let recent: Vec<&RunRecord> = history
+        .iter()
+        .rev()
+        .filter(|run| run.completed)
+        .take(RECENT_RUNS)
+        .collect();
+    if recent.len() < MIN_RUNS_FOR_SUGGESTION {
+        return Difficulty::Normal;
+    }
+
+    let wins = recent.iter().filter(|run| run.victory).count();
+    let losses = recent.len() - wins;
+    if wins <= 1 {
+        Difficulty::Easy
+    } else if losses <= 1 {
+        Difficulty::Hard
+    } else {
+        Difficulty::Normal
+    }
+}
@@ -1,10 +1,12 @@
 //! Game state and loop.
 
+mod difficulty;
 mod multiplayer;
 mod progression;
 mod save;
 mod state;
 
+pub use difficulty::*;
 pub use multiplayer::*;
 pub use progression::*;
 pub use save::*;
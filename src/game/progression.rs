@@ -2,21 +2,89 @@
 //!
 //! Persistent unlocks and upgrades that carry over between runs.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
+use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use super::save::save_dir;
 use crate::entity::PlayerClass;
+use crate::rng::GameRng;
 
 /// Progression file path.
 pub fn progression_path() -> PathBuf {
     save_dir().join("progression.json")
 }
 
+/// Essence a run grants: 1 per kill, 5 per room, 20 for victory.
+pub fn essence_for_run(victory: bool, kills: u32, rooms: u32) -> u32 {
+    let mut essence = kills; // 1 per kill
+    essence += rooms * 5; // 5 per room
+    if victory {
+        essence += 20; // Victory bonus
+    }
+    essence
+}
+
+/// Lines changed in a run's history per point of size bonus.
+pub const LINES_PER_ESSENCE: u32 = 1000;
+
+/// Most essence a run earns for the size of its history.
+pub const MAX_LINES_BONUS: u32 = 25;
+
+/// Essence for the size of a finished run's history: 1 per thousand
+/// lines changed, up to `MAX_LINES_BONUS`.
+pub fn lines_bonus(total_lines: u32) -> u32 {
+    (total_lines / LINES_PER_ESSENCE).min(MAX_LINES_BONUS)
+}
+
+/// Extra essence for each room slipped through without a fight.
+pub const PACIFIST_BONUS: u32 = 10;
+
+/// Essence bonus for pacifist rooms.
+pub fn pacifist_bonus(rooms: u32) -> u32 {
+    rooms * PACIFIST_BONUS
+}
+
+/// Extra essence for accusing the right room in a bisect run.
+pub const BISECT_BONUS: u32 = 30;
+
+/// Essence for a run abandoned part way: 5 per room cleared, nothing else.
+pub fn partial_essence(rooms: u32) -> u32 {
+    rooms * 5
+}
+
+/// Names a dungeon for comparing splits: runs from the same seed with the
+/// same number of rooms walk the same rooms in the same order.
+pub fn dungeon_key(seed: u64, rooms: usize) -> String {
+    format!("{}-{}", seed, rooms)
+}
+
+/// A record a finished run can set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Achievement {
+    /// The first victory ever.
+    FirstVictory,
+    /// A victory in fewer turns than any before.
+    FastestVictory,
+    /// More rooms cleared than in any run before.
+    MostRooms,
+}
+
+impl Achievement {
+    /// How the game-over screen names it.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Achievement::FirstVictory => "First victory!",
+            Achievement::FastestVictory => "Fastest victory!",
+            Achievement::MostRooms => "Most rooms cleared!",
+        }
+    }
+}
+
 /// Persistent progression data.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Progression {
@@ -40,6 +108,26 @@ pub struct Progression {
     pub best_rooms: u32,
     /// Fastest victory (fewest turns)
     pub fastest_victory: Option<u32>,
+    /// Fastest victory in real time, in milliseconds
+    #[serde(default)]
+    pub best_time_ms: Option<u64>,
+    /// Fastest time spent in each room across victories, in milliseconds,
+    /// per dungeon as named by [`dungeon_key`]. Older files kept a single
+    /// list under `best_splits_ms` mixing every dungeon; it's ignored.
+    #[serde(default)]
+    pub dungeon_splits_ms: HashMap<String, Vec<u64>>,
+    /// Enemies spawned from and defeated per commit author, across runs
+    #[serde(default)]
+    pub authors: HashMap<String, AuthorTally>,
+}
+
+/// Enemies from one author's commits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct AuthorTally {
+    /// Enemies that came from their commits
+    pub spawned: u32,
+    /// How many of those you defeated
+    pub defeated: u32,
 }
 
 /// Permanent upgrades purchasable with essence.
@@ -55,6 +143,9 @@ pub struct Upgrades {
     pub starting_weapon: u8,
     /// Chance to find better items (0-3, +5% per level)
     pub loot_luck: u8,
+    /// Bonus inventory slots (+2 per level)
+    #[serde(default)]
+    pub inventory_bonus: u8,
 }
 
 impl Upgrades {
@@ -64,6 +155,7 @@ impl Upgrades {
     pub const MAX_DAMAGE: u8 = 3;
     pub const MAX_WEAPON: u8 = 2;
     pub const MAX_LUCK: u8 = 3;
+    pub const MAX_INVENTORY: u8 = 3;
 
     /// Cost for an upgrade at a given level.
     pub fn cost(level: u8) -> u32 {
@@ -96,6 +188,11 @@ impl Upgrades {
     pub fn loot_luck_bonus(&self) -> i32 {
         self.loot_luck as i32 * 5
     }
+
+    /// Total bonus inventory slots from upgrades.
+    pub fn bonus_inventory(&self) -> usize {
+        self.inventory_bonus as usize * 2
+    }
 }
 
 impl Progression {
@@ -106,42 +203,129 @@ impl Progression {
         prog
     }
 
+    /// Record a victory's real time and room splits. Each split keeps its
+    /// own best among victories in the same dungeon, since the rooms of
+    /// another dungeon aren't comparable. Returns true if the total is a
+    /// new personal best.
+    pub fn record_speedrun(&mut self, dungeon: &str, total_ms: u64, splits_ms: &[u64]) -> bool {
+        let best_splits = self.dungeon_splits_ms.entry(dungeon.to_string()).or_default();
+        for (i, &split) in splits_ms.iter().enumerate() {
+            match best_splits.get_mut(i) {
+                Some(best) => *best = (*best).min(split),
+                None => best_splits.push(split),
+            }
+        }
+
+        let personal_best = match self.best_time_ms {
+            Some(best) => total_ms < best,
+            None => true,
+        };
+        if personal_best {
+            self.best_time_ms = Some(total_ms);
+        }
+        personal_best
+    }
+
     /// Award essence and update stats from a completed run.
-    pub fn complete_run(&mut self, victory: bool, kills: u32, rooms: u32, turns: u32) {
+    pub fn complete_run(&mut self, victory: bool, kills: u32, rooms: u32, turns: u32, total_lines: u32) {
         self.total_runs += 1;
         self.total_kills += kills;
         self.total_rooms += rooms;
 
-        // Calculate essence earned
-        let mut essence_earned = kills; // 1 per kill
-        essence_earned += rooms * 5; // 5 per room
+        let essence_earned = essence_for_run(victory, kills, rooms) + lines_bonus(total_lines);
 
+        for achievement in self.achievements_for(victory, rooms, turns) {
+            match achievement {
+                Achievement::FirstVictory | Achievement::FastestVictory => self.fastest_victory = Some(turns),
+                Achievement::MostRooms => self.best_rooms = rooms,
+            }
+        }
         if victory {
             self.victories += 1;
-            essence_earned += 20; // Victory bonus
+        }
+
+        self.essence += essence_earned;
+    }
 
-            // Track fastest victory
+    /// Records a finished run would set, as `complete_run` would update
+    /// them.
+    pub fn achievements_for(&self, victory: bool, rooms: u32, turns: u32) -> Vec<Achievement> {
+        let mut achievements = Vec::new();
+        if victory {
             match self.fastest_victory {
-                Some(best) if turns < best => self.fastest_victory = Some(turns),
-                None => self.fastest_victory = Some(turns),
+                None => achievements.push(Achievement::FirstVictory),
+                Some(best) if turns < best => achievements.push(Achievement::FastestVictory),
                 _ => {}
             }
         }
+        if rooms > self.best_rooms {
+            achievements.push(Achievement::MostRooms);
+        }
+        achievements
+    }
 
+    /// Whether new runs still start in the tutorial: until the first
+    /// victory. Losses and quit runs don't count.
+    pub fn needs_tutorial(&self) -> bool {
+        self.victories == 0
+    }
+
+    /// Award partial essence for a run the player quit.
+    ///
+    /// Quit runs don't count towards total runs or records. Returns the
+    /// essence awarded.
+    pub fn abandon_run(&mut self, rooms: u32) -> u32 {
+        let essence_earned = partial_essence(rooms);
+        self.total_rooms += rooms;
         self.essence += essence_earned;
+        essence_earned
+    }
 
-        // Track best run
-        if rooms > self.best_rooms {
-            self.best_rooms = rooms;
+    /// Add one run's per-author enemy counts to the leaderboard.
+    pub fn record_authors(&mut self, tallies: &HashMap<String, AuthorTally>) {
+        for (author, tally) in tallies {
+            let entry = self.authors.entry(author.clone()).or_default();
+            entry.spawned += tally.spawned;
+            entry.defeated += tally.defeated;
         }
     }
 
+    /// Authors ranked by enemies defeated, then by enemies spawned, then
+    /// by name.
+    pub fn leaderboard(&self) -> Vec<(&str, AuthorTally)> {
+        let mut ranking: Vec<_> = self
+            .authors
+            .iter()
+            .map(|(author, tally)| (author.as_str(), *tally))
+            .collect();
+        ranking.sort_by(|a, b| {
+            b.1.defeated
+                .cmp(&a.1.defeated)
+                .then(b.1.spawned.cmp(&a.1.spawned))
+                .then(a.0.cmp(b.0))
+        });
+        ranking
+    }
+
     /// Check if a class is unlocked.
     pub fn is_class_unlocked(&self, class: &PlayerClass) -> bool {
         let name = format!("{:?}", class);
         self.unlocked_classes.contains(&name)
     }
 
+    /// Roll a class uniformly from the unlocked ones.
+    ///
+    /// The same seed and unlocked set always roll the same class. Falls
+    /// back to Wanderer if nothing is unlocked.
+    pub fn roll_class(&self, seed: u64) -> PlayerClass {
+        let unlocked: Vec<PlayerClass> = PlayerClass::ALL
+            .into_iter()
+            .filter(|class| self.is_class_unlocked(class))
+            .collect();
+        let mut rng = GameRng::seed_from_u64(seed);
+        unlocked.choose(&mut rng).copied().unwrap_or(PlayerClass::Wanderer)
+    }
+
     /// Unlock a class (costs essence).
     pub fn unlock_class(&mut self, class: &PlayerClass, cost: u32) -> bool {
         if self.essence < cost {
@@ -225,6 +409,20 @@ impl Progression {
         self.upgrades.loot_luck += 1;
         true
     }
+
+    /// Purchase an inventory size upgrade.
+    pub fn upgrade_inventory(&mut self) -> bool {
+        if self.upgrades.inventory_bonus >= Upgrades::MAX_INVENTORY {
+            return false;
+        }
+        let cost = Upgrades::cost(self.upgrades.inventory_bonus);
+        if self.essence < cost {
+            return false;
+        }
+        self.essence -= cost;
+        self.upgrades.inventory_bonus += 1;
+        true
+    }
 }
 
 /// Save progression to file.
@@ -254,6 +452,45 @@ pub fn load_progression() -> Result<Progression> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_record_speedrun_tracks_best_time_and_splits() {
+        let mut prog = Progression::new();
+        let dungeon = dungeon_key(42, 3);
+
+        assert!(prog.record_speedrun(&dungeon, 9000, &[4000, 5000]));
+        assert!(!prog.record_speedrun(&dungeon, 9500, &[3000, 6000, 500]));
+        assert_eq!(prog.best_time_ms, Some(9000));
+        assert_eq!(prog.dungeon_splits_ms[&dungeon], vec![3000, 5000, 500]);
+
+        assert!(prog.record_speedrun(&dungeon, 8000, &[4000, 4000]));
+        assert_eq!(prog.best_time_ms, Some(8000));
+        assert_eq!(prog.dungeon_splits_ms[&dungeon], vec![3000, 4000, 500]);
+    }
+
+    #[test]
+    fn test_splits_are_kept_per_dungeon() {
+        let mut prog = Progression::new();
+
+        prog.record_speedrun(&dungeon_key(42, 3), 9000, &[4000, 5000]);
+        prog.record_speedrun(&dungeon_key(7, 3), 9500, &[1000, 8500]);
+        prog.record_speedrun(&dungeon_key(42, 5), 9500, &[2000, 7500]);
+
+        assert_eq!(prog.dungeon_splits_ms[&dungeon_key(42, 3)], vec![4000, 5000]);
+        assert_eq!(prog.dungeon_splits_ms[&dungeon_key(7, 3)], vec![1000, 8500]);
+        assert_eq!(prog.dungeon_splits_ms[&dungeon_key(42, 5)], vec![2000, 7500]);
+    }
+
+    #[test]
+    fn test_old_shared_splits_are_ignored() {
+        let json = serde_json::to_value(Progression::new()).unwrap();
+        let mut json = json.as_object().unwrap().clone();
+        json.remove("dungeon_splits_ms");
+        json.insert("best_splits_ms".to_string(), serde_json::json!([4000, 5000]));
+
+        let prog: Progression = serde_json::from_value(json.into()).unwrap();
+        assert!(prog.dungeon_splits_ms.is_empty());
+    }
+
     #[test]
     fn test_new_progression() {
         let prog = Progression::new();
@@ -265,7 +502,7 @@ mod tests {
     #[test]
     fn test_complete_run_loss() {
         let mut prog = Progression::new();
-        prog.complete_run(false, 10, 3, 50);
+        prog.complete_run(false, 10, 3, 50, 0);
 
         assert_eq!(prog.total_runs, 1);
         assert_eq!(prog.victories, 0);
@@ -278,7 +515,7 @@ mod tests {
     #[test]
     fn test_complete_run_victory() {
         let mut prog = Progression::new();
-        prog.complete_run(true, 20, 5, 100);
+        prog.complete_run(true, 20, 5, 100, 0);
 
         assert_eq!(prog.total_runs, 1);
         assert_eq!(prog.victories, 1);
@@ -287,19 +524,96 @@ mod tests {
         assert_eq!(prog.fastest_victory, Some(100));
     }
 
+    #[test]
+    fn test_bigger_history_earns_more_essence() {
+        let mut small = Progression::new();
+        small.complete_run(true, 10, 5, 100, 800);
+        let mut large = Progression::new();
+        large.complete_run(true, 10, 5, 100, 12_000);
+
+        // 10 kills + 25 rooms + 20 victory, plus 12 for 12k lines
+        assert_eq!(small.essence, 55);
+        assert_eq!(large.essence, 67);
+
+        // Capped so huge histories don't swamp the shop
+        let mut huge = Progression::new();
+        huge.complete_run(true, 10, 5, 100, 5_000_000);
+        assert_eq!(huge.essence, 55 + MAX_LINES_BONUS);
+    }
+
+    #[test]
+    fn test_leaderboard_ranks_by_defeated() {
+        let mut prog = Progression::new();
+        assert!(prog.leaderboard().is_empty());
+
+        let tally = |spawned, defeated| AuthorTally { spawned, defeated };
+        let run: HashMap<String, AuthorTally> = [
+            ("Carol".to_string(), tally(5, 3)),
+            ("Alice".to_string(), tally(4, 3)),
+            ("Bob".to_string(), tally(9, 1)),
+            ("Dave".to_string(), tally(4, 3)),
+        ]
+        .into_iter()
+        .collect();
+        prog.record_authors(&run);
+
+        let names: Vec<&str> = prog.leaderboard().iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, vec!["Carol", "Alice", "Dave", "Bob"]);
+    }
+
+    #[test]
+    fn test_abandon_run_awards_partial_essence() {
+        let mut prog = Progression::new();
+        let earned = prog.abandon_run(2);
+
+        // 2 rooms * 5, no kill or victory bonus
+        assert_eq!(earned, 10);
+        assert_eq!(prog.essence, 10);
+        assert_eq!(prog.total_rooms, 2);
+        assert_eq!(prog.total_runs, 0);
+        assert_eq!(prog.best_rooms, 0);
+    }
+
     #[test]
     fn test_fastest_victory_tracking() {
         let mut prog = Progression::new();
-        prog.complete_run(true, 10, 5, 100);
+        prog.complete_run(true, 10, 5, 100, 0);
         assert_eq!(prog.fastest_victory, Some(100));
 
-        prog.complete_run(true, 10, 5, 80);
+        prog.complete_run(true, 10, 5, 80, 0);
         assert_eq!(prog.fastest_victory, Some(80));
 
-        prog.complete_run(true, 10, 5, 90);
+        prog.complete_run(true, 10, 5, 90, 0);
         assert_eq!(prog.fastest_victory, Some(80)); // Didn't beat best
     }
 
+    #[test]
+    fn test_tutorial_until_first_victory() {
+        let mut prog = Progression::new();
+        assert!(prog.needs_tutorial());
+
+        prog.abandon_run(0);
+        prog.complete_run(false, 3, 2, 50, 0);
+        assert!(prog.needs_tutorial());
+
+        prog.complete_run(true, 3, 2, 50, 0);
+        assert!(!prog.needs_tutorial());
+    }
+
+    #[test]
+    fn test_achievements_match_recorded_records() {
+        let mut prog = Progression::new();
+        assert_eq!(
+            prog.achievements_for(true, 5, 100),
+            vec![Achievement::FirstVictory, Achievement::MostRooms]
+        );
+        prog.complete_run(true, 10, 5, 100, 0);
+
+        assert_eq!(prog.achievements_for(true, 5, 80), vec![Achievement::FastestVictory]);
+        assert_eq!(prog.achievements_for(false, 6, 80), vec![Achievement::MostRooms]);
+        assert!(prog.achievements_for(true, 5, 100).is_empty());
+    }
+
     #[test]
     fn test_upgrade_hp() {
         let mut prog = Progression::new();
@@ -365,6 +679,18 @@ mod tests {
         assert_eq!(prog.upgrades.loot_luck_bonus(), 5);
     }
 
+    #[test]
+    fn test_upgrade_inventory() {
+        let mut prog = Progression::new();
+        prog.essence = 1000;
+
+        assert!(prog.upgrade_inventory());
+        assert_eq!(prog.upgrades.bonus_inventory(), 2);
+
+        prog.upgrades.inventory_bonus = Upgrades::MAX_INVENTORY;
+        assert!(!prog.upgrade_inventory());
+    }
+
     #[test]
     fn test_unlock_class() {
         let mut prog = Progression::new();
@@ -384,13 +710,13 @@ mod tests {
     #[test]
     fn test_best_rooms_tracking() {
         let mut prog = Progression::new();
-        prog.complete_run(false, 5, 3, 50);
+        prog.complete_run(false, 5, 3, 50, 0);
         assert_eq!(prog.best_rooms, 3);
 
-        prog.complete_run(false, 5, 5, 50);
+        prog.complete_run(false, 5, 5, 50, 0);
         assert_eq!(prog.best_rooms, 5);
 
-        prog.complete_run(false, 5, 4, 50);
+        prog.complete_run(false, 5, 4, 50, 0);
         assert_eq!(prog.best_rooms, 5); // Didn't beat best
     }
 
@@ -403,4 +729,34 @@ mod tests {
         assert_eq!(Upgrades::cost(4), 200);
         assert_eq!(Upgrades::cost(5), 500);
     }
+
+    #[test]
+    fn test_roll_class_is_seeded() {
+        let mut prog = Progression::new();
+        prog.unlocked_classes.insert("InboxKnight".to_string());
+        prog.unlocked_classes.insert("Wanderer".to_string());
+
+        // Pinned so a change to how classes are rolled shows up here.
+        assert_eq!(prog.roll_class(0), PlayerClass::Wanderer);
+        assert_eq!(prog.roll_class(1), PlayerClass::InboxKnight);
+        assert_eq!(prog.roll_class(2), PlayerClass::CodeWarrior);
+        assert_eq!(prog.roll_class(1), PlayerClass::InboxKnight);
+    }
+
+    #[test]
+    fn test_roll_class_only_picks_unlocked() {
+        let mut prog = Progression::new();
+        prog.unlocked_classes.insert("Wanderer".to_string());
+
+        for seed in 0..20 {
+            let class = prog.roll_class(seed);
+            assert!(prog.is_class_unlocked(&class), "rolled locked class {:?}", class);
+        }
+    }
+
+    #[test]
+    fn test_roll_class_without_unlocks() {
+        let prog = Progression::default();
+        assert_eq!(prog.roll_class(7), PlayerClass::Wanderer);
+    }
 }
@@ -3,11 +3,11 @@
 use std::fs;
 use std::path::PathBuf;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::GameState;
+use super::{GameState, VictoryCondition};
 
 /// Record of a completed run.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +20,42 @@ pub struct RunRecord {
     pub enemies_killed: usize,
     pub final_level: u32,
     pub death_cause: Option<String>,
+    /// Victory condition the run was played under.
+    #[serde(default)]
+    pub goal: VictoryCondition,
+    /// False if the player quit before the run ended.
+    #[serde(default = "default_completed")]
+    pub completed: bool,
+    /// Lines changed across the commits the dungeon was built from.
+    #[serde(default)]
+    pub total_lines: u32,
+    /// Rooms survived, for runs in endless mode.
+    #[serde(default)]
+    pub endless_score: Option<u32>,
+}
+
+fn default_completed() -> bool {
+    true
+}
+
+impl RunRecord {
+    /// Build a record from a game, finished or quit.
+    pub fn from_state(state: &GameState) -> Self {
+        Self {
+            started_at: state.started_at,
+            ended_at: Utc::now(),
+            victory: state.victory,
+            turns: state.turn,
+            rooms_cleared: state.rooms_cleared(),
+            enemies_killed: state.enemies_killed as usize,
+            final_level: state.player.level,
+            death_cause: state.killed_by.clone().filter(|_| state.game_over && !state.victory),
+            goal: state.goal,
+            completed: state.game_over,
+            total_lines: state.total_lines,
+            endless_score: state.endless_score(),
+        }
+    }
 }
 
 /// Get the save directory path.
@@ -48,37 +84,110 @@ fn ensure_save_dir() -> Result<()> {
     Ok(())
 }
 
-/// Save game state to file.
+/// Slot used when none is given. It keeps the original `save.json`.
+pub const DEFAULT_SLOT: &str = "default";
+
+/// Get the save file path for a named slot.
+pub fn slot_path(slot: &str) -> PathBuf {
+    if slot == DEFAULT_SLOT {
+        save_path()
+    } else {
+        save_dir().join(format!("save_{}.json", slot))
+    }
+}
+
+/// Check that a slot name is safe to use as part of a file name.
+pub fn check_slot(slot: &str) -> Result<()> {
+    let valid = !slot.is_empty()
+        && slot.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        bail!("Invalid save slot '{}' (use letters, digits, '-' and '_')", slot);
+    }
+    Ok(())
+}
+
+/// Save game state to the default slot.
 pub fn save_game(state: &GameState) -> Result<()> {
+    save_game_to(state, DEFAULT_SLOT)
+}
+
+/// Save game state to a named slot.
+pub fn save_game_to(state: &GameState, slot: &str) -> Result<()> {
+    check_slot(slot)?;
     ensure_save_dir()?;
-    let path = save_path();
+    let path = slot_path(slot);
     let json = serde_json::to_string_pretty(state).context("Failed to serialize game state")?;
     fs::write(&path, json).context("Failed to write save file")?;
     Ok(())
 }
 
-/// Load game state from file.
+/// Load game state from the default slot.
 pub fn load_game() -> Result<GameState> {
-    let path = save_path();
+    load_game_from(DEFAULT_SLOT)
+}
+
+/// Load game state from a named slot.
+pub fn load_game_from(slot: &str) -> Result<GameState> {
+    check_slot(slot)?;
+    let path = slot_path(slot);
     let json = fs::read_to_string(&path).context("Failed to read save file")?;
-    let state: GameState = serde_json::from_str(&json).context("Failed to parse save file")?;
+    let mut state: GameState = serde_json::from_str(&json).context("Failed to parse save file")?;
+    // Visible tiles aren't saved, and enemies only notice a player they
+    // can see. Rebuild them so the next turn plays out as it would have.
+    state.update_fov();
     Ok(state)
 }
 
-/// Check if a save file exists.
+/// Check if a save file exists in the default slot.
 pub fn save_exists() -> bool {
-    save_path().exists()
+    slot_exists(DEFAULT_SLOT)
+}
+
+/// Check if a save file exists in a named slot.
+pub fn slot_exists(slot: &str) -> bool {
+    check_slot(slot).is_ok() && slot_path(slot).exists()
 }
 
-/// Delete save file.
+/// Delete the default slot's save file.
 pub fn delete_save() -> Result<()> {
-    let path = save_path();
+    delete_slot(DEFAULT_SLOT)
+}
+
+/// Delete a named slot's save file.
+pub fn delete_slot(slot: &str) -> Result<()> {
+    check_slot(slot)?;
+    let path = slot_path(slot);
     if path.exists() {
         fs::remove_file(&path).context("Failed to delete save file")?;
     }
     Ok(())
 }
 
+/// Names of all slots with a save file, sorted.
+pub fn list_saves() -> Result<Vec<String>> {
+    let dir = save_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut slots = Vec::new();
+    for entry in fs::read_dir(&dir).context("Failed to read save directory")? {
+        let name = entry.context("Failed to read save directory")?.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        if name == "save.json" {
+            slots.push(DEFAULT_SLOT.to_string());
+        } else if let Some(slot) = name.strip_prefix("save_").and_then(|n| n.strip_suffix(".json")) {
+            if check_slot(slot).is_ok() {
+                slots.push(slot.to_string());
+            }
+        }
+    }
+    slots.sort();
+    Ok(slots)
+}
+
 /// Save a run to history.
 pub fn save_run_history(record: RunRecord) -> Result<()> {
     ensure_save_dir()?;
@@ -1,35 +1,235 @@
 //! Game state management.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 use chrono::{DateTime, Utc};
 use rand::prelude::*;
-use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 
-use crate::combat::{enemy_attack, player_attack, EnemyAction, PlayerAction, WAIT_REGEN};
-use crate::entity::{Enemy, Player, PlayerClass};
+use super::{essence_for_run, lines_bonus, pacifist_bonus, Achievement, AuthorTally, Difficulty, Progression, BISECT_BONUS};
+use crate::combat::{decide_action, detection_radius, enemy_attack_with_variance, player_attack_with_variance, CombatResult, EnemyAction, EnergyCosts, PlayerAction, SANCTUARY_REGEN, WAIT_REGEN};
+use crate::entity::{Enemy, EnemyType, Player, PlayerClass};
 use crate::fov::calculate_fov;
 use crate::git::CommitData;
-use crate::world::{generate_dungeon, Tile, World};
+use crate::item::{HealMode, Item, ItemEffect, ItemType, Rarity};
+use crate::rng::GameRng;
+use crate::world::{
+    ensure_exit_reachable, generate_dungeon_with_options, generate_room, room_seed,
+    AmbienceCue, GenerationOptions, Room, Tile, World,
+};
 
 /// Events that occur during gameplay.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum GameEvent {
     PlayerMoved { x: i32, y: i32 },
     PlayerAttacked { damage: i32, killed: bool },
     PlayerDefending,
     PlayerUsedItem { name: String },
+    ItemPickedUp { name: String },
     PlayerLevelUp { level: u32 },
     EnemyAttacked { damage: i32, enemy_type: String },
+    EnemyHealed { amount: i32, enemy_type: String },
     EnemyKilled { enemy_type: String, xp: u32 },
     RoomEntered { room_id: usize },
+    /// Sent with every `RoomEntered`, for integrations that play music.
+    AmbienceCue { cue: AmbienceCue },
     RoomCleared { room_id: usize },
     GameOver { victory: bool },
     Message(String),
 }
 
+/// Condense one turn's events into a single line, e.g.
+/// "You hit for 12; Bug missed; Regression healed 2".
+pub fn turn_summary(events: &[GameEvent]) -> String {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            GameEvent::PlayerAttacked { damage: 0, .. } => Some("You missed".to_string()),
+            GameEvent::PlayerAttacked { damage, .. } => Some(format!("You hit for {}", damage)),
+            GameEvent::PlayerDefending => Some("You defend".to_string()),
+            GameEvent::PlayerUsedItem { name } => Some(format!("You use {}", name)),
+            GameEvent::ItemPickedUp { name } => Some(format!("You pick up {}", name)),
+            GameEvent::PlayerLevelUp { level } => Some(format!("Level {}!", level)),
+            GameEvent::EnemyAttacked { damage: 0, enemy_type } => Some(format!("{} missed", enemy_type)),
+            GameEvent::EnemyAttacked { damage, enemy_type } => Some(format!("{} hit you for {}", enemy_type, damage)),
+            GameEvent::EnemyHealed { amount, enemy_type } => Some(format!("{} healed {}", enemy_type, amount)),
+            GameEvent::EnemyKilled { enemy_type, xp } => Some(format!("{} destroyed (+{} XP)", enemy_type, xp)),
+            GameEvent::RoomEntered { .. } => Some("New room".to_string()),
+            GameEvent::RoomCleared { .. } => Some("Room cleared".to_string()),
+            GameEvent::GameOver { victory: true } => Some("Victory!".to_string()),
+            GameEvent::GameOver { victory: false } => Some("Defeated".to_string()),
+            GameEvent::PlayerMoved { .. } | GameEvent::AmbienceCue { .. } | GameEvent::Message(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Where the player is in the tutorial room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TutorialStep {
+    Move,
+    Attack,
+    PickUp,
+    Leave,
+}
+
+impl TutorialStep {
+    /// Instructions shown while this step is in progress.
+    pub fn prompt(self) -> &'static str {
+        match self {
+            TutorialStep::Move => "Tutorial: move with the arrow keys or hjkl.",
+            TutorialStep::Attack => "Tutorial: a Bug blocks the gap. Press a, then a direction, to attack it.",
+            TutorialStep::PickUp => "Tutorial: walk onto the ! to pick it up. Press i to see your inventory.",
+            TutorialStep::Leave => "Tutorial: step onto the exit (>) to enter the real dungeon.",
+        }
+    }
+
+    /// Whether this event finishes the step.
+    pub fn completed_by(self, event: &GameEvent) -> bool {
+        matches!(
+            (self, event),
+            (TutorialStep::Move, GameEvent::PlayerMoved { .. })
+                | (TutorialStep::Attack, GameEvent::EnemyKilled { .. })
+                | (TutorialStep::PickUp, GameEvent::ItemPickedUp { .. })
+                | (TutorialStep::Leave, GameEvent::RoomEntered { .. })
+        )
+    }
+
+    /// The step after this one, or `None` once the tutorial is over.
+    pub fn next(self) -> Option<TutorialStep> {
+        match self {
+            TutorialStep::Move => Some(TutorialStep::Attack),
+            TutorialStep::Attack => Some(TutorialStep::PickUp),
+            TutorialStep::PickUp => Some(TutorialStep::Leave),
+            TutorialStep::Leave => None,
+        }
+    }
+}
+
+/// What the player must do to win a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VictoryCondition {
+    /// Walk out of the last room's exit.
+    #[default]
+    ReachEnd,
+    /// Clear this many rooms.
+    ClearRooms(u32),
+    /// Stay alive for this many turns.
+    Survive(u32),
+    /// Keep going: the last room always leads on to a new, tougher one.
+    /// Scored by rooms survived.
+    Endless,
+}
+
+impl std::str::FromStr for VictoryCondition {
+    type Err = String;
+
+    /// Parse `end`, `endless`, `rooms:N` or `turns:N`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        if s == "end" {
+            return Ok(VictoryCondition::ReachEnd);
+        }
+        if s == "endless" {
+            return Ok(VictoryCondition::Endless);
+        }
+
+        let (kind, count) = s
+            .split_once(':')
+            .ok_or_else(|| format!("invalid goal '{}' (expected end, endless, rooms:N or turns:N)", s))?;
+        let count: u32 = count
+            .parse()
+            .map_err(|_| format!("invalid goal count '{}'", count))?;
+        if count == 0 {
+            return Err("goal count must be at least 1".to_string());
+        }
+
+        match kind {
+            "rooms" => Ok(VictoryCondition::ClearRooms(count)),
+            "turns" => Ok(VictoryCondition::Survive(count)),
+            _ => Err(format!("unknown goal '{}' (expected end, rooms or turns)", kind)),
+        }
+    }
+}
+
+impl std::fmt::Display for VictoryCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VictoryCondition::ReachEnd => write!(f, "Reach the end"),
+            VictoryCondition::ClearRooms(n) => write!(f, "Clear {} rooms", n),
+            VictoryCondition::Survive(n) => write!(f, "Survive {} turns", n),
+            VictoryCondition::Endless => write!(f, "Survive endlessly"),
+        }
+    }
+}
+
+/// Extra enemy HP and damage, in percent, for each lap of endless mode
+/// through the history.
+pub const ENDLESS_LAP_PERCENT: i32 = 25;
+
+/// HP restored by the safe room-clear reward.
+pub const CLEAR_REWARD_HEAL: i32 = 15;
+
+/// Chance that a room-clear chest is a mimic.
+pub const MIMIC_CHANCE: f64 = 0.35;
+
+/// What a room-clear chest turned out to hold.
+#[derive(Debug, Clone)]
+pub enum ChestOutcome {
+    /// A monster that attacks when opened.
+    Mimic,
+    /// A rare item.
+    Treasure(Item),
+}
+
+/// Open a room-clear chest.
+pub fn roll_chest(rng: &mut GameRng) -> ChestOutcome {
+    if rng.gen_bool(MIMIC_CHANCE) {
+        return ChestOutcome::Mimic;
+    }
+    let (name, item_type, effect) = match rng.gen_range(0..3) {
+        0 => ("Golden Patch", ItemType::Consumable, ItemEffect::Heal(35)),
+        1 => ("Cached Build", ItemType::Scroll, ItemEffect::RestoreEnergy(60)),
+        _ => ("Panic Button", ItemType::Consumable, ItemEffect::Blink),
+    };
+    ChestOutcome::Treasure(Item::new(name, item_type, effect, Rarity::Rare))
+}
+
+/// Combat counters for the game-over recap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunStats {
+    /// Damage the player's attacks dealt, overkill included.
+    pub damage_dealt: u32,
+    /// HP lost to enemy attacks.
+    pub damage_taken: u32,
+    /// Attacks the player made at an enemy.
+    pub attacks: u32,
+    /// Of those, attacks that hit.
+    pub hits: u32,
+    /// Items used from the inventory.
+    pub items_used: u32,
+}
+
+/// Speedrun clock readings kept in saves, so a continued run picks up its
+/// time and splits where it stopped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeedrunProgress {
+    /// Real time played so far, in milliseconds.
+    pub elapsed_ms: u64,
+    /// Each room's index and the time into the run, in milliseconds, at
+    /// which it was first cleared, in the order they were cleared.
+    pub clears_ms: Vec<(usize, u64)>,
+}
+
+impl RunStats {
+    /// Share of attacks that hit, as a whole percentage, or None before
+    /// the first attack.
+    pub fn accuracy(&self) -> Option<u32> {
+        (self.attacks > 0).then(|| self.hits * 100 / self.attacks)
+    }
+}
+
 /// Complete game state.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameState {
@@ -42,14 +242,154 @@ pub struct GameState {
     pub messages: Vec<String>,
     pub game_over: bool,
     pub victory: bool,
+    /// Run seed. Each turn's combat and enemy rolls come from an RNG seeded
+    /// with `seed + turn`, so a loaded save replays exactly the rolls it
+    /// would have had without storing any RNG state.
     pub seed: u64,
     /// Path to the git repository used to generate this dungeon.
     pub git_path: PathBuf,
     /// When this game was started.
     pub started_at: DateTime<Utc>,
+    /// Set when the player steps onto an item with a full inventory.
+    #[serde(skip)]
+    pub pending_pickup: bool,
+    /// Condition for winning this run.
+    #[serde(default)]
+    pub goal: VictoryCondition,
+    /// Dungeon rooms generated from history before endless mode began
+    /// adding more. Zero outside endless mode.
+    #[serde(default)]
+    pub endless_base: usize,
+    /// Lines changed across the commits the dungeon was built from.
+    #[serde(default)]
+    pub total_lines: u32,
+    /// Enemies slain this run.
+    #[serde(default)]
+    pub enemies_killed: u32,
+    /// Enemies slain this run, by the author of their commit.
+    #[serde(default)]
+    pub author_kills: HashMap<String, u32>,
+    /// Combat counters for this run.
+    #[serde(default)]
+    pub stats: RunStats,
+    /// Whether death ends the run. When off, the player revives.
+    #[serde(default = "default_permadeath")]
+    pub permadeath: bool,
+    /// Revives used this run (casual mode only).
+    #[serde(default)]
+    pub revives: u32,
+    /// What ended a lost run, e.g. the type of the enemy that landed the
+    /// final blow.
+    #[serde(default)]
+    pub killed_by: Option<String>,
+    /// Turns added by revive penalties. They advance `turn` but don't
+    /// count towards surviving a `turns:N` goal.
+    #[serde(default)]
+    pub penalty_turns: u32,
+    /// Furthest room index the player has reached.
+    #[serde(default)]
+    pub furthest_room: usize,
+    /// Rooms passed over with `--skip-rooms`. They count as cleared but
+    /// earn nothing.
+    #[serde(default)]
+    pub skipped_rooms: usize,
+    /// Whether cleared rooms get a weak enemy back on revisit.
+    #[serde(default)]
+    pub repopulate_on_revisit: bool,
+    /// Energy spent per action.
+    #[serde(default)]
+    pub energy_costs: EnergyCosts,
+    /// Whether rooms can be left past enemies that haven't noticed you.
+    #[serde(default)]
+    pub pacifist_exits: bool,
+    /// How heal item amounts are read.
+    #[serde(default)]
+    pub heal_mode: HealMode,
+    /// Enemy phases a Defend guards for. Zero guards until the player's
+    /// next action instead.
+    #[serde(default)]
+    pub guard_turns: u32,
+    /// How tough enemies are, applied when the run starts.
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// Offer a reward choice when a non-boss room is cleared.
+    #[serde(default)]
+    pub clear_rewards: bool,
+    /// Rooms that have already offered their reward.
+    #[serde(default)]
+    pub rewarded_rooms: HashSet<usize>,
+    /// How far hits may stray from their usual damage, in percent either
+    /// way. Zero keeps damage fixed.
+    #[serde(default)]
+    pub damage_variance: u32,
+    /// Scale applied to XP from kills. 1.0 keeps the usual rewards.
+    #[serde(default = "default_xp_multiplier")]
+    pub xp_multiplier: f32,
+    /// Enemy turns without attacks granted on entering a room.
+    #[serde(default = "default_entry_grace_turns")]
+    pub entry_grace_turns: u32,
+    /// Enemy turns left in which enemies may move but not attack.
+    #[serde(default)]
+    pub entry_grace: u32,
+    /// Whether this is a bisect run with one bug-introducing room hidden in it.
+    #[serde(default)]
+    pub bisect: bool,
+    /// Index of the room whose commit introduced the bug (bisect runs only).
+    #[serde(default)]
+    pub bad_room: usize,
+    /// Whether the bad room has been correctly accused.
+    #[serde(default)]
+    pub bisected: bool,
+    /// Current tutorial step, while the player is in the tutorial room.
+    #[serde(default)]
+    pub tutorial: Option<TutorialStep>,
+    /// Whether debug commands are enabled. Debug runs are kept out of
+    /// run history and progression.
+    #[serde(default)]
+    pub debug: bool,
+    /// Debug: enemy hits and wrong accusations deal no damage.
+    #[serde(default)]
+    pub god_mode: bool,
+    /// Debug: the whole current room is in view.
+    #[serde(default)]
+    pub reveal_map: bool,
+    /// Speedrun clock as of the last save.
+    #[serde(default)]
+    pub speedrun: SpeedrunProgress,
+    /// Direction of the last wall bump, so repeats don't spam the log.
+    #[serde(skip)]
+    last_bump: Option<(i32, i32)>,
+}
+
+fn default_permadeath() -> bool {
+    true
+}
+
+fn default_xp_multiplier() -> f32 {
+    1.0
+}
+
+fn default_entry_grace_turns() -> u32 {
+    1
+}
+
+/// Apply an XP multiplier, rounding to the nearest point. Negative or
+/// non-finite multipliers award nothing.
+pub fn scale_xp(xp: u32, multiplier: f32) -> u32 {
+    if !multiplier.is_finite() || multiplier <= 0.0 {
+        return 0;
+    }
+    (xp as f32 * multiplier).round() as u32
 }
 
 impl GameState {
+    /// Revives allowed per run with permadeath off.
+    pub const MAX_REVIVES: u32 = 3;
+    /// Turns lost each time the player revives.
+    pub const REVIVE_TURN_PENALTY: u32 = 10;
+    /// HP lost for accusing the wrong room in a bisect run.
+    pub const WRONG_ACCUSATION_DAMAGE: i32 = 5;
+
     /// Create a new game from git data.
     pub fn new(git_data: Vec<CommitData>, seed: u64, git_path: PathBuf) -> Self {
         Self::new_with_class(git_data, seed, None, git_path)
@@ -63,7 +403,25 @@ impl GameState {
         class: Option<PlayerClass>,
         git_path: PathBuf,
     ) -> Self {
-        let world = generate_dungeon(&git_data, seed);
+        Self::new_with_options(git_data, seed, class, git_path, &GenerationOptions::default())
+    }
+
+    /// Create a new game with an optional player class and generation options.
+    pub fn new_with_options(
+        git_data: Vec<CommitData>,
+        seed: u64,
+        class: Option<PlayerClass>,
+        git_path: PathBuf,
+        options: &GenerationOptions,
+    ) -> Self {
+        let world = generate_dungeon_with_options(&git_data, seed, options);
+        // Only commits that made it into a room count; days dropped by
+        // --max-rooms or --top-days add nothing
+        let total_lines = world
+            .rooms
+            .iter()
+            .flat_map(|room| &room.source_commits)
+            .fold(0u32, |total, c| total.saturating_add(c.lines_changed()));
         // Auto-detect class from git patterns if not specified
         let player_class = class.unwrap_or_else(|| PlayerClass::detect(&git_data));
         let player = Player::new(player_class);
@@ -79,6 +437,40 @@ impl GameState {
             seed,
             git_path,
             started_at: Utc::now(),
+            pending_pickup: false,
+            goal: VictoryCondition::default(),
+            endless_base: 0,
+            total_lines,
+            enemies_killed: 0,
+            author_kills: HashMap::new(),
+            stats: RunStats::default(),
+            permadeath: true,
+            revives: 0,
+            killed_by: None,
+            penalty_turns: 0,
+            furthest_room: 0,
+            skipped_rooms: 0,
+            repopulate_on_revisit: false,
+            energy_costs: EnergyCosts::default(),
+            pacifist_exits: false,
+            heal_mode: HealMode::default(),
+            guard_turns: 0,
+            difficulty: Difficulty::Normal,
+            clear_rewards: false,
+            rewarded_rooms: HashSet::new(),
+            damage_variance: 0,
+            xp_multiplier: default_xp_multiplier(),
+            entry_grace_turns: default_entry_grace_turns(),
+            entry_grace: 0,
+            debug: false,
+            god_mode: false,
+            reveal_map: false,
+            speedrun: SpeedrunProgress::default(),
+            bisect: false,
+            bad_room: 0,
+            bisected: false,
+            tutorial: None,
+            last_bump: None,
         };
 
         // Position player at entrance of first room
@@ -87,8 +479,22 @@ impl GameState {
             state.player.y = room.height as i32 / 2;
         }
 
+        if options.bisect {
+            state.start_bisect();
+        }
+
         state.update_fov();
         state.log(format!("You enter as a {:?}...", player_class));
+        if options.skip_rooms > 0 {
+            state.skip_rooms(options.skip_rooms);
+        }
+        if state.world.current_room < state.world.first_dungeon_room() {
+            state.tutorial = Some(TutorialStep::Move);
+            state.log(TutorialStep::Move.prompt());
+        }
+        state.announce_room_theme();
+        state.announce_featured_room();
+        state.announce_boss();
         state
     }
 
@@ -114,6 +520,40 @@ impl GameState {
             seed,
             git_path: source_path,
             started_at: Utc::now(),
+            pending_pickup: false,
+            goal: VictoryCondition::default(),
+            endless_base: 0,
+            total_lines: 0,
+            enemies_killed: 0,
+            author_kills: HashMap::new(),
+            stats: RunStats::default(),
+            permadeath: true,
+            revives: 0,
+            killed_by: None,
+            penalty_turns: 0,
+            furthest_room: 0,
+            skipped_rooms: 0,
+            repopulate_on_revisit: false,
+            energy_costs: EnergyCosts::default(),
+            pacifist_exits: false,
+            heal_mode: HealMode::default(),
+            guard_turns: 0,
+            difficulty: Difficulty::Normal,
+            clear_rewards: false,
+            rewarded_rooms: HashSet::new(),
+            damage_variance: 0,
+            xp_multiplier: default_xp_multiplier(),
+            entry_grace_turns: default_entry_grace_turns(),
+            entry_grace: 0,
+            debug: false,
+            god_mode: false,
+            reveal_map: false,
+            speedrun: SpeedrunProgress::default(),
+            bisect: false,
+            bad_room: 0,
+            bisected: false,
+            tutorial: None,
+            last_bump: None,
         };
 
         // Position player at entrance of first room
@@ -135,8 +575,16 @@ impl GameState {
 
         let mut events = Vec::new();
 
+        // Last turn's fallen enemies are gone for good
+        if let Some(room) = self.world.current_mut() {
+            room.dying.clear();
+        }
+
+        // A stance from Defend only lasts until the next action
+        self.player.defending = false;
+
         // Check energy cost
-        let cost = action.energy_cost();
+        let cost = action.energy_cost_with(&self.energy_costs);
         if cost > 0 && !self.player.use_energy(cost) {
             self.log("Not enough energy!");
             return events;
@@ -155,22 +603,42 @@ impl GameState {
                     room.get_enemy_at(new_x, new_y).is_some()
                 });
 
+                let shortcut = self.world.current().and_then(|room| {
+                    room.shortcut.filter(|_| room.shortcut_door() == (new_x, new_y))
+                });
+
                 if blocked_by_enemy {
                     self.log("An enemy blocks the way!");
                     self.player.regen_energy(cost);
+                } else if let Some(to) = shortcut {
+                    if self.take_shortcut(to) {
+                        self.push_room_entered(&mut events);
+                    } else {
+                        self.player.regen_energy(cost);
+                    }
                 } else if can_move {
+                    self.last_bump = None;
                     self.player.x = new_x;
                     self.player.y = new_y;
+                    if let Some(room) = self.world.current_mut() {
+                        room.visit_merge_branch((new_x, new_y));
+                    }
                     events.push(GameEvent::PlayerMoved { x: new_x, y: new_y });
                     self.update_fov();
+                    if let Some(name) = self.pickup_at_player() {
+                        events.push(GameEvent::ItemPickedUp { name });
+                    }
 
-                    if self.check_room_exit() {
-                        events.push(GameEvent::RoomEntered {
-                            room_id: self.world.current_room,
-                        });
+                    if self.check_room_exit() || self.check_room_entrance() {
+                        self.push_room_entered(&mut events);
                     }
                 } else {
                     self.player.regen_energy(cost);
+                    // Only the first bump in a direction is worth a message
+                    if self.last_bump != Some((dx, dy)) {
+                        self.last_bump = Some((dx, dy));
+                        self.log("You bump into a wall.");
+                    }
                 }
             }
 
@@ -184,13 +652,23 @@ impl GameState {
                 });
 
                 if let Some(idx) = enemy_idx {
-                    let mut rng = ChaCha8Rng::seed_from_u64(self.seed + self.turn as u64);
+                    let mut rng = GameRng::seed_from_u64(self.seed + self.turn as u64);
+                    if let Some(room) = self.world.current_mut() {
+                        room.fought = true;
+                        room.enemies[idx].aggro = true;
+                    }
                     
                     let result = {
                         let room = self.world.current_mut().unwrap();
-                        player_attack(&self.player, &mut room.enemies[idx], &mut rng)
+                        player_attack_with_variance(&self.player, &mut room.enemies[idx], &mut rng, self.damage_variance)
                     };
 
+                    self.stats.attacks += 1;
+                    if result.hit {
+                        self.stats.hits += 1;
+                        self.stats.damage_dealt += result.damage.max(0) as u32;
+                    }
+
                     self.log(&result.message);
                     events.push(GameEvent::PlayerAttacked {
                         damage: result.damage,
@@ -199,13 +677,20 @@ impl GameState {
 
                     if result.killed {
                         let room = self.world.current_mut().unwrap();
-                        let enemy = room.enemies.remove(idx);
-                        let xp = match enemy.enemy_type {
-                            crate::entity::EnemyType::Bug => 10,
-                            crate::entity::EnemyType::Regression => 20,
-                            crate::entity::EnemyType::TechDebt => 30,
-                            crate::entity::EnemyType::MergeConflict => 50,
-                        };
+                        let mut enemy = room.enemies.remove(idx);
+                        self.enemies_killed += 1;
+                        if let Some(author) = room.commit_author(&enemy.source_commit) {
+                            *self.author_kills.entry(author.to_string()).or_default() += 1;
+                        }
+                        let mut xp = enemy.enemy_type.xp_reward();
+                        self.drop_stolen_item(&mut enemy);
+
+                        // Mini-bosses give triple XP and drop loot from their commit
+                        if enemy.is_mini_boss() {
+                            xp *= 3;
+                            self.drop_mini_boss_loot(&enemy, &mut rng);
+                        }
+                        let xp = scale_xp(xp, self.xp_multiplier);
 
                         if self.player.add_xp(xp) {
                             events.push(GameEvent::PlayerLevelUp {
@@ -219,6 +704,12 @@ impl GameState {
                             xp,
                         });
 
+                        let room = self.world.current_mut().unwrap();
+                        room.dying.push(enemy);
+                        if room.lift_conflict_ward() {
+                            self.log("The conflict is resolved. The boss is exposed!");
+                        }
+
                         let room = self.world.current_mut().unwrap();
                         if room.enemies.is_empty() {
                             room.cleared = true;
@@ -233,15 +724,20 @@ impl GameState {
             }
 
             PlayerAction::Defend => {
-                self.player.defending = true;
+                if self.guard_turns > 0 {
+                    self.player.guard_turns = self.guard_turns;
+                } else {
+                    self.player.defending = true;
+                }
                 events.push(GameEvent::PlayerDefending);
                 self.log("You take a defensive stance.");
             }
 
             PlayerAction::UseItem(index) => {
                 if index < self.player.inventory.len() {
-                    let item = self.player.inventory.remove(index);
-                    let msg = crate::item::apply_effect(&item.effect, &mut self.player);
+                    let item = self.player.remove_item(index);
+                    self.stats.items_used += 1;
+                    let msg = crate::item::apply_effect_in_context(&item.effect, self);
                     self.log(&msg);
                     events.push(GameEvent::PlayerUsedItem { name: item.name });
                 } else {
@@ -257,13 +753,13 @@ impl GameState {
 
         self.turn += 1;
         
-        // Sanctuary rooms regenerate energy
-        let in_sanctuary = self.world.current().is_some_and(|room| {
-            room.room_type == crate::world::RoomType::Sanctuary
-        });
-        if in_sanctuary {
-            self.player.regen_energy(5);
+        self.player.regen_energy(self.energy_regen_here());
+
+        if self.check_goal() {
+            events.push(GameEvent::GameOver { victory: true });
         }
+
+        self.advance_tutorial(&events);
         
         events
     }
@@ -274,8 +770,12 @@ impl GameState {
             return vec![];
         }
 
+        // Just through the door: enemies close in but hold their attacks
+        let grace = self.entry_grace > 0;
+        self.entry_grace = self.entry_grace.saturating_sub(1);
+
         let mut events = Vec::new();
-        let mut rng = ChaCha8Rng::seed_from_u64(self.seed + self.turn as u64);
+        let mut rng = GameRng::seed_from_u64(self.seed + self.turn as u64);
 
         // Get enemy count first
         let enemy_count = self.world.current().map_or(0, |r| r.enemies.len());
@@ -288,10 +788,10 @@ impl GameState {
             }
 
             // Get enemy data for AI decision
-            let (enemy_x, enemy_y, enemy_type, enemy_hp, enemy_max_hp, enemy_damage, turns_alive) = {
+            let (enemy_x, enemy_y, enemy_type, enemy_hp, enemy_max_hp, enemy_damage, turns_alive, has_loot) = {
                 let room = self.world.current().unwrap();
                 let e = &room.enemies[i];
-                (e.x, e.y, e.enemy_type, e.hp, e.max_hp, e.damage, e.turns_alive)
+                (e.x, e.y, e.enemy_type, e.hp, e.max_hp, e.damage, e.turns_alive, e.stolen.is_some())
             };
 
             // Decide action based on enemy type and position
@@ -299,7 +799,27 @@ impl GameState {
             let player_y = self.player.y;
             let dist = (enemy_x - player_x).abs() + (enemy_y - player_y).abs();
 
-            let action = if dist == 1 {
+            // Enemies notice a player they can see up close
+            let noticed = dist <= detection_radius(self.player.stealth) && self.visible_tiles.contains(&(enemy_x, enemy_y));
+            let aggro = match self.world.current_mut() {
+                Some(room) => {
+                    room.enemies[i].aggro |= noticed;
+                    room.enemies[i].aggro
+                }
+                None => noticed,
+            };
+
+            let action = if self.pacifist_exits && !aggro {
+                // Unnoticed enemies stay put so the player can sneak past
+                EnemyAction::Wait
+            } else if grace && dist == 1 {
+                EnemyAction::Wait
+            } else if enemy_type == crate::entity::EnemyType::ScopeCreep {
+                // Stealing and running off with the loot is decided in one place
+                self.world.current().map_or(EnemyAction::Wait, |room| {
+                    decide_action(&room.enemies[i], &self.player, room)
+                })
+            } else if dist == 1 {
                 // Adjacent - check for special or attack
                 if enemy_type == crate::entity::EnemyType::Regression && enemy_hp < enemy_max_hp / 2 {
                     EnemyAction::Regenerate(2)
@@ -335,14 +855,40 @@ impl GameState {
                             room.enemies[i].x = new_x;
                             room.enemies[i].y = new_y;
                         }
+
+                        // Fast enemies take a second step if still closing in
+                        let (dx, dy) = ((player_x - new_x).signum(), (player_y - new_y).signum());
+                        let still_apart = (player_x - new_x).abs() + (player_y - new_y).abs() > 1;
+                        if enemy_type.is_fast() && !has_loot && still_apart {
+                            let (step_x, step_y) = if dx != 0 { (new_x + dx, new_y) } else { (new_x, new_y + dy) };
+                            if let Some(room) = self.world.current_mut() {
+                                if room.is_walkable(step_x, step_y) && room.get_enemy_at(step_x, step_y).is_none() {
+                                    room.enemies[i].x = step_x;
+                                    room.enemies[i].y = step_y;
+                                }
+                            }
+                        }
                     }
                 }
                 EnemyAction::Attack => {
+                    let hp_before = self.player.hp;
                     let result = {
                         let room = self.world.current_mut().unwrap();
                         room.enemies[i].turns_alive += 1;
-                        enemy_attack(&room.enemies[i], &mut self.player, &mut rng)
+                        enemy_attack_with_variance(&room.enemies[i], &mut self.player, &mut rng, self.damage_variance)
+                    };
+                    let result = if self.god_mode {
+                        self.player.hp = hp_before;
+                        CombatResult {
+                            damage: 0,
+                            killed: false,
+                            message: format!("The {} can't touch you.", enemy_type.symbol()),
+                            ..result
+                        }
+                    } else {
+                        result
                     };
+                    self.stats.damage_taken += (hp_before - self.player.hp).max(0) as u32;
                     
                     self.messages.push(result.message.clone());
                     events.push(GameEvent::EnemyAttacked {
@@ -351,17 +897,35 @@ impl GameState {
                     });
 
                     if result.killed {
+                        if self.try_revive() {
+                            return events;
+                        }
                         self.game_over = true;
                         self.victory = false;
+                        self.killed_by = Some(format!("{:?}", enemy_type));
                         events.push(GameEvent::GameOver { victory: false });
                         self.log("You have been defeated!");
                         return events;
                     }
                 }
+                EnemyAction::Steal => {
+                    let index = rng.gen_range(0..self.player.inventory.len());
+                    let item = self.player.remove_item(index);
+                    self.log(format!("The Scope Creep snatches your {} and runs!", item.name));
+                    if let Some(room) = self.world.current_mut() {
+                        room.enemies[i].stolen = Some(item);
+                        room.enemies[i].turns_alive += 1;
+                    }
+                }
                 EnemyAction::Regenerate(amount) => {
                     if let Some(room) = self.world.current_mut() {
-                        room.enemies[i].hp = (room.enemies[i].hp + amount).min(room.enemies[i].max_hp);
+                        let before = room.enemies[i].hp;
+                        room.enemies[i].hp = (before + amount).min(room.enemies[i].max_hp);
                         room.enemies[i].turns_alive += 1;
+                        events.push(GameEvent::EnemyHealed {
+                            amount: room.enemies[i].hp - before,
+                            enemy_type: format!("{:?}", enemy_type),
+                        });
                     }
                 }
                 EnemyAction::Grow(amount) => {
@@ -432,9 +996,381 @@ impl GameState {
             }
         }
 
+        self.player.tick_guard();
+        events
+    }
+
+    /// Revive at the room entrance if permadeath is off and revives remain.
+    /// Called when the player drops to 0 HP; returns whether they revived.
+    /// An enemy standing on the entrance pushes the player to the nearest
+    /// free tile instead.
+    fn try_revive(&mut self) -> bool {
+        if self.permadeath || self.revives >= Self::MAX_REVIVES {
+            return false;
+        }
+        self.revives += 1;
+
+        if let Some(room) = self.world.current() {
+            let entrance = (1, room.height as i32 / 2);
+            let (x, y) = if room.get_enemy_at(entrance.0, entrance.1).is_none() {
+                entrance
+            } else {
+                room.get_free_positions()
+                    .into_iter()
+                    .min_by_key(|&(x, y)| ((x - entrance.0).abs() + (y - entrance.1).abs(), y, x))
+                    .unwrap_or(entrance)
+            };
+            self.player.x = x;
+            self.player.y = y;
+        }
+        self.player.hp = (self.player.max_hp / 2).max(1);
+        self.player.defending = false;
+        self.player.guard_turns = 0;
+        self.turn += Self::REVIVE_TURN_PENALTY;
+        self.penalty_turns += Self::REVIVE_TURN_PENALTY;
+        self.update_fov();
+
+        let left = Self::MAX_REVIVES - self.revives;
+        self.log(format!(
+            "You collapse... and wake at the entrance, {} turns later. ({} revives left)",
+            Self::REVIVE_TURN_PENALTY,
+            left
+        ));
+        true
+    }
+
+    /// Start `count` dungeon rooms in, at the entrance of the room after
+    /// them. Every room passed over, tutorial included, is marked cleared
+    /// and emptied of enemies and items, so backtracking into one finds
+    /// no free loot. Skips stop at the last room.
+    pub fn skip_rooms(&mut self, count: usize) {
+        let Some(last) = self.world.rooms.len().checked_sub(1) else {
+            return;
+        };
+        let target = (self.world.first_dungeon_room() + count).min(last);
+        if target <= self.world.current_room {
+            return;
+        }
+
+        for room in &mut self.world.rooms[..target] {
+            room.enemies.clear();
+            room.items.clear();
+            room.cleared = true;
+        }
+        self.world.current_room = target;
+        self.furthest_room = target;
+        self.skipped_rooms = target.saturating_sub(self.world.first_dungeon_room());
+        self.tutorial = None;
+        if let Some(room) = self.world.current() {
+            self.player.x = 1;
+            self.player.y = room.height as i32 / 2;
+        }
+        self.update_fov();
+        self.log(format!("You skip ahead past {} rooms.", target));
+    }
+
+    /// Rooms cleared by actually playing them, leaving out any skipped.
+    pub fn rooms_played(&self) -> usize {
+        self.rooms_cleared().saturating_sub(self.skipped_rooms)
+    }
+
+    /// Number of rooms cleared so far: every room the player has left
+    /// heading forward, plus the furthest one once its last enemy falls.
+    /// The tutorial room isn't part of the dungeon, so it doesn't count.
+    pub fn rooms_cleared(&self) -> usize {
+        let furthest = self.furthest_room.max(self.world.current_room);
+        let cleared = self.world.rooms.get(furthest).is_some_and(|room| room.cleared);
+        (furthest + cleared as usize).saturating_sub(self.world.first_dungeon_room())
+    }
+
+    /// Leave through the current room's exit as if stepping onto it.
+    /// Returns the events of entering the next room, as a move onto the
+    /// exit would; if there are none, the player stayed put.
+    pub fn advance_room(&mut self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        let Some(exit) = self.world.current().and_then(|room| room.exit_position()) else {
+            return events;
+        };
+        let from = (self.player.x, self.player.y);
+        (self.player.x, self.player.y) = exit;
+        if self.check_room_exit() {
+            self.push_room_entered(&mut events);
+        } else {
+            (self.player.x, self.player.y) = from;
+        }
         events
     }
 
+    /// Turn on debug commands, marking the run as a debug run.
+    pub fn enable_debug(&mut self) {
+        self.debug = true;
+    }
+
+    /// Debug: toggle taking no damage. Returns whether it's now on.
+    pub fn toggle_god_mode(&mut self) -> bool {
+        if !self.debug {
+            return false;
+        }
+        self.god_mode = !self.god_mode;
+        self.log(format!("Debug: god mode {}.", if self.god_mode { "on" } else { "off" }));
+        self.god_mode
+    }
+
+    /// Debug: toggle seeing the whole room. Returns whether it's now on.
+    pub fn toggle_reveal_map(&mut self) -> bool {
+        if !self.debug {
+            return false;
+        }
+        self.reveal_map = !self.reveal_map;
+        self.update_fov();
+        self.log(format!("Debug: map reveal {}.", if self.reveal_map { "on" } else { "off" }));
+        self.reveal_map
+    }
+
+    /// Debug: clear the current room and move on to the next one.
+    /// Returns the events of entering it, or none if nothing happened.
+    pub fn debug_warp(&mut self) -> Vec<GameEvent> {
+        if !self.debug {
+            return Vec::new();
+        }
+        if let Some(room) = self.world.current_mut() {
+            room.enemies.clear();
+            room.cleared = true;
+        }
+        self.advance_room()
+    }
+
+    /// Debug: spawn an enemy on the free tile nearest the player.
+    /// Returns whether there was room for it.
+    pub fn debug_spawn_enemy(&mut self, enemy_type: EnemyType) -> bool {
+        if !self.debug {
+            return false;
+        }
+        let (px, py) = (self.player.x, self.player.y);
+        let difficulty = self.difficulty;
+        let Some(room) = self.world.current_mut() else {
+            return false;
+        };
+        let spot = room
+            .get_free_positions()
+            .into_iter()
+            .filter(|&pos| pos != (px, py))
+            .min_by_key(|&(x, y)| (x - px).abs().max((y - py).abs()));
+        let Some((x, y)) = spot else {
+            return false;
+        };
+
+        let mut enemy = Enemy::new(enemy_type, x, y, "debug");
+        difficulty.apply_to(&mut enemy);
+        room.enemies.push(enemy);
+        room.cleared = false;
+        self.log(format!("Debug: spawned a {:?}.", enemy_type));
+        true
+    }
+
+    /// Debug: put an item straight into the inventory. Returns false if
+    /// the inventory is full.
+    pub fn debug_give_item(&mut self, item: Item) -> bool {
+        if !self.debug {
+            return false;
+        }
+        let name = item.name.clone();
+        if !self.player.pickup_item(item) {
+            self.log("Debug: inventory full.");
+            return false;
+        }
+        self.log(format!("Debug: spawned a {}.", name));
+        true
+    }
+
+    /// Set the run's goal. Endless runs get an exit in the last room,
+    /// since it now leads somewhere.
+    pub fn set_goal(&mut self, goal: VictoryCondition) {
+        self.goal = goal;
+        if goal != VictoryCondition::Endless || self.endless_base > 0 {
+            return;
+        }
+        self.endless_base = self.world.rooms.len() - self.world.first_dungeon_room();
+        if let Some(room) = self.world.rooms.last_mut() {
+            room.set_tile(room.width as i32 - 1, room.height as i32 / 2, Tile::Exit);
+            ensure_exit_reachable(room);
+        }
+    }
+
+    /// Rooms survived in endless mode, or None in other modes.
+    pub fn endless_score(&self) -> Option<u32> {
+        (self.goal == VictoryCondition::Endless).then(|| self.rooms_cleared() as u32)
+    }
+
+    /// Add another room past the last one for endless mode.
+    ///
+    /// Rooms cycle through the history's days again, each lap with
+    /// enemies a quarter tougher than the last.
+    fn extend_endless(&mut self) {
+        let first = self.world.first_dungeon_room();
+        let index = self.world.rooms.len();
+        let base = self.endless_base.max(1);
+        let depth = index.saturating_sub(first);
+        let Some(template) = self.world.rooms.get(first + depth % base) else {
+            return;
+        };
+        let (date, commits) = (template.source_date, template.source_commits.clone());
+
+        let mut rng = GameRng::seed_from_u64(room_seed(self.seed, index, date));
+        let mut room = generate_room(date, &commits, index, &mut rng);
+        let mid_y = room.height as i32 / 2;
+        room.set_tile(0, mid_y, Tile::Entrance);
+        room.set_tile(room.width as i32 - 1, mid_y, Tile::Exit);
+        ensure_exit_reachable(&mut room);
+
+        let percent = 100 + ENDLESS_LAP_PERCENT * (depth / base) as i32;
+        let scale = |value: i32| ((value * percent + 50) / 100).max(1);
+        for enemy in &mut room.enemies {
+            enemy.hp = scale(enemy.hp);
+            enemy.max_hp = scale(enemy.max_hp);
+            enemy.damage = scale(enemy.damage);
+            self.difficulty.apply_to(enemy);
+        }
+        self.world.rooms.push(room);
+    }
+
+    /// Essence this run will grant when it ends.
+    pub fn essence_earned(&self) -> u32 {
+        essence_for_run(self.victory, self.enemies_killed, self.rooms_played() as u32)
+            + lines_bonus(self.total_lines)
+            + self.bonus_essence()
+    }
+
+    /// Records this run sets against `progression` when it ends.
+    pub fn achievements(&self, progression: &Progression) -> Vec<Achievement> {
+        progression.achievements_for(self.victory, self.rooms_played() as u32, self.turn)
+    }
+
+    /// Essence on top of the usual run reward: pacifist rooms and a
+    /// successful bisect.
+    pub fn bonus_essence(&self) -> u32 {
+        let bisect = if self.bisected { BISECT_BONUS } else { 0 };
+        pacifist_bonus(self.pacifist_rooms()) + bisect
+    }
+
+    /// Enemies met and slain this run, by the author of their commit.
+    /// Spawned counts those slain plus those still standing anywhere in
+    /// the dungeon.
+    pub fn author_tally(&self) -> HashMap<String, AuthorTally> {
+        let mut tally: HashMap<String, AuthorTally> = HashMap::new();
+        for (author, &kills) in &self.author_kills {
+            let entry = tally.entry(author.clone()).or_default();
+            entry.spawned += kills;
+            entry.defeated += kills;
+        }
+        for room in &self.world.rooms {
+            for enemy in &room.enemies {
+                if let Some(author) = room.commit_author(&enemy.source_commit) {
+                    tally.entry(author.to_string()).or_default().spawned += 1;
+                }
+            }
+        }
+        tally
+    }
+
+    /// Rooms left behind without a fight.
+    pub fn pacifist_rooms(&self) -> u32 {
+        self.world.rooms.iter().filter(|r| r.pacifist).count() as u32
+    }
+
+    /// Move the tutorial on once this turn's events finish its step.
+    fn advance_tutorial(&mut self, events: &[GameEvent]) {
+        let Some(step) = self.tutorial else {
+            return;
+        };
+        if !events.iter().any(|e| step.completed_by(e)) {
+            return;
+        }
+
+        self.tutorial = step.next();
+        match self.tutorial {
+            Some(next) => self.log(next.prompt()),
+            None => self.log("Tutorial complete. Your history awaits!"),
+        }
+    }
+
+    /// Hide a bug-introducing commit in one room, picked from the seed.
+    ///
+    /// Enemies in that room and every later one get tougher the further
+    /// past it they are, which is the player's clue to where it hides.
+    pub fn start_bisect(&mut self) {
+        if self.world.first_dungeon_room() >= self.world.rooms.len() {
+            return;
+        }
+
+        let mut rng = GameRng::seed_from_u64(self.seed);
+        self.bisect = true;
+        self.bad_room = rng.gen_range(self.world.first_dungeon_room()..self.world.rooms.len());
+        for (index, room) in self.world.rooms.iter_mut().enumerate().skip(self.bad_room) {
+            room.apply_regression((index - self.bad_room + 1) as i32);
+        }
+        self.log("One of these commits broke the build. Find it and accuse it with B.");
+    }
+
+    /// Accuse the current room of introducing the bug (bisect runs only).
+    ///
+    /// The right room ends the run in victory; a wrong one costs HP.
+    pub fn accuse_room(&mut self) -> bool {
+        if !self.bisect || self.game_over {
+            return false;
+        }
+
+        if self.world.current_room == self.bad_room {
+            let date = self.world.current().map_or(String::new(), |r| r.source_date.to_string());
+            self.bisected = true;
+            self.game_over = true;
+            self.victory = true;
+            self.log(format!("{} is the first bad commit. Victory!", date));
+            return true;
+        }
+
+        if self.god_mode {
+            self.log("Wrong commit! The real bug lashes out, but can't touch you.");
+            return false;
+        }
+        self.player.hp -= Self::WRONG_ACCUSATION_DAMAGE;
+        self.log(format!(
+            "Wrong commit! The real bug lashes out for {} damage.",
+            Self::WRONG_ACCUSATION_DAMAGE
+        ));
+        if self.player.hp <= 0 && !self.try_revive() {
+            self.player.hp = 0;
+            self.game_over = true;
+            self.victory = false;
+            self.killed_by = Some("Wrong accusation".to_string());
+            self.log("You have been defeated!");
+        }
+        false
+    }
+
+    /// Check the run's goal and declare victory if it has been met.
+    pub fn check_goal(&mut self) -> bool {
+        if self.game_over {
+            return false;
+        }
+
+        let message = match self.goal {
+            VictoryCondition::ReachEnd | VictoryCondition::Endless => return false,
+            VictoryCondition::ClearRooms(n) if self.rooms_played() >= n as usize => {
+                format!("Victory! You cleared {} rooms!", n)
+            }
+            VictoryCondition::Survive(n) if self.turn.saturating_sub(self.penalty_turns) >= n => {
+                format!("Victory! You survived {} turns!", n)
+            }
+            _ => return false,
+        };
+
+        self.game_over = true;
+        self.victory = true;
+        self.log(message);
+        true
+    }
+
     /// Check if player is at room exit and handle transition.
     pub fn check_room_exit(&mut self) -> bool {
         let at_exit = self.world.current().is_some_and(|room| {
@@ -445,13 +1381,34 @@ impl GameState {
             return false;
         }
 
+        if !self.world.current().is_some_and(|room| room.branches_merged()) {
+            self.log("Both branches must be walked before they can merge. Explore the other side.");
+            return false;
+        }
+
         let is_cleared = self.world.current().is_some_and(|room| room.is_cleared());
-        if !is_cleared {
+        let sneaking = !is_cleared
+            && self.pacifist_exits
+            && self.world.current().is_some_and(|room| room.undetected());
+        if !is_cleared && !sneaking {
             self.log("You must defeat all enemies before leaving!");
             return false;
         }
 
-        if self.world.is_last_room() {
+        if sneaking {
+            let first_time = self.world.current_mut().is_some_and(|room| {
+                let first = !room.fought && !room.pacifist;
+                room.pacifist |= !room.fought;
+                first
+            });
+            if first_time {
+                self.log("You slip past unnoticed. Pacifist clear!");
+            }
+        }
+
+        if self.world.is_last_room() && self.goal == VictoryCondition::Endless {
+            self.extend_endless();
+        } else if self.world.is_last_room() {
             self.game_over = true;
             self.victory = true;
             self.log("Victory! You have conquered the dungeon!");
@@ -461,7 +1418,7 @@ impl GameState {
         if self.world.next_room() {
             let (room_name, room_date) = self.world.current().map_or(
                 ("Room".to_string(), "".to_string()),
-                |r| (r.room_type.name().to_string(), r.source_date.to_string())
+                |r| (r.name(), r.source_date.to_string())
             );
             
             if let Some(room) = self.world.current() {
@@ -470,15 +1427,364 @@ impl GameState {
             }
             
             self.update_fov();
+            self.entry_grace = self.entry_grace_turns;
             self.log(format!("You enter {} ({})", room_name, room_date));
+
+            if self.world.current_room <= self.furthest_room {
+                self.repopulate_if_enabled();
+            } else {
+                self.furthest_room = self.world.current_room;
+                self.announce_room_theme();
+                self.announce_featured_room();
+                self.announce_boss();
+            }
             return true;
         }
 
         false
     }
 
+    /// Energy regenerated each turn where the player stands, before
+    /// waiting: `SANCTUARY_REGEN` in a Sanctuary, otherwise none.
+    /// Waiting adds `WAIT_REGEN` on top.
+    pub fn energy_regen_here(&self) -> i32 {
+        let in_sanctuary = self.world.current().is_some_and(|room| {
+            room.room_type == crate::world::RoomType::Sanctuary
+        });
+        if in_sanctuary {
+            SANCTUARY_REGEN
+        } else {
+            0
+        }
+    }
+
+    /// Mood of the room the player is in. See `Room::ambience`.
+    pub fn current_ambience(&self) -> AmbienceCue {
+        self.world.current().map_or(AmbienceCue::Calm, Room::ambience)
+    }
+
+    /// Report entering the current room, with its ambience cue.
+    fn push_room_entered(&self, events: &mut Vec<GameEvent>) {
+        events.push(GameEvent::RoomEntered {
+            room_id: self.world.current_room,
+        });
+        events.push(GameEvent::AmbienceCue {
+            cue: self.current_ambience(),
+        });
+    }
+
+    /// Step through the current room's shortcut door into room `to`.
+    ///
+    /// Shortcuts only lead to rooms the player has already reached, and
+    /// like exits they stay shut until the room is cleared.
+    pub fn take_shortcut(&mut self, to: usize) -> bool {
+        if to > self.furthest_room.max(self.world.current_room) {
+            self.log("The shortcut leads somewhere you haven't been yet.");
+            return false;
+        }
+        if !self.world.current().is_some_and(|room| room.is_cleared()) {
+            self.log("You must defeat all enemies before leaving!");
+            return false;
+        }
+
+        let Some(target) = self.world.rooms.get(to) else {
+            return false;
+        };
+        let (x, y) = target.shortcut_arrival();
+        if target.get_enemy_at(x, y).is_some() {
+            self.log("Something blocks the other side of the shortcut.");
+            return false;
+        }
+        let (room_name, room_date) = (target.name(), target.source_date);
+
+        self.world.current_room = to;
+        self.player.x = x;
+        self.player.y = y;
+        self.update_fov();
+        self.entry_grace = self.entry_grace_turns;
+        self.log(format!("You take the shortcut to {} ({})", room_name, room_date));
+        self.repopulate_if_enabled();
+        true
+    }
+
+    /// Check if player is at room entrance and backtrack to the previous room.
+    pub fn check_room_entrance(&mut self) -> bool {
+        let at_entrance = self.world.current().is_some_and(|room| {
+            matches!(room.get_tile(self.player.x, self.player.y), Some(Tile::Entrance))
+        });
+
+        if !at_entrance || !self.world.prev_room() {
+            return false;
+        }
+
+        let (room_name, room_date) = self.world.current().map_or(
+            ("Room".to_string(), "".to_string()),
+            |r| (r.name(), r.source_date.to_string())
+        );
+
+        // Arrive beside the exit we came through
+        if let Some(room) = self.world.current() {
+            self.player.x = room.width as i32 - 2;
+            self.player.y = room.height as i32 / 2;
+        }
+
+        self.update_fov();
+        self.entry_grace = self.entry_grace_turns;
+        self.log(format!("You head back to {} ({})", room_name, room_date));
+        self.repopulate_if_enabled();
+        true
+    }
+
+    /// Respawn a weak enemy in a revisited, cleared room if the setting is on.
+    fn repopulate_if_enabled(&mut self) {
+        if !self.repopulate_on_revisit {
+            return;
+        }
+
+        let (px, py) = (self.player.x, self.player.y);
+        let difficulty = self.difficulty;
+        let Some(room) = self.world.current_mut() else {
+            return;
+        };
+        if !room.is_cleared() || room.room_type == crate::world::RoomType::Sanctuary {
+            return;
+        }
+
+        // Spawn as far from the player as possible so it isn't an ambush
+        let spot = room
+            .get_free_positions()
+            .into_iter()
+            .filter(|&(x, y)| (x, y) != (px, py))
+            .max_by_key(|&(x, y)| (x - px).abs() + (y - py).abs());
+        let Some((x, y)) = spot else {
+            return;
+        };
+
+        let mut enemy = Enemy::new(crate::entity::EnemyType::Bug, x, y, "revisit");
+        difficulty.apply_to(&mut enemy);
+        room.enemies.push(enemy);
+        room.cleared = false;
+        self.log("Something has crept back in while you were away...");
+    }
+
+    /// Set the run's difficulty and scale every enemy in the dungeon.
+    ///
+    /// Call once, when the run starts. Enemies that creep back into
+    /// revisited rooms are scaled as they spawn.
+    pub fn apply_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+        for room in &mut self.world.rooms {
+            for enemy in &mut room.enemies {
+                difficulty.apply_to(enemy);
+            }
+        }
+    }
+
+    /// Announce the file a themed room is built around.
+    fn announce_room_theme(&mut self) {
+        let file = self.world.current().and_then(|room| room.theme_file.clone());
+        if let Some(file) = file {
+            self.log(format!("This place is built from {}, the file you edit most.", file));
+        }
+    }
+
+    /// Announce the featured commit's mini-boss if it guards the current room.
+    fn announce_featured_room(&mut self) {
+        let title = self.world.current().and_then(|room| {
+            let featured = room.featured_commit.as_ref()?;
+            room.enemies
+                .iter()
+                .filter(|e| &e.source_commit == featured)
+                .find_map(|e| e.title.clone())
+        });
+
+        if let Some(title) = title {
+            self.log(format!("*** Commit of the day: \"{}\" guards this room! ***", title));
+        }
+    }
+
+    /// Announce the boss of a boss chamber, unless it's the featured
+    /// commit's mini-boss, which has its own announcement.
+    fn announce_boss(&mut self) {
+        let title = self.world.current().and_then(|room| {
+            let boss = room.boss()?;
+            if room.featured_commit.as_ref() == Some(&boss.source_commit) {
+                return None;
+            }
+            boss.title.clone()
+        });
+
+        if let Some(title) = title {
+            self.log(format!("*** {} blocks the way! ***", title));
+        }
+    }
+
+    /// Drop an item forged from a slain mini-boss's commit.
+    fn drop_mini_boss_loot(&mut self, enemy: &Enemy, rng: &mut GameRng) {
+        let Some(room) = self.world.current_mut() else {
+            return;
+        };
+        let Some(commit) = room.source_commits.iter().find(|c| c.hash == enemy.source_commit) else {
+            return;
+        };
+
+        let item = crate::item::generate_item(commit, rng).at(enemy.x, enemy.y);
+        let name = item.name.clone();
+        room.items.push(item);
+
+        let title = enemy.title.as_deref().unwrap_or("mini-boss");
+        self.log(format!("\"{}\" drops a {}!", title, name));
+    }
+
+    /// Drop whatever a thief was carrying where it died.
+    fn drop_stolen_item(&mut self, enemy: &mut Enemy) {
+        let Some(item) = enemy.stolen.take() else {
+            return;
+        };
+        let name = item.name.clone();
+        if let Some(room) = self.world.current_mut() {
+            room.items.push(item.at(enemy.x, enemy.y));
+        }
+        self.log(format!("The Scope Creep drops your {}.", name));
+    }
+
+    /// Pick up the item under the player, or flag a pending pickup
+    /// if the inventory is full.
+    fn pickup_at_player(&mut self) -> Option<String> {
+        self.pending_pickup = false;
+        let (x, y) = (self.player.x, self.player.y);
+        let room = self.world.current_mut()?;
+        let index = room.items.iter().position(|i| i.x == x && i.y == y)?;
+
+        if self.player.inventory_full() {
+            let name = room.items[index].name.clone();
+            self.pending_pickup = true;
+            self.log(format!("Your inventory is full. You see a {} here.", name));
+            return None;
+        }
+
+        let item = room.items.remove(index);
+        let name = item.name.clone();
+        self.player.pickup_item(item);
+        self.log(format!("You pick up the {}.", name));
+        Some(name)
+    }
+
+    /// Resolve a pending pickup by dropping the lowest-rarity item
+    /// in exchange for the one on the floor.
+    pub fn accept_pending_pickup(&mut self) -> bool {
+        if !self.pending_pickup {
+            return false;
+        }
+        self.pending_pickup = false;
+
+        let (x, y) = (self.player.x, self.player.y);
+        let Some(room) = self.world.current_mut() else {
+            return false;
+        };
+        let Some(index) = room.items.iter().position(|i| i.x == x && i.y == y) else {
+            return false;
+        };
+
+        if self.player.lowest_rarity_index().is_none() {
+            return false;
+        }
+
+        let item = room.items.remove(index);
+        let name = item.name.clone();
+        let Some(dropped) = self.player.swap_lowest_rarity(item) else {
+            return false;
+        };
+        let dropped_name = dropped.name.clone();
+        room.items.push(dropped.at(x, y));
+        self.log(format!("You drop the {} and pick up the {}.", dropped_name, name));
+        true
+    }
+
+    /// Leave the floor item where it is.
+    pub fn decline_pending_pickup(&mut self) {
+        if self.pending_pickup {
+            self.pending_pickup = false;
+            self.log("You leave it where it lies.");
+        }
+    }
+
+    /// Offer the reward for clearing a room, if rewards are on.
+    ///
+    /// Boss rooms offer none, and each room offers one at most, so
+    /// re-clearing a room (after a mimic, say) gives nothing more.
+    /// Returns whether a reward is on offer.
+    pub fn offer_clear_reward(&mut self, room_id: usize) -> bool {
+        if !self.clear_rewards || self.game_over {
+            return false;
+        }
+        match self.world.rooms.get(room_id) {
+            Some(room) if room.room_type != crate::world::RoomType::Boss => {}
+            _ => return false,
+        }
+        self.rewarded_rooms.insert(room_id)
+    }
+
+    /// Take the safe room-clear reward: a small heal.
+    pub fn take_clear_heal(&mut self) {
+        let before = self.player.hp;
+        self.player.heal(CLEAR_REWARD_HEAL);
+        self.log(format!("You catch your breath (+{} HP).", self.player.hp - before));
+    }
+
+    /// Take the risky room-clear reward: open a chest.
+    ///
+    /// The outcome is fixed by the seed and the room. A mimic leaps out
+    /// next to the player; treasure goes into the inventory, or onto the
+    /// floor if it's full.
+    pub fn open_clear_chest(&mut self) -> ChestOutcome {
+        let room_id = self.world.current_room;
+        let sub_seed = self.seed ^ (room_id as u64 + 1).wrapping_mul(0xA24B_AED4_963E_E407);
+        let mut rng = GameRng::seed_from_u64(sub_seed);
+        let outcome = roll_chest(&mut rng);
+
+        let (px, py) = (self.player.x, self.player.y);
+        match &outcome {
+            ChestOutcome::Mimic => {
+                let difficulty = self.difficulty;
+                let Some(room) = self.world.current_mut() else {
+                    return outcome;
+                };
+                let spot = room
+                    .get_free_positions()
+                    .into_iter()
+                    .filter(|&pos| pos != (px, py))
+                    .min_by_key(|&(x, y)| ((x - px).abs() + (y - py).abs(), (y, x)));
+                if let Some((x, y)) = spot {
+                    let mut mimic = Enemy::new(crate::entity::EnemyType::Regression, x, y, "mimic");
+                    mimic.promote("Mimic");
+                    difficulty.apply_to(&mut mimic);
+                    room.enemies.push(mimic);
+                    room.cleared = false;
+                }
+                self.log("The chest sprouts teeth. It's a Mimic!");
+            }
+            ChestOutcome::Treasure(item) => {
+                let name = item.name.clone();
+                if self.player.pickup_item(item.clone()) {
+                    self.log(format!("The chest holds a {}!", name));
+                } else if let Some(room) = self.world.current_mut() {
+                    room.items.push(item.clone().at(px, py));
+                    self.log(format!("The chest holds a {}, but your pack is full.", name));
+                }
+            }
+        }
+        outcome
+    }
+
     /// Update field of view.
     pub fn update_fov(&mut self) {
+        if self.world.current().is_some_and(|room| room.is_empty()) {
+            self.visible_tiles.clear();
+            return;
+        }
+        self.keep_player_in_room();
+
         let (origin, blocking_tiles) = if let Some(room) = self.world.current() {
             let tiles = room.tiles.clone();
             ((self.player.x, self.player.y), tiles)
@@ -486,17 +1792,51 @@ impl GameState {
             return;
         };
 
-        self.visible_tiles = calculate_fov(origin, 5, |x, y| {
-            if x < 0 || y < 0 {
-                return true;
+        if self.reveal_map {
+            if let Some(room) = self.world.current() {
+                self.visible_tiles = (0..room.height as i32)
+                    .flat_map(|y| (0..room.width as i32).map(move |x| (x, y)))
+                    .collect();
             }
-            let (ux, uy) = (x as usize, y as usize);
-            blocking_tiles
-                .get(uy)
-                .and_then(|row| row.get(ux))
-                .map(|t| t.is_blocking())
-                .unwrap_or(true)
-        });
+        } else {
+            self.visible_tiles = calculate_fov(origin, 5, |x, y| {
+                if x < 0 || y < 0 {
+                    return true;
+                }
+                let (ux, uy) = (x as usize, y as usize);
+                blocking_tiles
+                    .get(uy)
+                    .and_then(|row| row.get(ux))
+                    .map(|t| t.is_blocking())
+                    .unwrap_or(true)
+            });
+        }
+
+        let turn = self.turn;
+        if let Some(room) = self.world.current_mut() {
+            room.mark_seen(&self.visible_tiles, turn);
+        }
+    }
+
+    /// Move a player standing outside the current room's bounds to the
+    /// nearest walkable tile. Returns whether the player was moved.
+    pub fn keep_player_in_room(&mut self) -> bool {
+        let (x, y) = (self.player.x, self.player.y);
+        let Some(room) = self.world.current() else {
+            return false;
+        };
+        if room.contains(x, y) {
+            return false;
+        }
+        let Some((nx, ny)) = room.nearest_walkable((x, y)) else {
+            return false;
+        };
+        (self.player.x, self.player.y) = (nx, ny);
+        self.log(format!(
+            "Warning: you were outside the room at ({}, {}) and were moved back in.",
+            x, y
+        ));
+        true
     }
 
     /// Add a message to the log.
@@ -1,10 +1,10 @@
 //! Git repository parsing.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
-use chrono::{Duration, NaiveDate, Utc};
-use git2::{Commit, Diff, DiffOptions, Repository};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use git2::{Commit, Diff, DiffFormat, DiffOptions, Repository};
 
 use super::types::{CommitData, CommitStats, FileCategories, GitError};
 
@@ -12,6 +12,39 @@ use super::types::{CommitData, CommitStats, FileCategories, GitError};
 ///
 /// Returns commits from the last `days` days, sorted by date (oldest first).
 pub fn parse_repository(path: &Path, days: u32) -> Result<Vec<CommitData>, GitError> {
+    let cutoff = Utc::now() - Duration::days(days as i64);
+    let commits = walk_commits(path, cutoff, None)?;
+
+    if commits.is_empty() {
+        return Err(GitError::NoCommits(days));
+    }
+    Ok(commits)
+}
+
+/// Parse a git repository, keeping commits made from `since` through
+/// `until` (both inclusive, UTC dates), sorted by date (oldest first).
+pub fn parse_repository_range(path: &Path, since: NaiveDate, until: NaiveDate) -> Result<Vec<CommitData>, GitError> {
+    if since > until {
+        return Err(GitError::InvalidRange(since, until));
+    }
+
+    let cutoff = since.and_time(NaiveTime::MIN).and_utc();
+    let commits = walk_commits(path, cutoff, Some(until))?;
+
+    if commits.is_empty() {
+        return Err(GitError::NoCommitsInRange(since, until));
+    }
+    Ok(commits)
+}
+
+/// Whether a commit date falls within `since..=until`.
+pub fn in_date_range(date: DateTime<Utc>, since: NaiveDate, until: NaiveDate) -> bool {
+    (since..=until).contains(&date.date_naive())
+}
+
+/// Walk back from HEAD until `cutoff`, skipping commits after `until`.
+/// Returns commits oldest first.
+fn walk_commits(path: &Path, cutoff: DateTime<Utc>, until: Option<NaiveDate>) -> Result<Vec<CommitData>, GitError> {
     let repo = Repository::open(path).map_err(|e| {
         if e.code() == git2::ErrorCode::NotFound {
             GitError::NotARepository(path.display().to_string())
@@ -20,10 +53,11 @@ pub fn parse_repository(path: &Path, days: u32) -> Result<Vec<CommitData>, GitEr
         }
     })?;
 
-    let cutoff = Utc::now() - Duration::days(days as i64);
+    let tags = tags_by_commit(&repo);
     let mut revwalk = repo.revwalk().map_err(|e| GitError::WalkFailed(e.message().to_string()))?;
     revwalk.push_head().map_err(|e| GitError::WalkFailed(e.message().to_string()))?;
 
+    let since = cutoff.date_naive();
     let mut commits = Vec::new();
 
     for oid in revwalk {
@@ -37,6 +71,9 @@ pub fn parse_repository(path: &Path, days: u32) -> Result<Vec<CommitData>, GitEr
         if datetime < cutoff {
             break;
         }
+        if until.is_some_and(|until| !in_date_range(datetime, since, until)) {
+            continue;
+        }
 
         let (stats, categories) = get_commit_stats_and_categories(&repo, &commit)?;
         let is_merge = commit.parent_count() > 1;
@@ -44,41 +81,76 @@ pub fn parse_repository(path: &Path, days: u32) -> Result<Vec<CommitData>, GitEr
         commits.push(CommitData {
             hash: oid.to_string(),
             date: datetime,
-            message: commit.message().unwrap_or("").to_string(),
+            // Legacy encodings aren't valid UTF-8; keep what we can read
+            message: String::from_utf8_lossy(commit.message_bytes()).into_owned(),
             insertions: stats.insertions,
             deletions: stats.deletions,
             files_changed: stats.files_changed,
             author: commit.author().name().unwrap_or("unknown").to_string(),
             is_merge,
             file_categories: categories,
+            tag: tags.get(&oid.to_string()).cloned(),
+            diff_snippet: None,
+            churn: 0,
         });
     }
 
-    if commits.is_empty() {
-        return Err(GitError::NoCommits(days));
-    }
-
     // Reverse to get oldest first
     commits.reverse();
+    annotate_churn(&mut commits);
     Ok(commits)
 }
 
+/// Map commit hashes to the name of a tag pointing at them.
+///
+/// Annotated and lightweight tags both count. When a commit has several
+/// tags, the first in name order wins. Tags that can't be resolved to a
+/// commit are skipped.
+pub fn tags_by_commit(repo: &Repository) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+    let Ok(names) = repo.tag_names(None) else {
+        return tags;
+    };
+
+    let mut names: Vec<&str> = names.iter().flatten().collect();
+    names.sort_unstable();
+    for name in names {
+        let commit = repo
+            .revparse_single(&format!("refs/tags/{}", name))
+            .and_then(|object| object.peel_to_commit());
+        if let Ok(commit) = commit {
+            tags.entry(commit.id().to_string()).or_insert_with(|| name.to_string());
+        }
+    }
+
+    tags
+}
+
+/// Fill in each commit's diff snippet, for diff lore.
+///
+/// Snippets mean printing every diff, so parsing leaves them out and
+/// this is only called when lore is on. Commits that can't be found in
+/// the repository keep no snippet.
+pub fn attach_diff_snippets(path: &Path, commits: &mut [CommitData]) -> Result<(), GitError> {
+    let repo = Repository::open(path).map_err(|e| GitError::OpenFailed(e.message().to_string()))?;
+    for commit_data in commits {
+        let Some(commit) = git2::Oid::from_str(&commit_data.hash)
+            .ok()
+            .and_then(|oid| repo.find_commit(oid).ok())
+        else {
+            continue;
+        };
+        commit_data.diff_snippet = diff_snippet(&commit_diff(&repo, &commit)?);
+    }
+    Ok(())
+}
+
 /// Get statistics and file categories for a single commit.
 pub fn get_commit_stats_and_categories(
     repo: &Repository,
     commit: &Commit,
 ) -> Result<(CommitStats, FileCategories), GitError> {
-    let tree = commit.tree()?;
-
-    let parent_tree = if commit.parent_count() > 0 {
-        Some(commit.parent(0)?.tree()?)
-    } else {
-        None
-    };
-
-    let mut opts = DiffOptions::new();
-    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut opts))?;
-
+    let diff = commit_diff(repo, commit)?;
     let stats = diff.stats()?;
     let categories = categorize_files(&diff);
 
@@ -92,6 +164,81 @@ pub fn get_commit_stats_and_categories(
     ))
 }
 
+/// Diff a commit against its first parent, or against nothing for a
+/// root commit.
+fn commit_diff<'r>(repo: &'r Repository, commit: &Commit) -> Result<Diff<'r>, GitError> {
+    let tree = commit.tree()?;
+
+    let parent_tree = if commit.parent_count() > 0 {
+        Some(commit.parent(0)?.tree()?)
+    } else {
+        None
+    };
+
+    let mut opts = DiffOptions::new();
+    Ok(repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut opts))?)
+}
+
+/// Most lines kept in a diff snippet.
+pub const MAX_SNIPPET_LINES: usize = 24;
+/// Most bytes kept in a diff snippet, so snippets don't bloat saves.
+pub const MAX_SNIPPET_BYTES: usize = 1200;
+/// Most hunks kept in a diff snippet.
+const MAX_SNIPPET_HUNKS: usize = 3;
+/// Longer diff lines are cut to this many characters.
+const SNIPPET_LINE_WIDTH: usize = 72;
+
+/// Take the first few hunks of a diff as readable text.
+///
+/// Each file starts with its path, followed by its hunk headers and
+/// `+`/`-`/context lines. Returns `None` when the diff has no text
+/// changes, e.g. for binary files or an empty commit.
+pub fn diff_snippet(diff: &Diff) -> Option<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut bytes = 0;
+    let mut hunks = 0;
+    let mut current_path = None;
+
+    // Returning false stops the walk early; the resulting error is expected
+    let _ = diff.print(DiffFormat::Patch, |delta, _hunk, line| {
+        let text = String::from_utf8_lossy(line.content());
+        let text = text.trim_end();
+        let entry = match line.origin() {
+            'H' => {
+                hunks += 1;
+                if hunks > MAX_SNIPPET_HUNKS {
+                    return false;
+                }
+                let path = delta.new_file().path().or_else(|| delta.old_file().path());
+                let path = path.map(|p| p.to_string_lossy().into_owned());
+                if path != current_path {
+                    let header = format!("{}:", path.as_deref().unwrap_or("?"));
+                    bytes += header.len() + 1;
+                    lines.push(header);
+                    current_path = path;
+                }
+                text.to_string()
+            }
+            origin @ ('+' | '-' | ' ') => format!("{}{}", origin, text),
+            _ => return true,
+        };
+
+        let entry: String = entry.chars().take(SNIPPET_LINE_WIDTH).collect();
+        if lines.len() >= MAX_SNIPPET_LINES || bytes + entry.len() + 1 > MAX_SNIPPET_BYTES {
+            return false;
+        }
+        bytes += entry.len() + 1;
+        lines.push(entry);
+        true
+    });
+
+    // A lone file header means nothing readable made it in
+    if lines.len() < 2 {
+        return None;
+    }
+    Some(lines.join("\n"))
+}
+
 /// Get statistics for a single commit (for backward compatibility).
 pub fn get_commit_stats(repo: &Repository, commit: &Commit) -> Result<CommitStats, GitError> {
     let (stats, _) = get_commit_stats_and_categories(repo, commit)?;
@@ -99,6 +246,10 @@ pub fn get_commit_stats(repo: &Repository, commit: &Commit) -> Result<CommitStat
 }
 
 /// Group commits by date.
+///
+/// Within a day commits are ordered by time, then by hash, so commits
+/// sharing a timestamp always come out in the same order no matter how
+/// git walked them.
 pub fn group_by_date(commits: Vec<CommitData>) -> BTreeMap<NaiveDate, Vec<CommitData>> {
     let mut grouped: BTreeMap<NaiveDate, Vec<CommitData>> = BTreeMap::new();
 
@@ -107,9 +258,247 @@ pub fn group_by_date(commits: Vec<CommitData>) -> BTreeMap<NaiveDate, Vec<Commit
         grouped.entry(date).or_default().push(commit);
     }
 
+    for day in grouped.values_mut() {
+        day.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.hash.cmp(&b.hash)));
+    }
+
     grouped
 }
 
+/// Group commits into runs of `size`, oldest first, ignoring dates. The
+/// last group holds whatever is left over, and a size of 0 counts as 1.
+/// Each group is dated by its first commit, so groups from one busy day
+/// share a date.
+pub fn group_by_count(mut commits: Vec<CommitData>, size: usize) -> Vec<(NaiveDate, Vec<CommitData>)> {
+    commits.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.hash.cmp(&b.hash)));
+    commits
+        .chunks(size.max(1))
+        .map(|chunk| (chunk[0].date_naive(), chunk.to_vec()))
+        .collect()
+}
+
+/// Directory name for files at the top of the repository.
+pub const ROOT_DIRECTORY: &str = "(root)";
+
+/// Top-level directory of a repository path, or `ROOT_DIRECTORY` for a
+/// file at the top.
+pub fn top_level_dir(path: &str) -> &str {
+    match path.trim_start_matches("./").split_once('/') {
+        Some((dir, _)) if !dir.is_empty() => dir,
+        _ => ROOT_DIRECTORY,
+    }
+}
+
+/// Group commits by the top-level directory they touch most.
+///
+/// Each commit lands in exactly one group. Ties go to the directory that
+/// sorts first, and commits without recorded paths go to
+/// `ROOT_DIRECTORY`. Within a group commits keep their order.
+pub fn group_by_directory(commits: Vec<CommitData>) -> BTreeMap<String, Vec<CommitData>> {
+    let mut grouped: BTreeMap<String, Vec<CommitData>> = BTreeMap::new();
+
+    for commit in commits {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for path in &commit.file_categories.paths {
+            *counts.entry(top_level_dir(path)).or_default() += 1;
+        }
+        let dir = counts
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map_or(ROOT_DIRECTORY, |(dir, _)| dir)
+            .to_string();
+        grouped.entry(dir).or_default().push(commit);
+    }
+
+    grouped
+}
+
+/// Find the commit with the most lines changed.
+///
+/// Ties go to the earliest commit.
+pub fn largest_commit(commits: &[CommitData]) -> Option<&CommitData> {
+    commits.iter().reduce(|best, commit| {
+        let (lines, best_lines) = (commit.lines_changed(), best.lines_changed());
+        if lines > best_lines || (lines == best_lines && commit.date < best.date) {
+            commit
+        } else {
+            best
+        }
+    })
+}
+
+/// Median lines changed per commit.
+///
+/// With an even number of commits this is the mean of the middle two,
+/// rounded down. Returns `None` for no commits.
+pub fn median_commit_size(commits: &[CommitData]) -> Option<u32> {
+    let mut sizes: Vec<u32> = commits.iter().map(CommitData::lines_changed).collect();
+    if sizes.is_empty() {
+        return None;
+    }
+    sizes.sort_unstable();
+    let mid = sizes.len() / 2;
+    if sizes.len() % 2 == 1 {
+        Some(sizes[mid])
+    } else {
+        Some(((sizes[mid - 1] as u64 + sizes[mid] as u64) / 2) as u32)
+    }
+}
+
+/// A commit this many times the median size looks like squashed work.
+pub const SQUASH_OUTLIER_MULTIPLE: u32 = 5;
+
+/// Most commits one squashed commit is counted as.
+pub const MAX_SQUASH_SPLIT: usize = 4;
+
+/// Whether a commit is an outlier against the median commit size, more
+/// than `SQUASH_OUTLIER_MULTIPLE` times as large. A median of zero is
+/// treated as one line.
+pub fn is_squash_outlier(commit: &CommitData, median: u32) -> bool {
+    commit.lines_changed() > median.max(1).saturating_mul(SQUASH_OUTLIER_MULTIPLE)
+}
+
+/// How many ordinary commits a commit stands for: one, or for an outlier
+/// its size over the median, capped at `MAX_SQUASH_SPLIT`.
+pub fn squash_weight(commit: &CommitData, median: u32) -> usize {
+    if !is_squash_outlier(commit, median) {
+        return 1;
+    }
+    let weight = commit.lines_changed() / median.max(1);
+    (weight as usize).clamp(1, MAX_SQUASH_SPLIT)
+}
+
+/// Find the file changed in the most commits.
+///
+/// Each commit counts a path once. Ties go to the path that sorts first.
+/// Returns `None` when no commit recorded any paths.
+pub fn most_edited_file(commits: &[CommitData]) -> Option<String> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for commit in commits {
+        let mut paths: Vec<&str> = commit.file_categories.paths.iter().map(String::as_str).collect();
+        paths.sort_unstable();
+        paths.dedup();
+        for path in paths {
+            *counts.entry(path).or_default() += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(path, _)| path.to_string())
+}
+
+/// Commits touching the same file before it counts as high churn.
+pub const HIGH_CHURN: u32 = 5;
+
+/// Most extra damage churn gives an enemy. It gets twice as much HP.
+pub const MAX_CHURN_BONUS: i32 = 3;
+
+/// Score every commit's churn: the most commits in `commits` that touched
+/// any one of its files. Each commit counts a path once.
+pub fn annotate_churn(commits: &mut [CommitData]) {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for commit in commits.iter() {
+        let mut paths: Vec<&String> = commit.file_categories.paths.iter().collect();
+        paths.sort_unstable();
+        paths.dedup();
+        for path in paths {
+            *counts.entry(path.clone()).or_default() += 1;
+        }
+    }
+
+    for commit in commits.iter_mut() {
+        commit.churn = commit
+            .file_categories
+            .paths
+            .iter()
+            .filter_map(|path| counts.get(path))
+            .copied()
+            .max()
+            .unwrap_or(0);
+    }
+}
+
+/// Extra damage for an enemy spawned from a commit with this churn: one
+/// per `HIGH_CHURN` commits on its hottest file, up to `MAX_CHURN_BONUS`.
+pub fn churn_bonus(churn: u32) -> i32 {
+    ((churn / HIGH_CHURN) as i32).min(MAX_CHURN_BONUS)
+}
+
+/// Words that signal a frustrated committer.
+const FRUSTRATION_WORDS: &[&str] = &[
+    "ugh", "argh", "hack", "hacky", "fixme", "xxx", "wtf", "stupid", "again", "finally",
+];
+
+/// Swear stems, matched as word prefixes.
+const SWEAR_STEMS: &[&str] = &["damn", "crap", "shit", "fuck", "bloody"];
+
+/// Highest frustration score a commit can get.
+pub const MAX_FRUSTRATION: u8 = 5;
+
+/// Score how frustrated a commit message sounds, from 0 to `MAX_FRUSTRATION`.
+///
+/// Each frustration word or swear counts once; repeated exclamation
+/// marks add one more.
+pub fn commit_frustration(commit: &CommitData) -> u8 {
+    let message = commit.message.to_lowercase();
+    let mut score = 0u8;
+
+    for word in message.split(|c: char| !c.is_alphanumeric()) {
+        if word.is_empty() {
+            continue;
+        }
+        if FRUSTRATION_WORDS.contains(&word) || SWEAR_STEMS.iter().any(|s| word.starts_with(s)) {
+            score = score.saturating_add(1);
+        }
+    }
+
+    if message.contains("!!") {
+        score = score.saturating_add(1);
+    }
+
+    score.min(MAX_FRUSTRATION)
+}
+
+/// Words too common in commit messages to name anything after.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "the", "of", "to", "in", "into", "on", "for", "from", "with", "by", "at", "is", "it", "this",
+    "that", "merge", "merged", "branch", "pull", "request", "remote", "tracking", "origin", "main", "master",
+    "develop", "dev", "head", "fix", "fixes", "add", "update", "wip",
+];
+
+/// The most significant word of a commit message's subject line.
+///
+/// Punctuation around words is dropped, but `/`, `-`, `_` and `.` inside
+/// them are kept, so branch names survive: "Merge branch 'feature/x' into
+/// main" gives `feature/x`. Stopwords, numbers and words under three
+/// characters are skipped, and the longest remaining word wins, the
+/// earliest on ties.
+pub fn significant_word(message: &str) -> Option<String> {
+    let subject = message.lines().next().unwrap_or("");
+    let mut best: Option<&str> = None;
+
+    for raw in subject.split_whitespace() {
+        let word = raw.trim_matches(|c: char| !c.is_alphanumeric());
+        if word.chars().count() < 3
+            || word.chars().all(|c| c.is_ascii_digit())
+            || STOPWORDS.contains(&word.to_lowercase().as_str())
+        {
+            continue;
+        }
+        let longer = match best {
+            Some(b) => word.chars().count() > b.chars().count(),
+            None => true,
+        };
+        if longer {
+            best = Some(word);
+        }
+    }
+
+    best.map(str::to_string)
+}
+
 /// Categorize files in a diff by type.
 pub fn categorize_files(diff: &Diff) -> FileCategories {
     let mut categories = FileCategories::default();
@@ -118,6 +507,7 @@ pub fn categorize_files(diff: &Diff) -> FileCategories {
         let path = delta.new_file().path().or_else(|| delta.old_file().path());
 
         if let Some(path) = path {
+            categories.paths.push(path.to_string_lossy().into_owned());
             let path_str = path.to_string_lossy().to_lowercase();
 
             if is_test_file(&path_str) {
@@ -151,3 +541,31 @@ fn is_config_file(path: &str) -> bool {
 fn is_doc_file(path: &str) -> bool {
     path.ends_with(".md") || path.ends_with(".txt") || path.ends_with(".rst")
 }
+
+/// Adjectives for author pseudonyms.
+const PSEUDONYM_ADJECTIVES: &[&str] = &[
+    "Quiet", "Brave", "Sleepy", "Clever", "Swift", "Gentle", "Curious", "Stoic",
+    "Restless", "Patient", "Cheerful", "Grumpy", "Lucky", "Nimble", "Wary", "Bold",
+];
+
+/// Creatures for author pseudonyms.
+const PSEUDONYM_CREATURES: &[&str] = &[
+    "Otter", "Heron", "Badger", "Lynx", "Moth", "Raven", "Newt", "Fox",
+    "Wombat", "Gecko", "Marten", "Owl", "Hare", "Crab", "Bison", "Shrew",
+];
+
+/// Replace an author's name with a stable pseudonym, e.g. "Quiet Otter 42".
+///
+/// Uses FNV-1a rather than the std hasher so a name maps to the same
+/// pseudonym across builds and platforms.
+pub fn pseudonymize(name: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in name.trim().bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    let adjective = PSEUDONYM_ADJECTIVES[(hash % PSEUDONYM_ADJECTIVES.len() as u64) as usize];
+    let creature = PSEUDONYM_CREATURES[((hash >> 16) % PSEUDONYM_CREATURES.len() as u64) as usize];
+    format!("{} {} {}", adjective, creature, (hash >> 32) % 100)
+}
@@ -16,6 +16,17 @@ pub struct CommitData {
     pub author: String,
     pub is_merge: bool,
     pub file_categories: FileCategories,
+    /// Name of the git tag pointing at this commit, if any.
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Opening lines of the commit's diff, capped by `MAX_SNIPPET_LINES`
+    /// and `MAX_SNIPPET_BYTES`.
+    #[serde(default)]
+    pub diff_snippet: Option<String>,
+    /// Most commits in the history that touched any one of this commit's
+    /// files, this one included. Set by `annotate_churn`.
+    #[serde(default)]
+    pub churn: u32,
 }
 
 /// Statistics for a commit diff.
@@ -33,6 +44,42 @@ pub struct FileCategories {
     pub config_files: u32,
     pub doc_files: u32,
     pub other_files: u32,
+    /// Paths of the files touched, as they appear in the diff.
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+/// Which stretch of history to build the dungeon from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitWindow {
+    /// The last N days up to now.
+    Days(u32),
+    /// A fixed window of dates, both ends inclusive.
+    Range { since: NaiveDate, until: NaiveDate },
+}
+
+impl CommitWindow {
+    /// Build a window from CLI options. Either date switches to a fixed
+    /// range: a missing `until` means today, and a missing `since` means
+    /// `days` before `until`.
+    pub fn new(days: u32, since: Option<NaiveDate>, until: Option<NaiveDate>) -> Self {
+        if since.is_none() && until.is_none() {
+            return CommitWindow::Days(days);
+        }
+
+        let until = until.unwrap_or_else(|| Utc::now().date_naive());
+        let since = since.unwrap_or(until - chrono::Duration::days(days as i64));
+        CommitWindow::Range { since, until }
+    }
+}
+
+impl std::fmt::Display for CommitWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommitWindow::Days(days) => write!(f, "{} days", days),
+            CommitWindow::Range { since, until } => write!(f, "{} to {}", since, until),
+        }
+    }
 }
 
 /// Errors that can occur during git parsing.
@@ -50,6 +97,12 @@ pub enum GitError {
     #[error("No commits found in last {0} days")]
     NoCommits(u32),
 
+    #[error("Invalid date range: {0} is after {1}")]
+    InvalidRange(NaiveDate, NaiveDate),
+
+    #[error("No commits found between {0} and {1}")]
+    NoCommitsInRange(NaiveDate, NaiveDate),
+
     #[error("Git error: {0}")]
     Git(#[from] git2::Error),
 }
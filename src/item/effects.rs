@@ -1,17 +1,25 @@
 //! Item effect application.
 
-use rand::Rng;
+use rand::prelude::*;
 
-use crate::entity::Player;
+use crate::entity::{Enemy, EnemyType, Player};
+use crate::game::GameState;
 use crate::git::CommitData;
+use crate::rng::GameRng;
 
-use super::{Item, ItemEffect, ItemType, Rarity};
+use super::{HealMode, Item, ItemEffect, ItemType, Rarity, Resource};
 
 /// Apply an item effect to the player.
 pub fn apply_effect(effect: &ItemEffect, player: &mut Player) -> String {
+    apply_effect_with(effect, player, HealMode::Absolute)
+}
+
+/// Apply an item effect to the player, reading heals with `heal_mode`.
+pub fn apply_effect_with(effect: &ItemEffect, player: &mut Player, heal_mode: HealMode) -> String {
     match effect {
         ItemEffect::Heal(amount) => {
-            player.heal(*amount);
+            let amount = heal_mode.heal_amount(*amount, player.max_hp);
+            player.heal(amount);
             format!("Healed for {} HP", amount)
         }
         ItemEffect::RestoreEnergy(amount) => {
@@ -37,13 +45,133 @@ pub fn apply_effect(effect: &ItemEffect, player: &mut Player) -> String {
                 super::Stat::Damage => {
                     player.damage += *amount;
                 }
+                super::Stat::Stealth => {
+                    player.stealth += *amount;
+                }
             }
             format!("Increased {:?} by {}", stat, amount)
         }
         ItemEffect::RevealMap => "Map revealed".to_string(),
+        // Needs the room to pick a landing tile; see apply_effect_in_context
+        ItemEffect::Blink => "Nowhere to blink to".to_string(),
+        ItemEffect::Transmute { from, to, amount } => transmute(player, *from, *to, *amount),
+        ItemEffect::Lore(_) => "You read the fragment.".to_string(),
+        // Needs the room; see apply_effect_in_context
+        ItemEffect::Refactor => "Nothing to refactor here".to_string(),
+    }
+}
+
+/// Convert one resource into another, 1:1.
+///
+/// Only as much as the source can spare and the target can hold is
+/// traded: XP can't go below zero, HP never drops below 1, and HP and
+/// energy can't exceed their max.
+fn transmute(player: &mut Player, from: Resource, to: Resource, amount: i32) -> String {
+    let available = match from {
+        Resource::Xp => player.xp as i32,
+        Resource::Hp => player.hp - 1,
+        Resource::Energy => player.energy,
+    };
+    let capacity = match to {
+        Resource::Xp => i32::MAX,
+        Resource::Hp => player.max_hp - player.hp,
+        Resource::Energy => player.max_energy - player.energy,
+    };
+
+    let traded = amount.min(available).min(capacity).max(0);
+    if traded == 0 || from == to {
+        return format!("Nothing to transmute into {}", to.name());
+    }
+
+    match from {
+        Resource::Xp => player.xp -= traded as u32,
+        Resource::Hp => player.hp -= traded,
+        Resource::Energy => player.energy -= traded,
+    }
+    match to {
+        Resource::Xp => {
+            player.add_xp(traded as u32);
+        }
+        Resource::Hp => player.hp += traded,
+        Resource::Energy => player.energy += traded,
+    }
+
+    format!("Transmuted {} {} into {}", traded, from.name(), to.name())
+}
+
+/// Apply an item effect with access to the whole game.
+///
+/// Effects that touch the room or world are handled here; everything
+/// else falls through to `apply_effect` on the player.
+pub fn apply_effect_in_context(effect: &ItemEffect, state: &mut GameState) -> String {
+    match effect {
+        ItemEffect::Blink => blink(state),
+        ItemEffect::Refactor => refactor(state),
+        _ => apply_effect_with(effect, &mut state.player, state.heal_mode),
     }
 }
 
+/// Teleport the player to a random enemy-free tile in the current room.
+fn blink(state: &mut GameState) -> String {
+    let mut rng = GameRng::seed_from_u64(state.seed + state.turn as u64);
+    let here = (state.player.x, state.player.y);
+    let target = state.world.current().and_then(|room| {
+        let positions: Vec<_> = room
+            .get_free_positions()
+            .into_iter()
+            .filter(|&pos| pos != here)
+            .collect();
+        positions.choose(&mut rng).copied()
+    });
+
+    match target {
+        Some((x, y)) => {
+            state.player.x = x;
+            state.player.y = y;
+            state.update_fov();
+            "You blink to safety!".to_string()
+        }
+        None => "The panic button fizzles - nowhere to go.".to_string(),
+    }
+}
+
+/// Remove every enemy in the current room and leave one strong
+/// TechDebt on the free tile furthest from the player.
+///
+/// Removed enemies give no XP. If there is nowhere to put the debt,
+/// the room is simply cleared.
+fn refactor(state: &mut GameState) -> String {
+    let here = (state.player.x, state.player.y);
+    let Some(room) = state.world.current_mut() else {
+        return "Nothing to refactor here".to_string();
+    };
+    if room.enemies.is_empty() {
+        return "Nothing to refactor here".to_string();
+    }
+
+    let removed = room.enemies.len();
+    room.enemies.clear();
+
+    let target = room
+        .get_free_positions()
+        .into_iter()
+        .filter(|&pos| pos != here)
+        .max_by_key(|&(x, y)| ((x - here.0).abs() + (y - here.1).abs(), std::cmp::Reverse((y, x))));
+    let Some((x, y)) = target else {
+        room.cleared = true;
+        return format!("You refactor away {} enemies!", removed);
+    };
+
+    let mut debt = Enemy::new(EnemyType::TechDebt, x, y, "refactor");
+    debt.hp *= 2;
+    debt.max_hp *= 2;
+    debt.damage += 2;
+    room.enemies.push(debt);
+    room.cleared = false;
+
+    format!("You refactor away {} enemies, but Tech Debt piles up!", removed)
+}
+
 /// Calculate item rarity from lines changed.
 pub fn calculate_rarity(lines_changed: u32) -> Rarity {
     match lines_changed {
@@ -55,7 +183,13 @@ pub fn calculate_rarity(lines_changed: u32) -> Rarity {
 }
 
 /// Generate an item from commit data.
-pub fn generate_item(commit: &CommitData, rng: &mut impl Rng) -> Item {
+///
+/// Custom items from `items.toml` may take the place of the built-in one.
+pub fn generate_item(commit: &CommitData, rng: &mut GameRng) -> Item {
+    if let Some(item) = crate::config::item_registry().roll(rng) {
+        return item.from_commit(&commit.hash);
+    }
+
     let rarity = calculate_rarity(commit.lines_changed());
     let msg = commit.message.to_lowercase();
 
@@ -77,6 +211,18 @@ pub fn generate_item(commit: &CommitData, rng: &mut impl Rng) -> Item {
             Rarity::Legendary => 100,
         };
         ("Config Scroll".to_string(), ItemEffect::RestoreEnergy(energy), ItemType::Scroll)
+    } else if msg.contains("refactor") && rng.gen_ratio(1, 4) {
+        // Converting tech debt into something useful
+        if rarity >= Rarity::Rare {
+            let effect = ItemEffect::Transmute { from: Resource::Energy, to: Resource::Xp, amount: 40 };
+            ("Refactor Reactor".to_string(), effect, ItemType::Consumable)
+        } else {
+            let effect = ItemEffect::Transmute { from: Resource::Xp, to: Resource::Hp, amount: 25 };
+            ("Debt Converter".to_string(), effect, ItemType::Consumable)
+        }
+    } else if rarity >= Rarity::Rare && rng.gen_ratio(1, 10) {
+        // Big commits occasionally leave behind an escape hatch
+        ("Panic Button".to_string(), ItemEffect::Blink, ItemType::Consumable)
     } else {
         // Random effect
         let roll: u8 = rng.gen_range(0..3);
@@ -10,8 +10,22 @@ pub enum ItemType {
     Scroll,
 }
 
-/// Item rarity.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+impl ItemType {
+    /// Every item type, in a fixed order.
+    pub const ALL: [ItemType; 3] = [ItemType::Consumable, ItemType::Equipment, ItemType::Scroll];
+
+    /// ASCII symbol for this item type.
+    pub fn symbol(&self) -> char {
+        match self {
+            ItemType::Consumable => '!',
+            ItemType::Equipment => ')',
+            ItemType::Scroll => '?',
+        }
+    }
+}
+
+/// Item rarity, ordered from most to least common.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Rarity {
     Common,
     Uncommon,
@@ -19,6 +33,20 @@ pub enum Rarity {
     Legendary,
 }
 
+impl Rarity {
+    /// Every rarity, most common first.
+    pub const ALL: [Rarity; 4] = [Rarity::Common, Rarity::Uncommon, Rarity::Rare, Rarity::Legendary];
+
+    /// The next rarity up, capped at Legendary.
+    pub fn upgraded(self) -> Rarity {
+        match self {
+            Rarity::Common => Rarity::Uncommon,
+            Rarity::Uncommon => Rarity::Rare,
+            Rarity::Rare | Rarity::Legendary => Rarity::Legendary,
+        }
+    }
+}
+
 /// Stat that can be modified.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Stat {
@@ -26,6 +54,47 @@ pub enum Stat {
     MaxEnergy,
     Focus,
     Damage,
+    Stealth,
+}
+
+/// Player resource that can be traded by a Transmute item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Resource {
+    Xp,
+    Hp,
+    Energy,
+}
+
+impl Resource {
+    /// Display name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Resource::Xp => "XP",
+            Resource::Hp => "HP",
+            Resource::Energy => "energy",
+        }
+    }
+}
+
+/// How `ItemEffect::Heal` amounts are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealMode {
+    /// Heal exactly the listed HP.
+    #[default]
+    Absolute,
+    /// Heal the listed percentage of max HP, so heals keep up with level-ups.
+    Percent,
+}
+
+impl HealMode {
+    /// HP restored by a heal of `amount` for a player with `max_hp`.
+    pub fn heal_amount(self, amount: i32, max_hp: i32) -> i32 {
+        match self {
+            HealMode::Absolute => amount,
+            HealMode::Percent => (max_hp * amount / 100).max(1),
+        }
+    }
 }
 
 /// Effect an item can have.
@@ -36,4 +105,60 @@ pub enum ItemEffect {
     Damage(i32),
     Buff(Stat, i32, u32), // stat, amount, duration
     RevealMap,
+    /// Teleport to a random safe tile in the current room.
+    Blink,
+    /// Trade up to `amount` of one resource for the same amount of another.
+    Transmute { from: Resource, to: Resource, amount: i32 },
+    /// Text to read, such as a snippet of a commit's diff.
+    Lore(String),
+    /// Remove every enemy in the room, leaving one strong TechDebt behind.
+    Refactor,
+}
+
+impl ItemEffect {
+    /// Short description of what the effect does.
+    pub fn describe(&self) -> String {
+        match self {
+            ItemEffect::Heal(amount) => format!("restores {} HP", amount),
+            ItemEffect::RestoreEnergy(amount) => format!("restores {} energy", amount),
+            ItemEffect::Damage(amount) => format!("deals {} damage", amount),
+            ItemEffect::Buff(stat, amount, duration) => {
+                format!("+{} {:?} for {} turns", amount, stat, duration)
+            }
+            ItemEffect::RevealMap => "reveals the map".to_string(),
+            ItemEffect::Blink => "teleports you to a safe tile".to_string(),
+            ItemEffect::Transmute { from, to, amount } => {
+                format!("trades {} {} for {}", amount, from.name(), to.name())
+            }
+            ItemEffect::Lore(_) => "can be read".to_string(),
+            ItemEffect::Refactor => "clears the room, but leaves Tech Debt".to_string(),
+        }
+    }
+
+    /// What the item does when thrown at an enemy, or `None` if it can't
+    /// be thrown. Only damage items hurt; potions shatter and scrolls
+    /// scatter, using the item up for nothing. Gear and lore stay in the
+    /// pack. Throwing should go through here so new items get a thrown
+    /// behavior in one place.
+    pub fn thrown_effect(&self) -> Option<ThrownEffect> {
+        match self {
+            ItemEffect::Damage(amount) => Some(ThrownEffect::Damage(*amount)),
+            ItemEffect::Heal(_)
+            | ItemEffect::RestoreEnergy(_)
+            | ItemEffect::RevealMap
+            | ItemEffect::Blink
+            | ItemEffect::Transmute { .. }
+            | ItemEffect::Refactor => Some(ThrownEffect::Wasted),
+            ItemEffect::Buff(..) | ItemEffect::Lore(_) => None,
+        }
+    }
+}
+
+/// What a thrown item does where it lands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrownEffect {
+    /// Hits the target for this much damage.
+    Damage(i32),
+    /// Lands harmlessly and is used up.
+    Wasted,
 }
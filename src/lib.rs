@@ -10,6 +10,7 @@ pub mod fov;
 pub mod game;
 pub mod git;
 pub mod item;
+pub mod rng;
 pub mod ui;
 pub mod weather;
 pub mod world;
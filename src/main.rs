@@ -2,10 +2,14 @@
 
 use std::path::PathBuf;
 
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand, ValueEnum};
 
-use penumbra::cli;
+use penumbra::cli::{self, ClassChoice, DifficultyChoice};
 use penumbra::entity::PlayerClass;
+use penumbra::game::{check_slot, Difficulty, VictoryCondition};
+use penumbra::git::CommitWindow;
+use penumbra::world::{GenerationOptions, RoomGrouping};
 
 /// Player class for CLI parsing.
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -14,19 +18,66 @@ enum CliPlayerClass {
     MeetingSurvivor,
     InboxKnight,
     Wanderer,
+    /// Roll among unlocked classes from the seed
+    Random,
 }
 
-impl From<CliPlayerClass> for PlayerClass {
-    fn from(c: CliPlayerClass) -> Self {
-        match c {
-            CliPlayerClass::CodeWarrior => PlayerClass::CodeWarrior,
-            CliPlayerClass::MeetingSurvivor => PlayerClass::MeetingSurvivor,
-            CliPlayerClass::InboxKnight => PlayerClass::InboxKnight,
-            CliPlayerClass::Wanderer => PlayerClass::Wanderer,
-        }
+/// Turn the optional CLI class into a class choice.
+fn class_choice(class: Option<CliPlayerClass>) -> ClassChoice {
+    match class {
+        None => ClassChoice::Auto,
+        Some(CliPlayerClass::CodeWarrior) => ClassChoice::Fixed(PlayerClass::CodeWarrior),
+        Some(CliPlayerClass::MeetingSurvivor) => ClassChoice::Fixed(PlayerClass::MeetingSurvivor),
+        Some(CliPlayerClass::InboxKnight) => ClassChoice::Fixed(PlayerClass::InboxKnight),
+        Some(CliPlayerClass::Wanderer) => ClassChoice::Fixed(PlayerClass::Wanderer),
+        Some(CliPlayerClass::Random) => ClassChoice::Random,
+    }
+}
+
+/// Difficulty for CLI parsing.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliDifficulty {
+    Easy,
+    Normal,
+    Hard,
+    /// Ease up or ramp up based on your recent wins and losses
+    Auto,
+}
+
+/// Turn the CLI difficulty into a difficulty choice.
+fn difficulty_choice(difficulty: CliDifficulty) -> DifficultyChoice {
+    match difficulty {
+        CliDifficulty::Easy => DifficultyChoice::Fixed(Difficulty::Easy),
+        CliDifficulty::Normal => DifficultyChoice::Fixed(Difficulty::Normal),
+        CliDifficulty::Hard => DifficultyChoice::Fixed(Difficulty::Hard),
+        CliDifficulty::Auto => DifficultyChoice::Auto,
     }
 }
 
+/// Room grouping for CLI parsing.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliGroupBy {
+    /// One room per day
+    Date,
+    /// One room per top-level directory
+    Dir,
+}
+
+/// Turn the CLI grouping into a room grouping.
+fn room_grouping(group_by: CliGroupBy) -> RoomGrouping {
+    match group_by {
+        CliGroupBy::Date => RoomGrouping::Date,
+        CliGroupBy::Dir => RoomGrouping::Directory,
+    }
+}
+
+/// Reject bad slot names up front, rather than losing the save at the
+/// end of the run.
+fn parse_slot(slot: &str) -> Result<String, String> {
+    check_slot(slot).map_err(|e| e.to_string())?;
+    Ok(slot.to_string())
+}
+
 #[derive(Parser)]
 #[command(name = "penumbra")]
 #[command(about = "A roguelike where dungeons generate from your git history")]
@@ -88,6 +139,14 @@ enum Commands {
         #[arg(long, default_value = "30")]
         days: u32,
 
+        /// Only use commits from this date on, YYYY-MM-DD (git only)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Only use commits up to this date, YYYY-MM-DD (git only)
+        #[arg(long)]
+        until: Option<NaiveDate>,
+
         /// RNG seed for reproducibility
         #[arg(long)]
         seed: Option<u64>,
@@ -95,24 +154,158 @@ enum Commands {
         /// Player class
         #[arg(long, value_enum)]
         class: Option<CliPlayerClass>,
+
+        /// Victory condition: end, endless, rooms:N or turns:N
+        #[arg(long, default_value = "end")]
+        goal: VictoryCondition,
+
+        /// Enemies from frustrated commit messages hit harder (git only)
+        #[arg(long)]
+        frustration: bool,
+
+        /// Enemy toughness (git only)
+        #[arg(long, value_enum, default_value = "normal")]
+        difficulty: CliDifficulty,
+
+        /// Keep only the most recent N days as rooms (git only)
+        #[arg(long)]
+        max_rooms: Option<usize>,
+
+        /// Keep only the N days with the most lines changed, in date order (git only)
+        #[arg(long, value_name = "N")]
+        top_days: Option<usize>,
+
+        /// Start at the most recent day and descend into the past (git only)
+        #[arg(long)]
+        reverse: bool,
+
+        /// Hide a bug-introducing room among the days; find and accuse it (git only)
+        #[arg(long)]
+        bisect: bool,
+
+        /// Merge rooms split into two branches you must walk before leaving (git only)
+        #[arg(long)]
+        merge_puzzle: bool,
+
+        /// Commits that mostly delete code also spawn fast, frail Deletion swarms (git only)
+        #[arg(long)]
+        deletion_swarms: bool,
+
+        /// Doors link rooms from days that touched the same files (git only)
+        #[arg(long)]
+        shortcuts: bool,
+
+        /// Rooms drop Diff Fragments you can read, with snippets of real changes (git only)
+        #[arg(long)]
+        diff_lore: bool,
+
+        /// Theme the first room after the file you edit most (git only)
+        #[arg(long)]
+        themed_start: bool,
+
+        /// Guarantee a boss room at least every N rooms (git only)
+        #[arg(long, value_name = "N")]
+        boss_every: Option<usize>,
+
+        /// Make each room a day of commits or a top-level directory (git only)
+        #[arg(long, value_enum, default_value = "date")]
+        group_by: CliGroupBy,
+
+        /// Make each room N commits in date order, ignoring days (git only)
+        #[arg(long, value_name = "N", conflicts_with = "group_by")]
+        chunk_size: Option<usize>,
+
+        /// Start N rooms in, with the rooms before counted as cleared (git only)
+        #[arg(long, value_name = "N", default_value = "0")]
+        skip_rooms: usize,
+
+        /// Start with a tutorial room, even after your first victory (git only)
+        #[arg(long, conflicts_with = "no_tutorial")]
+        tutorial: bool,
+
+        /// Skip the tutorial room you start in until your first victory (git only)
+        #[arg(long)]
+        no_tutorial: bool,
+
+        /// Save slot for this run, so runs for different repos don't overwrite each other
+        #[arg(long, default_value = "default", value_parser = parse_slot)]
+        slot: String,
+
+        /// Enable debug commands on '`'; the run is kept out of history (git only)
+        #[arg(long, hide = true)]
+        debug: bool,
+
+        /// Stream game events as JSON lines to this file, or '-' for stderr (git only)
+        #[arg(long, value_name = "PATH")]
+        emit_events: Option<PathBuf>,
+
+        /// Browse nearby seeds and their dungeons before playing (git only)
+        #[arg(long)]
+        choose_seed: bool,
+
+        /// Detect your class from this author's commits only, unless --class is given (git only)
+        #[arg(long, value_name = "AUTHOR")]
+        as_author: Option<String>,
     },
 
     /// Continue saved game
-    Continue,
+    Continue {
+        /// Save slot to continue from
+        #[arg(long, default_value = "default", value_parser = parse_slot)]
+        slot: String,
+    },
+
+    /// Preview the rooms a git history would generate, with a difficulty
+    /// estimate for each
+    Inspect {
+        /// Path to git repository
+        #[arg(long, default_value = ".")]
+        git: PathBuf,
+
+        /// Days of history to use
+        #[arg(long, default_value = "30")]
+        days: u32,
+
+        /// Only use commits from this date on, YYYY-MM-DD
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Only use commits up to this date, YYYY-MM-DD
+        #[arg(long)]
+        until: Option<NaiveDate>,
+
+        /// RNG seed, to preview the dungeon `play --seed` will build
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Keep only the most recent N days as rooms
+        #[arg(long)]
+        max_rooms: Option<usize>,
+
+        /// Guarantee a boss room at least every N rooms
+        #[arg(long, value_name = "N")]
+        boss_every: Option<usize>,
+    },
 
     /// Show past runs
     History,
+
+    /// Rank commit authors by the enemies you've defeated from their commits
+    Leaderboard,
 }
 
 fn main() {
     let cli = Cli::parse();
+    penumbra::config::install_enemy_config(penumbra::config::load_enemy_config());
+    penumbra::config::install_item_registry(penumbra::config::load_item_registry());
+    penumbra::ui::install_palette(penumbra::ui::load_palette());
 
     let result = match cli.command {
-        Commands::Play { git, calendar, email, imap, imap_user, imap_port, imap_folder, imap_limit, weather_city, weather_lat, weather_lon, days, seed, class } => {
+        Commands::Play { git, calendar, email, imap, imap_user, imap_port, imap_folder, imap_limit, weather_city, weather_lat, weather_lon, days, since, until, seed, class, goal, frustration, difficulty, max_rooms, top_days, reverse, bisect, merge_puzzle, deletion_swarms, shortcuts, diff_lore, themed_start, boss_every, group_by, chunk_size, skip_rooms, tutorial, no_tutorial, slot, debug, emit_events, choose_seed, as_author } => {
             if let Some(cal_path) = calendar {
-                cli::play_calendar(&cal_path, days, seed, class.map(|c| c.into()))
+                cli::play_calendar(&cal_path, days, seed, class_choice(class), goal, &slot)
             } else if let Some(email_path) = email {
-                cli::play_email(&email_path, seed, class.map(|c| c.into()))
+                cli::play_email(&email_path, seed, class_choice(class), goal, &slot)
             } else if let Some(imap_host) = imap {
                 let imap_config = penumbra::email::ImapConfig {
                     host: imap_host,
@@ -122,21 +315,52 @@ fn main() {
                     folder: imap_folder,
                     use_tls: true,
                 };
-                cli::play_imap(&imap_config, imap_limit, seed, class.map(|c| c.into()))
+                cli::play_imap(&imap_config, imap_limit, seed, class_choice(class), goal, &slot)
             } else if let Some(city) = weather_city {
-                cli::play_weather_city(&city, seed, class.map(|c| c.into()))
+                cli::play_weather_city(&city, seed, class_choice(class), goal, &slot)
             } else if let (Some(lat), Some(lon)) = (weather_lat, weather_lon) {
-                cli::play_weather_coords(lat, lon, seed, class.map(|c| c.into()))
+                cli::play_weather_coords(lat, lon, seed, class_choice(class), goal, &slot)
             } else {
-                cli::play(&git, days, seed, class.map(|c| c.into()))
+                let generation = GenerationOptions {
+                    max_rooms,
+                    top_days,
+                    reverse,
+                    bisect,
+                    merge_puzzle,
+                    deletion_swarms,
+                    tutorial: cli::wants_tutorial(tutorial, no_tutorial),
+                    shortcuts,
+                    diff_lore,
+                    themed_start,
+                    boss_every,
+                    group_by: chunk_size.map_or(room_grouping(group_by), RoomGrouping::Chunks),
+                    skip_rooms,
+                    // Read from the gameplay settings
+                    max_enemies_per_room: None,
+                    simple_layout: false,
+                    conflict_markers: false,
+                    squash_aware: false,
+                };
+                cli::play(&git, CommitWindow::new(days, since, until), seed, class_choice(class), goal, frustration, difficulty_choice(difficulty), &generation, &slot, debug, emit_events.as_deref(), choose_seed, as_author.as_deref())
             }
         }
-        Commands::Continue => {
-            cli::continue_game()
+        Commands::Continue { slot } => {
+            cli::continue_game(&slot)
+        }
+        Commands::Inspect { git, days, since, until, seed, max_rooms, boss_every } => {
+            let generation = GenerationOptions {
+                max_rooms,
+                boss_every,
+                ..GenerationOptions::default()
+            };
+            cli::inspect(&git, CommitWindow::new(days, since, until), seed, &generation)
         }
         Commands::History => {
             cli::show_history()
         }
+        Commands::Leaderboard => {
+            cli::show_leaderboard()
+        }
     };
 
     if let Err(e) = result {
@@ -7,17 +7,151 @@ use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 use ratatui::prelude::*;
 use ratatui::Terminal;
 
-use crate::combat::PlayerAction;
-use crate::game::GameState;
-use crate::world::Direction;
+use crate::combat::{find_path, preview_attack_with_variance, PlayerAction};
+use crate::game::{turn_summary, GameEvent, GameState, Progression};
+use crate::git::pseudonymize;
+use crate::entity::{Enemy, EnemyType};
+use crate::item::{Item, ItemEffect, ItemType, Rarity};
+use crate::world::{Direction, RoomSummary, RoomType};
+
+use super::{key_to_direction, Cursor, EventSink, SpeedrunTimer};
+
+/// Builds a fresh game from the same data for a given seed, so a run can
+/// be restarted from the game-over screen.
+pub type NewRun = Box<dyn Fn(u64) -> GameState>;
+
+/// A seed for restarting with a new dungeon, never the last run's.
+fn fresh_seed(previous: u64) -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    let seed = now ^ previous.rotate_left(17);
+    if seed == previous {
+        seed.wrapping_add(1)
+    } else {
+        seed
+    }
+}
+
+/// Enemies spawned by the debug keys 1 to 6.
+const DEBUG_ENEMIES: [EnemyType; 6] = [
+    EnemyType::Bug,
+    EnemyType::Regression,
+    EnemyType::TechDebt,
+    EnemyType::MergeConflict,
+    EnemyType::ScopeCreep,
+    EnemyType::Deletion,
+];
+
+/// Most queued key presses handled before the screen is redrawn.
+const MAX_KEYS_PER_FRAME: usize = 32;
+
+/// Turns a room banner stays up after entering a special room. It shows
+/// dimmed on its last turn.
+pub const BANNER_TURNS: u32 = 2;
+
+/// How the dungeon map colors rooms, like `git blame` for the dungeon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlameMode {
+    /// By where you've been.
+    #[default]
+    Off,
+    /// By the author of most of the day's commits.
+    Author,
+    /// By the day's age, older rooms darker.
+    Age,
+}
+
+impl BlameMode {
+    /// The mode after this one, wrapping back to Off.
+    pub fn next(self) -> Self {
+        match self {
+            BlameMode::Off => BlameMode::Author,
+            BlameMode::Author => BlameMode::Age,
+            BlameMode::Age => BlameMode::Off,
+        }
+    }
+
+    /// Display name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BlameMode::Off => "Explored",
+            BlameMode::Author => "Blame: author",
+            BlameMode::Age => "Blame: age",
+        }
+    }
+}
 
 /// Application state.
 pub struct App {
     pub state: GameState,
     pub show_help: bool,
+    /// Whether the glyph legend is open.
+    pub show_legend: bool,
+    /// Whether the dungeon map is open.
+    pub show_map: bool,
+    /// How the dungeon map colors rooms.
+    pub blame: BlameMode,
     pub show_inventory: bool,
     pub selected_item: usize,
+    /// Lore item open for reading.
+    pub reading: Option<Item>,
+    /// First line shown of the item being read.
+    pub reading_scroll: u16,
     pub attack_mode: bool,
+    /// Whether the player is picking a target tile.
+    pub targeting: bool,
+    /// Map cursor, present while a tile is being picked.
+    pub cursor: Option<Cursor>,
+    /// Last confirmed target tile.
+    pub target: Option<(i32, i32)>,
+    /// Whether the player is examining tiles with the cursor.
+    pub inspecting: bool,
+    /// Show commit authors as pseudonyms.
+    pub anonymize_authors: bool,
+    /// Color enemies by their commit's author.
+    pub color_by_author: bool,
+    /// Turns for remembered tiles to fade into the fog. `None` hides
+    /// tiles as soon as they leave view.
+    pub fog_memory: Option<u32>,
+    /// Draw a path hint to the exit once the room is cleared.
+    pub show_exit_hint: bool,
+    /// Preview the next room from beside a cleared room's exit.
+    pub peek_next_room: bool,
+    /// Waiting for the player to confirm a bisect accusation.
+    pub confirm_accuse: bool,
+    /// Waiting for the player to pick a room-clear reward.
+    pub reward_choice: bool,
+    /// Offer to walk straight to the next room once one is cleared.
+    pub auto_advance: bool,
+    /// Percent of max HP below which the player is warned. Zero is off.
+    pub low_hp_warning: u32,
+    /// Whether the player has been warned since HP last fell low.
+    pub low_hp_warned: bool,
+    /// Waiting for the player to confirm moving on to the next room.
+    pub confirm_advance: bool,
+    /// One-line recap of the last turn.
+    pub turn_summary: String,
+    /// Banner for the special room just entered, if one is showing.
+    pub banner: Option<&'static str>,
+    /// Turns left before the banner disappears.
+    pub banner_turns: u32,
+    /// Real-time clock for speedruns, when enabled.
+    pub speedrun: Option<SpeedrunTimer>,
+    /// Progression as it stood when the run began, so the game-over
+    /// screen can show the records the run sets.
+    pub progression: Option<Progression>,
+    /// Rebuilds the run for a restart. Without it, restarting is off.
+    pub new_run: Option<NewRun>,
+    /// Seed picked for a restart from the game-over screen.
+    pub restart_seed: Option<u64>,
+    /// Whether debug commands can be opened with '`'.
+    pub debug: bool,
+    /// Waiting for a debug command.
+    pub debug_mode: bool,
+    /// Where each turn's events are streamed as JSON lines, if anywhere.
+    pub event_sink: Option<EventSink>,
     pub quit: bool,
 }
 
@@ -27,25 +161,421 @@ impl App {
         Self {
             state,
             show_help: false,
+            show_legend: false,
+            show_map: false,
+            blame: BlameMode::default(),
             show_inventory: false,
             selected_item: 0,
+            reading: None,
+            reading_scroll: 0,
             attack_mode: false,
+            targeting: false,
+            cursor: None,
+            target: None,
+            inspecting: false,
+            anonymize_authors: false,
+            color_by_author: false,
+            fog_memory: None,
+            show_exit_hint: false,
+            peek_next_room: false,
+            confirm_accuse: false,
+            reward_choice: false,
+            auto_advance: false,
+            low_hp_warning: 0,
+            low_hp_warned: false,
+            confirm_advance: false,
+            turn_summary: String::new(),
+            banner: None,
+            banner_turns: 0,
+            speedrun: None,
+            progression: None,
+            new_run: None,
+            restart_seed: None,
+            debug: false,
+            debug_mode: false,
+            event_sink: None,
             quit: false,
         }
     }
 
+    /// Start over with a fresh game for `seed`, keeping display settings
+    /// and the speedrun best. Returns false if restarting is off.
+    pub fn restart(&mut self, seed: u64) -> bool {
+        let Some(new_run) = self.new_run.take() else {
+            return false;
+        };
+        let speedrun = self.speedrun.as_ref().map(|timer| {
+            let best = match timer.best {
+                Some(best) if self.state.victory => Some(best.min(timer.elapsed())),
+                None if self.state.victory => Some(timer.elapsed()),
+                best => best,
+            };
+            SpeedrunTimer::new(best)
+        });
+
+        let fresh = App::new(new_run(seed));
+        *self = App {
+            anonymize_authors: self.anonymize_authors,
+            color_by_author: self.color_by_author,
+            fog_memory: self.fog_memory,
+            peek_next_room: self.peek_next_room,
+            auto_advance: self.auto_advance,
+            low_hp_warning: self.low_hp_warning,
+            debug: self.debug,
+            blame: self.blame,
+            event_sink: self.event_sink.take(),
+            speedrun,
+            new_run: Some(new_run),
+            ..fresh
+        };
+        true
+    }
+
+    /// Run the player's action and the enemy phase, then recap the turn.
+    pub fn take_turn(&mut self, action: PlayerAction) {
+        let mut events = self.state.process_action(action);
+        events.extend(self.state.process_enemies());
+        self.turn_summary = turn_summary(&events);
+        self.announce(&events);
+
+        for event in &events {
+            if let GameEvent::RoomCleared { room_id } = event {
+                if self.state.offer_clear_reward(*room_id) {
+                    self.reward_choice = true;
+                } else {
+                    self.offer_advance();
+                }
+            }
+        }
+
+        if let Some(timer) = self.speedrun.as_mut() {
+            for event in &events {
+                if let GameEvent::RoomCleared { room_id } = event {
+                    timer.clear_room(*room_id);
+                }
+            }
+            if self.state.game_over {
+                timer.finish();
+            }
+        }
+        self.check_low_hp();
+    }
+
+    /// Pass events on to the room banner and the event stream.
+    fn announce(&mut self, events: &[GameEvent]) {
+        self.update_banner(events);
+        if let Some(sink) = self.event_sink.as_mut() {
+            // A broken stream shouldn't end the run; stop streaming instead
+            if sink.emit(events).is_err() {
+                self.event_sink = None;
+            }
+        }
+    }
+
+    /// Age the room banner by a turn, and raise a new one when a turn's
+    /// events enter a special room.
+    pub fn update_banner(&mut self, events: &[GameEvent]) {
+        self.banner_turns = self.banner_turns.saturating_sub(1);
+        if self.banner_turns == 0 {
+            self.banner = None;
+        }
+
+        for event in events {
+            if let GameEvent::RoomEntered { room_id } = event {
+                let room_type = self.state.world.rooms.get(*room_id).map(|room| room.room_type);
+                self.banner = room_type.and_then(|t| t.banner());
+                self.banner_turns = if self.banner.is_some() { BANNER_TURNS } else { 0 };
+            }
+        }
+    }
+
+    /// Whether HP is below the low-HP warning threshold.
+    pub fn is_low_hp(&self) -> bool {
+        let player = &self.state.player;
+        self.low_hp_warning > 0
+            && player.hp > 0
+            && (player.hp as i64) * 100 < player.max_hp as i64 * self.low_hp_warning as i64
+    }
+
+    /// Warn once when HP falls below the threshold. The warning re-arms
+    /// once HP climbs back above it. Returns whether a warning was logged.
+    pub fn check_low_hp(&mut self) -> bool {
+        if !self.is_low_hp() {
+            self.low_hp_warned = false;
+            return false;
+        }
+        if self.low_hp_warned {
+            return false;
+        }
+        self.low_hp_warned = true;
+        let player = &self.state.player;
+        let message = format!("Warning: HP is low ({}/{}). Heal or retreat!", player.hp, player.max_hp);
+        self.state.log(message);
+        true
+    }
+
+    /// Number of lines in the lore item being read.
+    pub fn reading_lines(&self) -> usize {
+        match self.reading.as_ref().map(|item| &item.effect) {
+            Some(ItemEffect::Lore(text)) => text.lines().count(),
+            _ => 0,
+        }
+    }
+
+    /// Enter targeting mode with the cursor on the nearest visible enemy,
+    /// or on the player if no enemy is in sight.
+    pub fn start_targeting(&mut self) {
+        let origin = (self.state.player.x, self.state.player.y);
+        let nearest = self.state.world.current().and_then(|room| {
+            room.enemies
+                .iter()
+                .filter(|e| self.state.visible_tiles.contains(&(e.x, e.y)))
+                .min_by_key(|e| (e.x - origin.0).abs() + (e.y - origin.1).abs())
+                .map(|e| (e.x, e.y))
+        });
+
+        let (x, y) = nearest.unwrap_or(origin);
+        self.cursor = Some(Cursor::new(x, y));
+        self.targeting = true;
+    }
+
+    /// Leave targeting mode without picking a target.
+    pub fn cancel_targeting(&mut self) {
+        self.targeting = false;
+        self.cursor = None;
+    }
+
+    /// Move the cursor, keeping it on visible tiles.
+    pub fn move_cursor(&mut self, dx: i32, dy: i32) {
+        if let Some(cursor) = self.cursor.as_mut() {
+            cursor.step(dx, dy, &self.state.visible_tiles);
+        }
+    }
+
+    /// Confirm the tile under the cursor as the target.
+    pub fn confirm_target(&mut self) -> Option<(i32, i32)> {
+        let (x, y) = self.cursor.map(|c| c.position())?;
+        if !self.state.visible_tiles.contains(&(x, y)) {
+            return None;
+        }
+
+        let enemy_type = self
+            .state
+            .world
+            .current()
+            .and_then(|room| room.get_enemy_at(x, y))
+            .map(|e| e.enemy_type);
+        match enemy_type {
+            Some(enemy_type) => self.state.log(format!("You take aim at the {:?}.", enemy_type)),
+            None => self.state.log("You mark the spot."),
+        }
+
+        self.target = Some((x, y));
+        self.cancel_targeting();
+        Some((x, y))
+    }
+
+    /// Enter inspect mode with the cursor on the player.
+    pub fn start_inspecting(&mut self) {
+        self.cursor = Some(Cursor::new(self.state.player.x, self.state.player.y));
+        self.inspecting = true;
+    }
+
+    /// Leave inspect mode.
+    pub fn stop_inspecting(&mut self) {
+        self.inspecting = false;
+        self.cursor = None;
+    }
+
+    /// Describe the tile under the cursor.
+    /// Only visible tiles are described.
+    pub fn inspect_details(&self) -> Vec<String> {
+        let Some((x, y)) = self.cursor.map(|c| c.position()) else {
+            return Vec::new();
+        };
+        if !self.state.visible_tiles.contains(&(x, y)) {
+            return vec!["You can't see there.".to_string()];
+        }
+        let Some(room) = self.state.world.current() else {
+            return Vec::new();
+        };
+
+        let mut details = Vec::new();
+        if let Some(tile) = room.get_tile(x, y) {
+            details.push(format!("Tile: {}", tile.name()));
+        }
+
+        if self.state.player.x == x && self.state.player.y == y {
+            details.push("You are here.".to_string());
+        } else if let Some(enemy) = room.get_enemy_at(x, y) {
+            details.push(format!(
+                "Enemy: {:?} ({})  HP {}/{}  DMG {}",
+                enemy.enemy_type,
+                enemy.symbol(),
+                enemy.hp,
+                enemy.max_hp,
+                enemy.damage
+            ));
+            if let Some(title) = &enemy.title {
+                details.push(format!("Mini-boss: \"{}\"", title));
+            }
+            if let Some(commit) = room.source_commits.iter().find(|c| c.hash == enemy.source_commit) {
+                let subject = commit.message.lines().next().unwrap_or("").trim();
+                details.push(format!("From \"{}\" by {}", subject, self.display_author(&commit.author)));
+            }
+        }
+
+        if let Some(item) = room.get_item_at(x, y) {
+            details.push(format!(
+                "Item: {} ({:?}) - {}",
+                item.name,
+                item.rarity,
+                item.effect.describe()
+            ));
+        }
+
+        details
+    }
+
+    /// An author's name as it should be shown, pseudonymized if enabled.
+    pub fn display_author(&self, name: &str) -> String {
+        if self.anonymize_authors {
+            pseudonymize(name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Tiles between the player and the exit, when the hint is on and
+    /// the room is cleared. Excludes the player's tile and the exit.
+    pub fn exit_hint(&self) -> Vec<(i32, i32)> {
+        if !self.show_exit_hint {
+            return Vec::new();
+        }
+        let Some(room) = self.state.world.current() else {
+            return Vec::new();
+        };
+        if !room.is_cleared() {
+            return Vec::new();
+        }
+        let Some(exit) = room.exit_position() else {
+            return Vec::new();
+        };
+
+        let from = (self.state.player.x, self.state.player.y);
+        match find_path(from, exit, room) {
+            Some(path) if path.len() > 2 => path[1..path.len() - 1].to_vec(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Summary of the next room, shown while the player stands beside
+    /// the exit of a cleared room with peeking on.
+    pub fn next_room_peek(&self) -> Option<RoomSummary> {
+        if !self.peek_next_room {
+            return None;
+        }
+        let room = self.state.world.current()?;
+        if !room.is_cleared() {
+            return None;
+        }
+        let (ex, ey) = room.exit_position()?;
+        let (px, py) = (self.state.player.x, self.state.player.y);
+        if (ex - px).abs().max((ey - py).abs()) > 1 {
+            return None;
+        }
+        self.state.world.peek_next()
+    }
+
+    /// Expected outcome of attacking, for the attack mode bar.
+    ///
+    /// Shows hit chance and the least damage a hit deals (the most on a
+    /// crit), then each adjacent enemy's HP and whether a hit or crit
+    /// could kill it. Everything comes from `preview_attack_with_variance`,
+    /// so wards and damage variance are accounted for.
+    pub fn attack_preview(&self) -> String {
+        let player = &self.state.player;
+        let variance = self.state.damage_variance;
+        // Header figures are against an ordinary, unwarded enemy
+        let plain = preview_attack_with_variance(player, &Enemy::new(EnemyType::Bug, 0, 0, ""), variance);
+
+        let mut preview = format!(
+            "Hit {}% | Dmg {} ({} on {}% crit)",
+            (plain.hit_chance * 100.0).round() as i32,
+            plain.min_damage,
+            plain.max_damage,
+            (plain.crit_chance * 100.0).round() as i32
+        );
+
+        let Some(room) = self.state.world.current() else {
+            return preview;
+        };
+        for (label, dir) in [
+            ('N', Direction::North),
+            ('S', Direction::South),
+            ('E', Direction::East),
+            ('W', Direction::West),
+        ] {
+            let (dx, dy) = dir.delta();
+            if let Some(enemy) = room.get_enemy_at(player.x + dx, player.y + dy) {
+                let attack = preview_attack_with_variance(player, enemy, variance);
+                let outcome = if attack.min_damage >= enemy.hp {
+                    " lethal"
+                } else if attack.max_damage >= enemy.hp {
+                    " lethal on crit"
+                } else {
+                    ""
+                };
+                preview.push_str(&format!(
+                    " | {}: {} {}HP{}",
+                    label,
+                    enemy.symbol(),
+                    enemy.hp,
+                    outcome
+                ));
+            }
+        }
+
+        preview
+    }
+
+    /// Check if a tile holds a valid target (a visible enemy).
+    pub fn is_valid_target(&self, x: i32, y: i32) -> bool {
+        self.state.visible_tiles.contains(&(x, y))
+            && self
+                .state
+                .world
+                .current()
+                .is_some_and(|room| room.get_enemy_at(x, y).is_some())
+    }
+
     /// Run the main event loop.
     pub fn run(&mut self, terminal: &mut Terminal<impl Backend>) -> io::Result<()> {
         while !self.quit && !self.state.game_over {
             terminal.draw(|frame| super::render(frame, self))?;
 
             if event::poll(Duration::from_millis(100))? {
-                if let Event::Key(key) = event::read()? {
-                    self.handle_input(key);
+                // Take everything already queued so quick presses aren't
+                // dropped, redrawing between bursts
+                let mut keys = Vec::new();
+                while keys.len() < MAX_KEYS_PER_FRAME {
+                    if let Event::Key(key) = event::read()? {
+                        keys.push(key);
+                    }
+                    if !event::poll(Duration::ZERO)? {
+                        break;
+                    }
                 }
+                self.handle_inputs(keys);
             }
         }
 
+        // Keep the clock's readings so a saved run resumes its time
+        if let Some(timer) = self.speedrun.as_mut() {
+            timer.finish();
+            self.state.speedrun = timer.progress();
+        }
+
         // Show final screen
         if self.state.game_over {
             terminal.draw(|frame| super::render(frame, self))?;
@@ -56,7 +586,13 @@ impl App {
                         break;
                     }
                     if key.code == KeyCode::Char('r') {
-                        // Could restart here
+                        if self.new_run.is_some() {
+                            self.restart_seed = Some(self.state.seed);
+                        }
+                        break;
+                    }
+                    if key.code == KeyCode::Char('n') && self.new_run.is_some() {
+                        self.restart_seed = Some(fresh_seed(self.state.seed));
                         break;
                     }
                 }
@@ -66,6 +602,75 @@ impl App {
         Ok(())
     }
 
+    /// Ask to move on to the next room when auto-advance is on and the
+    /// current room is done with. Boss loot still on the floor keeps you
+    /// there to pick it up.
+    fn offer_advance(&mut self) {
+        let ready = self.state.world.current().is_some_and(|room| {
+            let loot_left = room.room_type == RoomType::Boss && !room.items.is_empty();
+            room.is_cleared() && room.exit_position().is_some() && !loot_left
+        });
+        self.confirm_advance = self.auto_advance && ready;
+    }
+
+    /// Handle a burst of key presses in order. Stops once the game ends or
+    /// the player quits, so the rest of the burst can't act on the final
+    /// screen.
+    pub fn handle_inputs(&mut self, keys: impl IntoIterator<Item = KeyEvent>) {
+        for key in keys {
+            if self.quit || self.state.game_over {
+                break;
+            }
+            self.handle_input(key);
+        }
+    }
+
+    /// Run a debug command: g god mode, r reveal map, w warp to the next
+    /// room, 1-6 spawn an enemy, i spawn a potion.
+    fn handle_debug_command(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('g') => {
+                self.state.toggle_god_mode();
+            }
+            KeyCode::Char('r') => {
+                self.state.toggle_reveal_map();
+            }
+            KeyCode::Char('w') => {
+                let room = self.state.world.current_room;
+                let events = self.state.debug_warp();
+                if !events.is_empty() {
+                    if let Some(timer) = self.speedrun.as_mut() {
+                        timer.clear_room(room);
+                    }
+                    self.announce(&events);
+                }
+            }
+            KeyCode::Char(c @ '1'..='6') => {
+                let enemy_type = DEBUG_ENEMIES[c as usize - '1' as usize];
+                self.state.debug_spawn_enemy(enemy_type);
+            }
+            KeyCode::Char('i') => {
+                let potion = Item::new("Debug Potion", ItemType::Consumable, ItemEffect::Heal(100), Rarity::Legendary);
+                self.state.debug_give_item(potion);
+            }
+            _ => {}
+        }
+    }
+
+    /// Use an inventory item, or open it for reading if it's lore.
+    fn use_item(&mut self, index: usize) {
+        let Some(item) = self.state.player.inventory.get(index) else {
+            return;
+        };
+        // Reading is free and keeps the item
+        if matches!(item.effect, ItemEffect::Lore(_)) {
+            self.reading = Some(item.clone());
+            self.reading_scroll = 0;
+        } else {
+            self.take_turn(PlayerAction::UseItem(index));
+        }
+    }
+
     /// Handle a key event.
     pub fn handle_input(&mut self, key: KeyEvent) {
         // Check for quit
@@ -82,6 +687,39 @@ impl App {
             return;
         }
 
+        // Legend overlay
+        if self.show_legend {
+            if matches!(key.code, KeyCode::Esc | KeyCode::Char('L')) {
+                self.show_legend = false;
+            }
+            return;
+        }
+
+        // Dungeon map overlay
+        if self.show_map {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('M') => self.show_map = false,
+                KeyCode::Char('b') => self.blame = self.blame.next(),
+                _ => {}
+            }
+            return;
+        }
+
+        // Reading a lore item
+        if self.reading.is_some() {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.reading = None,
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.reading_scroll = self.reading_scroll.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') if (self.reading_scroll as usize) + 1 < self.reading_lines() => {
+                    self.reading_scroll += 1;
+                }
+                _ => {}
+            }
+            return;
+        }
+
         // Inventory overlay
         if self.show_inventory {
             match key.code {
@@ -98,12 +736,15 @@ impl App {
                         self.selected_item += 1;
                     }
                 }
-                KeyCode::Enter => {
-                    if self.selected_item < self.state.player.inventory.len() {
-                        let action = PlayerAction::UseItem(self.selected_item);
-                        self.state.process_action(action);
-                        self.state.process_enemies();
-                        self.show_inventory = false;
+                KeyCode::Enter if self.selected_item < self.state.player.inventory.len() => {
+                    self.use_item(self.selected_item);
+                    self.show_inventory = false;
+                }
+                KeyCode::Char(c @ '1'..='5') => {
+                    let slot = c as usize - '1' as usize;
+                    if self.state.player.assign_quick_slot(slot, self.selected_item) {
+                        let name = self.state.player.inventory[self.selected_item].name.clone();
+                        self.state.log(format!("{} bound to {}.", name, c));
                     }
                 }
                 _ => {}
@@ -111,6 +752,91 @@ impl App {
             return;
         }
 
+        // Full inventory - offer to swap for the floor item
+        if self.state.pending_pickup {
+            match key.code {
+                KeyCode::Char('y') => {
+                    self.state.accept_pending_pickup();
+                }
+                KeyCode::Char('n') | KeyCode::Esc => self.state.decline_pending_pickup(),
+                _ => {}
+            }
+            return;
+        }
+
+        // Room-clear reward - a safe heal or a risky chest
+        if self.reward_choice {
+            match key.code {
+                KeyCode::Char('h') => self.state.take_clear_heal(),
+                KeyCode::Char('c') => {
+                    self.state.open_clear_chest();
+                }
+                KeyCode::Esc => self.state.log("You leave the chest be."),
+                _ => return,
+            }
+            self.reward_choice = false;
+            self.offer_advance();
+            return;
+        }
+
+        // Auto-advance - confirm before leaving the cleared room
+        if self.confirm_advance {
+            self.confirm_advance = false;
+            if key.code == KeyCode::Enter {
+                let events = self.state.advance_room();
+                if !events.is_empty() {
+                    self.announce(&events);
+                }
+            }
+            return;
+        }
+
+        // Debug commands - one per '`'
+        if self.debug_mode {
+            self.debug_mode = false;
+            self.handle_debug_command(key.code);
+            return;
+        }
+
+        // Bisect accusation - confirm before risking HP
+        if self.confirm_accuse {
+            if key.code == KeyCode::Char('y') {
+                self.state.accuse_room();
+            }
+            self.confirm_accuse = false;
+            return;
+        }
+
+        // Targeting mode - moving the cursor
+        if self.targeting {
+            if let Some(dir) = key_to_direction(key.code) {
+                let (dx, dy) = dir.delta();
+                self.move_cursor(dx, dy);
+                return;
+            }
+            match key.code {
+                KeyCode::Enter => {
+                    self.confirm_target();
+                }
+                KeyCode::Esc => self.cancel_targeting(),
+                _ => {}
+            }
+            return;
+        }
+
+        // Inspect mode - moving the cursor
+        if self.inspecting {
+            if let Some(dir) = key_to_direction(key.code) {
+                let (dx, dy) = dir.delta();
+                self.move_cursor(dx, dy);
+                return;
+            }
+            if matches!(key.code, KeyCode::Esc | KeyCode::Char(';') | KeyCode::Char('x')) {
+                self.stop_inspecting();
+            }
+            return;
+        }
+
         // Attack mode - waiting for direction
         if self.attack_mode {
             let direction = match key.code {
@@ -127,8 +853,7 @@ impl App {
 
             if let Some(dir) = direction {
                 let action = PlayerAction::Attack(dir);
-                self.state.process_action(action);
-                self.state.process_enemies();
+                self.take_turn(action);
                 self.attack_mode = false;
             }
             return;
@@ -138,20 +863,16 @@ impl App {
         match key.code {
             // Movement
             KeyCode::Up | KeyCode::Char('k') => {
-                self.state.process_action(PlayerAction::Move(0, -1));
-                self.state.process_enemies();
+                self.take_turn(PlayerAction::Move(0, -1));
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                self.state.process_action(PlayerAction::Move(0, 1));
-                self.state.process_enemies();
+                self.take_turn(PlayerAction::Move(0, 1));
             }
             KeyCode::Left | KeyCode::Char('h') => {
-                self.state.process_action(PlayerAction::Move(-1, 0));
-                self.state.process_enemies();
+                self.take_turn(PlayerAction::Move(-1, 0));
             }
             KeyCode::Right | KeyCode::Char('l') => {
-                self.state.process_action(PlayerAction::Move(1, 0));
-                self.state.process_enemies();
+                self.take_turn(PlayerAction::Move(1, 0));
             }
 
             // Attack mode
@@ -159,16 +880,39 @@ impl App {
                 self.attack_mode = true;
             }
 
+            // Targeting
+            KeyCode::Char('t') => {
+                self.start_targeting();
+            }
+
+            // Inspect
+            KeyCode::Char(';') | KeyCode::Char('x') => {
+                self.start_inspecting();
+            }
+
             // Defend
             KeyCode::Char('d') => {
-                self.state.process_action(PlayerAction::Defend);
-                self.state.process_enemies();
+                self.take_turn(PlayerAction::Defend);
             }
 
             // Wait
             KeyCode::Char('.') | KeyCode::Char(' ') => {
-                self.state.process_action(PlayerAction::Wait);
-                self.state.process_enemies();
+                self.take_turn(PlayerAction::Wait);
+            }
+
+            // Exit hint
+            KeyCode::Char('g') => {
+                self.show_exit_hint = !self.show_exit_hint;
+            }
+
+            // Debug commands (--debug only)
+            KeyCode::Char('`') if self.debug => {
+                self.debug_mode = true;
+            }
+
+            // Accuse this room (bisect runs)
+            KeyCode::Char('B') if self.state.bisect => {
+                self.confirm_accuse = true;
             }
 
             // Inventory
@@ -177,11 +921,30 @@ impl App {
                 self.selected_item = 0;
             }
 
+            // Quick slots
+            KeyCode::Char(c @ '1'..='5') => {
+                let slot = c as usize - '1' as usize;
+                match self.state.player.quick_slot(slot) {
+                    Some(index) => self.use_item(index),
+                    None => self.state.log(format!("Nothing bound to {}.", c)),
+                }
+            }
+
             // Help
             KeyCode::Char('?') => {
                 self.show_help = true;
             }
 
+            // Legend
+            KeyCode::Char('L') => {
+                self.show_legend = true;
+            }
+
+            // Dungeon map
+            KeyCode::Char('M') => {
+                self.show_map = true;
+            }
+
             // Quit
             KeyCode::Char('q') | KeyCode::Esc => {
                 self.quit = true;
@@ -1,7 +1,14 @@
 //! Color scheme for the UI.
 
+use chrono::NaiveDate;
 use ratatui::style::Color;
 
+use crate::entity::EnemyType;
+use crate::item::Rarity;
+use crate::world::Tile;
+
+use super::theme::palette;
+
 // Tile colors
 pub const FLOOR_COLOR: Color = Color::DarkGray;
 pub const WALL_COLOR: Color = Color::Gray;
@@ -17,6 +24,8 @@ pub const BUG_COLOR: Color = Color::Red;
 pub const REGRESSION_COLOR: Color = Color::Magenta;
 pub const TECH_DEBT_COLOR: Color = Color::LightRed;
 pub const MERGE_CONFLICT_COLOR: Color = Color::LightMagenta;
+pub const SCOPE_CREEP_COLOR: Color = Color::LightYellow;
+pub const DELETION_COLOR: Color = Color::Rgb(200, 80, 80);
 
 // Item colors
 pub const ITEM_COMMON: Color = Color::Gray;
@@ -34,3 +43,140 @@ pub const HP_MED: Color = Color::Yellow;
 pub const HP_LOW: Color = Color::Red;
 pub const ENERGY_COLOR: Color = Color::Cyan;
 pub const FOCUS_COLOR: Color = Color::Magenta;
+
+// Targeting colors
+pub const CURSOR_COLOR: Color = Color::Yellow;
+pub const TARGET_LINE_COLOR: Color = Color::Rgb(80, 80, 0);
+pub const TARGET_VALID_COLOR: Color = Color::Rgb(120, 0, 0);
+pub const EXIT_HINT_COLOR: Color = Color::Rgb(0, 90, 40);
+
+// Blame colors: a room's day on the dungeon map, oldest to newest
+pub const BLAME_OLD_COLOR: Color = Color::Rgb(45, 25, 10);
+pub const BLAME_NEW_COLOR: Color = Color::Rgb(255, 180, 80);
+
+/// How far a tile that just left view is already dimmed toward the fog.
+pub const MEMORY_DIM: f32 = 0.4;
+
+/// Color for a remembered tile `elapsed` turns after it was last seen.
+///
+/// Starts `MEMORY_DIM` of the way to `FOG_COLOR` and reaches it after
+/// `fade_turns`.
+pub fn remembered_color(color: Color, elapsed: u32, fade_turns: u32) -> Color {
+    let progress = (elapsed as f32 / fade_turns.max(1) as f32).min(1.0);
+    blend(color, FOG_COLOR, MEMORY_DIM + (1.0 - MEMORY_DIM) * progress)
+}
+
+/// Color for a room's day in blame mode, darker the older it is.
+///
+/// Days are placed between the dungeon's oldest and newest; a dungeon
+/// spanning a single day shows at full brightness.
+pub fn age_color(date: NaiveDate, oldest: NaiveDate, newest: NaiveDate) -> Color {
+    let span = (newest - oldest).num_days();
+    if span <= 0 {
+        return BLAME_NEW_COLOR;
+    }
+    let age = (date - oldest).num_days().clamp(0, span);
+    blend(BLAME_OLD_COLOR, BLAME_NEW_COLOR, age as f32 / span as f32)
+}
+
+/// Mix two colors, `amount` of the way from `from` to `to`.
+fn blend(from: Color, to: Color, amount: f32) -> Color {
+    let (fr, fg, fb) = rgb(from);
+    let (tr, tg, tb) = rgb(to);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount).round() as u8;
+    Color::Rgb(mix(fr, tr), mix(fg, tg), mix(fb, tb))
+}
+
+/// RGB components of a color, using the usual terminal values for named
+/// colors.
+fn rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (128, 0, 0),
+        Color::Green => (0, 128, 0),
+        Color::Yellow => (128, 128, 0),
+        Color::Blue => (0, 0, 128),
+        Color::Magenta => (128, 0, 128),
+        Color::Cyan => (0, 128, 128),
+        Color::Gray => (192, 192, 192),
+        Color::DarkGray => (128, 128, 128),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (0, 0, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        _ => (192, 192, 192),
+    }
+}
+
+/// Color for a tile in view, from the installed palette.
+pub fn tile_color(tile: Tile) -> Color {
+    let palette = palette();
+    match tile {
+        Tile::Floor => palette.floor,
+        Tile::Wall => palette.wall,
+        Tile::Door(_, _) => palette.door,
+        Tile::Exit => palette.exit,
+        Tile::Entrance => palette.entrance,
+        Tile::HealingZone => palette.healing_zone,
+    }
+}
+
+/// Color for an enemy type, from the installed palette.
+pub fn enemy_color(enemy_type: EnemyType) -> Color {
+    let palette = palette();
+    match enemy_type {
+        EnemyType::Bug => palette.bug,
+        EnemyType::Regression => palette.regression,
+        EnemyType::TechDebt => palette.tech_debt,
+        EnemyType::MergeConflict => palette.merge_conflict,
+        EnemyType::ScopeCreep => palette.scope_creep,
+        EnemyType::Deletion => palette.deletion,
+    }
+}
+
+/// Color for an item's rarity, from the installed palette.
+pub fn rarity_color(rarity: Rarity) -> Color {
+    let palette = palette();
+    match rarity {
+        Rarity::Common => palette.common,
+        Rarity::Uncommon => palette.uncommon,
+        Rarity::Rare => palette.rare,
+        Rarity::Legendary => palette.legendary,
+    }
+}
+
+/// Stable color for a commit author.
+///
+/// The hue comes from an FNV-1a hash of the name, so it's the same across
+/// runs and platforms. Saturation and lightness are fixed high enough
+/// that every hue reads against the black background and the fog.
+pub fn author_color(name: &str) -> Color {
+    let mut hash: u32 = 0x811c_9dc5;
+    for byte in name.trim().bytes() {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hsl_to_rgb((hash % 360) as f32, 0.7, 0.65)
+}
+
+/// Convert a hue in degrees, saturation and lightness to an RGB color.
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> Color {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |v: f32| ((v + m) * 255.0).round() as u8;
+    Color::Rgb(channel(r), channel(g), channel(b))
+}
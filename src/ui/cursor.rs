@@ -0,0 +1,63 @@
+//! Map cursor for targeting and inspecting tiles.
+
+use std::collections::HashSet;
+
+/// A cursor over the current room, restricted to visible tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Cursor {
+    /// Create a cursor at a position.
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// Get the cursor position.
+    pub fn position(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    /// Move the cursor by a delta if the destination is visible.
+    /// Returns true if the cursor moved.
+    pub fn step(&mut self, dx: i32, dy: i32, visible: &HashSet<(i32, i32)>) -> bool {
+        let next = (self.x + dx, self.y + dy);
+        if visible.contains(&next) {
+            self.x = next.0;
+            self.y = next.1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Tiles on a straight line from `from` to `to` (Bresenham).
+///
+/// The origin is excluded and the destination is included.
+pub fn line_to(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+    let (mut x, mut y) = from;
+    let dx = (to.0 - x).abs();
+    let dy = -(to.1 - y).abs();
+    let sx = if x < to.0 { 1 } else { -1 };
+    let sy = if y < to.1 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    let mut points = Vec::new();
+    while (x, y) != to {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+        points.push((x, y));
+    }
+
+    points
+}
@@ -0,0 +1,49 @@
+//! JSON lines stream of game events, for overlays and bots.
+//!
+//! The TUI owns stdout, so events go to a file or stderr instead.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::game::GameEvent;
+
+/// Write each event as one line of JSON.
+pub fn write_event_lines<W: Write>(writer: &mut W, events: &[GameEvent]) -> io::Result<()> {
+    for event in events {
+        serde_json::to_writer(&mut *writer, event)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Buffered destination for the event stream, flushed once per turn.
+pub struct EventSink {
+    writer: BufWriter<Box<dyn Write>>,
+}
+
+impl EventSink {
+    /// Stream to any writer.
+    pub fn new(writer: impl Write + 'static) -> Self {
+        Self {
+            writer: BufWriter::new(Box::new(writer)),
+        }
+    }
+
+    /// Stream to a file, created or truncated, or to stderr for `-`.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        if path == Path::new("-") {
+            return Ok(Self::new(io::stderr()));
+        }
+        Ok(Self::new(File::create(path)?))
+    }
+
+    /// Write a turn's events and flush them so readers see the whole turn.
+    pub fn emit(&mut self, events: &[GameEvent]) -> io::Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        write_event_lines(&mut self.writer, events)?;
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,58 @@
+//! ASCII commit graph shown on the victory screen.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::world::{Room, RoomType};
+
+/// Rows in the commit graph.
+pub const COMMIT_GRAPH_HEIGHT: usize = 4;
+
+/// Draw the history behind a dungeon as rows of ASCII, one column per day
+/// with commits. Each column is a stack of dots scaled to that day's
+/// commit count, topped with a `B` on days that built a boss chamber.
+/// Histories longer than `width` days are bucketed, so one column can
+/// cover several consecutive days.
+pub fn commit_graph(rooms: &[Room], width: usize) -> Vec<String> {
+    let mut days: BTreeMap<NaiveDate, (usize, bool)> = BTreeMap::new();
+    for room in rooms {
+        for commit in &room.source_commits {
+            let day = days.entry(commit.date_naive()).or_default();
+            day.0 += 1;
+            day.1 |= room.room_type == RoomType::Boss;
+        }
+    }
+    if days.is_empty() || width == 0 {
+        return Vec::new();
+    }
+
+    let days: Vec<(usize, bool)> = days.into_values().collect();
+    let columns = days.len().min(width);
+    let buckets: Vec<(usize, bool)> = (0..columns)
+        .map(|i| {
+            let bucket = &days[i * days.len() / columns..(i + 1) * days.len() / columns];
+            (bucket.iter().map(|d| d.0).sum(), bucket.iter().any(|d| d.1))
+        })
+        .collect();
+    let busiest = buckets.iter().map(|b| b.0).max().unwrap_or(1).max(1);
+    let heights: Vec<usize> = buckets
+        .iter()
+        .map(|b| (b.0 * COMMIT_GRAPH_HEIGHT).div_ceil(busiest).max(1))
+        .collect();
+
+    (0..COMMIT_GRAPH_HEIGHT)
+        .map(|row| {
+            let level = COMMIT_GRAPH_HEIGHT - row;
+            buckets
+                .iter()
+                .zip(&heights)
+                .map(|(&(_, boss), &height)| match height {
+                    h if h == level && boss => 'B',
+                    h if h >= level => '.',
+                    _ => ' ',
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -2,11 +2,23 @@
 
 mod app;
 mod colors;
+mod cursor;
+mod events;
+mod graph;
 mod input;
 mod render;
+mod seeds;
+mod theme;
+mod timer;
 pub mod widgets;
 
 pub use app::*;
 pub use colors::*;
+pub use cursor::*;
+pub use events::*;
+pub use graph::*;
 pub use input::*;
 pub use render::*;
+pub use seeds::*;
+pub use theme::*;
+pub use timer::*;
@@ -1,10 +1,19 @@
 //! Main render function.
 
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
 
+use crate::combat::WAIT_REGEN;
+use crate::entity::{Enemy, DEATH_SYMBOL};
+use crate::game::CLEAR_REWARD_HEAL;
+use crate::world::{Room, RoomSummary};
+
 use super::colors::*;
-use super::App;
+use super::theme::palette;
+use super::{commit_graph, format_time, line_to, App, BlameMode, COMMIT_GRAPH_HEIGHT};
 
 /// Main render entry point.
 pub fn render(frame: &mut Frame, app: &App) {
@@ -18,6 +27,18 @@ pub fn render(frame: &mut Frame, app: &App) {
         return;
     }
 
+    // Frame the whole screen in red while HP is low
+    let area = if app.is_low_hp() {
+        let border = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(HP_LOW).add_modifier(Modifier::BOLD));
+        let inner = border.inner(area);
+        frame.render_widget(border, area);
+        inner
+    } else {
+        area
+    };
+
     // Layout: sidebar on right (30%), map on left (70%), log at bottom
     let main_chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -32,8 +53,12 @@ pub fn render(frame: &mut Frame, app: &App) {
     // Render map
     render_map(frame, left_chunks[0], app);
 
-    // Render message log
-    render_log(frame, left_chunks[1], app);
+    // Render message log, or tile details while inspecting
+    if app.inspecting {
+        render_inspect(frame, left_chunks[1], app);
+    } else {
+        render_log(frame, left_chunks[1], app);
+    }
 
     // Render stats sidebar
     render_stats(frame, main_chunks[1], app);
@@ -43,22 +68,124 @@ pub fn render(frame: &mut Frame, app: &App) {
         render_help(frame, area);
     }
 
+    if app.show_legend {
+        render_legend(frame, area, app);
+    }
+
+    if app.show_map {
+        render_dungeon_map(frame, area, app);
+    }
+
     if app.show_inventory {
         render_inventory(frame, area, app);
     }
 
+    if app.reading.is_some() {
+        render_reading(frame, area, app);
+    }
+
+    if let Some(banner) = app.banner {
+        render_banner(frame, left_chunks[0], banner, app.banner_turns);
+    }
+
+    if let Some(summary) = app.next_room_peek() {
+        render_peek(frame, left_chunks[0], &summary);
+    }
+
     if app.state.game_over {
         render_game_over(frame, area, app);
     }
 
+    if let Some(step) = app.state.tutorial {
+        let msg = Paragraph::new(step.prompt()).style(Style::default().fg(Color::Yellow));
+        let msg_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        frame.render_widget(msg, msg_area);
+    }
+
     if app.attack_mode {
-        let msg = Paragraph::new("Attack mode - press direction")
+        let msg = Paragraph::new(format!("Attack - press direction | {}", app.attack_preview()))
+            .style(Style::default().fg(Color::Yellow));
+        let msg_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        frame.render_widget(msg, msg_area);
+    }
+
+    if app.inspecting {
+        let msg = Paragraph::new("Inspect - move cursor, Esc to close")
+            .style(Style::default().fg(Color::Yellow));
+        let msg_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        frame.render_widget(msg, msg_area);
+    }
+
+    if let Some(prompt) = pickup_prompt(app) {
+        let msg = Paragraph::new(prompt).style(Style::default().fg(Color::Yellow));
+        let msg_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        frame.render_widget(msg, msg_area);
+    }
+
+    if app.reward_choice {
+        let msg = Paragraph::new(format!(
+            "Room cleared! h: rest (+{} HP), c: open the chest (might bite), Esc: move on",
+            CLEAR_REWARD_HEAL
+        ))
+        .style(Style::default().fg(Color::Yellow));
+        let msg_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        frame.render_widget(msg, msg_area);
+    }
+
+    if app.confirm_advance {
+        let msg = Paragraph::new("Room cleared! Enter: on to the next room, any other key: stay")
+            .style(Style::default().fg(Color::Yellow));
+        let msg_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        frame.render_widget(msg, msg_area);
+    }
+
+    if app.debug_mode {
+        let msg = Paragraph::new("Debug - g: god mode  r: reveal  w: warp  1-6: enemy  i: potion  Esc: cancel")
+            .style(Style::default().fg(Color::Magenta));
+        let msg_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        frame.render_widget(msg, msg_area);
+    }
+
+    if app.confirm_accuse {
+        let msg = Paragraph::new("Accuse this room of introducing the bug? (y/n)")
+            .style(Style::default().fg(Color::Yellow));
+        let msg_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        frame.render_widget(msg, msg_area);
+    }
+
+    if app.targeting {
+        let msg = Paragraph::new("Targeting - move cursor, Enter to confirm, Esc to cancel")
             .style(Style::default().fg(Color::Yellow));
-        let msg_area = Rect::new(0, area.height - 1, area.width, 1);
+        let msg_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
         frame.render_widget(msg, msg_area);
     }
 }
 
+/// Color for an enemy on the map: its author's when coloring by author,
+/// otherwise its type's.
+fn enemy_glyph_color(app: &App, room: &Room, enemy: &Enemy) -> Color {
+    if app.color_by_author {
+        if let Some(author) = room.commit_author(&enemy.source_commit) {
+            return author_color(author);
+        }
+    }
+    enemy_color(enemy.enemy_type)
+}
+
+/// Prompt for swapping the lowest-rarity item with the one underfoot.
+fn pickup_prompt(app: &App) -> Option<String> {
+    if !app.state.pending_pickup {
+        return None;
+    }
+    let player = &app.state.player;
+    let floor = app.state.world.current()?.get_item_at(player.x, player.y)?;
+    let lowest = &player.inventory[player.lowest_rarity_index()?];
+    Some(format!(
+        "Inventory full - drop {} for {}? (y/n)",
+        lowest.name, floor.name
+    ))
+}
+
 /// Render the map.
 fn render_map(frame: &mut Frame, area: Rect, app: &App) {
     let block = Block::default()
@@ -70,6 +197,14 @@ fn render_map(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(block, area);
 
     if let Some(room) = app.state.world.current() {
+        if room.is_empty() {
+            let msg = Paragraph::new("This room has no floor to stand on.")
+                .style(Style::default().fg(UI_TEXT));
+            frame.render_widget(msg, inner);
+            return;
+        }
+        let hint: HashSet<(i32, i32)> = app.exit_hint().into_iter().collect();
+
         for y in 0..room.height as i32 {
             for x in 0..room.width as i32 {
                 let screen_x = inner.x + x as u16;
@@ -83,7 +218,7 @@ fn render_map(frame: &mut Frame, area: Rect, app: &App) {
 
                 // Player
                 if app.state.player.x == x && app.state.player.y == y {
-                    let span = Span::styled("@", Style::default().fg(PLAYER_COLOR));
+                    let span = Span::styled("@", Style::default().fg(palette().player));
                     frame.render_widget(
                         Paragraph::new(span),
                         Rect::new(screen_x, screen_y, 1, 1),
@@ -94,12 +229,7 @@ fn render_map(frame: &mut Frame, area: Rect, app: &App) {
                 // Enemy
                 if visible {
                     if let Some(enemy) = room.get_enemy_at(x, y) {
-                        let color = match enemy.enemy_type {
-                            crate::entity::EnemyType::Bug => BUG_COLOR,
-                            crate::entity::EnemyType::Regression => REGRESSION_COLOR,
-                            crate::entity::EnemyType::TechDebt => TECH_DEBT_COLOR,
-                            crate::entity::EnemyType::MergeConflict => MERGE_CONFLICT_COLOR,
-                        };
+                        let color = enemy_glyph_color(app, room, enemy);
                         let span = Span::styled(
                             enemy.symbol().to_string(),
                             Style::default().fg(color),
@@ -111,15 +241,24 @@ fn render_map(frame: &mut Frame, area: Rect, app: &App) {
                         continue;
                     }
 
+                    // Enemy that fell this turn
+                    if let Some(enemy) = room.get_dying_at(x, y) {
+                        let color = enemy_glyph_color(app, room, enemy);
+                        let span = Span::styled(DEATH_SYMBOL.to_string(), Style::default().fg(color));
+                        frame.render_widget(
+                            Paragraph::new(span),
+                            Rect::new(screen_x, screen_y, 1, 1),
+                        );
+                        continue;
+                    }
+
                     // Item
                     if let Some(item) = room.get_item_at(x, y) {
-                        let color = match item.rarity {
-                            crate::item::Rarity::Common => ITEM_COMMON,
-                            crate::item::Rarity::Uncommon => ITEM_UNCOMMON,
-                            crate::item::Rarity::Rare => ITEM_RARE,
-                            crate::item::Rarity::Legendary => ITEM_LEGENDARY,
-                        };
-                        let span = Span::styled("!", Style::default().fg(color));
+                        let color = rarity_color(item.rarity);
+                        let span = Span::styled(
+                            item.item_type.symbol().to_string(),
+                            Style::default().fg(color),
+                        );
                         frame.render_widget(
                             Paragraph::new(span),
                             Rect::new(screen_x, screen_y, 1, 1),
@@ -128,18 +267,24 @@ fn render_map(frame: &mut Frame, area: Rect, app: &App) {
                     }
                 }
 
+                // Exit hint, only over tiles the player can see
+                if visible && hint.contains(&(x, y)) {
+                    let span = Span::styled("·", Style::default().fg(EXIT_HINT_COLOR));
+                    frame.render_widget(
+                        Paragraph::new(span),
+                        Rect::new(screen_x, screen_y, 1, 1),
+                    );
+                    continue;
+                }
+
                 // Tile
                 if let Some(tile) = room.get_tile(x, y) {
+                    let remembered = app.fog_memory.zip(room.last_seen(x, y));
                     let (ch, color) = if visible {
-                        let color = match tile {
-                            crate::world::Tile::Floor => FLOOR_COLOR,
-                            crate::world::Tile::Wall => WALL_COLOR,
-                            crate::world::Tile::Door(_, _) => DOOR_COLOR,
-                            crate::world::Tile::Exit => EXIT_COLOR,
-                            crate::world::Tile::Entrance => ENTRANCE_COLOR,
-                            crate::world::Tile::HealingZone => HEALING_ZONE_COLOR,
-                        };
-                        (tile.symbol(), color)
+                        (tile.symbol(), tile_color(*tile))
+                    } else if let Some((fade_turns, seen)) = remembered {
+                        let elapsed = app.state.turn.saturating_sub(seen);
+                        (tile.symbol(), remembered_color(tile_color(*tile), elapsed, fade_turns))
                     } else {
                         (' ', FOG_COLOR)
                     };
@@ -152,7 +297,46 @@ fn render_map(frame: &mut Frame, area: Rect, app: &App) {
                 }
             }
         }
+
+        if app.cursor.is_some() {
+            render_cursor(frame, inner, app);
+        }
+    }
+}
+
+/// Highlight the cursor on the map. While targeting, also highlight
+/// valid targets and the line of fire.
+fn render_cursor(frame: &mut Frame, inner: Rect, app: &App) {
+    let Some(cursor) = app.cursor else {
+        return;
+    };
+
+    let highlight = |frame: &mut Frame, x: i32, y: i32, color: Color| {
+        if x < 0 || y < 0 || x as u16 >= inner.width || y as u16 >= inner.height {
+            return;
+        }
+        let cell = Rect::new(inner.x + x as u16, inner.y + y as u16, 1, 1);
+        frame.buffer_mut().set_style(cell, Style::default().bg(color));
+    };
+
+    if app.targeting {
+        if let Some(room) = app.state.world.current() {
+            for enemy in &room.enemies {
+                if app.is_valid_target(enemy.x, enemy.y) {
+                    highlight(frame, enemy.x, enemy.y, TARGET_VALID_COLOR);
+                }
+            }
+        }
+
+        let origin = (app.state.player.x, app.state.player.y);
+        for (x, y) in line_to(origin, cursor.position()) {
+            if (x, y) != cursor.position() {
+                highlight(frame, x, y, TARGET_LINE_COLOR);
+            }
+        }
     }
+
+    highlight(frame, cursor.x, cursor.y, CURSOR_COLOR);
 }
 
 /// Render the message log.
@@ -165,17 +349,47 @@ fn render_log(frame: &mut Frame, area: Rect, app: &App) {
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    let messages: Vec<Line> = app
-        .state
-        .messages
-        .iter()
-        .rev()
-        .take(inner.height as usize)
-        .rev()
-        .map(|m| Line::from(m.as_str()))
+    // Last turn's recap sits above the scrolling log
+    let mut lines = Vec::new();
+    if !app.turn_summary.is_empty() {
+        lines.push(Line::styled(
+            app.turn_summary.as_str(),
+            Style::default().fg(UI_HIGHLIGHT).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    let room = (inner.height as usize).saturating_sub(lines.len());
+    lines.extend(
+        app.state
+            .messages
+            .iter()
+            .rev()
+            .take(room)
+            .rev()
+            .map(|m| Line::from(m.as_str())),
+    );
+
+    let para = Paragraph::new(lines).style(Style::default().fg(UI_TEXT));
+    frame.render_widget(para, inner);
+}
+
+/// Render details of the tile under the inspect cursor.
+fn render_inspect(frame: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .title(" Inspect ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(UI_BORDER));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines: Vec<Line> = app
+        .inspect_details()
+        .into_iter()
+        .map(Line::from)
         .collect();
 
-    let para = Paragraph::new(messages).style(Style::default().fg(UI_TEXT));
+    let para = Paragraph::new(lines).style(Style::default().fg(UI_TEXT));
     frame.render_widget(para, inner);
 }
 
@@ -190,6 +404,7 @@ fn render_stats(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(block, area);
 
     let player = &app.state.player;
+    let regen = app.state.energy_regen_here();
 
     // HP bar
     let hp_pct = player.hp as f32 / player.max_hp as f32;
@@ -216,6 +431,13 @@ fn render_stats(frame: &mut Frame, area: Rect, app: &App) {
                 Style::default().fg(ENERGY_COLOR),
             ),
         ]),
+        Line::from(vec![
+            Span::raw("    "),
+            Span::styled(
+                format!("+{}/turn (+{} wait)", regen, regen + WAIT_REGEN),
+                Style::default().fg(UI_TEXT),
+            ),
+        ]),
         Line::from(vec![
             Span::raw("FO: "),
             Span::styled(
@@ -227,9 +449,14 @@ fn render_stats(frame: &mut Frame, area: Rect, app: &App) {
         Line::from(format!("Level: {}", player.level)),
         Line::from(format!("XP: {}/{}", player.xp, player.level * 100)),
         Line::from(format!("Turn: {}", app.state.turn)),
-        Line::from(""),
     ];
 
+    if let Some(timer) = &app.speedrun {
+        lines.push(Line::from(format!("Time: {}", format_time(timer.elapsed()))));
+        lines.push(Line::from(format!("Split: {}", format_time(timer.current_split()))));
+    }
+    lines.push(Line::from(""));
+
     // Room info
     if let Some(room) = app.state.world.current() {
         lines.push(Line::from(format!(
@@ -237,9 +464,16 @@ fn render_stats(frame: &mut Frame, area: Rect, app: &App) {
             app.state.world.current_room + 1,
             app.state.world.rooms.len()
         )));
-        lines.push(Line::from(room.room_type.name()));
+        lines.push(Line::from(room.name()));
         lines.push(Line::from(format!("{}", room.source_date)));
 
+        if let Some(boss) = room.boss() {
+            lines.push(Line::from(""));
+            let title = boss.title.clone().unwrap_or_default();
+            lines.push(Line::from(title).style(Style::default().fg(UI_TITLE)));
+            lines.push(boss_hp_bar(boss));
+        }
+
         // Enemy breakdown by type (spec requirement)
         let enemy_breakdown = format_enemy_breakdown(&room.enemies);
         lines.push(Line::from(enemy_breakdown));
@@ -265,6 +499,24 @@ fn render_stats(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(para, inner);
 }
 
+/// Cells in the boss HP bar.
+const BOSS_BAR_WIDTH: usize = 10;
+
+/// A boss's HP as a bar, e.g. "[#####-----] 60/120".
+fn boss_hp_bar(boss: &Enemy) -> Line<'static> {
+    let filled = if boss.max_hp > 0 {
+        (boss.hp.max(0) as usize * BOSS_BAR_WIDTH).div_ceil(boss.max_hp as usize).min(BOSS_BAR_WIDTH)
+    } else {
+        0
+    };
+    Line::from(vec![
+        Span::raw("["),
+        Span::styled("#".repeat(filled), Style::default().fg(HP_LOW)),
+        Span::raw("-".repeat(BOSS_BAR_WIDTH - filled)),
+        Span::raw(format!("] {}/{}", boss.hp.max(0), boss.max_hp)),
+    ])
+}
+
 /// Format enemy breakdown by type.
 fn format_enemy_breakdown(enemies: &[crate::entity::Enemy]) -> String {
     if enemies.is_empty() {
@@ -276,6 +528,8 @@ fn format_enemy_breakdown(enemies: &[crate::entity::Enemy]) -> String {
     let mut regressions = 0;
     let mut tech_debt = 0;
     let mut merge_conflicts = 0;
+    let mut scope_creeps = 0;
+    let mut deletions = 0;
 
     for enemy in enemies {
         match enemy.enemy_type {
@@ -283,6 +537,8 @@ fn format_enemy_breakdown(enemies: &[crate::entity::Enemy]) -> String {
             EnemyType::Regression => regressions += 1,
             EnemyType::TechDebt => tech_debt += 1,
             EnemyType::MergeConflict => merge_conflicts += 1,
+            EnemyType::ScopeCreep => scope_creeps += 1,
+            EnemyType::Deletion => deletions += 1,
         }
     }
 
@@ -299,6 +555,12 @@ fn format_enemy_breakdown(enemies: &[crate::entity::Enemy]) -> String {
     if merge_conflicts > 0 {
         parts.push(format!("{} Merge", merge_conflicts));
     }
+    if scope_creeps > 0 {
+        parts.push(format!("{} Creep", scope_creeps));
+    }
+    if deletions > 0 {
+        parts.push(format!("{} Del", deletions));
+    }
 
     if parts.is_empty() {
         "Enemies: 0".to_string()
@@ -315,8 +577,15 @@ fn render_help(frame: &mut Frame, area: Rect) {
         Line::from("Movement: Arrow keys or hjkl"),
         Line::from("Attack:   a + direction"),
         Line::from("Defend:   d"),
+        Line::from("Target:   t + move, Enter"),
+        Line::from("Inspect:  ; or x"),
         Line::from("Wait:     . or space"),
+        Line::from("Exit hint: g"),
+        Line::from("Accuse:   B (bisect)"),
         Line::from("Inventory: i"),
+        Line::from("Quick use: 1-5 (bind in inventory)"),
+        Line::from("Legend:   L"),
+        Line::from("Map:      M (b: blame)"),
         Line::from("Help:     ?"),
         Line::from("Quit:     q or Esc"),
         Line::from(""),
@@ -340,6 +609,238 @@ fn render_help(frame: &mut Frame, area: Rect) {
     frame.render_widget(para, help_area);
 }
 
+/// One legend row: a colored glyph and what it means.
+fn legend_line(symbol: char, color: Color, label: impl Into<String>) -> Line<'static> {
+    Line::from(vec![
+        Span::raw("  "),
+        Span::styled(symbol.to_string(), Style::default().fg(color)),
+        Span::raw(format!("  {}", label.into())),
+    ])
+}
+
+/// Render the glyph legend overlay, built from the entity, item and tile
+/// enums so it stays in step with the map.
+fn render_legend(frame: &mut Frame, area: Rect, app: &App) {
+    use crate::entity::EnemyType;
+    use crate::item::{ItemType, Rarity};
+    use crate::world::Tile;
+
+    let heading = |text: &'static str| Line::from(text).style(Style::default().fg(UI_TITLE));
+
+    let mut left = vec![heading("Creatures"), legend_line('@', palette().player, "You")];
+    left.extend(
+        EnemyType::ALL
+            .iter()
+            .map(|t| legend_line(t.symbol(), enemy_color(*t), format!("{:?}", t))),
+    );
+    left.push(Line::from(""));
+    left.push(heading("Items"));
+    left.extend(
+        ItemType::ALL
+            .iter()
+            .map(|t| legend_line(t.symbol(), UI_TEXT, format!("{:?}", t))),
+    );
+    left.extend(
+        Rarity::ALL
+            .iter()
+            .map(|r| legend_line('!', rarity_color(*r), format!("{:?}", r))),
+    );
+
+    let mut right = vec![heading("Tiles")];
+    right.extend(
+        Tile::LEGEND
+            .iter()
+            .map(|t| legend_line(t.symbol(), tile_color(*t), t.name())),
+    );
+
+    if app.color_by_author {
+        let authors = room_authors(app);
+        if !authors.is_empty() {
+            right.push(Line::from(""));
+            right.push(heading("Authors"));
+            right.extend(authors.iter().take(LEGEND_MAX_AUTHORS).map(|author| {
+                legend_line('*', author_color(author), app.display_author(author))
+            }));
+        }
+    }
+
+    let width = 50;
+    let height = left.len().max(right.len()) as u16 + 4;
+    let x = (area.width - width) / 2;
+    let y = area.height.saturating_sub(height) / 2;
+
+    let block = Block::default()
+        .title(" Legend (Esc to close) ")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+
+    let legend_area = Rect::new(x, y, width, height.min(area.height));
+    let inner = block.inner(legend_area);
+    frame.render_widget(block, legend_area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner.inner(Margin::new(1, 1)));
+    frame.render_widget(Paragraph::new(left), columns[0]);
+    frame.render_widget(Paragraph::new(right), columns[1]);
+}
+
+/// Most authors listed in the legend, so it fits in a minimum-size terminal.
+const LEGEND_MAX_AUTHORS: usize = 6;
+
+/// Authors of the current room's enemies, in the order they first appear.
+fn room_authors(app: &App) -> Vec<String> {
+    let mut authors: Vec<String> = Vec::new();
+    if let Some(room) = app.state.world.current() {
+        for enemy in &room.enemies {
+            if let Some(author) = room.commit_author(&enemy.source_commit) {
+                if !authors.iter().any(|a| a == author) {
+                    authors.push(author.to_string());
+                }
+            }
+        }
+    }
+    authors
+}
+
+/// Rooms per row on the dungeon map.
+const MAP_ROOMS_PER_ROW: usize = 16;
+
+/// Color of a room on the dungeon map under the current blame mode.
+fn map_room_color(app: &App, index: usize, room: &Room, dates: (NaiveDate, NaiveDate)) -> Color {
+    match app.blame {
+        BlameMode::Off if index == app.state.world.current_room => UI_HIGHLIGHT,
+        BlameMode::Off if index <= app.state.furthest_room => UI_TEXT,
+        BlameMode::Off => FOG_COLOR,
+        BlameMode::Author => room.dominant_author().map_or(FOG_COLOR, author_color),
+        BlameMode::Age => age_color(room.source_date, dates.0, dates.1),
+    }
+}
+
+/// Render the whole dungeon as a grid of rooms, colored by the blame
+/// mode, with a legend underneath.
+fn render_dungeon_map(frame: &mut Frame, area: Rect, app: &App) {
+    let rooms = &app.state.world.rooms;
+    let oldest = rooms.iter().map(|r| r.source_date).min();
+    let newest = rooms.iter().map(|r| r.source_date).max();
+    let (Some(oldest), Some(newest)) = (oldest, newest) else {
+        return;
+    };
+
+    let mut lines: Vec<Line> = rooms
+        .chunks(MAP_ROOMS_PER_ROW)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let spans: Vec<Span> = chunk
+                .iter()
+                .enumerate()
+                .map(|(col, room)| {
+                    let index = row * MAP_ROOMS_PER_ROW + col;
+                    let glyph = if index == app.state.world.current_room { "@@ " } else { "## " };
+                    let color = map_room_color(app, index, room, (oldest, newest));
+                    Span::styled(glyph, Style::default().fg(color))
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+
+    lines.push(Line::from(""));
+    match app.blame {
+        BlameMode::Off => {
+            lines.push(legend_line('@', UI_HIGHLIGHT, "You are here"));
+            lines.push(legend_line('#', UI_TEXT, "Explored"));
+            lines.push(legend_line('#', FOG_COLOR, "Unexplored"));
+        }
+        BlameMode::Author => {
+            let mut authors: Vec<&str> = Vec::new();
+            for author in rooms.iter().filter_map(|r| r.dominant_author()) {
+                if !authors.contains(&author) {
+                    authors.push(author);
+                }
+            }
+            lines.extend(authors.iter().take(LEGEND_MAX_AUTHORS).map(|author| {
+                legend_line('#', author_color(author), app.display_author(author))
+            }));
+            if authors.len() > LEGEND_MAX_AUTHORS {
+                lines.push(Line::from(format!(
+                    "  ...and {} more",
+                    authors.len() - LEGEND_MAX_AUTHORS
+                )));
+            }
+        }
+        BlameMode::Age => {
+            let mut spans = vec![Span::raw(format!("  {} ", oldest))];
+            let span = (newest - oldest).num_days();
+            for step in 0..=4 {
+                let date = oldest + chrono::Duration::days(span * step / 4);
+                spans.push(Span::styled("#", Style::default().fg(age_color(date, oldest, newest))));
+            }
+            spans.push(Span::raw(format!(" {}", newest)));
+            lines.push(Line::from(spans));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from("b: change coloring, Esc: close").style(Style::default().fg(UI_TEXT)));
+
+    let width = (MAP_ROOMS_PER_ROW as u16 * 3 + 4).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let x = (area.width - width) / 2;
+    let y = (area.height - height) / 2;
+
+    let block = Block::default()
+        .title(format!(" Dungeon Map - {} ", app.blame.name()))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+
+    let map_area = Rect::new(x, y, width, height);
+    let inner = block.inner(map_area);
+    frame.render_widget(block, map_area);
+    frame.render_widget(Paragraph::new(lines), inner.inner(Margin::new(1, 0)));
+}
+
+/// Render the next room preview in the map's top-right corner.
+fn render_peek(frame: &mut Frame, map_area: Rect, summary: &RoomSummary) {
+    let lines = vec![
+        Line::from(summary.room_type.name()).style(Style::default().fg(UI_HIGHLIGHT)),
+        Line::from(format!("Enemies: {}", summary.enemies)).style(Style::default().fg(UI_TEXT)),
+        Line::from(format!("Items:   {}", summary.items)).style(Style::default().fg(UI_TEXT)),
+    ];
+
+    let width = 18;
+    let height = lines.len() as u16 + 2;
+    let x = map_area.x + map_area.width.saturating_sub(width + 1);
+    let y = map_area.y + 1;
+
+    let block = Block::default()
+        .title(" Next Room ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(UI_BORDER))
+        .style(Style::default().bg(Color::Black));
+    let peek_area = Rect::new(x, y, width, height);
+    frame.render_widget(Paragraph::new(lines).block(block), peek_area);
+}
+
+/// Draw a room banner centered on the map, dimmed on its last turn.
+fn render_banner(frame: &mut Frame, map_area: Rect, banner: &str, turns_left: u32) {
+    let color = if turns_left > 1 { UI_HIGHLIGHT } else { Color::DarkGray };
+    let width = (banner.chars().count() as u16 + 4).min(map_area.width);
+    let height = 3;
+    let x = map_area.x + (map_area.width - width) / 2;
+    let y = map_area.y + map_area.height.saturating_sub(height) / 2;
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(color))
+        .style(Style::default().bg(Color::Black));
+    let para = Paragraph::new(banner)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(color))
+        .block(block);
+    frame.render_widget(para, Rect::new(x, y, width, height));
+}
+
 /// Render inventory overlay.
 fn render_inventory(frame: &mut Frame, area: Rect, app: &App) {
     let width = 50;
@@ -374,7 +875,11 @@ fn render_inventory(frame: &mut Frame, area: Rect, app: &App) {
                 } else {
                     Style::default().fg(UI_TEXT)
                 };
-                Line::from(format!("{}{}", prefix, item.name)).style(style)
+                let slot = match app.state.player.quick_slot_of(i) {
+                    Some(slot) => format!(" [{}]", slot + 1),
+                    None => String::new(),
+                };
+                Line::from(format!("{}{} {}{}", prefix, item.item_type.symbol(), item.name, slot)).style(style)
             })
             .collect();
 
@@ -383,6 +888,54 @@ fn render_inventory(frame: &mut Frame, area: Rect, app: &App) {
     }
 }
 
+/// Color a diff line by what it does.
+fn diff_line_color(line: &str) -> Color {
+    if line.starts_with('+') {
+        Color::Green
+    } else if line.starts_with('-') {
+        Color::Red
+    } else if line.starts_with("@@") {
+        Color::Cyan
+    } else {
+        UI_TEXT
+    }
+}
+
+/// Render the lore item being read, scrolled to `reading_scroll`.
+fn render_reading(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(item) = &app.reading else {
+        return;
+    };
+    let text = match &item.effect {
+        crate::item::ItemEffect::Lore(text) => text.as_str(),
+        _ => "",
+    };
+
+    let width = 76;
+    let height = 20;
+    let x = (area.width - width) / 2;
+    let y = (area.height - height) / 2;
+
+    let title = match &item.source_commit {
+        Some(hash) => format!(" {} ({}) ", item.name, &hash[..hash.len().min(7)]),
+        None => format!(" {} ", item.name),
+    };
+    let block = Block::default()
+        .title(title)
+        .title_bottom(" j/k scroll, Esc close ")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+
+    let lines: Vec<Line> = text
+        .lines()
+        .map(|line| Line::from(line.to_string()).style(Style::default().fg(diff_line_color(line))))
+        .collect();
+
+    let read_area = Rect::new(x, y, width, height);
+    let para = Paragraph::new(lines).block(block).scroll((app.reading_scroll, 0));
+    frame.render_widget(para, read_area);
+}
+
 /// Render game over screen.
 fn render_game_over(frame: &mut Frame, area: Rect, app: &App) {
     let title = if app.state.victory {
@@ -397,21 +950,77 @@ fn render_game_over(frame: &mut Frame, area: Rect, app: &App) {
         Color::Red
     };
 
-    let lines = vec![
+    let stats = &app.state.stats;
+    let accuracy = match stats.accuracy() {
+        Some(percent) => format!("{}% ({}/{})", percent, stats.hits, stats.attacks),
+        None => "-".to_string(),
+    };
+
+    let mut lines = vec![
         Line::from(title).style(Style::default().fg(color)),
         Line::from(""),
         Line::from(format!("Turns: {}", app.state.turn)),
-        Line::from(format!(
-            "Rooms: {}/{}",
-            app.state.world.current_room + 1,
-            app.state.world.rooms.len()
-        )),
+        Line::from(match app.state.endless_score() {
+            Some(score) => format!("Rooms survived: {}", score),
+            None => format!(
+                "Rooms: {}/{}",
+                app.state.world.current_room + 1,
+                app.state.world.rooms.len()
+            ),
+        }),
         Line::from(format!("Level: {}", app.state.player.level)),
+        Line::from(format!("Kills: {}", app.state.enemies_killed)),
         Line::from(""),
-        Line::from("Press Q to quit"),
+        Line::from(format!("Damage dealt: {}", stats.damage_dealt)),
+        Line::from(format!("Damage taken: {}", stats.damage_taken)),
+        Line::from(format!("Accuracy: {}", accuracy)),
+        Line::from(format!("Items used: {}", stats.items_used)),
+        Line::from(""),
+        Line::from(format!("Essence earned: {}", app.state.essence_earned()))
+            .style(Style::default().fg(Color::Magenta)),
     ];
+    // Debug runs record nothing, so they set no records either
+    if let Some(progression) = app.progression.as_ref().filter(|_| !app.state.debug) {
+        for achievement in app.state.achievements(progression) {
+            lines.push(Line::from(format!("  {}", achievement.label())).style(Style::default().fg(Color::Yellow)));
+        }
+    }
+    lines.extend([
+        Line::from(""),
+        Line::from(if app.new_run.is_some() {
+            "R: retry  N: new seed  Q: quit"
+        } else {
+            "Press Q to quit"
+        }),
+    ]);
+
+    if let Some(timer) = &app.speedrun {
+        let time = format_time(timer.elapsed());
+        let line = if app.state.victory && timer.is_personal_best() {
+            Line::from(format!("Time: {} - New PB!", time)).style(Style::default().fg(Color::Yellow))
+        } else {
+            let best = timer.best.map_or("-".to_string(), format_time);
+            Line::from(format!("Time: {} (PB {})", time, best))
+        };
+        lines.insert(6, line);
+    }
 
     let width = 40;
+
+    // The commit graph is a trophy for winning; leave it out when the
+    // terminal is too short to fit it under the stats.
+    let graph_rows = COMMIT_GRAPH_HEIGHT + 1;
+    if app.state.victory && lines.len() + graph_rows + 2 <= area.height as usize {
+        let graph = commit_graph(&app.state.world.rooms, width as usize - 4);
+        if !graph.is_empty() {
+            let at = lines.len() - 1;
+            lines.insert(at, Line::from(""));
+            for (i, row) in graph.into_iter().enumerate() {
+                lines.insert(at + i, Line::from(row).style(Style::default().fg(Color::Green)));
+            }
+        }
+    }
+
     let height = lines.len() as u16 + 2;
     let x = (area.width - width) / 2;
     let y = (area.height - height) / 2;
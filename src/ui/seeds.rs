@@ -0,0 +1,116 @@
+//! Seed browser shown before play with `--choose-seed`.
+
+use std::io;
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::world::DungeonSummary;
+
+use super::colors::*;
+
+/// Where the player is in the seed browser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedChoice {
+    Browsing,
+    Picked(u64),
+    Cancelled,
+}
+
+/// A list of candidate dungeons to pick a seed from.
+pub struct SeedBrowser {
+    pub candidates: Vec<DungeonSummary>,
+    pub selected: usize,
+}
+
+impl SeedBrowser {
+    /// Browse the given candidates, starting at the first.
+    pub fn new(candidates: Vec<DungeonSummary>) -> Self {
+        Self {
+            candidates,
+            selected: 0,
+        }
+    }
+
+    /// Move the selection or settle on a seed.
+    pub fn handle_key(&mut self, code: KeyCode) -> SeedChoice {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                SeedChoice::Browsing
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.selected + 1 < self.candidates.len() {
+                    self.selected += 1;
+                }
+                SeedChoice::Browsing
+            }
+            KeyCode::Enter => match self.candidates.get(self.selected) {
+                Some(candidate) => SeedChoice::Picked(candidate.seed),
+                None => SeedChoice::Cancelled,
+            },
+            KeyCode::Esc | KeyCode::Char('q') => SeedChoice::Cancelled,
+            _ => SeedChoice::Browsing,
+        }
+    }
+
+    /// Show the browser until the player picks a seed or backs out.
+    /// Returns the picked seed, or `None` if they backed out.
+    pub fn run(&mut self, terminal: &mut Terminal<impl Backend>) -> io::Result<Option<u64>> {
+        loop {
+            terminal.draw(|frame| render_seed_browser(frame, self))?;
+            if !event::poll(Duration::from_millis(100))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                match self.handle_key(key.code) {
+                    SeedChoice::Browsing => {}
+                    SeedChoice::Picked(seed) => return Ok(Some(seed)),
+                    SeedChoice::Cancelled => return Ok(None),
+                }
+            }
+        }
+    }
+}
+
+/// One row of the browser, e.g. `seed 42  12 rooms  1 boss  37 enemies`.
+pub fn seed_line(candidate: &DungeonSummary) -> String {
+    format!(
+        "seed {:<20} {:>3} rooms  {:>2} boss{}  {:>4} enemies",
+        candidate.seed,
+        candidate.rooms,
+        candidate.bosses,
+        if candidate.bosses == 1 { "" } else { "es" },
+        candidate.enemies
+    )
+}
+
+/// Draw the candidate list with the selection highlighted.
+pub fn render_seed_browser(frame: &mut Frame, browser: &SeedBrowser) {
+    let area = frame.area();
+    let mut lines = vec![
+        Line::from("=== CHOOSE A SEED ===").style(Style::default().fg(UI_TITLE)),
+        Line::from(""),
+    ];
+    for (i, candidate) in browser.candidates.iter().enumerate() {
+        let (marker, style) = if i == browser.selected {
+            ("> ", Style::default().fg(Color::Yellow))
+        } else {
+            ("  ", Style::default())
+        };
+        lines.push(Line::from(format!("{}{}", marker, seed_line(candidate))).style(style));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from("Up/Down: browse  Enter: play  Esc: keep first seed"));
+
+    let width = 64.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let x = (area.width - width) / 2;
+    let y = (area.height - height) / 2;
+
+    let block = Block::default().title(" Seeds ").borders(Borders::ALL);
+    let para = Paragraph::new(lines).block(block);
+    frame.render_widget(para, Rect::new(x, y, width, height));
+}
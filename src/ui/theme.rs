@@ -0,0 +1,200 @@
+//! User-defined color themes.
+//!
+//! An optional `~/.penumbra/theme.toml` recolors the map. Each key names
+//! something drawn on it and maps to a color, by name or as `#rrggbb`:
+//!
+//! ```toml
+//! player = "#ffaf00"
+//! wall = "dark_gray"
+//! bug = "light_red"
+//! legendary = "magenta"
+//! ```
+//!
+//! Keys are the tiles (`floor`, `wall`, `door`, `exit`, `entrance`,
+//! `healing_zone`), `player`, the enemy types (`bug`, `regression`,
+//! `tech_debt`, `merge_conflict`, `scope_creep`, `deletion`) and the item
+//! rarities (`common`, `uncommon`, `rare`, `legendary`). Unknown keys and
+//! colors that don't parse are ignored, keeping the built-in color, and a
+//! file that isn't valid TOML is ignored as a whole.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use ratatui::style::Color;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+
+use super::colors::*;
+
+/// Colors for everything drawn on the map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub floor: Color,
+    pub wall: Color,
+    pub door: Color,
+    pub exit: Color,
+    pub entrance: Color,
+    pub healing_zone: Color,
+    pub player: Color,
+    pub bug: Color,
+    pub regression: Color,
+    pub tech_debt: Color,
+    pub merge_conflict: Color,
+    pub scope_creep: Color,
+    pub deletion: Color,
+    pub common: Color,
+    pub uncommon: Color,
+    pub rare: Color,
+    pub legendary: Color,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            floor: FLOOR_COLOR,
+            wall: WALL_COLOR,
+            door: DOOR_COLOR,
+            exit: EXIT_COLOR,
+            entrance: ENTRANCE_COLOR,
+            healing_zone: HEALING_ZONE_COLOR,
+            player: PLAYER_COLOR,
+            bug: BUG_COLOR,
+            regression: REGRESSION_COLOR,
+            tech_debt: TECH_DEBT_COLOR,
+            merge_conflict: MERGE_CONFLICT_COLOR,
+            scope_creep: SCOPE_CREEP_COLOR,
+            deletion: DELETION_COLOR,
+            common: ITEM_COMMON,
+            uncommon: ITEM_UNCOMMON,
+            rare: ITEM_RARE,
+            legendary: ITEM_LEGENDARY,
+        }
+    }
+}
+
+/// A top-level entry of `theme.toml`: a color string, or anything else,
+/// which is ignored.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawEntry {
+    Color(String),
+    Other(IgnoredAny),
+}
+
+impl Palette {
+    /// Parse a `theme.toml`. Invalid TOML gives the built-in palette.
+    pub fn parse(content: &str) -> Self {
+        let mut palette = Self::default();
+        let Ok(entries) = toml::from_str::<HashMap<String, RawEntry>>(content) else {
+            return palette;
+        };
+
+        for (key, entry) in entries {
+            let RawEntry::Color(value) = entry else {
+                continue;
+            };
+            if let (Some(slot), Some(color)) = (palette.slot(&key), parse_color(&value)) {
+                *slot = color;
+            }
+        }
+        palette
+    }
+
+    /// Load a theme from a file, or the built-in palette if it can't be read.
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    /// The color a `theme.toml` key sets.
+    fn slot(&mut self, key: &str) -> Option<&mut Color> {
+        let slot = match key.to_lowercase().replace('-', "_").as_str() {
+            "floor" => &mut self.floor,
+            "wall" => &mut self.wall,
+            "door" => &mut self.door,
+            "exit" => &mut self.exit,
+            "entrance" => &mut self.entrance,
+            "healing_zone" => &mut self.healing_zone,
+            "player" => &mut self.player,
+            "bug" => &mut self.bug,
+            "regression" => &mut self.regression,
+            "tech_debt" => &mut self.tech_debt,
+            "merge_conflict" => &mut self.merge_conflict,
+            "scope_creep" => &mut self.scope_creep,
+            "deletion" => &mut self.deletion,
+            "common" => &mut self.common,
+            "uncommon" => &mut self.uncommon,
+            "rare" => &mut self.rare,
+            "legendary" => &mut self.legendary,
+            _ => return None,
+        };
+        Some(slot)
+    }
+}
+
+/// Parse a terminal color name or a `#rrggbb` hex code.
+///
+/// Names are case-insensitive, and `grey` is accepted for `gray`.
+pub fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+
+    let name = value.to_lowercase().replace(['-', ' '], "_").replace("grey", "gray");
+    let color = match name.as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" => Color::Gray,
+        "dark_gray" | "darkgray" => Color::DarkGray,
+        "light_red" | "lightred" => Color::LightRed,
+        "light_green" | "lightgreen" => Color::LightGreen,
+        "light_yellow" | "lightyellow" => Color::LightYellow,
+        "light_blue" | "lightblue" => Color::LightBlue,
+        "light_magenta" | "lightmagenta" => Color::LightMagenta,
+        "light_cyan" | "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// Get the theme file path.
+pub fn theme_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".penumbra")
+        .join("theme.toml")
+}
+
+/// Load the theme from the config dir, falling back to the built-in palette.
+pub fn load_palette() -> Palette {
+    Palette::load_from(&theme_path())
+}
+
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+
+/// Make `palette` the map colors for the rest of the process.
+///
+/// Call once at startup, before anything is drawn. Returns false if a
+/// palette was already in use, in which case it is kept.
+pub fn install_palette(palette: Palette) -> bool {
+    PALETTE.set(palette).is_ok()
+}
+
+/// The palette in use: the installed theme, or the built-in colors.
+pub fn palette() -> &'static Palette {
+    PALETTE.get_or_init(Palette::default)
+}
@@ -0,0 +1,122 @@
+//! Wall-clock speedrun timer with per-room splits.
+//!
+//! The live clock lives in the app rather than the game state. Saves carry
+//! only its readings as a [`SpeedrunProgress`], which a continued run
+//! resumes from.
+
+use std::time::{Duration, Instant};
+
+use crate::game::SpeedrunProgress;
+
+/// Real time spent on a run, split each time a room is first cleared.
+#[derive(Debug, Clone)]
+pub struct SpeedrunTimer {
+    started: Instant,
+    /// Time played before this session, e.g. before the run was saved.
+    carried: Duration,
+    /// Each cleared room's index and the time into the run at which it was
+    /// first cleared, in order.
+    clears: Vec<(usize, Duration)>,
+    /// Run length, frozen once the run ends.
+    finished: Option<Duration>,
+    /// Fastest victory before this run.
+    pub best: Option<Duration>,
+}
+
+impl SpeedrunTimer {
+    /// Start timing now, against a previous best victory time.
+    pub fn new(best: Option<Duration>) -> Self {
+        Self::resume(best, &SpeedrunProgress::default())
+    }
+
+    /// Carry on timing a saved run from where its clock stopped.
+    pub fn resume(best: Option<Duration>, progress: &SpeedrunProgress) -> Self {
+        Self {
+            started: Instant::now(),
+            carried: Duration::from_millis(progress.elapsed_ms),
+            clears: progress
+                .clears_ms
+                .iter()
+                .map(|&(room, ms)| (room, Duration::from_millis(ms)))
+                .collect(),
+            finished: None,
+            best,
+        }
+    }
+
+    /// Time elapsed so far, or the final time once finished.
+    pub fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(|| self.carried + self.started.elapsed())
+    }
+
+    /// Close the current split the first time a room is cleared. Clearing
+    /// it again after backtracking changes nothing.
+    pub fn clear_room(&mut self, room: usize) {
+        if self.finished.is_none() && !self.clears.iter().any(|&(cleared, _)| cleared == room) {
+            self.clears.push((room, self.elapsed()));
+        }
+    }
+
+    /// Stop the clock. Later calls keep the first final time.
+    pub fn finish(&mut self) {
+        if self.finished.is_none() {
+            self.finished = Some(self.elapsed());
+        }
+    }
+
+    /// Whether the run has ended.
+    pub fn is_finished(&self) -> bool {
+        self.finished.is_some()
+    }
+
+    /// Time each cleared room took, in the order they were cleared.
+    pub fn splits(&self) -> Vec<Duration> {
+        let marks: Vec<Duration> = self.clears.iter().map(|&(_, at)| at).collect();
+        split_times(&marks)
+    }
+
+    /// Time since the last room was cleared.
+    pub fn current_split(&self) -> Duration {
+        let last = self.clears.last().map_or(Duration::ZERO, |&(_, at)| at);
+        self.elapsed().saturating_sub(last)
+    }
+
+    /// The clock's readings, for saving.
+    pub fn progress(&self) -> SpeedrunProgress {
+        SpeedrunProgress {
+            elapsed_ms: self.elapsed().as_millis() as u64,
+            clears_ms: self
+                .clears
+                .iter()
+                .map(|&(room, at)| (room, at.as_millis() as u64))
+                .collect(),
+        }
+    }
+
+    /// Whether a finished run beat the previous best.
+    pub fn is_personal_best(&self) -> bool {
+        match (self.finished, self.best) {
+            (Some(time), Some(best)) => time < best,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+/// Turn room clear times into the time each room took.
+///
+/// `clears` holds when each room was cleared, measured from the start of
+/// the run, in order. The first room's split starts at zero.
+pub fn split_times(clears: &[Duration]) -> Vec<Duration> {
+    std::iter::once(&Duration::ZERO)
+        .chain(clears)
+        .zip(clears)
+        .map(|(start, end)| end.saturating_sub(*start))
+        .collect()
+}
+
+/// Format a duration as `m:ss.t`.
+pub fn format_time(time: Duration) -> String {
+    let tenths = time.as_millis() / 100;
+    format!("{}:{:02}.{}", tenths / 600, (tenths / 10) % 60, tenths % 10)
+}
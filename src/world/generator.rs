@@ -1,48 +1,355 @@
 //! Dungeon generation from git, calendar, email, and weather data.
 
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
 use rand::prelude::*;
-use rand_chacha::ChaCha8Rng;
 
 use crate::calendar::{EventCategory, EventData};
+use crate::combat::find_path;
 use crate::git::CommitData;
+use crate::item::{Item, ItemEffect, ItemType, Rarity, Stat};
+use crate::rng::GameRng;
 use crate::weather::{DungeonAtmosphere, WeatherCondition, WeatherData};
 
-use super::{Room, RoomType, Tile, World};
+use super::{prepend_tutorial, DungeonSummary, Room, RoomType, Tile, World, MAX_ENEMIES_PER_ROOM};
+
+/// Size of a room whose commits changed no lines at all, such as empty
+/// commits or merges without a diff. Big enough to hold a token enemy or
+/// item beside the player.
+pub const EMPTY_COMMIT_ROOM_SIZE: (u8, u8) = (5, 5);
+
+/// What each room of a git dungeon stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoomGrouping {
+    /// One room per day of commits.
+    #[default]
+    Date,
+    /// One room per top-level directory, so the dungeon maps the codebase.
+    Directory,
+    /// One room per N commits in date order, however they fall on days.
+    /// Each room takes the date of its first commit, so a busy day can
+    /// give several rooms the same date.
+    Chunks(usize),
+}
+
+/// Options that shape a git dungeon.
+#[derive(Debug, Clone, Default)]
+pub struct GenerationOptions {
+    /// Keep only the most recent N days as rooms.
+    pub max_rooms: Option<usize>,
+    /// Keep only the N days with the most lines changed, still in date
+    /// order. Applied before `max_rooms`.
+    pub top_days: Option<usize>,
+    /// Order rooms newest first, so the run starts today and digs into the past.
+    pub reverse: bool,
+    /// Hide a bug-introducing room the player must find and accuse.
+    pub bisect: bool,
+    /// Split merge rooms into two branches that must both be walked.
+    pub merge_puzzle: bool,
+    /// Commits that mostly delete code also spawn swarms of Deletion enemies.
+    pub deletion_swarms: bool,
+    /// Start with a scripted tutorial room.
+    pub tutorial: bool,
+    /// Link non-adjacent rooms whose days touched the same files.
+    pub shortcuts: bool,
+    /// Rooms drop a readable fragment of one of their commits' diffs.
+    pub diff_lore: bool,
+    /// Theme the opening room after the most edited file.
+    pub themed_start: bool,
+    /// Cap on enemies spawned from a room's commits. `None` uses
+    /// `MAX_ENEMIES_PER_ROOM`.
+    pub max_enemies_per_room: Option<usize>,
+    /// Guarantee a boss room at least every N rooms.
+    pub boss_every: Option<usize>,
+    /// Plain rectangular rooms with the entrance and exit in fixed spots:
+    /// no merge branches and no shortcut doors.
+    pub simple_layout: bool,
+    /// Merge Conflict bosses are guarded by conflict markers and take
+    /// reduced damage until the markers die.
+    pub conflict_markers: bool,
+    /// Commits far larger than the median, such as squash merges, make
+    /// epic rooms with extra enemies for the work they fold together.
+    pub squash_aware: bool,
+    /// Whether rooms are days or directories.
+    pub group_by: RoomGrouping,
+    /// Start this many rooms in, with the rooms before marked cleared.
+    /// Applied by the game state rather than the generator.
+    pub skip_rooms: usize,
+}
 
 /// Generate a complete dungeon from git commit data.
 pub fn generate_dungeon(git_data: &[CommitData], seed: u64) -> World {
-    let mut rng = ChaCha8Rng::seed_from_u64(seed);
-    let grouped = crate::git::group_by_date(git_data.to_vec());
+    generate_dungeon_with_options(git_data, seed, &GenerationOptions::default())
+}
+
+/// Generate a dungeon from git commit data with generation options.
+///
+/// Each room draws from its own RNG (see `room_seed`), so a room's
+/// contents don't depend on how many rooms came before it.
+pub fn generate_dungeon_with_options(
+    git_data: &[CommitData],
+    seed: u64,
+    options: &GenerationOptions,
+) -> World {
+    if options.group_by == RoomGrouping::Directory {
+        return generate_dungeon_by_module(git_data, seed, options);
+    }
+
+    let mut days: Vec<_> = match options.group_by {
+        RoomGrouping::Chunks(size) => crate::git::group_by_count(git_data.to_vec(), size),
+        _ => crate::git::group_by_date(git_data.to_vec()).into_iter().collect(),
+    };
+
+    if let Some(top) = options.top_days {
+        keep_top_days(&mut days, top);
+    }
+
+    // Days are oldest first, so the most recent N are at the end
+    if let Some(max) = options.max_rooms {
+        let excess = days.len().saturating_sub(max.max(1));
+        days.drain(..excess);
+    }
+
+    if options.reverse {
+        days.reverse();
+    }
 
     let mut rooms = Vec::new();
 
-    for (index, (date, commits)) in grouped.iter().enumerate() {
-        let room = generate_room(*date, commits, index, &mut rng);
+    for (index, (date, commits)) in days.iter().enumerate() {
+        let mut rng = GameRng::seed_from_u64(room_seed(seed, index, *date));
+        let room = generate_room_with_options(*date, commits, index, options, &mut rng);
+        rooms.push(room);
+    }
+
+    let kept: Vec<CommitData> = days.into_iter().flat_map(|(_, commits)| commits).collect();
+    finish_dungeon(rooms, &kept, git_data, seed, options)
+}
+
+/// Seeds offered by the seed browser at once.
+pub const SEED_CANDIDATES: usize = 8;
+
+/// Summarize the dungeons built from `count` consecutive seeds starting
+/// at `first`, so a player can pick a shape before playing. The same
+/// inputs always give the same list.
+pub fn seed_candidates(
+    git_data: &[CommitData],
+    first: u64,
+    count: usize,
+    options: &GenerationOptions,
+) -> Vec<DungeonSummary> {
+    (0..count as u64)
+        .map(|offset| {
+            let seed = first.wrapping_add(offset);
+            DungeonSummary::of(&generate_dungeon_with_options(git_data, seed, options), seed)
+        })
+        .collect()
+}
+
+/// Keep the `count` busiest days, in date order.
+///
+/// Days rank by lines changed, then by number of commits. Remaining ties
+/// go to the earlier day. A count of 0 keeps one day.
+pub fn keep_top_days(days: &mut Vec<(NaiveDate, Vec<CommitData>)>, count: usize) {
+    let activity = |commits: &[CommitData]| {
+        let lines: u64 = commits.iter().map(|c| c.lines_changed() as u64).sum();
+        (lines, commits.len())
+    };
+    days.sort_by(|a, b| activity(&b.1).cmp(&activity(&a.1)).then_with(|| a.0.cmp(&b.0)));
+    days.truncate(count.max(1));
+    days.sort_by_key(|(date, _)| *date);
+}
+
+/// Generate a dungeon with one room per top-level directory.
+///
+/// Directories come in name order, or reversed with `reverse`, and
+/// `max_rooms` keeps the first N. Each room is dated by its latest
+/// commit, which also seeds it.
+pub fn generate_dungeon_by_module(
+    git_data: &[CommitData],
+    seed: u64,
+    options: &GenerationOptions,
+) -> World {
+    let mut modules: Vec<_> = crate::git::group_by_directory(git_data.to_vec()).into_iter().collect();
+    if let Some(max) = options.max_rooms {
+        modules.truncate(max.max(1));
+    }
+    if options.reverse {
+        modules.reverse();
+    }
+
+    let mut rooms = Vec::new();
+    for (index, (dir, commits)) in modules.iter().enumerate() {
+        let Some(date) = commits.iter().map(CommitData::date_naive).max() else {
+            continue;
+        };
+        let mut rng = GameRng::seed_from_u64(room_seed(seed, index, date));
+        let mut room = generate_room_with_options(date, commits, index, options, &mut rng);
+        room.module = Some(dir.clone());
         rooms.push(room);
     }
 
+    let kept: Vec<CommitData> = modules.into_iter().flat_map(|(_, commits)| commits).collect();
+    finish_dungeon(rooms, &kept, git_data, seed, options)
+}
+
+/// Connect generated rooms and apply the dungeon-wide options: the
+/// mini-boss, squash waves, boss pacing, themed start, tutorial and
+/// shortcuts. `kept` holds the commits that made it into rooms.
+fn finish_dungeon(
+    mut rooms: Vec<Room>,
+    kept: &[CommitData],
+    git_data: &[CommitData],
+    seed: u64,
+    options: &GenerationOptions,
+) -> World {
     // Place connections between rooms
     place_connections(&mut rooms);
+    repair_unreachable_exits(&mut rooms);
+
+    // The largest commit in the dungeon becomes a mini-boss in its room
+    if let Some(featured) = crate::git::largest_commit(kept) {
+        let room = rooms
+            .iter_mut()
+            .find(|r| r.source_commits.iter().any(|c| c.hash == featured.hash));
+        if let Some(room) = room {
+            // Separate stream so the mini-boss doesn't shift the room's layout
+            let sub_seed = room_seed(seed, room.id, room.source_date).wrapping_add(1);
+            let mut rng = GameRng::seed_from_u64(sub_seed);
+            room.spawn_mini_boss(featured, &mut rng);
+        }
+    }
+
+    if options.squash_aware {
+        if let Some(median) = crate::git::median_commit_size(kept) {
+            let max_enemies = options.max_enemies_per_room.unwrap_or(MAX_ENEMIES_PER_ROOM);
+            for room in &mut rooms {
+                // Separate stream so the waves don't shift the room's layout
+                let sub_seed = room_seed(seed, room.id, room.source_date).wrapping_add(2);
+                let mut rng = GameRng::seed_from_u64(sub_seed);
+                room.spawn_squash_waves(median, max_enemies, &mut rng);
+            }
+        }
+    }
+
+    if let Some(every) = options.boss_every {
+        enforce_boss_pacing(&mut rooms, every);
+    }
+
+    if options.themed_start {
+        let file = crate::git::most_edited_file(git_data);
+        if let (Some(file), Some(room)) = (file, rooms.first_mut()) {
+            room.apply_file_theme(&file);
+        }
+    }
+
+    if options.tutorial {
+        prepend_tutorial(&mut rooms);
+    }
+
+    if options.shortcuts && !options.simple_layout {
+        place_shortcuts(&mut rooms);
+    }
 
     World::new(rooms)
 }
 
+/// Make sure every window of `every` rooms holds a boss.
+///
+/// Rooms are split into consecutive windows of `every`. A window with no
+/// merge boss promotes its hardest normal room, by total enemy HP, with
+/// ties going to the earlier room. Windows without a normal room are left
+/// alone, and an `every` of 0 does nothing.
+pub fn enforce_boss_pacing(rooms: &mut [Room], every: usize) {
+    if every == 0 {
+        return;
+    }
+    for window in rooms.chunks_mut(every) {
+        if window.iter().any(|r| r.room_type == RoomType::Boss) {
+            continue;
+        }
+        let hardest = window
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.room_type == RoomType::Normal)
+            .max_by_key(|(i, r)| {
+                let hp: i32 = r.enemies.iter().map(|e| e.max_hp).sum();
+                (hp, std::cmp::Reverse(*i))
+            })
+            .map(|(i, _)| i);
+        if let Some(index) = hardest {
+            window[index].promote_to_boss();
+        }
+    }
+}
+
+/// Derive a room's RNG seed from the dungeon seed, its index and its date.
+pub fn room_seed(seed: u64, index: usize, date: NaiveDate) -> u64 {
+    let day = date.num_days_from_ce() as u64;
+    seed ^ (index as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ day.wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+}
+
 /// Generate a single room from a day's commits.
 pub fn generate_room(
     date: NaiveDate,
     commits: &[CommitData],
     index: usize,
-    rng: &mut impl Rng,
+    rng: &mut GameRng,
+) -> Room {
+    generate_room_with_options(date, commits, index, &GenerationOptions::default(), rng)
+}
+
+/// Generate a single room from a day's commits with generation options.
+///
+/// The room is populated from the same commits: one enemy per commit up
+/// to the room's enemy cap (see `Room::spawn_enemies_capped`), and items
+/// from `Room::spawn_items`. Both draw from `rng` after the layout, so
+/// they stay fixed for a given room seed.
+pub fn generate_room_with_options(
+    date: NaiveDate,
+    commits: &[CommitData],
+    index: usize,
+    options: &GenerationOptions,
+    rng: &mut GameRng,
 ) -> Room {
     let total_lines: u32 = commits.iter().map(|c| c.lines_changed()).sum();
-    let (width, height) = calculate_room_size(total_lines);
+    // A 3x3 room only fits the player, leaving empty commits pointless
+    let (width, height) = if total_lines == 0 && !commits.is_empty() {
+        EMPTY_COMMIT_ROOM_SIZE
+    } else {
+        calculate_room_size(total_lines)
+    };
     let room_type = determine_room_type(commits);
 
     let mut room = Room::new(index, width, height, room_type, date);
     room.source_commits = commits.to_vec();
+    // Rooms are saved with their commits; only keep snippets that get used
+    if !options.diff_lore {
+        for commit in &mut room.source_commits {
+            commit.diff_snippet = None;
+        }
+    }
 
     generate_layout(&mut room, rng);
+    // Merge rooms become two branches joined at both ends
+    if options.merge_puzzle && !options.simple_layout && room.room_type == RoomType::Boss {
+        room.add_merge_branches();
+    }
+    let max_enemies = options.max_enemies_per_room.unwrap_or(MAX_ENEMIES_PER_ROOM);
+    room.spawn_enemies_capped(commits, max_enemies, rng);
+    if room.room_type == RoomType::Boss {
+        room.name_merge_boss();
+        if options.conflict_markers {
+            room.spawn_conflict_markers(max_enemies);
+        }
+    }
+    room.spawn_items(commits, rng);
+    if options.deletion_swarms {
+        room.spawn_deletion_swarms(commits, max_enemies, rng);
+    }
+    if options.diff_lore {
+        room.spawn_diff_fragment(commits, rng);
+    }
 
     room
 }
@@ -126,7 +433,7 @@ pub fn determine_room_type(commits: &[CommitData]) -> RoomType {
 }
 
 /// Generate the room layout (walls, doors).
-fn generate_layout(room: &mut Room, _rng: &mut impl Rng) {
+fn generate_layout(room: &mut Room, _rng: &mut GameRng) {
     let w = room.width as i32;
     let h = room.height as i32;
 
@@ -177,24 +484,112 @@ pub fn place_connections(rooms: &mut [Room]) {
     }
 }
 
+/// Link rooms whose days touched the same files with shortcut doors.
+///
+/// Only rooms at least two apart are linked, since neighbours already
+/// share a door. Pairs sharing the most files go first, and each room
+/// gets at most one shortcut. Returns the linked pairs, earlier room first.
+pub fn place_shortcuts(rooms: &mut [Room]) -> Vec<(usize, usize)> {
+    let files: Vec<_> = rooms.iter().map(|r| r.files_touched()).collect();
+
+    let mut candidates = Vec::new();
+    for i in 0..rooms.len() {
+        for j in (i + 2)..rooms.len() {
+            let shared = files[i].intersection(&files[j]).count();
+            if shared > 0 {
+                candidates.push((shared, i, j));
+            }
+        }
+    }
+    // Most shared files first, then earliest rooms, so ties are stable
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+
+    let mut linked = Vec::new();
+    let mut used = vec![false; rooms.len()];
+    for (_, i, j) in candidates {
+        if used[i] || used[j] {
+            continue;
+        }
+        used[i] = true;
+        used[j] = true;
+        linked.push((i, j));
+    }
+
+    for &(i, j) in &linked {
+        rooms[i].add_shortcut(j);
+        rooms[j].add_shortcut(i);
+    }
+    linked
+}
+
+/// Make sure every room's exit can be reached from where the player enters.
+pub fn repair_unreachable_exits(rooms: &mut [Room]) {
+    for room in rooms {
+        ensure_exit_reachable(room);
+    }
+}
+
+/// Make sure the exit can be reached from the player's start tile.
+///
+/// If there is no path, floor is carved along the start row and then
+/// down the column beside the exit, through whatever is in the way.
+/// Returns whether the room needed repair.
+pub fn ensure_exit_reachable(room: &mut Room) -> bool {
+    let Some(exit) = room.exit_position() else {
+        return false;
+    };
+    let start = (1, room.height as i32 / 2);
+    if find_path(start, exit, room).is_some() {
+        return false;
+    }
+
+    // Stay inside the perimeter; the exit itself sits in the wall
+    let (w, h) = (room.width as i32, room.height as i32);
+    let corner_x = exit.0.clamp(1, (w - 2).max(1));
+    let corner_y = exit.1.clamp(1, (h - 2).max(1));
+
+    let row = (start.0.min(corner_x)..=start.0.max(corner_x)).map(|x| (x, start.1));
+    let column = (start.1.min(corner_y)..=start.1.max(corner_y)).map(|y| (corner_x, y));
+    for (x, y) in row.chain(column) {
+        if (x, y) != exit && !room.is_walkable(x, y) {
+            room.set_tile(x, y, Tile::Floor);
+        }
+    }
+    true
+}
+
 // ============================================================================
 // Calendar-based dungeon generation
 // ============================================================================
 
 /// Generate a complete dungeon from calendar event data.
 pub fn generate_dungeon_from_calendar(events: &[EventData], seed: u64) -> World {
-    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    generate_dungeon_from_calendar_smoothed(events, seed, 0)
+}
+
+/// Generate a dungeon from calendar event data, sizing each room from the
+/// average intensity of its day and up to `smoothing` days either side.
+/// Zero sizes rooms from their own day alone.
+pub fn generate_dungeon_from_calendar_smoothed(events: &[EventData], seed: u64, smoothing: usize) -> World {
+    let mut rng = GameRng::seed_from_u64(seed);
     let grouped = crate::calendar::group_by_date(events.to_vec());
 
+    let raw: Vec<u32> = grouped
+        .values()
+        .map(|day_events| day_events.iter().map(|e| e.intensity()).sum())
+        .collect();
+    let intensities = smooth_intensities(&raw, smoothing);
+
     let mut rooms = Vec::new();
 
     for (index, (date, day_events)) in grouped.iter().enumerate() {
-        let room = generate_room_from_events(*date, day_events, index, &mut rng);
+        let room = build_room_from_events(*date, day_events, intensities[index], index, &mut rng);
         rooms.push(room);
     }
 
     // Place connections between rooms
     place_connections(&mut rooms);
+    repair_unreachable_exits(&mut rooms);
 
     World::new(rooms)
 }
@@ -204,20 +599,81 @@ pub fn generate_room_from_events(
     date: NaiveDate,
     events: &[EventData],
     index: usize,
-    rng: &mut impl Rng,
+    rng: &mut GameRng,
 ) -> Room {
     // Calculate total intensity (duration + attendees)
     let total_intensity: u32 = events.iter().map(|e| e.intensity()).sum();
-    let (width, height) = calculate_room_size_from_intensity(total_intensity);
+    build_room_from_events(date, events, total_intensity, index, rng)
+}
+
+/// Build a day's room at a given intensity, which may be smoothed across
+/// neighbouring days rather than the day's own total.
+fn build_room_from_events(
+    date: NaiveDate,
+    events: &[EventData],
+    intensity: u32,
+    index: usize,
+    rng: &mut GameRng,
+) -> Room {
+    let (width, height) = calculate_room_size_from_intensity(intensity);
     let room_type = determine_room_type_from_events(events);
 
     let mut room = Room::new(index, width, height, room_type, date);
 
     generate_layout(&mut room, rng);
+    if room.room_type == RoomType::Treasure {
+        spawn_event_items(&mut room, events, rng);
+    }
 
     room
 }
 
+/// Stock a calendar Treasure room with 2-3 items, drawn in turn from the
+/// day's focus blocks (Focus Crystals) and 1:1s (Energy Vials). Blocks of
+/// two hours or more give rare items.
+fn spawn_event_items(room: &mut Room, events: &[EventData], rng: &mut GameRng) {
+    let sources: Vec<&EventData> = events
+        .iter()
+        .filter(|e| matches!(e.category, EventCategory::FocusTime | EventCategory::OneOnOne))
+        .collect();
+    let mut positions = room.get_free_positions();
+    if sources.is_empty() {
+        return;
+    }
+
+    let count = rng.gen_range(2..=3);
+    for event in sources.iter().cycle().take(count) {
+        if positions.is_empty() {
+            break;
+        }
+        let (x, y) = positions.remove(rng.gen_range(0..positions.len()));
+        let rarity = if event.duration_minutes >= 120 { Rarity::Rare } else { Rarity::Uncommon };
+        let item = match event.category {
+            EventCategory::FocusTime => Item::new(
+                "Focus Crystal",
+                ItemType::Consumable,
+                ItemEffect::Buff(Stat::Focus, 4, 5),
+                rarity,
+            ),
+            _ => Item::new("Energy Vial", ItemType::Consumable, ItemEffect::RestoreEnergy(10), rarity),
+        };
+        room.items.push(item.at(x, y));
+    }
+}
+
+/// Moving average of day intensities over `radius` days either side,
+/// rounded to the nearest point. Days near the ends average over the
+/// neighbours they have.
+pub fn smooth_intensities(raw: &[u32], radius: usize) -> Vec<u32> {
+    (0..raw.len())
+        .map(|i| {
+            let window = &raw[i.saturating_sub(radius)..(i + radius + 1).min(raw.len())];
+            let len = window.len() as u32;
+            (window.iter().sum::<u32>() + len / 2) / len
+        })
+        .collect()
+}
+
 /// Calculate room dimensions from total event intensity.
 /// Higher intensity (longer/busier meetings) = larger rooms.
 pub fn calculate_room_size_from_intensity(intensity: u32) -> (u8, u8) {
@@ -258,7 +714,12 @@ pub fn determine_room_type_from_events(events: &[EventData]) -> RoomType {
         return RoomType::Normal;
     }
 
-    // Focus time or break-heavy days = Sanctuary (restorative)
+    // Focus-heavy days = Treasure (the work gets done)
+    if focus_time * 2 > total {
+        return RoomType::Treasure;
+    }
+
+    // Break-heavy days, or focus mixed with breaks = Sanctuary (restorative)
     if (focus_time + breaks) * 2 > total {
         return RoomType::Sanctuary;
     }
@@ -279,7 +740,7 @@ pub fn determine_room_type_from_events(events: &[EventData]) -> RoomType {
 pub fn generate_dungeon_from_email(emails: &[crate::email::EmailData], seed: u64) -> World {
     use std::collections::HashMap;
     
-    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut rng = GameRng::seed_from_u64(seed);
     
     // Group emails by date
     let mut grouped: HashMap<NaiveDate, Vec<&crate::email::EmailData>> = HashMap::new();
@@ -302,6 +763,7 @@ pub fn generate_dungeon_from_email(emails: &[crate::email::EmailData], seed: u64
     
     // Place connections between rooms
     place_connections(&mut rooms);
+    repair_unreachable_exits(&mut rooms);
     
     World::new(rooms)
 }
@@ -311,7 +773,7 @@ pub fn generate_room_from_emails(
     date: &NaiveDate,
     emails: &[&crate::email::EmailData],
     index: usize,
-    rng: &mut impl Rng,
+    rng: &mut GameRng,
 ) -> Room {
     // Calculate total intensity from emails
     let total_intensity: u32 = emails.iter().map(|e| e.intensity()).sum();
@@ -371,7 +833,7 @@ pub fn determine_room_type_from_emails(emails: &[&crate::email::EmailData]) -> R
 /// Generate a complete dungeon from weather data.
 /// Weather creates a unique single-session dungeon with atmosphere modifiers.
 pub fn generate_dungeon_from_weather(weather: &WeatherData, seed: u64) -> World {
-    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut rng = GameRng::seed_from_u64(seed);
     let atmosphere = crate::weather::generate_atmosphere(weather);
 
     // Weather dungeons have 5-10 rooms based on difficulty
@@ -389,6 +851,7 @@ pub fn generate_dungeon_from_weather(weather: &WeatherData, seed: u64) -> World
 
     // Place connections between rooms
     place_connections(&mut rooms);
+    repair_unreachable_exits(&mut rooms);
 
     World::new(rooms)
 }
@@ -399,7 +862,7 @@ pub fn generate_room_from_weather(
     weather: &WeatherData,
     atmosphere: &DungeonAtmosphere,
     index: usize,
-    rng: &mut impl Rng,
+    rng: &mut GameRng,
 ) -> Room {
     // Use weather intensity for room size
     let intensity = weather.intensity();
@@ -420,7 +883,7 @@ pub fn determine_room_type_from_weather(
     weather: &WeatherData,
     atmosphere: &DungeonAtmosphere,
     index: usize,
-    rng: &mut impl Rng,
+    rng: &mut GameRng,
 ) -> RoomType {
     // Last room is always boss for storm/hail
     if index >= 4 && matches!(weather.condition, WeatherCondition::Storm | WeatherCondition::Hail) {
@@ -634,17 +1097,42 @@ mod calendar_tests {
     }
 
     #[test]
-    fn test_focus_time_creates_sanctuary() {
+    fn test_focus_time_creates_treasure() {
         let events = vec![
             make_event("Focus Time", 120, 1),
             make_event("Deep Work", 60, 1),
         ];
+        assert_eq!(
+            determine_room_type_from_events(&events),
+            RoomType::Treasure
+        );
+    }
+
+    #[test]
+    fn test_breaks_create_sanctuary() {
+        let events = vec![
+            make_event("Lunch", 60, 1),
+            make_event("Focus Time", 60, 1),
+            make_event("Team Standup", 15, 5),
+            make_event("Coffee break", 15, 1),
+        ];
         assert_eq!(
             determine_room_type_from_events(&events),
             RoomType::Sanctuary
         );
     }
 
+    #[test]
+    fn test_focus_time_room_holds_focus_items() {
+        let events = vec![make_event("Focus Time", 180, 1), make_event("Deep Work", 90, 1)];
+        let mut rng = GameRng::seed_from_u64(42);
+        let room = generate_room_from_events(NaiveDate::from_ymd_opt(2026, 2, 15).unwrap(), &events, 0, &mut rng);
+
+        assert_eq!(room.room_type, RoomType::Treasure);
+        assert!((2..=3).contains(&room.items.len()));
+        assert!(room.items.iter().all(|item| item.name == "Focus Crystal"));
+    }
+
     #[test]
     fn test_one_on_ones_create_treasure() {
         let events = vec![
@@ -701,4 +1189,38 @@ mod calendar_tests {
         let world = generate_dungeon_from_calendar(&events, 42);
         assert_eq!(world.rooms.len(), 2);
     }
+
+    #[test]
+    fn test_smooth_intensities() {
+        assert_eq!(smooth_intensities(&[30, 0, 30, 0], 0), vec![30, 0, 30, 0]);
+        assert_eq!(smooth_intensities(&[30, 0, 30, 0], 1), vec![15, 20, 10, 15]);
+        assert!(smooth_intensities(&[], 2).is_empty());
+    }
+
+    #[test]
+    fn test_calendar_smoothing_evens_out_spiky_days() {
+        // Packed all-day workshops alternating with a single short check-in
+        let events: Vec<EventData> = (0..8)
+            .map(|day| {
+                let mut event = if day % 2 == 0 {
+                    make_event("Workshop", 300, 4)
+                } else {
+                    make_event("Check-in", 15, 1)
+                };
+                event.uid = format!("day-{}", day);
+                event.start = Utc.with_ymd_and_hms(2026, 2, 1 + day, 10, 0, 0).unwrap();
+                event.end = event.start + chrono::Duration::minutes(event.duration_minutes as i64);
+                event
+            })
+            .collect();
+
+        let swing = |world: &World| -> i32 {
+            world.rooms.windows(2).map(|pair| (pair[0].width as i32 - pair[1].width as i32).abs()).sum()
+        };
+        let raw = generate_dungeon_from_calendar(&events, 42);
+        let smoothed = generate_dungeon_from_calendar_smoothed(&events, 42, 1);
+
+        assert_eq!(raw.rooms.len(), smoothed.rooms.len());
+        assert!(swing(&smoothed) < swing(&raw));
+    }
 }
@@ -3,9 +3,11 @@
 mod generator;
 mod room;
 mod tile;
+mod tutorial;
 mod types;
 
 pub use generator::*;
 pub use room::*;
 pub use tile::*;
+pub use tutorial::*;
 pub use types::*;
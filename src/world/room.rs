@@ -1,5 +1,7 @@
 //! Room structure and generation.
 
+use std::collections::HashSet;
+
 use chrono::NaiveDate;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
@@ -7,8 +9,46 @@ use serde::{Deserialize, Serialize};
 use crate::entity::{Enemy, EnemyType};
 use crate::git::CommitData;
 use crate::item::{Item, ItemEffect, ItemType, Rarity};
+use crate::rng::GameRng;
+
+use super::{AmbienceCue, Direction, DoorState, RoomType, Tile};
+
+/// Commits touching at least this many files spawn a Scope Creep.
+pub const SCOPE_CREEP_FILES: u32 = 20;
+
+/// Lines a commit must remove, and remove more than it adds, to spawn a
+/// deletion swarm. Each multiple of this adds one Deletion to the swarm.
+pub const DELETION_SWARM_LINES: u32 = 50;
+
+/// Largest swarm a single commit can spawn.
+pub const DELETION_SWARM_MAX: usize = 4;
+
+/// Enemies never spawn within this many steps of the player's entry point.
+pub const ENTRANCE_SAFE_RADIUS: i32 = 2;
+
+/// Lines a refactor commit must change to drop a Refactor Scroll.
+pub const REFACTOR_SCROLL_LINES: u32 = 200;
+
+/// Default cap on enemies spawned from a room's commits.
+pub const MAX_ENEMIES_PER_ROOM: usize = 10;
+
+/// Conflict markers spawned to guard a merge boss.
+pub const CONFLICT_MARKERS: usize = 2;
+
+/// Living enemies that make any room's ambience tense.
+pub const TENSE_ENEMY_COUNT: usize = 5;
+
+/// Highest rating from `Room::estimated_difficulty`.
+pub const MAX_DIFFICULTY_STARS: u32 = 5;
+
+/// Threat added to the difficulty estimate for a Boss Chamber.
+const BOSS_THREAT: i32 = 60;
+
+/// Threat added to the difficulty estimate per room of depth.
+const DEPTH_THREAT: i32 = 5;
 
-use super::{RoomType, Tile};
+/// Threat that earns one more star in the difficulty estimate.
+const THREAT_PER_STAR: i32 = 60;
 
 /// A single room in the dungeon.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +63,43 @@ pub struct Room {
     pub source_commits: Vec<CommitData>,
     pub room_type: RoomType,
     pub cleared: bool,
+    /// Hash of the featured commit guarded by this room's mini-boss.
+    #[serde(default)]
+    pub featured_commit: Option<String>,
+    /// Whether the player has attacked anything in this room.
+    #[serde(default)]
+    pub fought: bool,
+    /// Left with enemies still standing and without a fight.
+    #[serde(default)]
+    pub pacifist: bool,
+    /// Which branches of a merge room have been walked, north then south.
+    /// `None` for rooms without the merge layout.
+    #[serde(default)]
+    pub merge_branches: Option<[bool; 2]>,
+    /// Turn each tile was last in view, row by row. Empty until the
+    /// player first sees into the room.
+    #[serde(default)]
+    pub seen_turns: Vec<Option<u32>>,
+    /// Room index a shortcut door here leads to, for rooms whose day
+    /// touched the same files as a non-adjacent day.
+    #[serde(default)]
+    pub shortcut: Option<usize>,
+    /// Path of the file this room is themed after, for an opening room
+    /// built around the most edited file.
+    #[serde(default)]
+    pub theme_file: Option<String>,
+    /// Built from a squashed commit far larger than the dungeon's median,
+    /// which brings extra waves of enemies.
+    #[serde(default)]
+    pub epic: bool,
+    /// Top-level directory this room maps, for dungeons grouped by
+    /// directory instead of by day.
+    #[serde(default)]
+    pub module: Option<String>,
+    /// Enemies killed this turn. They no longer act or block anything,
+    /// and are only kept to draw a death glyph until the next turn.
+    #[serde(skip)]
+    pub dying: Vec<Enemy>,
 }
 
 impl Room {
@@ -40,9 +117,276 @@ impl Room {
             source_commits: Vec::new(),
             room_type,
             cleared: false,
+            featured_commit: None,
+            fought: false,
+            pacifist: false,
+            merge_branches: None,
+            seen_turns: Vec::new(),
+            shortcut: None,
+            theme_file: None,
+            epic: false,
+            module: None,
+            dying: Vec::new(),
         }
     }
 
+    /// Display name: the room type, or the theme file's or directory's name.
+    pub fn name(&self) -> String {
+        match &self.theme_file {
+            Some(path) => format!("The {} Halls", file_name(path)),
+            None if self.module.is_some() => {
+                format!("The {}/ Wing", self.module.as_deref().unwrap_or_default())
+            }
+            None if self.epic => format!("Epic {}", self.room_type.name()),
+            None => self.room_type.name().to_string(),
+        }
+    }
+
+    /// Split squashed commits into extra waves of enemies.
+    ///
+    /// Each commit that `is_squash_outlier` against `median` spawns
+    /// another enemy of its type for every commit it stands for beyond
+    /// the first (see `squash_weight`), still within `max_enemies` and
+    /// the room's size. Rooms with an outlier are marked epic. Returns
+    /// how many enemies were added.
+    pub fn spawn_squash_waves(&mut self, median: u32, max_enemies: usize, rng: &mut GameRng) -> usize {
+        let outliers: Vec<CommitData> = self
+            .source_commits
+            .iter()
+            .filter(|c| crate::git::is_squash_outlier(c, median))
+            .cloned()
+            .collect();
+        if outliers.is_empty() {
+            return 0;
+        }
+        self.epic = true;
+        if self.room_type == RoomType::Sanctuary {
+            return 0;
+        }
+
+        let room_size = (self.width as usize * self.height as usize) / 4;
+        let mut room_left = room_size.min(max_enemies).saturating_sub(self.enemies.len());
+        let mut positions: Vec<_> = self
+            .get_free_positions()
+            .into_iter()
+            .filter(|&pos| !self.near_start(pos))
+            .collect();
+
+        let mut added = 0;
+        for commit in &outliers {
+            let extra = crate::git::squash_weight(commit, median) - 1;
+            for _ in 0..extra {
+                if room_left == 0 || positions.is_empty() {
+                    return added;
+                }
+                let (x, y) = positions.remove(rng.gen_range(0..positions.len()));
+                let enemy_type = Self::enemy_type_from_commit(commit);
+                self.enemies.push(Enemy::new(enemy_type, x, y, &commit.hash));
+                room_left -= 1;
+                added += 1;
+            }
+        }
+        if added > 0 {
+            self.cleared = false;
+        }
+        added
+    }
+
+    /// Theme the room after a file and crown a keeper for it.
+    ///
+    /// The keeper is promoted like a mini-boss, from an enemy whose
+    /// commit touched the file if there is one. The dungeon's own
+    /// mini-boss is left alone, and rooms without enemies get no keeper.
+    pub fn apply_file_theme(&mut self, path: &str) {
+        self.theme_file = Some(path.to_string());
+
+        let touched: HashSet<&str> = self
+            .source_commits
+            .iter()
+            .filter(|c| c.file_categories.paths.iter().any(|p| p == path))
+            .map(|c| c.hash.as_str())
+            .collect();
+        let keeper = self
+            .enemies
+            .iter()
+            .position(|e| !e.is_mini_boss() && touched.contains(e.source_commit.as_str()))
+            .or_else(|| self.enemies.iter().position(|e| !e.is_mini_boss()));
+
+        if let Some(index) = keeper {
+            self.enemies[index].promote(format!("Keeper of {}", file_name(path)));
+        }
+    }
+
+    /// Name the boss of a merge room after its merge commit.
+    ///
+    /// The enemy from the first merge commit with a significant word
+    /// (see `significant_word`) is titled "The {word} Conflict". Only the
+    /// name changes; it keeps its stats. Returns whether a boss was named.
+    pub fn name_merge_boss(&mut self) -> bool {
+        let named = self.source_commits.iter().filter(|c| c.is_merge).find_map(|commit| {
+            let word = crate::git::significant_word(&commit.message)?;
+            let index = self
+                .enemies
+                .iter()
+                .position(|e| e.source_commit == commit.hash && !e.is_mini_boss())?;
+            Some((index, word))
+        });
+
+        match named {
+            Some((index, word)) => {
+                self.enemies[index].title = Some(format!("The {} Conflict", word));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Guard a Merge Conflict boss with conflict markers.
+    ///
+    /// Up to `CONFLICT_MARKERS` Bugs from the boss's commit spawn on the
+    /// free tiles nearest it, and the boss is warded until they all die.
+    /// Markers count towards `max_enemies` and the room's size, so a room
+    /// already full leaves the boss unguarded. Returns whether any markers
+    /// were placed.
+    pub fn spawn_conflict_markers(&mut self, max_enemies: usize) -> bool {
+        let Some(boss) = self.boss().filter(|b| b.enemy_type == EnemyType::MergeConflict) else {
+            return false;
+        };
+        let (bx, by, commit) = (boss.x, boss.y, boss.source_commit.clone());
+
+        let mut spots: Vec<_> = self
+            .get_free_positions()
+            .into_iter()
+            .filter(|&pos| !self.near_start(pos))
+            .collect();
+        spots.sort_by_key(|&(x, y)| (x - bx).abs().max((y - by).abs()));
+        let room_size = (self.width as usize * self.height as usize) / 4;
+        let room_left = room_size.min(max_enemies).saturating_sub(self.enemies.len());
+        if spots.is_empty() || room_left == 0 {
+            return false;
+        }
+
+        for &(x, y) in spots.iter().take(CONFLICT_MARKERS.min(room_left)) {
+            let mut marker = Enemy::new(EnemyType::Bug, x, y, &commit);
+            marker.conflict_marker = true;
+            self.enemies.push(marker);
+        }
+        if let Some(boss) = self.enemies.iter_mut().find(|e| e.x == bx && e.y == by) {
+            boss.warded = true;
+        }
+        true
+    }
+
+    /// Lift the ward once the last conflict marker is gone. Returns
+    /// whether a ward was lifted.
+    pub fn lift_conflict_ward(&mut self) -> bool {
+        if self.enemies.iter().any(|e| e.conflict_marker) {
+            return false;
+        }
+        let mut lifted = false;
+        for enemy in self.enemies.iter_mut().filter(|e| e.warded) {
+            enemy.warded = false;
+            lifted = true;
+        }
+        lifted
+    }
+
+    /// The boss of a boss chamber: its toughest titled enemy.
+    pub fn boss(&self) -> Option<&Enemy> {
+        if self.room_type != RoomType::Boss {
+            return None;
+        }
+        self.enemies
+            .iter()
+            .filter(|e| e.is_mini_boss())
+            .max_by_key(|e| e.max_hp)
+    }
+
+    /// Turn the room into a boss chamber.
+    ///
+    /// Its toughest enemy is promoted like a mini-boss and named after its
+    /// commit. The dungeon's own mini-boss is left alone, and a room
+    /// without other enemies just changes type.
+    pub fn promote_to_boss(&mut self) {
+        self.room_type = RoomType::Boss;
+
+        let boss = self
+            .enemies
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| !e.is_mini_boss())
+            .max_by_key(|(i, e)| (e.max_hp, e.damage, std::cmp::Reverse(*i)))
+            .map(|(i, _)| i);
+        if let Some(index) = boss {
+            let hash = self.enemies[index].source_commit.clone();
+            let title = match self.source_commits.iter().find(|c| c.hash == hash) {
+                Some(commit) => mini_boss_title(commit),
+                None => "The Nameless Commit".to_string(),
+            };
+            self.enemies[index].promote(title);
+            self.cleared = false;
+        }
+    }
+
+    /// Record that these tiles were in view on `turn`.
+    pub fn mark_seen<'a>(&mut self, tiles: impl IntoIterator<Item = &'a (i32, i32)>, turn: u32) {
+        let (w, h) = (self.width as usize, self.height as usize);
+        if self.seen_turns.len() != w * h {
+            self.seen_turns = vec![None; w * h];
+        }
+        for &(x, y) in tiles {
+            if x >= 0 && y >= 0 && (x as usize) < w && (y as usize) < h {
+                self.seen_turns[y as usize * w + x as usize] = Some(turn);
+            }
+        }
+    }
+
+    /// Turn a tile was last in view, or `None` if it never has been.
+    pub fn last_seen(&self, x: i32, y: i32) -> Option<u32> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        let index = y as usize * self.width as usize + x as usize;
+        self.seen_turns.get(index).copied().flatten()
+    }
+
+    /// Author of one of this room's source commits.
+    pub fn commit_author(&self, hash: &str) -> Option<&str> {
+        self.source_commits
+            .iter()
+            .find(|c| c.hash == hash)
+            .map(|c| c.author.as_str())
+    }
+
+    /// Author of the most source commits, the first listed on ties.
+    pub fn dominant_author(&self) -> Option<&str> {
+        let mut best: Option<(&str, usize)> = None;
+        for commit in &self.source_commits {
+            let count = self
+                .source_commits
+                .iter()
+                .filter(|c| c.author == commit.author)
+                .count();
+            match best {
+                Some((_, most)) if most >= count => {}
+                _ => best = Some((commit.author.as_str(), count)),
+            }
+        }
+        best.map(|(author, _)| author)
+    }
+
+    /// Rough 1-5 star rating of how hard this room will be.
+    ///
+    /// Each enemy adds its HP plus three times its damage, Boss Chambers
+    /// add a flat amount, and deeper rooms a little more per room.
+    pub fn estimated_difficulty(&self) -> u32 {
+        let enemies: i32 = self.enemies.iter().map(|e| e.max_hp + e.damage * 3).sum();
+        let boss = if self.room_type == RoomType::Boss { BOSS_THREAT } else { 0 };
+        let depth = self.id as i32 * DEPTH_THREAT;
+        let stars = 1 + (enemies + boss + depth) / THREAT_PER_STAR;
+        (stars as u32).min(MAX_DIFFICULTY_STARS)
+    }
+
     /// Check if a position is walkable.
     pub fn is_walkable(&self, x: i32, y: i32) -> bool {
         if x < 0 || y < 0 {
@@ -52,7 +396,26 @@ impl Room {
         if ux >= self.width as usize || uy >= self.height as usize {
             return false;
         }
-        self.tiles[uy][ux].is_walkable()
+        self.get_tile(x, y).is_some_and(|t| t.is_walkable())
+    }
+
+    /// Whether a position lies inside the room's bounds.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32
+    }
+
+    /// Whether the room has no tiles at all, from degenerate generation.
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0 || self.tiles.iter().all(|row| row.is_empty())
+    }
+
+    /// Walkable tile nearest a position, by steps in a straight line;
+    /// ties go to the topmost, then leftmost tile.
+    pub fn nearest_walkable(&self, (x, y): (i32, i32)) -> Option<(i32, i32)> {
+        (0..self.height as i32)
+            .flat_map(|ty| (0..self.width as i32).map(move |tx| (tx, ty)))
+            .filter(|&(tx, ty)| self.is_walkable(tx, ty))
+            .min_by_key(|&(tx, ty)| ((tx - x).abs().max((ty - y).abs()), ty, tx))
     }
 
     /// Get tile at position.
@@ -79,18 +442,83 @@ impl Room {
         self.enemies.iter().find(|e| e.x == x && e.y == y)
     }
 
+    /// Get the enemy that fell at a position this turn.
+    pub fn get_dying_at(&self, x: i32, y: i32) -> Option<&Enemy> {
+        self.dying.iter().find(|e| e.x == x && e.y == y)
+    }
+
     /// Get item at position.
     pub fn get_item_at(&self, x: i32, y: i32) -> Option<&Item> {
         self.items.iter().find(|i| i.x == x && i.y == y)
     }
 
+    /// The room's mood, from its type and how many enemies remain.
+    ///
+    /// Sanctuaries, the tutorial and cleared rooms are calm. Boss chambers
+    /// and rooms with at least `TENSE_ENEMY_COUNT` enemies are tense.
+    pub fn ambience(&self) -> AmbienceCue {
+        match self.room_type {
+            RoomType::Sanctuary | RoomType::Tutorial => AmbienceCue::Calm,
+            _ if self.is_cleared() => AmbienceCue::Calm,
+            RoomType::Boss => AmbienceCue::Tense,
+            _ if self.enemies.len() >= TENSE_ENEMY_COUNT => AmbienceCue::Tense,
+            RoomType::Treasure | RoomType::Library => AmbienceCue::Mysterious,
+            RoomType::Normal => AmbienceCue::Uneasy,
+        }
+    }
+
     /// Check if room is cleared of enemies.
     pub fn is_cleared(&self) -> bool {
         self.enemies.is_empty() || self.cleared
     }
 
+    /// Position of this room's exit, if it has one.
+    pub fn exit_position(&self) -> Option<(i32, i32)> {
+        self.tiles.iter().enumerate().find_map(|(y, row)| {
+            row.iter()
+                .position(|t| *t == Tile::Exit)
+                .map(|x| (x as i32, y as i32))
+        })
+    }
+
+    /// Where a shortcut door sits: the middle of the north wall.
+    pub fn shortcut_door(&self) -> (i32, i32) {
+        (self.width as i32 / 2, 0)
+    }
+
+    /// Where the player arrives through a shortcut: just inside its door.
+    pub fn shortcut_arrival(&self) -> (i32, i32) {
+        let (x, y) = self.shortcut_door();
+        (x, y + 1)
+    }
+
+    /// Set a shortcut door leading to room `to`.
+    pub fn add_shortcut(&mut self, to: usize) {
+        let (x, y) = self.shortcut_door();
+        self.set_tile(x, y, Tile::Door(Direction::North, DoorState::Closed));
+
+        let (ax, ay) = self.shortcut_arrival();
+        if !self.is_walkable(ax, ay) {
+            self.set_tile(ax, ay, Tile::Floor);
+        }
+        self.shortcut = Some(to);
+    }
+
+    /// Distinct file paths touched by this room's commits.
+    pub fn files_touched(&self) -> HashSet<&str> {
+        self.source_commits
+            .iter()
+            .flat_map(|c| c.file_categories.paths.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Whether no enemy here has noticed the player.
+    pub fn undetected(&self) -> bool {
+        self.enemies.iter().all(|e| !e.aggro)
+    }
+
     /// Get walkable positions not occupied by enemies or items.
-    fn get_free_positions(&self) -> Vec<(i32, i32)> {
+    pub fn get_free_positions(&self) -> Vec<(i32, i32)> {
         let mut positions = Vec::new();
         for y in 1..(self.height as i32 - 1) {
             for x in 1..(self.width as i32 - 1) {
@@ -105,6 +533,48 @@ impl Room {
         positions
     }
 
+    /// Whether a position is within `ENTRANCE_SAFE_RADIUS` steps of where
+    /// the player enters the room, `(1, height/2)`.
+    pub fn near_start(&self, (x, y): (i32, i32)) -> bool {
+        let (sx, sy) = (1, self.height as i32 / 2);
+        (x - sx).abs() + (y - sy).abs() <= ENTRANCE_SAFE_RADIUS
+    }
+
+    /// Whether a commit mostly removes code, enough to spawn a deletion swarm.
+    pub fn is_deletion_heavy(commit: &CommitData) -> bool {
+        commit.deletions >= DELETION_SWARM_LINES && commit.deletions > commit.insertions
+    }
+
+    /// Spawn a swarm of Deletion enemies for each deletion-heavy commit,
+    /// one per `DELETION_SWARM_LINES` removed, up to `DELETION_SWARM_MAX`.
+    ///
+    /// The room's total enemy count stays within the same cap as
+    /// `spawn_enemies_capped`: a quarter of the room's tiles, and at most
+    /// `max_enemies`. Sanctuary rooms have no enemies.
+    pub fn spawn_deletion_swarms(&mut self, commits: &[CommitData], max_enemies: usize, rng: &mut GameRng) {
+        if self.room_type == RoomType::Sanctuary {
+            return;
+        }
+
+        let cap = ((self.width as usize * self.height as usize) / 4).min(max_enemies);
+        let mut positions: Vec<_> = self
+            .get_free_positions()
+            .into_iter()
+            .filter(|&pos| !self.near_start(pos))
+            .collect();
+
+        for commit in commits.iter().filter(|c| Self::is_deletion_heavy(c)) {
+            let size = (commit.deletions / DELETION_SWARM_LINES) as usize;
+            for _ in 0..size.min(DELETION_SWARM_MAX) {
+                if positions.is_empty() || self.enemies.len() >= cap {
+                    return;
+                }
+                let (x, y) = positions.remove(rng.gen_range(0..positions.len()));
+                self.enemies.push(Enemy::new(EnemyType::Deletion, x, y, &commit.hash));
+            }
+        }
+    }
+
     /// Determine enemy type from commit data.
     /// Spec: Bug (<20 lines), Regression (revert), TechDebt (old code), MergeConflict (merge)
     fn enemy_type_from_commit(commit: &CommitData) -> EnemyType {
@@ -112,6 +582,11 @@ impl Room {
         if commit.is_merge {
             return EnemyType::MergeConflict;
         }
+
+        // Commits that sprawl across many files spawn Scope Creep
+        if commit.files_changed >= SCOPE_CREEP_FILES {
+            return EnemyType::ScopeCreep;
+        }
         
         let msg = commit.message.to_lowercase();
         
@@ -134,21 +609,33 @@ impl Room {
         EnemyType::TechDebt
     }
 
-    /// Spawn enemies based on commits.
+    /// Spawn enemies based on commits, at most `MAX_ENEMIES_PER_ROOM`.
+    pub fn spawn_enemies(&mut self, commits: &[CommitData], rng: &mut GameRng) {
+        self.spawn_enemies_capped(commits, MAX_ENEMIES_PER_ROOM, rng);
+    }
+
+    /// Spawn enemies based on commits, at most `max_enemies`.
     ///
-    /// Count: min(commits.len(), room_size/4)
+    /// Count: min(commits.len(), room_size/4, max_enemies)
     /// Type based on commit message keywords.
+    /// Commits on high-churn files get tougher enemies (see `churn_bonus`).
     /// Sanctuary rooms have no enemies.
-    pub fn spawn_enemies<R: Rng>(&mut self, commits: &[CommitData], rng: &mut R) {
+    pub fn spawn_enemies_capped(&mut self, commits: &[CommitData], max_enemies: usize, rng: &mut GameRng) {
         // Sanctuary rooms are safe - no enemies spawn
         if self.room_type == RoomType::Sanctuary {
             return;
         }
-        
+
+        // Room size stays an upper bound, so small rooms don't overflow
         let room_size = (self.width as usize * self.height as usize) / 4;
-        let count = commits.len().min(room_size).min(10); // Cap at 10 enemies
+        let count = commits.len().min(room_size).min(max_enemies);
 
-        let mut positions = self.get_free_positions();
+        // Keep the area around the player's starting tile clear
+        let mut positions: Vec<_> = self
+            .get_free_positions()
+            .into_iter()
+            .filter(|&pos| !self.near_start(pos))
+            .collect();
         if positions.is_empty() || count == 0 {
             return;
         }
@@ -160,11 +647,114 @@ impl Room {
             let pos_idx = rng.gen_range(0..positions.len());
             let (x, y) = positions.remove(pos_idx);
             let enemy_type = Self::enemy_type_from_commit(commit);
-            let enemy = Enemy::new(enemy_type, x, y, &commit.hash);
+            let mut enemy = Enemy::new(enemy_type, x, y, &commit.hash);
+            // Code that keeps changing breeds tougher bugs
+            let bonus = crate::git::churn_bonus(commit.churn);
+            enemy.damage += bonus;
+            enemy.max_hp += bonus * 2;
+            enemy.hp += bonus * 2;
             self.enemies.push(enemy);
         }
     }
 
+    /// Spawn a mini-boss for the featured commit.
+    ///
+    /// Like other enemies, it keeps `ENTRANCE_SAFE_RADIUS` away from the
+    /// player's starting tile. Returns false if there was no room to place it.
+    pub fn spawn_mini_boss(&mut self, commit: &CommitData, rng: &mut GameRng) -> bool {
+        self.featured_commit = Some(commit.hash.clone());
+        // The mini-boss replaces the commit's regular enemy, but not its
+        // Deletion swarm
+        self.enemies
+            .retain(|e| e.source_commit != commit.hash || e.enemy_type == EnemyType::Deletion);
+
+        let positions: Vec<_> = self
+            .get_free_positions()
+            .into_iter()
+            .filter(|&pos| !self.near_start(pos))
+            .collect();
+        if positions.is_empty() {
+            return false;
+        }
+
+        let (x, y) = positions[rng.gen_range(0..positions.len())];
+        let mut enemy = Enemy::new(Self::enemy_type_from_commit(commit), x, y, &commit.hash);
+        enemy.promote(mini_boss_title(commit));
+        self.enemies.push(enemy);
+        self.cleared = false;
+        true
+    }
+
+    /// Make enemies from frustrated commits hit harder.
+    ///
+    /// Each point of `commit_frustration` adds one damage.
+    pub fn apply_frustration(&mut self) {
+        for enemy in &mut self.enemies {
+            let score = self
+                .source_commits
+                .iter()
+                .find(|c| c.hash == enemy.source_commit)
+                .map_or(0, crate::git::commit_frustration);
+            enemy.damage += score as i32;
+        }
+    }
+
+    /// Split the room into a north and a south branch with a wall across
+    /// the middle row, open at both ends so either branch leads from the
+    /// entrance to the exit.
+    ///
+    /// Rooms smaller than 7x5 have no space for branches and are left
+    /// alone. Returns whether the layout was applied.
+    pub fn add_merge_branches(&mut self) -> bool {
+        let (w, h) = (self.width as i32, self.height as i32);
+        if w < 7 || h < 5 {
+            return false;
+        }
+
+        for x in 2..=(w - 3) {
+            self.set_tile(x, h / 2, Tile::Wall);
+        }
+        self.merge_branches = Some([false, false]);
+        true
+    }
+
+    /// Which merge branch a position lies in: 0 for north, 1 for south.
+    /// The open columns at either end belong to neither branch.
+    pub fn merge_branch_at(&self, (x, y): (i32, i32)) -> Option<usize> {
+        self.merge_branches?;
+        let mid = self.height as i32 / 2;
+        if !(2..=self.width as i32 - 3).contains(&x) || y == mid {
+            return None;
+        }
+        Some(if y < mid { 0 } else { 1 })
+    }
+
+    /// Mark the merge branch at a position as walked.
+    pub fn visit_merge_branch(&mut self, pos: (i32, i32)) {
+        if let Some(branch) = self.merge_branch_at(pos) {
+            if let Some(visited) = self.merge_branches.as_mut() {
+                visited[branch] = true;
+            }
+        }
+    }
+
+    /// Whether every merge branch has been walked. Always true for rooms
+    /// without the merge layout.
+    pub fn branches_merged(&self) -> bool {
+        self.merge_branches.iter().flatten().all(|&visited| visited)
+    }
+
+    /// Toughen enemies downstream of a bisect run's bad commit.
+    ///
+    /// Each step adds one damage and two HP.
+    pub fn apply_regression(&mut self, steps: i32) {
+        for enemy in &mut self.enemies {
+            enemy.damage += steps;
+            enemy.max_hp += steps * 2;
+            enemy.hp += steps * 2;
+        }
+    }
+
     /// Determine rarity from commit size.
     fn rarity_from_lines(lines: u32) -> Rarity {
         if lines > 500 {
@@ -179,12 +769,21 @@ impl Room {
     }
 
     /// Create an item based on commit characteristics.
-    fn item_from_commit(commit: &CommitData) -> Item {
+    ///
+    /// Tagged commits are releases: their item is one rarity higher and
+    /// named after the tag.
+    pub fn item_from_commit(commit: &CommitData) -> Item {
         let msg = commit.message.to_lowercase();
-        let rarity = Self::rarity_from_lines(commit.lines_changed());
+        let mut rarity = Self::rarity_from_lines(commit.lines_changed());
+        if commit.tag.is_some() {
+            rarity = rarity.upgraded();
+        }
 
         // Determine item based on commit type
-        let (name, item_type, effect) = if msg.contains("doc") || msg.contains("readme") {
+        let (name, item_type, effect) = if msg.contains("refactor") && commit.lines_changed() >= REFACTOR_SCROLL_LINES {
+            // Big refactors: clear the room, take on debt
+            ("Refactor Scroll".to_string(), ItemType::Scroll, ItemEffect::Refactor)
+        } else if msg.contains("doc") || msg.contains("readme") {
             // Doc commits: Map scrolls
             ("Map Scroll".to_string(), ItemType::Scroll, ItemEffect::RevealMap)
         } else if msg.contains("test") {
@@ -209,6 +808,13 @@ impl Room {
                 ItemType::Consumable,
                 ItemEffect::Buff(crate::item::Stat::Focus, amount, 5),
             )
+        } else if msg.contains("silence") || msg.contains("quiet") || msg.contains("suppress") {
+            // Commits that quiet warnings or logs: Stealth
+            (
+                "Cloak of Silence".to_string(),
+                ItemType::Equipment,
+                ItemEffect::Buff(crate::item::Stat::Stealth, 1, 0),
+            )
         } else {
             // Default: energy restoration
             let energy = match rarity {
@@ -220,17 +826,26 @@ impl Room {
             ("Energy Vial".to_string(), ItemType::Consumable, ItemEffect::RestoreEnergy(energy))
         };
 
+        let name = match &commit.tag {
+            Some(tag) => format!("{} Artifact", tag),
+            None => name,
+        };
+
         Item::new(name, item_type, effect, rarity).from_commit(&commit.hash)
     }
 
     /// Spawn items based on commits and room type.
     ///
+    /// - Large refactor commits: Refactor scrolls
     /// - Doc commits: Map scrolls
     /// - Test commits: Healing items
     /// - Config commits: Buff items
+    /// - Commits silencing warnings or logs: Stealth cloaks
+    /// - Tagged commits: release artifacts, placed first
+    /// - Any other drop may be swapped for a custom item from `items.toml`
     /// - Treasure rooms: 2-3 items
     /// - Library rooms: 2-3 items (more scrolls)
-    pub fn spawn_items<R: Rng>(&mut self, commits: &[CommitData], rng: &mut R) {
+    pub fn spawn_items(&mut self, commits: &[CommitData], rng: &mut GameRng) {
         let mut positions = self.get_free_positions();
         if positions.is_empty() {
             return;
@@ -246,14 +861,69 @@ impl Room {
             }
         };
 
-        for commit in commits.iter().take(item_count) {
+        // Release commits get first pick of the item slots
+        let mut ordered: Vec<&CommitData> = commits.iter().collect();
+        ordered.sort_by_key(|c| c.tag.is_none());
+
+        for commit in ordered.into_iter().take(item_count) {
             if positions.is_empty() {
                 break;
             }
             let pos_idx = rng.gen_range(0..positions.len());
             let (x, y) = positions.remove(pos_idx);
-            let item = Self::item_from_commit(commit).at(x, y);
-            self.items.push(item);
+            // Releases always keep their artifact
+            let custom = match commit.tag {
+                Some(_) => None,
+                None => crate::config::item_registry().roll(rng),
+            };
+            let item = match custom {
+                Some(item) => item.from_commit(&commit.hash),
+                None => Self::item_from_commit(commit),
+            };
+            self.items.push(item.at(x, y));
+        }
+    }
+
+    /// Drop a readable Diff Fragment from the day's largest commit that
+    /// has a diff snippet.
+    pub fn spawn_diff_fragment(&mut self, commits: &[CommitData], rng: &mut GameRng) {
+        let with_snippets: Vec<CommitData> = commits
+            .iter()
+            .filter(|c| c.diff_snippet.is_some())
+            .cloned()
+            .collect();
+        let Some(commit) = crate::git::largest_commit(&with_snippets) else {
+            return;
+        };
+        let Some(snippet) = commit.diff_snippet.clone() else {
+            return;
+        };
+
+        let positions = self.get_free_positions();
+        if positions.is_empty() {
+            return;
         }
+        let (x, y) = positions[rng.gen_range(0..positions.len())];
+        let item = Item::new("Diff Fragment", ItemType::Scroll, ItemEffect::Lore(snippet), Rarity::Common);
+        self.items.push(item.from_commit(&commit.hash).at(x, y));
+    }
+}
+
+/// Last component of a path.
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Title for a featured commit's mini-boss, from its subject line.
+fn mini_boss_title(commit: &CommitData) -> String {
+    let subject = commit.message.lines().next().unwrap_or("").trim();
+    if subject.is_empty() {
+        return "The Nameless Commit".to_string();
+    }
+    if subject.chars().count() > 24 {
+        let short: String = subject.chars().take(21).collect();
+        format!("{}...", short)
+    } else {
+        subject.to_string()
     }
 }
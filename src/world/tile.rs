@@ -31,6 +31,17 @@ pub enum Tile {
 }
 
 impl Tile {
+    /// One of each kind of tile, for legends. Doors appear closed and open.
+    pub const LEGEND: [Tile; 7] = [
+        Tile::Floor,
+        Tile::Wall,
+        Tile::Door(Direction::North, DoorState::Closed),
+        Tile::Door(Direction::North, DoorState::Open),
+        Tile::Exit,
+        Tile::Entrance,
+        Tile::HealingZone,
+    ];
+
     /// Check if entities can walk on this tile.
     pub fn is_walkable(&self) -> bool {
         match self {
@@ -58,6 +69,19 @@ impl Tile {
         }
     }
 
+    /// Get the display name for this tile.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tile::Floor => "Floor",
+            Tile::Wall => "Wall",
+            Tile::Door(_, DoorState::Closed) => "Closed door",
+            Tile::Door(_, DoorState::Open) => "Open door",
+            Tile::Exit => "Exit",
+            Tile::Entrance => "Entrance",
+            Tile::HealingZone => "Healing zone",
+        }
+    }
+
     /// Check if this is a door tile.
     pub fn is_door(&self) -> bool {
         matches!(self, Tile::Door(_, _))
@@ -0,0 +1,66 @@
+//! Scripted tutorial room shown before the real dungeon.
+
+use chrono::NaiveDate;
+
+use crate::entity::{Enemy, EnemyType};
+use crate::item::{Item, ItemEffect, ItemType, Rarity};
+
+use super::{Room, RoomType, Tile};
+
+/// Tutorial room width.
+pub const TUTORIAL_WIDTH: u8 = 13;
+/// Tutorial room height.
+pub const TUTORIAL_HEIGHT: u8 = 7;
+
+/// Build the tutorial room.
+///
+/// A wall splits the room in two, and a weak Bug stands in the only gap.
+/// Past it lie a potion and the exit, so the player has to move, attack
+/// and pick something up, in that order, to get out.
+pub fn tutorial_room(date: NaiveDate) -> Room {
+    let (w, h) = (TUTORIAL_WIDTH as i32, TUTORIAL_HEIGHT as i32);
+    let mid_y = h / 2;
+    let mut room = Room::new(0, TUTORIAL_WIDTH, TUTORIAL_HEIGHT, RoomType::Tutorial, date);
+
+    for x in 0..w {
+        room.set_tile(x, 0, Tile::Wall);
+        room.set_tile(x, h - 1, Tile::Wall);
+    }
+    for y in 0..h {
+        room.set_tile(0, y, Tile::Wall);
+        room.set_tile(w - 1, y, Tile::Wall);
+        if y != mid_y {
+            room.set_tile(w / 2, y, Tile::Wall);
+        }
+    }
+    room.set_tile(w - 1, mid_y, Tile::Exit);
+
+    let mut bug = Enemy::new(EnemyType::Bug, w / 2, mid_y, "tutorial");
+    bug.hp = 2;
+    bug.max_hp = 2;
+    bug.damage = 1;
+    room.enemies.push(bug);
+
+    let potion = Item::new("Training Potion", ItemType::Consumable, ItemEffect::Heal(10), Rarity::Common);
+    room.items.push(potion.at(w / 2 + 3, mid_y));
+
+    room
+}
+
+/// Put the tutorial room in front of a generated dungeon.
+///
+/// The tutorial is added after generation so the git rooms keep the
+/// layouts their seed gives them. Room ids are renumbered to match.
+pub fn prepend_tutorial(rooms: &mut Vec<Room>) {
+    let Some(first) = rooms.first_mut() else {
+        return;
+    };
+
+    first.set_tile(0, first.height as i32 / 2, Tile::Entrance);
+    let date = first.source_date;
+    rooms.insert(0, tutorial_room(date));
+
+    for (id, room) in rooms.iter_mut().enumerate() {
+        room.id = id;
+    }
+}
@@ -17,6 +17,8 @@ pub enum RoomType {
     Library,
     /// Merge commit room - boss encounter.
     Boss,
+    /// Scripted tutorial room, not from the data source.
+    Tutorial,
 }
 
 impl RoomType {
@@ -28,6 +30,76 @@ impl RoomType {
             RoomType::Treasure => "Treasury",
             RoomType::Library => "Library",
             RoomType::Boss => "Boss Chamber",
+            RoomType::Tutorial => "Training Room",
+        }
+    }
+
+    /// Banner shown on entering a special room, or `None` for plain rooms.
+    pub fn banner(&self) -> Option<&'static str> {
+        match self {
+            RoomType::Sanctuary => Some("You enter a Sanctuary \u{2014} rest and recover"),
+            RoomType::Treasure => Some("You enter a Treasury \u{2014} riches lie within"),
+            RoomType::Library => Some("You enter a Library \u{2014} old knowledge waits"),
+            RoomType::Boss => Some("You enter a Boss Chamber \u{2014} steel yourself"),
+            RoomType::Normal | RoomType::Tutorial => None,
+        }
+    }
+}
+
+/// Mood of a room, for integrations that map rooms to music or ambient
+/// sound. Penumbra plays no audio itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AmbienceCue {
+    /// Safe rooms and rooms already cleared.
+    Calm,
+    /// Treasuries and libraries with a few enemies about.
+    Mysterious,
+    /// Ordinary rooms with a few enemies about.
+    Uneasy,
+    /// Boss chambers and crowded rooms.
+    Tense,
+}
+
+/// What a room holds, as seen from the doorway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomSummary {
+    pub room_type: RoomType,
+    pub enemies: usize,
+    pub items: usize,
+}
+
+impl RoomSummary {
+    /// Summarize a room.
+    pub fn of(room: &Room) -> Self {
+        Self {
+            room_type: room.room_type,
+            enemies: room.enemies.len(),
+            items: room.items.len(),
+        }
+    }
+}
+
+/// Shape of a whole dungeon, for comparing seeds before playing one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DungeonSummary {
+    pub seed: u64,
+    pub rooms: usize,
+    pub bosses: usize,
+    pub enemies: usize,
+}
+
+impl DungeonSummary {
+    /// Summarize a world generated from `seed`.
+    pub fn of(world: &World, seed: u64) -> Self {
+        Self {
+            seed,
+            rooms: world.rooms.len(),
+            bosses: world
+                .rooms
+                .iter()
+                .filter(|room| room.room_type == RoomType::Boss)
+                .count(),
+            enemies: world.rooms.iter().map(|room| room.enemies.len()).sum(),
         }
     }
 }
@@ -68,6 +140,37 @@ impl World {
         }
     }
 
+    /// Go back to the previous room.
+    pub fn prev_room(&mut self) -> bool {
+        if self.current_room > 0 {
+            self.current_room -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Make enemies from frustrated commits more aggressive in every room.
+    pub fn apply_frustration(&mut self) {
+        for room in &mut self.rooms {
+            room.apply_frustration();
+        }
+    }
+
+    /// Index of the first room built from the data source, past any
+    /// tutorial room.
+    pub fn first_dungeon_room(&self) -> usize {
+        self.rooms
+            .iter()
+            .take_while(|r| r.room_type == RoomType::Tutorial)
+            .count()
+    }
+
+    /// Summary of the room after the current one, or None in the last room.
+    pub fn peek_next(&self) -> Option<RoomSummary> {
+        self.rooms.get(self.current_room + 1).map(RoomSummary::of)
+    }
+
     /// Check if this is the last room.
     pub fn is_last_room(&self) -> bool {
         self.current_room + 1 >= self.rooms.len()
@@ -1,15 +1,18 @@
 //! Tests for combat system.
 
 use chrono::NaiveDate;
-use rand::SeedableRng;
-use rand_chacha::ChaCha8Rng;
+use rand::{Rng, SeedableRng};
 
 use penumbra::combat::{
-    calculate_damage, calculate_hit_chance, decide_action, enemy_attack, find_path,
-    get_adjacent_positions, player_attack, should_use_special, EnemyAction, PlayerAction,
-    ATTACK_COST, DEFEND_COST, MOVE_COST, USE_ITEM_COST,
+    apply_variance, calculate_damage, calculate_hit_chance, decide_action, enemy_attack, find_path,
+    get_adjacent_positions, player_attack, player_attack_with_variance, preview_attack, preview_attack_with_variance,
+    should_use_special, EnemyAction, PlayerAction, EnergyCosts, ATTACK_COST, CRITICAL_CHANCE, DEFEND_COST, MOVE_COST,
+    USE_ITEM_COST, WARDED_DAMAGE_PERCENT,
 };
+use penumbra::config::GameplaySettings;
 use penumbra::entity::{Enemy, EnemyType, Player, PlayerClass};
+use penumbra::item::{Item, ItemEffect, ItemType, Rarity};
+use penumbra::rng::GameRng;
 use penumbra::world::{Room, RoomType};
 
 // === Combat System Tests (Task 9) ===
@@ -18,7 +21,7 @@ use penumbra::world::{Room, RoomType};
 fn player_attack_can_hit() {
     let player = Player::new(PlayerClass::CodeWarrior);
     let _enemy = Enemy::new(EnemyType::Bug, 0, 0, "");
-    let mut rng = ChaCha8Rng::seed_from_u64(12345);
+    let mut rng = GameRng::seed_from_u64(12345);
     
     // Run multiple attacks, at least one should hit
     let mut hit_count = 0;
@@ -36,7 +39,7 @@ fn player_attack_can_hit() {
 fn player_attack_applies_damage() {
     let player = Player::new(PlayerClass::CodeWarrior);
     let mut enemy = Enemy::new(EnemyType::MergeConflict, 0, 0, ""); // High HP enemy
-    let mut rng = ChaCha8Rng::seed_from_u64(42); // Seed that hits
+    let mut rng = GameRng::seed_from_u64(42); // Seed that hits
     
     let initial_hp = enemy.hp;
     let result = player_attack(&player, &mut enemy, &mut rng);
@@ -51,7 +54,7 @@ fn player_attack_applies_damage() {
 fn player_attack_can_miss() {
     let player = Player::new(PlayerClass::Wanderer);
     let _enemy = Enemy::new(EnemyType::Bug, 0, 0, "");
-    let mut rng = ChaCha8Rng::seed_from_u64(12345);
+    let mut rng = GameRng::seed_from_u64(12345);
     
     let mut miss_count = 0;
     for _ in 0..50 {
@@ -68,7 +71,7 @@ fn player_attack_can_miss() {
 fn player_attack_can_kill() {
     let player = Player::new(PlayerClass::CodeWarrior);
     let mut enemy = Enemy::new(EnemyType::Bug, 0, 0, ""); // Low HP
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     
     // Keep attacking until killed
     for _ in 0..20 {
@@ -86,7 +89,7 @@ fn player_attack_can_kill() {
 fn enemy_attack_applies_damage() {
     let enemy = Enemy::new(EnemyType::Bug, 0, 0, "");
     let mut player = Player::new(PlayerClass::Wanderer);
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     
     let initial_hp = player.hp;
     let result = enemy_attack(&enemy, &mut player, &mut rng);
@@ -144,6 +147,36 @@ fn action_energy_costs() {
     assert_eq!(PlayerAction::Wait.energy_cost(), 0);
 }
 
+#[test]
+fn action_energy_costs_from_table() {
+    let costs = EnergyCosts { movement: 0, attack: 8, defend: 4, use_item: 1 };
+    assert_eq!(PlayerAction::Move(0, 1).energy_cost_with(&costs), 0);
+    assert_eq!(PlayerAction::Attack(penumbra::world::Direction::North).energy_cost_with(&costs), 8);
+    assert_eq!(PlayerAction::Defend.energy_cost_with(&costs), 4);
+    assert_eq!(PlayerAction::UseItem(0).energy_cost_with(&costs), 1);
+    assert_eq!(PlayerAction::Wait.energy_cost_with(&costs), 0);
+}
+
+#[test]
+fn default_energy_costs_match_constants() {
+    let costs = EnergyCosts::default();
+    assert_eq!(costs.movement, MOVE_COST);
+    assert_eq!(costs.attack, ATTACK_COST);
+    assert_eq!(costs.defend, DEFEND_COST);
+    assert_eq!(costs.use_item, USE_ITEM_COST);
+}
+
+#[test]
+fn negative_energy_costs_load_as_free() {
+    let settings: GameplaySettings = toml::from_str(
+        "default_days = 30\nauto_pickup = true\nconfirm_attacks = false\n\n[energy_costs]\nmovement = -3\nattack = 4\n",
+    )
+    .unwrap();
+    assert_eq!(settings.energy_costs.movement, 0);
+    assert_eq!(settings.energy_costs.attack, 4);
+    assert_eq!(settings.energy_costs.defend, DEFEND_COST);
+}
+
 #[test]
 fn action_is_movement() {
     assert!(PlayerAction::Move(1, 0).is_movement());
@@ -187,6 +220,58 @@ fn decide_action_moves_when_not_adjacent() {
     assert!(matches!(action, EnemyAction::Move { .. }));
 }
 
+fn make_loot() -> Item {
+    Item::new("Health Potion", ItemType::Consumable, ItemEffect::Heal(10), Rarity::Common)
+}
+
+#[test]
+fn scope_creep_steals_when_adjacent() {
+    let room = make_test_room();
+    let enemy = Enemy::new(EnemyType::ScopeCreep, 5, 5, "");
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.x = 5;
+    player.y = 4;
+    player.pickup_item(make_loot());
+
+    assert_eq!(decide_action(&enemy, &player, &room), EnemyAction::Steal);
+}
+
+#[test]
+fn scope_creep_attacks_empty_handed_player() {
+    let room = make_test_room();
+    let enemy = Enemy::new(EnemyType::ScopeCreep, 5, 5, "");
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.x = 5;
+    player.y = 4;
+
+    assert_eq!(decide_action(&enemy, &player, &room), EnemyAction::Attack);
+}
+
+#[test]
+fn scope_creep_flees_with_loot() {
+    let room = make_test_room();
+    let mut enemy = Enemy::new(EnemyType::ScopeCreep, 5, 5, "");
+    enemy.stolen = Some(make_loot());
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.x = 5;
+    player.y = 4;
+
+    assert_eq!(decide_action(&enemy, &player, &room), EnemyAction::Move { dx: 0, dy: 1 });
+}
+
+#[test]
+fn fleeing_heads_straight_away_from_the_player() {
+    let room = make_test_room();
+    let mut enemy = Enemy::new(EnemyType::ScopeCreep, 5, 5, "");
+    enemy.stolen = Some(make_loot());
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.x = 4;
+    player.y = 5;
+
+    // North and south gain as much distance as east, but east is straight away
+    assert_eq!(decide_action(&enemy, &player, &room), EnemyAction::Move { dx: 1, dy: 0 });
+}
+
 #[test]
 fn find_path_returns_valid_path() {
     let room = make_test_room();
@@ -261,3 +346,107 @@ fn get_adjacent_positions_returns_four() {
     assert!(positions.contains(&(4, 5)));
     assert!(positions.contains(&(6, 5)));
 }
+
+#[test]
+fn warded_enemy_takes_reduced_damage() {
+    let player = Player::new(PlayerClass::CodeWarrior);
+    let mut hits = 0;
+    for seed in 0..20 {
+        let mut warded = Enemy::new(EnemyType::MergeConflict, 0, 0, "");
+        warded.warded = true;
+        let mut exposed = Enemy::new(EnemyType::MergeConflict, 0, 0, "");
+
+        let reduced = player_attack(&player, &mut warded, &mut GameRng::seed_from_u64(seed));
+        let full = player_attack(&player, &mut exposed, &mut GameRng::seed_from_u64(seed));
+        assert_eq!(reduced.hit, full.hit);
+        if full.hit {
+            hits += 1;
+            assert_eq!(reduced.damage, (full.damage * WARDED_DAMAGE_PERCENT / 100).max(1));
+            assert!(reduced.damage < full.damage);
+        }
+    }
+    assert!(hits > 0);
+}
+
+#[test]
+fn damage_variance_spreads_hits() {
+    let mut rng = GameRng::seed_from_u64(7);
+    let rolls: Vec<i32> = (0..200).map(|_| apply_variance(20, 20, &mut rng)).collect();
+
+    assert!(rolls.iter().all(|&d| (16..=24).contains(&d)));
+    let distinct: std::collections::HashSet<_> = rolls.iter().collect();
+    assert!(distinct.len() >= 5, "only {:?}", distinct);
+    assert!(rolls.iter().any(|&d| d < 20) && rolls.iter().any(|&d| d > 20));
+
+    // Small hits never drop to zero
+    assert!((0..50).all(|_| apply_variance(1, 50, &mut rng) >= 1));
+}
+
+#[test]
+fn zero_variance_leaves_damage_and_rng_alone() {
+    let mut rng = GameRng::seed_from_u64(7);
+    let mut untouched = GameRng::seed_from_u64(7);
+    assert_eq!(apply_variance(20, 0, &mut rng), 20);
+    assert_eq!(rng.gen::<u64>(), untouched.gen::<u64>());
+
+    let player = Player::new(PlayerClass::CodeWarrior);
+    let mut plain = Enemy::new(EnemyType::MergeConflict, 0, 0, "");
+    let mut varied = Enemy::new(EnemyType::MergeConflict, 0, 0, "");
+    let a = player_attack(&player, &mut plain, &mut GameRng::seed_from_u64(42));
+    let b = player_attack_with_variance(&player, &mut varied, &mut GameRng::seed_from_u64(42), 0);
+    assert_eq!(a.damage, b.damage);
+}
+
+#[test]
+fn preview_attack_composes_hit_damage_and_crit() {
+    let mut player = Player::new(PlayerClass::CodeWarrior);
+    player.level = 3;
+    let enemy = Enemy::new(EnemyType::Bug, 0, 0, "");
+    let preview = preview_attack(&player, &enemy);
+
+    let base = calculate_damage(player.damage, player.level, false);
+    assert_eq!(preview.hit_chance, calculate_hit_chance(player.focus));
+    assert_eq!(preview.min_damage, base);
+    assert_eq!(preview.max_damage, base * 2);
+    assert_eq!(preview.crit_chance, CRITICAL_CHANCE);
+
+    // Wards cut both bounds
+    let mut boss = Enemy::new(EnemyType::MergeConflict, 0, 0, "");
+    boss.warded = true;
+    let warded = preview_attack(&player, &boss);
+    assert_eq!(warded.min_damage, (base * WARDED_DAMAGE_PERCENT / 100).max(1));
+    assert_eq!(warded.max_damage, (base * 2 * WARDED_DAMAGE_PERCENT / 100).max(1));
+}
+
+#[test]
+fn resolved_attacks_land_within_the_preview() {
+    let player = Player::new(PlayerClass::CodeWarrior);
+    for (variance, warded) in [(0, false), (30, false), (0, true), (30, true)] {
+        let mut target = Enemy::new(EnemyType::MergeConflict, 0, 0, "");
+        target.warded = warded;
+        let preview = preview_attack_with_variance(&player, &target, variance);
+        let mut rng = GameRng::seed_from_u64(7);
+
+        let (mut lowest, mut highest, mut hits) = (i32::MAX, i32::MIN, 0);
+        for _ in 0..2000 {
+            let mut enemy = target.clone();
+            enemy.hp = 10_000;
+            let result = player_attack_with_variance(&player, &mut enemy, &mut rng, variance);
+            if result.hit {
+                hits += 1;
+                lowest = lowest.min(result.damage);
+                highest = highest.max(result.damage);
+            }
+        }
+
+        assert!(lowest >= preview.min_damage, "variance {variance}, warded {warded}");
+        assert!(highest <= preview.max_damage, "variance {variance}, warded {warded}");
+        // Without variance the bounds are hit exactly
+        if variance == 0 {
+            assert_eq!(lowest, preview.min_damage);
+            assert_eq!(highest, preview.max_damage);
+        }
+        let rate = hits as f32 / 2000.0;
+        assert!((rate - preview.hit_chance).abs() < 0.05);
+    }
+}
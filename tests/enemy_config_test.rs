@@ -0,0 +1,79 @@
+//! Tests for enemies.toml overrides.
+//!
+//! The installed config is process-wide, so only
+//! `installed_override_changes_new_bugs` installs one; keep it that way.
+
+use penumbra::config::{install_enemy_config, EnemyConfig, EnemyOverride};
+use penumbra::entity::{Enemy, EnemyType};
+use tempfile::TempDir;
+
+#[test]
+fn installed_override_changes_new_bugs() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("enemies.toml");
+    std::fs::write(&path, "[bug]\nbase_hp = 25\nbase_damage = 7\nsymbol = \"b\"\n").unwrap();
+
+    assert!(install_enemy_config(EnemyConfig::load_from(&path)));
+
+    let bug = Enemy::new(EnemyType::Bug, 1, 1, "abc");
+    assert_eq!(bug.hp, 25);
+    assert_eq!(bug.max_hp, 25);
+    assert_eq!(bug.damage, 7);
+    assert_eq!(bug.symbol(), 'b');
+
+    // Types without overrides keep their stats
+    let debt = Enemy::new(EnemyType::TechDebt, 1, 1, "abc");
+    assert_eq!(debt.hp, EnemyType::TechDebt.base_hp());
+    assert_eq!(debt.symbol(), 'D');
+
+    // Stats are fixed once installed
+    assert!(!install_enemy_config(EnemyConfig::default()));
+    assert_eq!(Enemy::new(EnemyType::Bug, 1, 1, "abc").hp, 25);
+}
+
+#[test]
+fn missing_file_keeps_builtin_stats() {
+    let dir = TempDir::new().unwrap();
+    let config = EnemyConfig::load_from(&dir.path().join("enemies.toml"));
+
+    assert_eq!(config, EnemyConfig::default());
+    assert_eq!(config.base_hp(EnemyType::Bug), EnemyType::Bug.base_hp());
+    assert_eq!(config.symbol(EnemyType::Bug), 'B');
+}
+
+#[test]
+fn invalid_toml_keeps_builtin_stats() {
+    assert_eq!(EnemyConfig::parse("[bug\nbase_hp = "), EnemyConfig::default());
+}
+
+#[test]
+fn overrides_by_type_name() {
+    let config = EnemyConfig::parse("[tech_debt]\nbase_damage = 9\n\n[MergeConflict]\nbase_hp = 80\n");
+
+    assert_eq!(config.base_damage(EnemyType::TechDebt), 9);
+    assert_eq!(config.base_hp(EnemyType::TechDebt), EnemyType::TechDebt.base_hp());
+    assert_eq!(config.base_hp(EnemyType::MergeConflict), 80);
+}
+
+#[test]
+fn nonsense_values_are_ignored() {
+    let config = EnemyConfig::parse("[bug]\nbase_hp = 0\nbase_damage = -3\nsymbol = \"bb\"\n");
+
+    assert_eq!(config.base_hp(EnemyType::Bug), EnemyType::Bug.base_hp());
+    assert_eq!(config.base_damage(EnemyType::Bug), EnemyType::Bug.base_damage());
+    assert_eq!(config.symbol(EnemyType::Bug), 'B');
+}
+
+#[test]
+fn unknown_types_and_keys_are_ignored() {
+    let config = EnemyConfig::parse(
+        "version = 2\n\n[dragon]\nbase_hp = 500\n\n[bug]\nbase_hp = 12\nspeed = 3\n\n[regression]\nbase_hp = \"ten\"\n",
+    );
+
+    assert_eq!(
+        config.get(EnemyType::Bug),
+        Some(&EnemyOverride { base_hp: Some(12), base_damage: None, symbol: None })
+    );
+    assert_eq!(config.get(EnemyType::Regression), None);
+    assert_eq!(config.base_hp(EnemyType::Regression), EnemyType::Regression.base_hp());
+}
@@ -38,6 +38,12 @@ fn wanderer_has_balanced_stats() {
     assert_eq!(player.damage, 15);
 }
 
+#[test]
+fn wanderer_has_bonus_stealth() {
+    assert_eq!(Player::new(PlayerClass::Wanderer).stealth, 1);
+    assert_eq!(Player::new(PlayerClass::CodeWarrior).stealth, 0);
+}
+
 #[test]
 fn player_take_damage_reduces_hp() {
     let mut player = Player::new(PlayerClass::Wanderer);
@@ -68,6 +74,32 @@ fn player_defending_halves_damage() {
     assert_eq!(player.hp, initial - 10); // Half of 20
 }
 
+#[test]
+fn player_defending_halves_every_hit() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.defending = true;
+    let initial = player.hp;
+    player.take_damage(20);
+    player.take_damage(20);
+    assert_eq!(player.hp, initial - 20);
+    assert!(player.is_guarding());
+}
+
+#[test]
+fn player_guard_turns_halve_damage_until_spent() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.guard_turns = 1;
+    let initial = player.hp;
+    player.take_damage(20);
+    assert_eq!(player.hp, initial - 10);
+
+    player.tick_guard();
+    player.take_damage(20);
+    assert_eq!(player.hp, initial - 30);
+    player.tick_guard();
+    assert_eq!(player.guard_turns, 0);
+}
+
 #[test]
 fn player_heal_increases_hp() {
     let mut player = Player::new(PlayerClass::Wanderer);
@@ -161,6 +193,58 @@ fn player_pickup_item_fails_when_full() {
     assert!(!player.pickup_item(extra));
 }
 
+#[test]
+fn player_default_max_inventory_is_ten() {
+    let player = Player::new(PlayerClass::Wanderer);
+    assert_eq!(player.max_inventory, 10);
+}
+
+#[test]
+fn player_pickup_respects_custom_capacity() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.max_inventory = 2;
+    let potion = || Item::new("Potion", ItemType::Consumable, ItemEffect::Heal(10), Rarity::Common);
+
+    assert!(player.pickup_item(potion()));
+    assert!(player.pickup_item(potion()));
+    assert!(player.inventory_full());
+    assert!(!player.pickup_item(potion()));
+    assert_eq!(player.inventory.len(), 2);
+}
+
+#[test]
+fn player_lowest_rarity_index_prefers_earliest() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.pickup_item(Item::new("Rare", ItemType::Equipment, ItemEffect::Damage(5), Rarity::Rare));
+    player.pickup_item(Item::new("First", ItemType::Consumable, ItemEffect::Heal(10), Rarity::Uncommon));
+    player.pickup_item(Item::new("Second", ItemType::Consumable, ItemEffect::Heal(10), Rarity::Uncommon));
+
+    assert_eq!(player.lowest_rarity_index(), Some(1));
+}
+
+#[test]
+fn player_swap_lowest_rarity_drops_weakest() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.max_inventory = 2;
+    player.pickup_item(Item::new("Legend", ItemType::Equipment, ItemEffect::Damage(9), Rarity::Legendary));
+    player.pickup_item(Item::new("Junk", ItemType::Consumable, ItemEffect::Heal(1), Rarity::Common));
+
+    let new = Item::new("Shiny", ItemType::Equipment, ItemEffect::RestoreEnergy(3), Rarity::Rare);
+    let dropped = player.swap_lowest_rarity(new).unwrap();
+
+    assert_eq!(dropped.name, "Junk");
+    assert_eq!(player.inventory.len(), 2);
+    assert!(player.inventory.iter().any(|i| i.name == "Shiny"));
+}
+
+#[test]
+fn player_swap_lowest_rarity_on_empty_inventory() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    let item = Item::new("Potion", ItemType::Consumable, ItemEffect::Heal(10), Rarity::Common);
+    assert!(player.swap_lowest_rarity(item).is_none());
+    assert!(player.inventory.is_empty());
+}
+
 // === Enemy Tests (Task 7) ===
 
 #[test]
@@ -232,6 +316,19 @@ fn enemy_stores_source_commit() {
     assert_eq!(enemy.source_commit, "abc123");
 }
 
+#[test]
+fn enemy_promote_makes_mini_boss() {
+    let mut enemy = Enemy::new(EnemyType::Bug, 0, 0, "abc123");
+    assert!(!enemy.is_mini_boss());
+
+    enemy.promote("Rewrite everything");
+    assert!(enemy.is_mini_boss());
+    assert_eq!(enemy.max_hp, 20);
+    assert_eq!(enemy.hp, 20);
+    assert_eq!(enemy.damage, 5);
+    assert_eq!(enemy.title.as_deref(), Some("Rewrite everything"));
+}
+
 #[test]
 fn enemy_type_base_hp() {
     assert_eq!(EnemyType::Bug.base_hp(), 10);
@@ -262,7 +359,7 @@ fn make_commit(msg: &str, lines: u32) -> CommitData {
         deletions: 0,
         files_changed: 1,
         author: "Test".to_string(),
-        is_merge: false, file_categories: Default::default(),
+        is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
     }
 }
 
@@ -297,3 +394,49 @@ fn detect_inbox_knight_high_test_ratio() {
     // 75% are test-related
     assert_eq!(PlayerClass::detect(&commits), PlayerClass::InboxKnight);
 }
+
+#[test]
+fn detect_for_author_uses_only_their_commits() {
+    let by = |author: &str, msg: &str| {
+        let mut commit = make_commit(msg, 50);
+        commit.author = author.to_string();
+        commit
+    };
+    let mut commits: Vec<CommitData> = (0..8).map(|i| by("Alice", &format!("Add feature {}", i))).collect();
+    commits.push(by("Bob", "Add test for parser"));
+    commits.push(by("Bob", "Fix flaky test"));
+    commits.push(by("Bob", "Refactor lexer"));
+
+    // The team as a whole isn't test-heavy, but Bob is
+    assert_eq!(PlayerClass::detect(&commits), PlayerClass::Wanderer);
+    assert_eq!(PlayerClass::detect_for_author(&commits, "Bob"), PlayerClass::InboxKnight);
+    assert_eq!(PlayerClass::detect_for_author(&commits, "bob"), PlayerClass::InboxKnight);
+    assert_eq!(PlayerClass::detect_for_author(&commits, "Alice"), PlayerClass::Wanderer);
+    assert_eq!(PlayerClass::detect_for_author(&commits, "Carol"), PlayerClass::Wanderer);
+}
+
+#[test]
+fn quick_slots_follow_items_on_removal() {
+    let mut player = Player::new(PlayerClass::CodeWarrior);
+    for name in ["A", "B", "C"] {
+        player.pickup_item(Item::new(name, ItemType::Consumable, ItemEffect::Heal(5), Rarity::Common));
+    }
+    assert!(player.assign_quick_slot(0, 0));
+    assert!(player.assign_quick_slot(1, 2));
+    assert!(!player.assign_quick_slot(5, 1));
+    assert!(!player.assign_quick_slot(2, 3));
+
+    let removed = player.remove_item(1);
+    assert_eq!(removed.name, "B");
+    assert_eq!(player.quick_slot(0), Some(0));
+    assert_eq!(player.quick_slot(1), Some(1));
+    assert_eq!(player.inventory[1].name, "C");
+
+    player.remove_item(0);
+    assert_eq!(player.quick_slot(0), None);
+    assert_eq!(player.quick_slot_of(0), Some(1));
+
+    // Binding an item again moves it to the new slot
+    assert!(player.assign_quick_slot(4, 0));
+    assert_eq!(player.quick_slots, [None, None, None, None, Some(0)]);
+}
@@ -3,11 +3,17 @@
 use std::path::PathBuf;
 
 use chrono::{NaiveDate, Utc};
-use penumbra::combat::PlayerAction;
+use penumbra::cli::settle_run;
+use penumbra::combat::{EnergyCosts, PlayerAction, SANCTUARY_REGEN, WAIT_REGEN};
 use penumbra::entity::{Enemy, EnemyType, Player, PlayerClass};
-use penumbra::game::GameState;
+use penumbra::game::{
+    roll_chest, turn_summary, ChestOutcome, Difficulty, GameEvent, GameState, Progression, TutorialStep, VictoryCondition,
+    BISECT_BONUS, CLEAR_REWARD_HEAL, MIMIC_CHANCE,
+};
 use penumbra::git::CommitData;
-use penumbra::world::{Room, RoomType, Tile, World};
+use penumbra::item::{Item, ItemEffect, ItemType, Rarity};
+use penumbra::ui::App;
+use penumbra::world::{AmbienceCue, Direction, GenerationOptions, Room, RoomType, Tile, World};
 
 fn make_commit(msg: &str, lines: u32) -> CommitData {
     CommitData {
@@ -18,7 +24,7 @@ fn make_commit(msg: &str, lines: u32) -> CommitData {
         deletions: 0,
         files_changed: 1,
         author: "Test".to_string(),
-        is_merge: false, file_categories: Default::default(),
+        is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
     }
 }
 
@@ -86,6 +92,179 @@ fn game_over_on_player_death() {
     assert!(!state.victory);
 }
 
+fn dying_state() -> GameState {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
+    room.enemies.push(Enemy::new(EnemyType::MergeConflict, 2, 2, "test"));
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 1;
+    state.player.y = 2;
+    state.player.hp = 1;
+    state
+}
+
+#[test]
+fn permadeath_is_on_by_default() {
+    let mut state = dying_state();
+    assert!(state.permadeath);
+
+    state.process_enemies();
+
+    assert!(state.game_over);
+    assert_eq!(state.revives, 0);
+}
+
+#[test]
+fn casual_mode_revives_at_entrance() {
+    let mut state = dying_state();
+    state.permadeath = false;
+
+    // Same fatal hit as with permadeath on
+    state.process_enemies();
+
+    assert!(!state.game_over);
+    assert_eq!(state.revives, 1);
+    assert_eq!(state.player.hp, state.player.max_hp / 2);
+    assert_eq!((state.player.x, state.player.y), (1, 3));
+    assert!(state.messages.iter().any(|m| m.contains("wake at the entrance")));
+}
+
+#[test]
+fn casual_revive_costs_turns() {
+    let mut state = dying_state();
+    state.permadeath = false;
+    state.turn = 0;
+
+    state.process_enemies();
+
+    assert_eq!(state.turn, GameState::REVIVE_TURN_PENALTY);
+    assert_eq!(state.penalty_turns, GameState::REVIVE_TURN_PENALTY);
+}
+
+#[test]
+fn revive_penalty_does_not_count_towards_survival() {
+    let mut state = dying_state();
+    state.permadeath = false;
+    state.goal = VictoryCondition::Survive(5);
+
+    state.process_enemies();
+
+    assert!(state.turn >= 5);
+    assert!(!state.check_goal());
+    assert!(!state.game_over);
+}
+
+#[test]
+fn revive_avoids_an_enemy_on_the_entrance() {
+    let mut state = dying_state();
+    state.permadeath = false;
+    let room = state.world.current_mut().unwrap();
+    room.enemies.push(Enemy::new(EnemyType::Bug, 1, 3, "blocker"));
+
+    state.process_enemies();
+
+    assert_eq!(state.revives, 1);
+    let room = state.world.current().unwrap();
+    assert!(room.get_enemy_at(state.player.x, state.player.y).is_none());
+    assert_ne!((state.player.x, state.player.y), (1, 3));
+}
+
+#[test]
+fn revives_run_out() {
+    let mut state = dying_state();
+    state.permadeath = false;
+    state.revives = GameState::MAX_REVIVES;
+
+    state.process_enemies();
+
+    assert!(state.game_over);
+    assert_eq!(state.turn, 0);
+}
+
+#[test]
+fn sanctuary_reports_more_energy_regen_than_plain_floor() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, false, true)]);
+    state.player.x = 1;
+    state.player.y = 3;
+    assert_eq!(state.energy_regen_here(), 0);
+
+    state.world.rooms[0].room_type = RoomType::Sanctuary;
+    state.world.rooms[0].set_tile(1, 3, Tile::HealingZone);
+    assert_eq!(state.energy_regen_here(), SANCTUARY_REGEN);
+
+    // The report matches what a turn actually restores
+    state.player.energy = 0;
+    state.process_action(PlayerAction::Wait);
+    assert_eq!(state.player.energy, SANCTUARY_REGEN + WAIT_REGEN);
+}
+
+#[test]
+fn god_mode_prevents_game_over_from_enemy_hits() {
+    let mut state = dying_state();
+    state.enable_debug();
+    assert!(state.toggle_god_mode());
+
+    for _ in 0..10 {
+        state.process_enemies();
+    }
+
+    assert!(!state.game_over);
+    assert_eq!(state.player.hp, 1);
+    assert_eq!(state.revives, 0);
+    assert_eq!(state.stats.damage_taken, 0);
+}
+
+#[test]
+fn debug_commands_need_debug_enabled() {
+    let mut state = dying_state();
+    assert!(!state.toggle_god_mode());
+    assert!(!state.toggle_reveal_map());
+    assert!(!state.debug_spawn_enemy(EnemyType::Bug));
+    assert!(state.debug_warp().is_empty());
+
+    state.process_enemies();
+    assert!(state.game_over);
+}
+
+#[test]
+fn debug_reveal_warp_and_spawn() {
+    let rooms: Vec<Room> = (0..2).map(|id| make_test_room(id, true, true)).collect();
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(rooms);
+    state.player.x = 1;
+    state.player.y = 3;
+    state.enable_debug();
+
+    assert!(state.toggle_reveal_map());
+    assert_eq!(state.visible_tiles.len(), 49);
+
+    assert!(state.debug_spawn_enemy(EnemyType::Regression));
+    let spawned = state.world.current().unwrap().enemies.last().unwrap();
+    assert_eq!(spawned.enemy_type, EnemyType::Regression);
+    assert!((spawned.x - 1).abs() <= 1 && (spawned.y - 3).abs() <= 1);
+
+    let events = state.debug_warp();
+    assert!(events.iter().any(|e| matches!(e, GameEvent::RoomEntered { room_id: 1 })));
+    assert!(events.iter().any(|e| matches!(e, GameEvent::AmbienceCue { .. })));
+    assert_eq!(state.world.current_room, 1);
+    assert!(state.world.rooms[0].is_cleared());
+}
+
+#[test]
+fn casual_mode_runs_out_of_revives() {
+    let mut state = dying_state();
+    state.permadeath = false;
+    state.revives = GameState::MAX_REVIVES;
+
+    state.process_enemies();
+
+    assert!(state.game_over);
+    assert!(!state.victory);
+}
+
 #[test]
 fn victory_on_last_room_exit() {
     // Single room that's already cleared
@@ -110,6 +289,38 @@ fn victory_on_last_room_exit() {
     assert!(state.victory);
 }
 
+fn entering_beside_a_bug(grace: u32) -> GameState {
+    let mut next = make_test_room(1, false, true);
+    next.enemies.push(Enemy::new(EnemyType::Bug, 2, 3, "test"));
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, false, true), next]);
+    state.entry_grace_turns = grace;
+    state.player.x = 5;
+    state.player.y = 3;
+    assert!(state.check_room_exit());
+    state
+}
+
+fn enemy_attacked(events: &[GameEvent]) -> bool {
+    events.iter().any(|e| matches!(e, GameEvent::EnemyAttacked { .. }))
+}
+
+#[test]
+fn enemies_hold_attacks_on_first_turn_in_a_room() {
+    let mut state = entering_beside_a_bug(1);
+    let hp = state.player.hp;
+
+    assert!(!enemy_attacked(&state.process_enemies()));
+    assert_eq!(state.player.hp, hp);
+    assert!(enemy_attacked(&state.process_enemies()));
+}
+
+#[test]
+fn no_entry_grace_when_turned_off() {
+    let mut state = entering_beside_a_bug(0);
+    assert!(enemy_attacked(&state.process_enemies()));
+}
+
 #[test]
 fn game_tracks_turns() {
     let commits = vec![make_commit("Test", 50)];
@@ -124,6 +335,293 @@ fn game_tracks_turns() {
     assert_eq!(state.turn, 2);
 }
 
+// === Victory Condition Tests ===
+
+#[test]
+fn goal_parses_from_cli_strings() {
+    assert_eq!("end".parse::<VictoryCondition>(), Ok(VictoryCondition::ReachEnd));
+    assert_eq!("rooms:3".parse::<VictoryCondition>(), Ok(VictoryCondition::ClearRooms(3)));
+    assert_eq!("TURNS:200".parse::<VictoryCondition>(), Ok(VictoryCondition::Survive(200)));
+    assert_eq!("endless".parse::<VictoryCondition>(), Ok(VictoryCondition::Endless));
+    assert!("rooms:0".parse::<VictoryCondition>().is_err());
+    assert!("floors:3".parse::<VictoryCondition>().is_err());
+    assert!("rooms".parse::<VictoryCondition>().is_err());
+}
+
+#[test]
+fn reach_end_ignores_turns_and_rooms() {
+    let mut room = make_test_room(0, false, true);
+    room.cleared = true;
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room, make_test_room(1, true, true)]);
+
+    for _ in 0..5 {
+        state.process_action(PlayerAction::Wait);
+    }
+
+    assert_eq!(state.goal, VictoryCondition::ReachEnd);
+    assert!(!state.game_over);
+}
+
+#[test]
+fn clear_rooms_goal_triggers_victory() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![
+        make_test_room(0, false, true),
+        make_test_room(1, true, true),
+        make_test_room(2, true, true),
+    ]);
+    state.goal = VictoryCondition::ClearRooms(2);
+    state.world.current_mut().unwrap().cleared = true;
+    state.player.x = 4;
+    state.player.y = 3;
+
+    // Walk out of the first room
+    state.process_action(PlayerAction::Move(1, 0));
+    assert_eq!(state.world.current_room, 1);
+    assert_eq!(state.rooms_cleared(), 1);
+    assert!(!state.game_over);
+
+    // Kill the bug guarding the second room
+    state.world.current_mut().unwrap().enemies[0].hp = 1;
+    state.player.x = 2;
+    state.player.y = 3;
+    state.process_action(PlayerAction::Attack(Direction::East));
+
+    assert_eq!(state.rooms_cleared(), 2);
+    assert!(state.game_over);
+    assert!(state.victory);
+}
+
+#[test]
+fn survive_goal_triggers_victory() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, false, false)]);
+    state.goal = VictoryCondition::Survive(3);
+
+    state.process_action(PlayerAction::Wait);
+    state.process_action(PlayerAction::Wait);
+    assert!(!state.game_over);
+
+    state.process_action(PlayerAction::Wait);
+    assert!(state.game_over);
+    assert!(state.victory);
+    assert!(state.messages.iter().any(|m| m.contains("survived 3 turns")));
+}
+
+#[test]
+fn endless_mode_generates_a_room_past_the_last() {
+    let mut first = make_test_room(0, false, true);
+    first.source_commits = vec![make_commit("Fix parser", 120), make_commit("Add lexer", 80)];
+    let mut last = make_test_room(1, false, true);
+    last.cleared = true;
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![first, last]);
+    state.world.current_room = 1;
+    state.furthest_room = 1;
+    state.set_goal(VictoryCondition::Endless);
+    assert_eq!(state.world.rooms[1].get_tile(6, 3), Some(&Tile::Exit));
+
+    state.player.x = 5;
+    state.player.y = 3;
+    assert!(state.check_room_exit());
+
+    assert!(!state.game_over);
+    assert_eq!(state.world.rooms.len(), 3);
+    assert_eq!(state.world.current_room, 2);
+    assert_eq!(state.endless_score(), Some(2));
+
+    // Second lap: built from the first room's day, a quarter tougher
+    let room = &state.world.rooms[2];
+    assert_eq!(room.source_date, state.world.rooms[0].source_date);
+    assert!(room.exit_position().is_some());
+    assert!(!room.enemies.is_empty());
+    for enemy in &room.enemies {
+        let base = Enemy::new(enemy.enemy_type, 0, 0, "base").max_hp;
+        assert_eq!(enemy.max_hp, (base * 125 + 50) / 100);
+    }
+}
+
+#[test]
+fn endless_score_only_in_endless_mode() {
+    let state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    assert_eq!(state.endless_score(), None);
+}
+
+// === Run Stats Tests ===
+
+#[test]
+fn killing_enemy_increments_counter() {
+    let mut room = make_test_room(0, false, false);
+    let mut bug = Enemy::new(EnemyType::Bug, 2, 3, "test");
+    bug.hp = 1;
+    room.enemies.push(bug);
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 1;
+    state.player.y = 3;
+    assert_eq!(state.enemies_killed, 0);
+
+    state.process_action(PlayerAction::Attack(Direction::East));
+
+    assert_eq!(state.enemies_killed, 1);
+    assert_eq!(state.rooms_cleared(), 1);
+}
+
+fn xp_from_killing_a_bug(multiplier: f32) -> u32 {
+    let mut room = make_test_room(0, false, false);
+    let mut bug = Enemy::new(EnemyType::Bug, 2, 3, "test");
+    bug.hp = 1;
+    room.enemies.push(bug);
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 1;
+    state.player.y = 3;
+    state.xp_multiplier = multiplier;
+
+    state.process_action(PlayerAction::Attack(Direction::East));
+    assert_eq!(state.enemies_killed, 1);
+    state.player.xp
+}
+
+#[test]
+fn xp_multiplier_scales_kill_rewards() {
+    assert_eq!(xp_from_killing_a_bug(1.0), EnemyType::Bug.xp_reward());
+    assert_eq!(xp_from_killing_a_bug(2.0), EnemyType::Bug.xp_reward() * 2);
+}
+
+#[test]
+fn killed_enemy_lingers_as_dying_for_one_turn() {
+    let mut room = make_test_room(0, false, false);
+    let mut bug = Enemy::new(EnemyType::Bug, 2, 3, "test");
+    bug.hp = 1;
+    room.enemies.push(bug);
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 1;
+    state.player.y = 3;
+
+    state.process_action(PlayerAction::Attack(Direction::East));
+
+    let room = state.world.current().unwrap();
+    assert!(room.get_enemy_at(2, 3).is_none());
+    assert!(room.get_dying_at(2, 3).is_some());
+    assert!(room.cleared);
+
+    // The fallen enemy doesn't block the way
+    state.process_action(PlayerAction::Move(1, 0));
+
+    assert_eq!((state.player.x, state.player.y), (2, 3));
+    assert!(state.world.current().unwrap().dying.is_empty());
+}
+
+#[test]
+fn run_stats_survive_serialization() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.enemies_killed = 4;
+
+    let json = serde_json::to_string(&state).unwrap();
+    let loaded: GameState = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded.enemies_killed, 4);
+    assert_eq!(loaded.rooms_cleared(), state.rooms_cleared());
+}
+
+// === Essence Preview Tests ===
+
+#[test]
+fn essence_preview_matches_complete_run() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![
+        make_test_room(0, false, true),
+        make_test_room(1, false, true),
+        make_test_room(2, true, true),
+    ]);
+    state.world.current_room = 2;
+    state.enemies_killed = 7;
+    state.game_over = true;
+    state.victory = true;
+
+    let mut prog = Progression::new();
+    prog.complete_run(true, state.enemies_killed, state.rooms_cleared() as u32, state.turn, state.total_lines);
+
+    assert_eq!(state.essence_earned(), prog.essence);
+    // 7 kills + 2 rooms * 5 + 20 victory
+    assert_eq!(state.essence_earned(), 37);
+}
+
+#[test]
+fn bigger_history_raises_essence_preview() {
+    let mut small = GameState::new(vec![make_commit("Small", 300)], 42, test_git_path());
+    let mut big = GameState::new(
+        vec![make_commit("Big", 6000), make_commit("Bigger", 9000)],
+        42,
+        test_git_path(),
+    );
+    assert_eq!(small.total_lines, 300);
+    assert_eq!(big.total_lines, 15_000);
+
+    // Same dungeon, so only the history's size differs
+    small.world = World::new(vec![make_test_room(0, true, true)]);
+    big.world = World::new(vec![make_test_room(0, true, true)]);
+    assert_eq!(big.essence_earned(), small.essence_earned() + 15);
+}
+
+#[test]
+fn days_left_out_of_the_dungeon_add_no_lines() {
+    let mut old_big_day = make_commit("Big", 10_000);
+    old_big_day.date = Utc::now() - chrono::Duration::days(5);
+    let commits = vec![old_big_day, make_commit("Small", 300)];
+    let options = GenerationOptions { max_rooms: Some(1), ..Default::default() };
+
+    let mut trimmed = GameState::new_with_options(commits, 42, None, test_git_path(), &options);
+    let mut small = GameState::new(vec![make_commit("Small", 300)], 42, test_git_path());
+    assert_eq!(trimmed.total_lines, 300);
+
+    trimmed.world = World::new(vec![make_test_room(0, true, true)]);
+    small.world = World::new(vec![make_test_room(0, true, true)]);
+    assert_eq!(trimmed.essence_earned(), small.essence_earned());
+}
+
+#[test]
+fn quitting_after_two_rooms_awards_partial_essence() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![
+        make_test_room(0, false, true),
+        make_test_room(1, false, true),
+        make_test_room(2, true, true),
+    ]);
+    state.world.current_room = 2;
+    state.enemies_killed = 9;
+
+    let mut app = App::new(state);
+    app.quit = true;
+
+    let mut prog = Progression::new();
+    let earned = settle_run(&mut prog, &app);
+
+    // Rooms only: no kill or victory essence for a quit run
+    assert_eq!(earned, Some(10));
+    assert_eq!(prog.essence, 10);
+    assert_eq!(prog.total_rooms, 2);
+    assert_eq!(prog.total_runs, 0);
+}
+
+#[test]
+fn finishing_a_run_counts_it_instead_of_abandoning() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, false, true)]);
+    state.game_over = true;
+
+    let mut prog = Progression::new();
+    assert_eq!(settle_run(&mut prog, &App::new(state)), None);
+    assert_eq!(prog.total_runs, 1);
+}
+
 // === Room Transition Tests (Task 22) ===
 
 #[test]
@@ -250,68 +748,515 @@ fn room_marked_cleared_when_enemies_defeated() {
     assert!(room.is_cleared());
 }
 
-// === Sanctuary Tests ===
+#[test]
+fn mini_boss_gives_bonus_xp_and_loot() {
+    let mut room = make_test_room(0, false, false);
+    let commit = make_commit("Rewrite the parser", 300);
+    let mut boss = Enemy::new(EnemyType::Bug, 2, 3, &commit.hash);
+    boss.promote("Rewrite the parser");
+    boss.hp = 1;
+    room.enemies.push(boss);
+    room.source_commits.push(commit);
 
-fn make_sanctuary_room(id: usize) -> Room {
-    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
-    let mut room = Room::new(id, 7, 7, RoomType::Sanctuary, date);
-    
-    // Add walls around edges
-    for x in 0..7 {
-        room.set_tile(x, 0, Tile::Wall);
-        room.set_tile(x, 6, Tile::Wall);
-    }
-    for y in 0..7 {
-        room.set_tile(0, y, Tile::Wall);
-        room.set_tile(6, y, Tile::Wall);
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 1;
+    state.player.y = 3;
+
+    for _ in 0..10 {
+        state.process_action(PlayerAction::Attack(Direction::East));
+        if state.world.current().unwrap().enemies.is_empty() {
+            break;
+        }
     }
-    
-    room
+
+    let room = state.world.current().unwrap();
+    assert!(room.enemies.is_empty());
+    assert_eq!(state.player.xp, 30);
+    assert!(room.get_item_at(2, 3).is_some());
+    assert!(state.messages.iter().any(|m| m.contains("drops")));
 }
 
 #[test]
-fn sanctuary_regenerates_energy() {
-    let room = make_sanctuary_room(0);
-    let world = World::new(vec![room]);
-    
-    let mut player = Player::new(PlayerClass::Wanderer);
-    player.x = 3;
-    player.y = 3;
-    player.energy = 50; // Start with half energy
-    
-    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
-    state.world = world;
-    state.player = player;
-    
-    // Process an action (Wait)
-    state.process_action(PlayerAction::Wait);
-    
-    // Should have +2 from Wait + 5 from Sanctuary = 57
-    assert!(state.player.energy > 52);
+fn featured_room_announced_on_start() {
+    let commits = vec![make_commit("Fix typo", 10), make_commit("Rewrite the parser", 120)];
+    let state = GameState::new(commits, 42, test_git_path());
+    assert!(state.messages.iter().any(|m| m.contains("Commit of the day")));
 }
 
-#[test]
-fn sanctuary_has_no_enemies() {
-    use rand::SeedableRng;
-    use rand_chacha::ChaCha8Rng;
-    
-    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
-    let mut room = Room::new(0, 7, 7, RoomType::Sanctuary, date);
-    
-    let commits = vec![
-        make_commit("Test 1", 50),
-        make_commit("Test 2", 50),
-        make_commit("Test 3", 50),
-    ];
-    
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
-    room.spawn_enemies(&commits, &mut rng);
-    
-    // Sanctuary rooms should never have enemies
-    assert!(room.enemies.is_empty());
+// === Backtracking Tests ===
+
+fn make_linked_rooms() -> World {
+    let mut rooms = vec![make_test_room(0, false, false), make_test_room(1, false, false)];
+    rooms[0].set_tile(6, 3, Tile::Exit);
+    rooms[1].set_tile(0, 3, Tile::Entrance);
+    World::new(rooms)
 }
 
-// === Class Tests ===
+fn backtracking_state(repopulate: bool) -> GameState {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = make_linked_rooms();
+    state.repopulate_on_revisit = repopulate;
+    state.player.x = 5;
+    state.player.y = 3;
+
+    // Step through the exit, then back out the entrance
+    state.process_action(PlayerAction::Move(1, 0));
+    assert_eq!(state.world.current_room, 1);
+    state.process_action(PlayerAction::Move(-1, 0));
+    state
+}
+
+#[test]
+fn entrance_leads_back_to_previous_room() {
+    let state = backtracking_state(false);
+
+    assert_eq!(state.world.current_room, 0);
+    assert_eq!((state.player.x, state.player.y), (5, 3));
+    assert_eq!(state.furthest_room, 1);
+}
+
+#[test]
+fn revisited_room_is_safe_when_repopulation_off() {
+    let state = backtracking_state(false);
+
+    let room = state.world.current().unwrap();
+    assert!(room.enemies.is_empty());
+    assert!(room.is_cleared());
+}
+
+#[test]
+fn revisited_room_repopulates_when_enabled() {
+    let mut state = backtracking_state(true);
+
+    let room = state.world.current().unwrap();
+    assert_eq!(room.enemies.len(), 1);
+    assert_eq!(room.enemies[0].enemy_type, EnemyType::Bug);
+    assert!(!room.is_cleared());
+    assert!(state.messages.iter().any(|m| m.contains("crept back")));
+
+    // The way forward is blocked until it's dealt with
+    state.process_action(PlayerAction::Move(1, 0));
+    assert_eq!(state.world.current_room, 0);
+}
+
+#[test]
+fn first_room_entrance_goes_nowhere() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    let mut room = make_test_room(0, false, false);
+    room.set_tile(0, 3, Tile::Entrance);
+    state.world = World::new(vec![room]);
+    state.player.x = 1;
+    state.player.y = 3;
+
+    state.process_action(PlayerAction::Move(-1, 0));
+
+    assert_eq!(state.world.current_room, 0);
+    assert!(!state.check_room_entrance());
+}
+
+// === Pacifist Tests ===
+
+fn sneaking_state(pacifist_exits: bool) -> GameState {
+    let mut world = make_linked_rooms();
+    world.rooms[0].enemies.push(Enemy::new(EnemyType::Bug, 1, 1, "sleeper"));
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = world;
+    state.pacifist_exits = pacifist_exits;
+    state.player.x = 5;
+    state.player.y = 3;
+    state.update_fov();
+    state
+}
+
+#[test]
+fn pacifist_exit_past_unnoticed_enemies() {
+    let mut state = sneaking_state(true);
+    let essence = state.essence_earned();
+
+    state.process_action(PlayerAction::Move(1, 0));
+
+    assert_eq!(state.world.current_room, 1);
+    assert!(state.world.rooms[0].pacifist);
+    assert_eq!(state.pacifist_rooms(), 1);
+    assert!(state.essence_earned() >= essence + 10);
+    assert!(state.messages.iter().any(|m| m.contains("Pacifist clear")));
+}
+
+#[test]
+fn enemies_block_exit_without_pacifist_rule() {
+    let mut state = sneaking_state(false);
+    state.process_action(PlayerAction::Move(1, 0));
+
+    assert_eq!(state.world.current_room, 0);
+    assert!(!state.world.rooms[0].pacifist);
+}
+
+#[test]
+fn noticed_enemy_blocks_pacifist_exit() {
+    let mut state = sneaking_state(true);
+    state.world.current_mut().unwrap().enemies[0].aggro = true;
+
+    state.process_action(PlayerAction::Move(1, 0));
+
+    assert_eq!(state.world.current_room, 0);
+}
+
+#[test]
+fn attacking_forfeits_pacifist_clear() {
+    let mut state = sneaking_state(true);
+    state.world.current_mut().unwrap().enemies.push(Enemy::new(EnemyType::Bug, 5, 2, "poked"));
+    state.process_action(PlayerAction::Attack(Direction::North));
+    // Knock out the aggroed enemy's awareness so only the fight matters
+    for enemy in &mut state.world.current_mut().unwrap().enemies {
+        enemy.aggro = false;
+    }
+
+    state.process_action(PlayerAction::Move(1, 0));
+
+    assert_eq!(state.world.current_room, 1);
+    assert!(!state.world.rooms[0].pacifist);
+}
+
+#[test]
+fn unnoticed_enemies_stay_put_with_pacifist_rule() {
+    let mut state = sneaking_state(true);
+    state.process_enemies();
+
+    let enemy = &state.world.current().unwrap().enemies[0];
+    assert_eq!((enemy.x, enemy.y), (1, 1));
+    assert!(!enemy.aggro);
+}
+
+fn noticed_two_steps_away(stealth: i32) -> bool {
+    let mut state = sneaking_state(true);
+    state.player.stealth = stealth;
+    state.player.x = 3;
+    state.player.y = 1;
+    state.update_fov();
+
+    state.process_enemies();
+
+    state.world.current().unwrap().enemies[0].aggro
+}
+
+#[test]
+fn stealth_delays_notice_at_a_distance() {
+    assert!(noticed_two_steps_away(0));
+    assert!(!noticed_two_steps_away(1));
+}
+
+#[test]
+fn enemies_notice_player_up_close() {
+    let mut state = sneaking_state(true);
+    state.player.x = 2;
+    state.player.y = 1;
+    state.update_fov();
+
+    state.process_enemies();
+
+    assert!(state.world.current().unwrap().enemies[0].aggro);
+}
+
+// === Scope Creep Tests ===
+
+#[test]
+fn scope_creep_steals_and_drops_item_on_death() {
+    let mut room = make_test_room(0, false, false);
+    room.enemies.push(Enemy::new(EnemyType::ScopeCreep, 3, 3, "sprawl"));
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 2;
+    state.player.y = 3;
+    state.player.pickup_item(Item::new("Lucky Charm", ItemType::Consumable, ItemEffect::Heal(10), Rarity::Rare));
+    let hp = state.player.hp;
+
+    // Steals instead of attacking
+    state.process_enemies();
+    assert!(state.player.inventory.is_empty());
+    assert_eq!(state.player.hp, hp);
+    let thief = &state.world.current().unwrap().enemies[0];
+    assert_eq!(thief.stolen.as_ref().map(|i| i.name.as_str()), Some("Lucky Charm"));
+
+    // Then runs away from the player
+    state.process_enemies();
+    let thief = &state.world.current().unwrap().enemies[0];
+    assert_eq!((thief.x, thief.y), (4, 3));
+
+    // Chase it down and kill it
+    state.player.x = 3;
+    for _ in 0..50 {
+        if state.world.current().unwrap().enemies.is_empty() {
+            break;
+        }
+        state.player.energy = state.player.max_energy;
+        state.process_action(PlayerAction::Attack(Direction::East));
+    }
+    let room = state.world.current().unwrap();
+    assert!(room.enemies.is_empty());
+    assert!(room.items.iter().any(|i| i.name == "Lucky Charm" && (i.x, i.y) == (4, 3)));
+
+    // Walk over it to get it back
+    state.process_action(PlayerAction::Move(1, 0));
+    assert!(state.player.inventory.iter().any(|i| i.name == "Lucky Charm"));
+}
+
+// === Pickup Tests ===
+
+fn make_item(name: &str, rarity: Rarity) -> Item {
+    Item::new(name, ItemType::Consumable, ItemEffect::Heal(10), rarity)
+}
+
+fn state_with_floor_item(item: Item) -> GameState {
+    let mut room = make_test_room(0, false, false);
+    room.items.push(item.at(2, 3));
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 1;
+    state.player.y = 3;
+    state
+}
+
+#[test]
+fn stepping_on_item_picks_it_up() {
+    let mut state = state_with_floor_item(make_item("Potion", Rarity::Common));
+    state.process_action(PlayerAction::Move(1, 0));
+
+    assert_eq!(state.player.inventory.len(), 1);
+    assert!(state.world.current().unwrap().items.is_empty());
+    assert!(!state.pending_pickup);
+}
+
+#[test]
+fn full_inventory_flags_pending_pickup() {
+    let mut state = state_with_floor_item(make_item("Elixir", Rarity::Rare));
+    state.player.max_inventory = 1;
+    state.player.pickup_item(make_item("Junk", Rarity::Common));
+
+    state.process_action(PlayerAction::Move(1, 0));
+
+    assert!(state.pending_pickup);
+    assert_eq!(state.player.inventory[0].name, "Junk");
+    assert!(state.world.current().unwrap().get_item_at(2, 3).is_some());
+}
+
+#[test]
+fn accepting_pending_pickup_swaps_lowest_rarity() {
+    let mut state = state_with_floor_item(make_item("Elixir", Rarity::Rare));
+    state.player.max_inventory = 2;
+    state.player.pickup_item(make_item("Sword", Rarity::Legendary));
+    state.player.pickup_item(make_item("Junk", Rarity::Common));
+
+    state.process_action(PlayerAction::Move(1, 0));
+    assert!(state.accept_pending_pickup());
+
+    let names: Vec<_> = state.player.inventory.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(names, vec!["Sword", "Elixir"]);
+    let floor = state.world.current().unwrap().get_item_at(2, 3).unwrap();
+    assert_eq!(floor.name, "Junk");
+    assert!(!state.pending_pickup);
+}
+
+#[test]
+fn declining_pending_pickup_leaves_item() {
+    let mut state = state_with_floor_item(make_item("Elixir", Rarity::Rare));
+    state.player.max_inventory = 0;
+
+    state.process_action(PlayerAction::Move(1, 0));
+    state.decline_pending_pickup();
+
+    assert!(!state.pending_pickup);
+    assert!(state.player.inventory.is_empty());
+    assert!(state.world.current().unwrap().get_item_at(2, 3).is_some());
+}
+
+#[test]
+fn blink_relocates_player_to_safe_tile() {
+    let mut room = make_test_room(0, false, false);
+    room.enemies.push(Enemy::new(EnemyType::Bug, 2, 3, "test"));
+    room.enemies.push(Enemy::new(EnemyType::Bug, 1, 2, "test"));
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 1;
+    state.player.y = 3;
+    state.player.pickup_item(Item::new(
+        "Panic Button",
+        ItemType::Consumable,
+        ItemEffect::Blink,
+        Rarity::Rare,
+    ));
+
+    state.process_action(PlayerAction::UseItem(0));
+
+    let (x, y) = (state.player.x, state.player.y);
+    let room = state.world.current().unwrap();
+    assert_ne!((x, y), (1, 3));
+    assert!(room.is_walkable(x, y));
+    assert!(room.get_enemy_at(x, y).is_none());
+    assert!(state.player.inventory.is_empty());
+}
+
+#[test]
+fn blink_fizzles_when_no_tile_is_free() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 3, 3, RoomType::Normal, date);
+    for x in 0..3 {
+        room.set_tile(x, 0, Tile::Wall);
+        room.set_tile(x, 2, Tile::Wall);
+    }
+    room.set_tile(0, 1, Tile::Wall);
+    room.set_tile(2, 1, Tile::Wall);
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 1;
+    state.player.y = 1;
+    state.player.pickup_item(Item::new("Panic Button", ItemType::Consumable, ItemEffect::Blink, Rarity::Rare));
+
+    state.process_action(PlayerAction::UseItem(0));
+
+    assert_eq!((state.player.x, state.player.y), (1, 1));
+    assert!(state.messages.iter().any(|m| m.contains("fizzles")));
+}
+
+// === Sanctuary Tests ===
+
+fn make_sanctuary_room(id: usize) -> Room {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(id, 7, 7, RoomType::Sanctuary, date);
+    
+    // Add walls around edges
+    for x in 0..7 {
+        room.set_tile(x, 0, Tile::Wall);
+        room.set_tile(x, 6, Tile::Wall);
+    }
+    for y in 0..7 {
+        room.set_tile(0, y, Tile::Wall);
+        room.set_tile(6, y, Tile::Wall);
+    }
+    
+    room
+}
+
+#[test]
+fn sanctuary_regenerates_energy() {
+    let room = make_sanctuary_room(0);
+    let world = World::new(vec![room]);
+    
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.x = 3;
+    player.y = 3;
+    player.energy = 50; // Start with half energy
+    
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = world;
+    state.player = player;
+    
+    // Process an action (Wait)
+    state.process_action(PlayerAction::Wait);
+    
+    // Should have +2 from Wait + 5 from Sanctuary = 57
+    assert!(state.player.energy > 52);
+}
+
+#[test]
+fn custom_energy_costs_apply_to_actions() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, false, false)]);
+    state.player.x = 2;
+    state.player.y = 3;
+    state.player.energy = 50;
+    state.energy_costs = EnergyCosts { movement: 0, defend: 7, ..EnergyCosts::default() };
+
+    state.process_action(PlayerAction::Move(0, -1));
+    assert_eq!(state.player.y, 2);
+    assert_eq!(state.player.energy, 50);
+
+    state.process_action(PlayerAction::Defend);
+    assert_eq!(state.player.energy, 43);
+}
+
+#[test]
+fn custom_energy_costs_gate_actions() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, true, false)]);
+    state.player.x = 2;
+    state.player.y = 3;
+    state.player.energy = 10;
+    state.energy_costs = EnergyCosts { attack: 20, ..EnergyCosts::default() };
+    let enemy_hp = state.world.current().unwrap().enemies[0].hp;
+
+    state.process_action(PlayerAction::Attack(Direction::East));
+
+    assert_eq!(state.player.energy, 10);
+    assert_eq!(state.world.current().unwrap().enemies[0].hp, enemy_hp);
+    assert!(state.messages.iter().any(|m| m == "Not enough energy!"));
+}
+
+fn bump_count(state: &GameState) -> usize {
+    state.messages.iter().filter(|m| *m == "You bump into a wall.").count()
+}
+
+#[test]
+fn wall_bump_logs_once_per_direction() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, false, false)]);
+    state.player.x = 1;
+    state.player.y = 3;
+
+    for _ in 0..5 {
+        state.process_action(PlayerAction::Move(-1, 0));
+    }
+    assert_eq!(bump_count(&state), 1);
+    assert_eq!(state.player.x, 1);
+
+    // A different wall is worth a new message
+    state.process_action(PlayerAction::Move(0, -1));
+    state.process_action(PlayerAction::Move(0, -1));
+    state.process_action(PlayerAction::Move(0, -1));
+    assert_eq!(state.player.y, 1);
+    assert_eq!(bump_count(&state), 2);
+}
+
+#[test]
+fn wall_bump_message_resets_after_moving() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, false, false)]);
+    state.player.x = 1;
+    state.player.y = 3;
+
+    state.process_action(PlayerAction::Move(-1, 0));
+    state.process_action(PlayerAction::Move(1, 0));
+    state.process_action(PlayerAction::Move(-1, 0));
+    state.process_action(PlayerAction::Move(-1, 0));
+
+    assert_eq!(bump_count(&state), 2);
+}
+
+#[test]
+fn sanctuary_has_no_enemies() {
+    use penumbra::rng::GameRng;
+    use rand::SeedableRng;
+    
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 7, 7, RoomType::Sanctuary, date);
+    
+    let commits = vec![
+        make_commit("Test 1", 50),
+        make_commit("Test 2", 50),
+        make_commit("Test 3", 50),
+    ];
+    
+    let mut rng = GameRng::seed_from_u64(42);
+    room.spawn_enemies(&commits, &mut rng);
+    
+    // Sanctuary rooms should never have enemies
+    assert!(room.enemies.is_empty());
+}
+
+// === Class Tests ===
 
 #[test]
 fn new_with_class_sets_class() {
@@ -340,3 +1285,709 @@ fn class_auto_detection_inbox_knight() {
     let state = GameState::new_with_class(commits, 42, None, test_git_path());
     assert_eq!(state.player.class, PlayerClass::InboxKnight);
 }
+
+// === Bisect Tests ===
+
+fn bisect_state() -> GameState {
+    let rooms = (0..3).map(|id| make_test_room(id, true, true)).collect();
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(rooms);
+    state.start_bisect();
+    state
+}
+
+#[test]
+fn bisect_toughens_rooms_from_the_bad_commit_on() {
+    let state = bisect_state();
+    assert!(state.bisect);
+    assert!(state.bad_room < 3);
+
+    let base = EnemyType::Bug.base_damage();
+    for (index, room) in state.world.rooms.iter().enumerate() {
+        let steps = if index >= state.bad_room { (index - state.bad_room + 1) as i32 } else { 0 };
+        assert_eq!(room.enemies[0].damage, base + steps);
+        assert_eq!(room.enemies[0].max_hp, EnemyType::Bug.base_hp() + steps * 2);
+    }
+}
+
+#[test]
+fn accusing_the_bad_room_wins_with_bonus() {
+    let mut state = bisect_state();
+    state.world.current_room = state.bad_room;
+
+    assert!(state.accuse_room());
+    assert!(state.game_over);
+    assert!(state.victory);
+    assert!(state.bisected);
+    assert_eq!(state.bonus_essence(), BISECT_BONUS);
+}
+
+#[test]
+fn accusing_the_wrong_room_costs_hp() {
+    let mut state = bisect_state();
+    state.world.current_room = (state.bad_room + 1) % 3;
+    let hp = state.player.hp;
+
+    assert!(!state.accuse_room());
+    assert_eq!(state.player.hp, hp - GameState::WRONG_ACCUSATION_DAMAGE);
+    assert!(!state.game_over);
+    assert!(!state.bisected);
+    assert_eq!(state.bonus_essence(), 0);
+}
+
+#[test]
+fn wrong_accusation_can_be_fatal() {
+    let mut state = bisect_state();
+    state.world.current_room = (state.bad_room + 1) % 3;
+    state.player.hp = 1;
+
+    state.accuse_room();
+    assert!(state.game_over);
+    assert!(!state.victory);
+}
+
+#[test]
+fn accusing_outside_bisect_does_nothing() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    let hp = state.player.hp;
+
+    assert!(!state.accuse_room());
+    assert_eq!(state.player.hp, hp);
+    assert!(!state.game_over);
+}
+
+// === Merge Puzzle Tests ===
+
+fn walk(state: &mut GameState, moves: &[(i32, i32)]) {
+    for &(dx, dy) in moves {
+        state.process_action(PlayerAction::Move(dx, dy));
+    }
+}
+
+#[test]
+fn merge_room_exit_opens_after_both_branches() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = make_linked_rooms();
+    state.world.rooms[0].add_merge_branches();
+    state.energy_costs.movement = 0;
+    state.player.x = 1;
+    state.player.y = 3;
+
+    // Through the north branch to the exit
+    walk(&mut state, &[(0, -1), (1, 0), (1, 0), (1, 0), (1, 0), (0, 1), (1, 0)]);
+    assert_eq!((state.player.x, state.player.y), (6, 3));
+    assert_eq!(state.world.current_room, 0);
+    assert_eq!(state.world.rooms[0].merge_branches, Some([true, false]));
+
+    // Back into the south branch, then out
+    walk(&mut state, &[(-1, 0), (0, 1), (-1, 0), (1, 0), (0, -1), (1, 0)]);
+    assert!(state.world.rooms[0].branches_merged());
+    assert_eq!(state.world.current_room, 1);
+}
+
+// === Deletion Tests ===
+
+#[test]
+fn deletions_move_two_steps_a_turn() {
+    let mut room = make_test_room(0, false, false);
+    room.enemies.push(Enemy::new(EnemyType::Deletion, 5, 3, "cut"));
+    room.enemies.push(Enemy::new(EnemyType::Bug, 5, 1, "bug"));
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 1;
+    state.player.y = 3;
+
+    state.process_enemies();
+    let enemies = &state.world.current().unwrap().enemies;
+    assert_eq!((enemies[0].x, enemies[0].y), (3, 3));
+    assert_eq!((enemies[1].x, enemies[1].y), (4, 1));
+
+    // Stops next to the player rather than on top of them
+    state.process_enemies();
+    let deletion = &state.world.current().unwrap().enemies[0];
+    assert_eq!((deletion.x, deletion.y), (2, 3));
+}
+
+// === Fog Memory Tests ===
+
+#[test]
+fn update_fov_remembers_when_tiles_were_seen() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, false, false)]);
+    state.player.x = 1;
+    state.player.y = 3;
+    state.turn = 7;
+    state.update_fov();
+
+    let room = state.world.current().unwrap();
+    assert_eq!(room.last_seen(1, 3), Some(7));
+    assert_eq!(room.last_seen(-1, 3), None);
+
+    state.turn = 9;
+    state.player.x = 2;
+    state.update_fov();
+    assert_eq!(state.world.current().unwrap().last_seen(1, 3), Some(9));
+}
+
+// === Turn summary ===
+
+#[test]
+fn turn_summary_condenses_mixed_events() {
+    let events = vec![
+        GameEvent::PlayerMoved { x: 2, y: 3 },
+        GameEvent::PlayerAttacked { damage: 12, killed: false },
+        GameEvent::EnemyAttacked { damage: 0, enemy_type: "Bug".to_string() },
+        GameEvent::EnemyHealed { amount: 2, enemy_type: "Regression".to_string() },
+        GameEvent::Message("ignored".to_string()),
+        GameEvent::EnemyAttacked { damage: 3, enemy_type: "TechDebt".to_string() },
+    ];
+
+    assert_eq!(
+        turn_summary(&events),
+        "You hit for 12; Bug missed; Regression healed 2; TechDebt hit you for 3"
+    );
+}
+
+#[test]
+fn turn_summary_reports_kills_and_room_changes() {
+    let events = vec![
+        GameEvent::PlayerAttacked { damage: 0, killed: false },
+        GameEvent::PlayerAttacked { damage: 9, killed: true },
+        GameEvent::PlayerLevelUp { level: 2 },
+        GameEvent::EnemyKilled { enemy_type: "Bug".to_string(), xp: 10 },
+        GameEvent::RoomCleared { room_id: 0 },
+    ];
+
+    assert_eq!(
+        turn_summary(&events),
+        "You missed; You hit for 9; Level 2!; Bug destroyed (+10 XP); Room cleared"
+    );
+}
+
+#[test]
+fn turn_summary_empty_for_quiet_turns() {
+    assert_eq!(turn_summary(&[]), "");
+    assert_eq!(turn_summary(&[GameEvent::PlayerMoved { x: 1, y: 1 }]), "");
+}
+
+// === Tutorial ===
+
+fn tutorial_state() -> GameState {
+    let options = GenerationOptions { tutorial: true, ..Default::default() };
+    let mut state =
+        GameState::new_with_options(vec![make_commit("Test", 50)], 42, Some(PlayerClass::CodeWarrior), test_git_path(), &options);
+    state.energy_costs = EnergyCosts { movement: 0, attack: 0, defend: 0, use_item: 0 };
+    state
+}
+
+#[test]
+fn tutorial_starts_in_training_room() {
+    let state = tutorial_state();
+    assert_eq!(state.world.current_room, 0);
+    assert_eq!(state.world.current().unwrap().room_type, RoomType::Tutorial);
+    assert_eq!(state.tutorial, Some(TutorialStep::Move));
+    assert!(state.messages.iter().any(|m| m == TutorialStep::Move.prompt()));
+}
+
+#[test]
+fn tutorial_prompts_advance_with_each_action() {
+    let mut state = tutorial_state();
+
+    walk(&mut state, &[(1, 0)]);
+    assert_eq!(state.tutorial, Some(TutorialStep::Attack));
+
+    // Up to the bug in the gap, then attack until it falls
+    walk(&mut state, &[(1, 0), (1, 0), (1, 0)]);
+    for _ in 0..10 {
+        state.process_action(PlayerAction::Attack(Direction::East));
+        if state.tutorial != Some(TutorialStep::Attack) {
+            break;
+        }
+    }
+    assert_eq!(state.tutorial, Some(TutorialStep::PickUp));
+
+    walk(&mut state, &[(1, 0), (1, 0), (1, 0), (1, 0)]);
+    assert_eq!(state.tutorial, Some(TutorialStep::Leave));
+    assert!(state.player.inventory.iter().any(|i| i.name == "Training Potion"));
+
+    walk(&mut state, &[(1, 0), (1, 0), (1, 0)]);
+    assert_eq!(state.tutorial, None);
+    assert_eq!(state.world.current_room, 1);
+    assert_ne!(state.world.current().unwrap().room_type, RoomType::Tutorial);
+}
+
+#[test]
+fn leaving_the_tutorial_does_not_count_as_a_cleared_room() {
+    let mut state = tutorial_state();
+    state.set_goal(VictoryCondition::ClearRooms(1));
+    state.world.rooms[0].enemies.clear();
+    state.world.rooms[0].cleared = true;
+    assert_eq!(state.rooms_cleared(), 0);
+
+    let exit = state.world.current().unwrap().exit_position().unwrap();
+    (state.player.x, state.player.y) = exit;
+    assert!(state.check_room_exit());
+    assert_eq!(state.world.current_room, 1);
+
+    assert_eq!(state.rooms_cleared(), 0);
+    assert_eq!(state.rooms_played(), 0);
+    assert!(!state.check_goal());
+    assert!(!state.victory);
+}
+
+#[test]
+fn skipping_past_the_tutorial_counts_only_dungeon_rooms() {
+    let options = GenerationOptions { tutorial: true, skip_rooms: 2, ..Default::default() };
+    let state = GameState::new_with_options(commits_over_days(5), 42, None, test_git_path(), &options);
+
+    assert_eq!(state.world.current_room, 3);
+    assert_eq!(state.skipped_rooms, 2);
+    // Two skipped dungeon rooms, plus the current one if it starts empty
+    let current = state.world.current().unwrap().cleared as usize;
+    assert_eq!(state.rooms_cleared(), 2 + current);
+    assert_eq!(state.rooms_played(), current);
+}
+
+#[test]
+fn tutorial_steps_wait_for_their_action() {
+    let mut state = tutorial_state();
+    state.process_action(PlayerAction::Wait);
+    state.process_action(PlayerAction::Defend);
+    assert_eq!(state.tutorial, Some(TutorialStep::Move));
+}
+
+#[test]
+fn bisect_never_picks_the_tutorial_room() {
+    for seed in 0..20 {
+        let options = GenerationOptions { tutorial: true, bisect: true, ..Default::default() };
+        let commits = vec![make_commit("Test", 50)];
+        let state = GameState::new_with_options(commits, seed, None, test_git_path(), &options);
+        assert!(state.bad_room >= 1);
+    }
+}
+
+// === Shortcuts ===
+
+fn shortcut_state(current: usize) -> GameState {
+    let mut rooms: Vec<Room> = (0..3).map(|id| make_test_room(id, false, true)).collect();
+    rooms[0].add_shortcut(2);
+    rooms[2].add_shortcut(0);
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(rooms);
+    state.world.current_room = current;
+    state.furthest_room = current;
+    let (x, y) = state.world.current().unwrap().shortcut_arrival();
+    state.player.x = x;
+    state.player.y = y;
+    state
+}
+
+#[test]
+fn shortcut_door_leads_back_to_linked_room() {
+    let mut state = shortcut_state(2);
+
+    let events = state.process_action(PlayerAction::Move(0, -1));
+
+    assert_eq!(state.world.current_room, 0);
+    assert_eq!((state.player.x, state.player.y), (3, 1));
+    assert!(events.iter().any(|e| matches!(e, GameEvent::RoomEntered { room_id: 0 })));
+    assert_eq!(state.furthest_room, 2);
+}
+
+#[test]
+fn entering_a_room_sends_its_ambience_cue() {
+    let mut state = shortcut_state(2);
+    state.world.rooms[0].room_type = RoomType::Boss;
+    state.world.rooms[0].enemies.push(Enemy::new(EnemyType::MergeConflict, 5, 5, "merge"));
+
+    let events = state.process_action(PlayerAction::Move(0, -1));
+
+    assert_eq!(state.current_ambience(), AmbienceCue::Tense);
+    assert!(events.iter().any(|e| matches!(e, GameEvent::AmbienceCue { cue: AmbienceCue::Tense })));
+
+    // Clearing the chamber calms it
+    state.world.rooms[0].enemies.clear();
+    assert_eq!(state.current_ambience(), AmbienceCue::Calm);
+}
+
+#[test]
+fn shortcut_sealed_until_destination_reached() {
+    let mut state = shortcut_state(0);
+
+    state.process_action(PlayerAction::Move(0, -1));
+
+    assert_eq!(state.world.current_room, 0);
+    assert_eq!((state.player.x, state.player.y), (3, 1));
+    assert!(state.messages.iter().any(|m| m.contains("haven't been yet")));
+}
+
+#[test]
+fn shortcut_needs_a_cleared_room() {
+    let mut state = shortcut_state(2);
+    state.world.current_mut().unwrap().enemies.push(Enemy::new(EnemyType::Bug, 1, 5, "test"));
+
+    state.process_action(PlayerAction::Move(0, -1));
+
+    assert_eq!(state.world.current_room, 2);
+}
+
+// === Defend ===
+
+/// Player boxed in by four hard-hitting Bugs.
+fn surrounded_state(seed: u64) -> GameState {
+    let mut room = make_test_room(0, false, false);
+    for (x, y) in [(2, 3), (4, 3), (3, 2), (3, 4)] {
+        let mut bug = Enemy::new(EnemyType::Bug, x, y, "test");
+        bug.damage = 40;
+        room.enemies.push(bug);
+    }
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], seed, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 3;
+    state.player.y = 3;
+    state.player.max_hp = 1000;
+    state.player.hp = 1000;
+    state.update_fov();
+    state
+}
+
+/// HP lost to one enemy phase after `action`.
+fn phase_damage(seed: u64, action: PlayerAction) -> i32 {
+    let mut state = surrounded_state(seed);
+    state.process_action(action);
+    let before = state.player.hp;
+    state.process_enemies();
+    before - state.player.hp
+}
+
+#[test]
+fn defend_blocks_every_hit_in_the_enemy_phase() {
+    let mut most_hits = 0;
+    for seed in 0..20 {
+        let guarded = phase_damage(seed, PlayerAction::Defend);
+        let full = phase_damage(seed, PlayerAction::Wait);
+
+        // Same rolls either way; every landed hit is cut from 40 to 10
+        assert_eq!(guarded * 4, full, "seed {}", seed);
+        most_hits = most_hits.max(full / 40);
+    }
+    assert!(most_hits >= 2);
+}
+
+#[test]
+fn defend_lasts_until_next_action() {
+    let mut state = surrounded_state(1);
+    state.process_action(PlayerAction::Defend);
+    state.process_enemies();
+    assert!(state.player.is_guarding());
+
+    state.process_action(PlayerAction::Wait);
+    assert!(!state.player.is_guarding());
+}
+
+#[test]
+fn timed_guard_counts_down_enemy_phases() {
+    let mut state = surrounded_state(1);
+    state.guard_turns = 2;
+
+    state.process_action(PlayerAction::Defend);
+    assert_eq!(state.player.guard_turns, 2);
+    state.process_enemies();
+
+    state.process_action(PlayerAction::Wait);
+    assert!(state.player.is_guarding());
+    state.process_enemies();
+
+    state.process_action(PlayerAction::Wait);
+    assert!(!state.player.is_guarding());
+}
+
+#[test]
+fn difficulty_scales_enemies() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, true, false)]);
+    let before: Vec<(i32, i32)> = state.world.rooms[0].enemies.iter().map(|e| (e.max_hp, e.damage)).collect();
+
+    state.apply_difficulty(Difficulty::Hard);
+
+    assert_eq!(state.difficulty, Difficulty::Hard);
+    for (enemy, (hp, damage)) in state.world.rooms[0].enemies.iter().zip(before) {
+        assert_eq!(enemy.max_hp, Difficulty::Hard.scale(hp));
+        assert_eq!(enemy.damage, Difficulty::Hard.scale(damage));
+        assert!(enemy.max_hp > hp);
+    }
+    assert_eq!(Difficulty::Easy.scale(10), 8);
+    assert_eq!(Difficulty::Easy.scale(1), 1);
+    assert_eq!(Difficulty::Normal.scale(7), 7);
+}
+
+#[test]
+fn chest_outcomes_follow_the_mimic_chance() {
+    use penumbra::rng::GameRng;
+    use rand::SeedableRng;
+
+    let mut mimics = 0;
+    for seed in 0..400 {
+        let mut rng = GameRng::seed_from_u64(seed);
+        match roll_chest(&mut rng) {
+            ChestOutcome::Mimic => mimics += 1,
+            ChestOutcome::Treasure(item) => assert_eq!(item.rarity, Rarity::Rare),
+        }
+    }
+    let share = mimics as f64 / 400.0;
+    assert!((share - MIMIC_CHANCE).abs() < 0.1, "mimic share {}", share);
+}
+
+#[test]
+fn clear_heal_restores_fixed_hp() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.player.hp = 10;
+    state.take_clear_heal();
+    assert_eq!(state.player.hp, 10 + CLEAR_REWARD_HEAL);
+
+    state.player.hp = state.player.max_hp - 1;
+    state.take_clear_heal();
+    assert_eq!(state.player.hp, state.player.max_hp);
+}
+
+#[test]
+fn clear_rewards_are_offered_once_outside_boss_rooms() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    let mut boss = make_test_room(1, false, false);
+    boss.room_type = RoomType::Boss;
+    state.world = World::new(vec![make_test_room(0, false, true), boss]);
+
+    assert!(!state.offer_clear_reward(0));
+    state.clear_rewards = true;
+    assert!(state.offer_clear_reward(0));
+    assert!(!state.offer_clear_reward(0));
+    assert!(!state.offer_clear_reward(1));
+}
+
+#[test]
+fn chest_outcome_is_seeded_and_applied() {
+    let mut seen_mimic = false;
+    let mut seen_treasure = false;
+    for seed in 0..30 {
+        let open = || {
+            let mut state = GameState::new(vec![make_commit("Test", 50)], seed, test_git_path());
+            state.world = World::new(vec![make_test_room(0, false, false)]);
+            state.player.x = 1;
+            state.player.y = 3;
+            state.player.inventory.clear();
+            let outcome = state.open_clear_chest();
+            (state, outcome)
+        };
+        let (state, outcome) = open();
+        let (_, again) = open();
+        let room = &state.world.rooms[0];
+
+        match (outcome, again) {
+            (ChestOutcome::Mimic, ChestOutcome::Mimic) => {
+                seen_mimic = true;
+                assert_eq!(room.enemies.len(), 1);
+                assert_eq!(room.enemies[0].title.as_deref(), Some("Mimic"));
+                assert!(!room.is_cleared());
+                assert!(state.player.inventory.is_empty());
+            }
+            (ChestOutcome::Treasure(item), ChestOutcome::Treasure(same)) => {
+                seen_treasure = true;
+                assert_eq!(item.name, same.name);
+                assert!(room.enemies.is_empty());
+                assert_eq!(state.player.inventory.len(), 1);
+                assert_eq!(state.player.inventory[0].name, item.name);
+            }
+            _ => panic!("seed {} gave different chests", seed),
+        }
+    }
+    assert!(seen_mimic && seen_treasure);
+}
+
+// === Run stats ===
+
+#[test]
+fn run_stats_track_attacks_damage_and_items() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, true, false)]);
+    state.player.x = 2;
+    state.player.y = 3;
+    {
+        let bug = &mut state.world.rooms[0].enemies[0];
+        bug.hp = 1000;
+        bug.max_hp = 1000;
+    }
+    assert_eq!(state.stats.accuracy(), None);
+
+    state.player.pickup_item(Item::new("Potion", ItemType::Consumable, ItemEffect::Heal(5), Rarity::Common));
+    state.process_action(PlayerAction::UseItem(0));
+    let start_hp = state.player.hp;
+
+    let mut hits = 0;
+    for _ in 0..5 {
+        let mut events = state.process_action(PlayerAction::Attack(Direction::East));
+        events.extend(state.process_enemies());
+        hits += events
+            .iter()
+            .filter(|e| matches!(e, GameEvent::PlayerAttacked { damage, .. } if *damage > 0))
+            .count() as u32;
+    }
+
+    assert!(!state.game_over);
+    assert_eq!(state.stats.attacks, 5);
+    assert_eq!(state.stats.hits, hits);
+    assert_eq!(state.stats.accuracy(), Some(hits * 100 / 5));
+    assert_eq!(state.stats.damage_dealt as i32, 1000 - state.world.rooms[0].enemies[0].hp);
+    assert_eq!(state.stats.damage_taken as i32, start_hp - state.player.hp);
+    assert_eq!(state.stats.items_used, 1);
+
+    // Counters survive a save and load
+    let json = serde_json::to_string(&state).unwrap();
+    let loaded: GameState = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.stats, state.stats);
+}
+
+#[test]
+fn run_stats_skip_empty_attacks() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![make_test_room(0, false, false)]);
+    state.player.x = 2;
+    state.player.y = 3;
+
+    state.process_action(PlayerAction::Attack(Direction::East));
+    assert_eq!(state.stats.attacks, 0);
+}
+
+// === Conflict Marker Tests ===
+
+#[test]
+fn boss_ward_lifts_when_conflict_markers_die() {
+    let mut room = make_test_room(0, false, true);
+    room.room_type = RoomType::Boss;
+    let mut boss = Enemy::new(EnemyType::MergeConflict, 4, 4, "merge");
+    boss.promote("The auth Conflict");
+    boss.warded = true;
+    room.enemies.push(boss);
+    let mut marker = Enemy::new(EnemyType::Bug, 3, 3, "merge");
+    marker.conflict_marker = true;
+    marker.hp = 1;
+    room.enemies.push(marker);
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 2;
+    state.player.y = 3;
+
+    for _ in 0..20 {
+        if state.world.current().unwrap().enemies.len() == 1 {
+            break;
+        }
+        state.process_action(PlayerAction::Attack(Direction::East));
+    }
+
+    let room = state.world.current().unwrap();
+    assert_eq!(room.enemies.len(), 1);
+    assert!(!room.enemies[0].warded);
+    assert!(state.messages.iter().any(|m| m.contains("boss is exposed")));
+}
+
+// === Author Leaderboard Tests ===
+
+#[test]
+fn defeating_enemies_updates_author_leaderboard() {
+    let mut room = make_test_room(0, false, true);
+    let mut alice_commit = make_commit("Fix login", 10);
+    alice_commit.hash = "alice1".to_string();
+    alice_commit.author = "Alice".to_string();
+    let mut bob_commit = make_commit("Add cache", 20);
+    bob_commit.hash = "bob1".to_string();
+    bob_commit.author = "Bob".to_string();
+    room.source_commits = vec![alice_commit, bob_commit];
+    let mut bug = Enemy::new(EnemyType::Bug, 3, 3, "alice1");
+    bug.hp = 1;
+    room.enemies.push(bug);
+    room.enemies.push(Enemy::new(EnemyType::Bug, 4, 5, "bob1"));
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 2;
+    state.player.y = 3;
+    for _ in 0..20 {
+        if state.enemies_killed > 0 {
+            break;
+        }
+        state.process_action(PlayerAction::Attack(Direction::East));
+    }
+    assert_eq!(state.author_kills.get("Alice"), Some(&1));
+
+    let tally = state.author_tally();
+    assert_eq!(tally["Alice"].defeated, 1);
+    assert_eq!(tally["Alice"].spawned, 1);
+    assert_eq!(tally["Bob"].defeated, 0);
+    assert_eq!(tally["Bob"].spawned, 1);
+
+    let mut prog = Progression::new();
+    prog.record_authors(&tally);
+    prog.record_authors(&tally);
+    let board = prog.leaderboard();
+    assert_eq!(board[0].0, "Alice");
+    assert_eq!(board[0].1.defeated, 2);
+    assert_eq!(board[1].0, "Bob");
+    assert_eq!(board[1].1.spawned, 2);
+}
+
+// === Skip Rooms Tests ===
+
+fn commits_over_days(days: i64) -> Vec<CommitData> {
+    (0..days)
+        .map(|day| {
+            let mut commit = make_commit("Fix parser", 60 + day as u32);
+            commit.date = Utc::now() - chrono::Duration::days(day);
+            commit
+        })
+        .collect()
+}
+
+#[test]
+fn skipping_rooms_starts_deeper_with_earlier_rooms_cleared() {
+    let options = GenerationOptions { skip_rooms: 2, ..GenerationOptions::default() };
+    let state = GameState::new_with_options(commits_over_days(5), 42, None, test_git_path(), &options);
+
+    assert_eq!(state.world.rooms.len(), 5);
+    assert_eq!(state.world.current_room, 2);
+    let room = state.world.current().unwrap();
+    assert_eq!((state.player.x, state.player.y), (1, room.height as i32 / 2));
+    for skipped in &state.world.rooms[..2] {
+        assert!(skipped.cleared);
+        assert!(skipped.enemies.is_empty());
+    }
+
+    // Skipped rooms count as cleared but earn nothing
+    assert!(state.rooms_cleared() >= 2);
+    assert_eq!(state.rooms_played(), state.rooms_cleared() - 2);
+}
+
+#[test]
+fn skipped_rooms_are_emptied_of_items() {
+    let mut state = GameState::new_with_options(commits_over_days(4), 42, None, test_git_path(), &GenerationOptions::default());
+    for room in &mut state.world.rooms {
+        room.items.push(make_item("Coffee", Rarity::Common).at(2, 2));
+    }
+
+    state.skip_rooms(2);
+    for skipped in &state.world.rooms[..2] {
+        assert!(skipped.items.is_empty());
+    }
+    assert!(!state.world.rooms[2].items.is_empty());
+}
+
+#[test]
+fn skipping_past_the_end_stops_at_the_last_room() {
+    let options = GenerationOptions { skip_rooms: 50, ..GenerationOptions::default() };
+    let state = GameState::new_with_options(commits_over_days(3), 42, None, test_git_path(), &options);
+
+    assert_eq!(state.world.current_room, 2);
+    assert!(state.world.is_last_room());
+    assert!(!state.game_over);
+}
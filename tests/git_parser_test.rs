@@ -3,11 +3,14 @@
 use std::path::Path;
 use std::process::Command;
 
-use chrono::Utc;
+use chrono::{Duration, NaiveDate, Utc};
 use tempfile::TempDir;
 
 use penumbra::git::{
-    group_by_date, parse_repository, CommitData, GitError,
+    annotate_churn, attach_diff_snippets, churn_bonus, commit_frustration, group_by_count, group_by_date, group_by_directory, in_date_range, is_squash_outlier, largest_commit, median_commit_size,
+    most_edited_file, parse_repository, parse_repository_range, pseudonymize, significant_word, squash_weight, CommitData,
+    CommitWindow, GitError, HIGH_CHURN, MAX_CHURN_BONUS, MAX_FRUSTRATION, MAX_SNIPPET_BYTES, MAX_SNIPPET_LINES, MAX_SQUASH_SPLIT, ROOT_DIRECTORY,
+    top_level_dir,
 };
 
 /// Create a temp git repo with some commits for testing.
@@ -123,6 +126,141 @@ fn commits_sorted_oldest_first() {
     }
 }
 
+#[test]
+fn parse_repository_marks_tagged_commits() {
+    let repo = create_test_repo();
+    Command::new("git")
+        .args(["tag", "v1.2.0", "HEAD~1"])
+        .current_dir(repo.path())
+        .output()
+        .unwrap();
+    Command::new("git")
+        .args(["tag", "-a", "v2.0.0", "-m", "Release 2.0"])
+        .current_dir(repo.path())
+        .output()
+        .unwrap();
+
+    let commits = parse_repository(repo.path(), 30).unwrap();
+    let tag_of = |message: &str| {
+        commits
+            .iter()
+            .find(|c| c.message.trim() == message)
+            .and_then(|c| c.tag.clone())
+    };
+
+    assert_eq!(tag_of("Initial commit"), None);
+    assert_eq!(tag_of("Add main").as_deref(), Some("v1.2.0"));
+    assert_eq!(tag_of("Add test").as_deref(), Some("v2.0.0"));
+}
+
+// === Date Range ===
+
+fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+/// Commit a new file with both author and committer dates set to `date`,
+/// such as `2025-03-02 23:00:00 +0000`.
+fn commit_at(path: &Path, name: &str, date: &str) {
+    std::fs::write(path.join(name), name).unwrap();
+    Command::new("git").args(["add", "."]).current_dir(path).output().unwrap();
+    Command::new("git")
+        .args(["commit", "-m", name])
+        .env("GIT_AUTHOR_DATE", date)
+        .env("GIT_COMMITTER_DATE", date)
+        .current_dir(path)
+        .output()
+        .unwrap();
+}
+
+/// A repo with commits either side of, and on the edges of, 3 to 14 March.
+fn create_dated_repo() -> TempDir {
+    let repo = TempDir::new().unwrap();
+    let path = repo.path();
+    Command::new("git").args(["init"]).current_dir(path).output().unwrap();
+    Command::new("git").args(["config", "user.email", "test@test.com"]).current_dir(path).output().unwrap();
+    Command::new("git").args(["config", "user.name", "Test"]).current_dir(path).output().unwrap();
+    commit_at(path, "before", "2025-03-02 23:00:00 +0000");
+    commit_at(path, "first", "2025-03-03 00:00:00 +0000");
+    commit_at(path, "middle", "2025-03-08 12:00:00 +0000");
+    commit_at(path, "middle2", "2025-03-08 17:00:00 +0000");
+    commit_at(path, "last", "2025-03-14 23:00:00 +0000");
+    commit_at(path, "after", "2025-03-15 00:00:00 +0000");
+    repo
+}
+
+#[test]
+fn in_date_range_is_inclusive() {
+    let at = |d: NaiveDate, hour: u32| d.and_hms_opt(hour, 0, 0).unwrap().and_utc();
+    assert!(in_date_range(at(ymd(2025, 3, 3), 0), ymd(2025, 3, 3), ymd(2025, 3, 14)));
+    assert!(in_date_range(at(ymd(2025, 3, 14), 23), ymd(2025, 3, 3), ymd(2025, 3, 14)));
+    assert!(!in_date_range(at(ymd(2025, 3, 2), 23), ymd(2025, 3, 3), ymd(2025, 3, 14)));
+    assert!(!in_date_range(at(ymd(2025, 3, 15), 0), ymd(2025, 3, 3), ymd(2025, 3, 14)));
+}
+
+#[test]
+fn parse_repository_range_keeps_inclusive_window() {
+    let repo = create_dated_repo();
+
+    let commits = parse_repository_range(repo.path(), ymd(2025, 3, 3), ymd(2025, 3, 14)).unwrap();
+    let messages: Vec<_> = commits.iter().map(|c| c.message.trim()).collect();
+    assert_eq!(messages, vec!["first", "middle", "middle2", "last"]);
+}
+
+#[test]
+fn parse_repository_range_single_day() {
+    let repo = create_dated_repo();
+
+    let commits = parse_repository_range(repo.path(), ymd(2025, 3, 8), ymd(2025, 3, 8)).unwrap();
+    let messages: Vec<_> = commits.iter().map(|c| c.message.trim()).collect();
+    assert_eq!(messages, vec!["middle", "middle2"]);
+}
+
+#[test]
+fn commit_window_defaults_to_days() {
+    assert_eq!(CommitWindow::new(30, None, None), CommitWindow::Days(30));
+
+    let until = ymd(2025, 3, 14);
+    assert_eq!(
+        CommitWindow::new(7, None, Some(until)),
+        CommitWindow::Range { since: ymd(2025, 3, 7), until }
+    );
+    assert_eq!(
+        CommitWindow::new(7, Some(ymd(2025, 1, 1)), Some(until)),
+        CommitWindow::Range { since: ymd(2025, 1, 1), until }
+    );
+}
+
+#[test]
+fn parse_repository_range_rejects_reversed_dates() {
+    let repo = create_test_repo();
+    let result = parse_repository_range(repo.path(), ymd(2025, 3, 14), ymd(2025, 3, 1));
+    match result.unwrap_err() {
+        GitError::InvalidRange(since, until) => {
+            assert_eq!((since, until), (ymd(2025, 3, 14), ymd(2025, 3, 1)));
+        }
+        e => panic!("Expected InvalidRange, got {:?}", e),
+    }
+}
+
+#[test]
+fn parse_repository_range_includes_today() {
+    let repo = create_test_repo();
+    let today = Utc::now().date_naive();
+    let commits = parse_repository_range(repo.path(), today - Duration::days(1), today + Duration::days(1)).unwrap();
+    assert!(commits.len() >= 3);
+}
+
+#[test]
+fn parse_repository_range_errors_on_empty_window() {
+    let repo = create_test_repo();
+    let result = parse_repository_range(repo.path(), ymd(2001, 1, 1), ymd(2001, 1, 31));
+    match result.unwrap_err() {
+        GitError::NoCommitsInRange(..) => (),
+        e => panic!("Expected NoCommitsInRange, got {:?}", e),
+    }
+}
+
 #[test]
 fn group_by_date_groups_correctly() {
     let commits = vec![
@@ -134,7 +272,7 @@ fn group_by_date_groups_correctly() {
             deletions: 5,
             files_changed: 1,
             author: "Test".to_string(),
-            is_merge: false, file_categories: Default::default(),
+            is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
         },
         CommitData {
             hash: "b".to_string(),
@@ -144,7 +282,7 @@ fn group_by_date_groups_correctly() {
             deletions: 10,
             files_changed: 2,
             author: "Test".to_string(),
-            is_merge: false, file_categories: Default::default(),
+            is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
         },
     ];
 
@@ -153,6 +291,214 @@ fn group_by_date_groups_correctly() {
     assert_eq!(grouped.values().next().unwrap().len(), 2);
 }
 
+#[test]
+fn group_by_date_orders_same_timestamp_commits_by_hash() {
+    let date = Utc::now();
+    let make = |hash: &str| {
+        let mut commit = make_sized_commit(hash, 10, 0, 0);
+        commit.date = date;
+        commit
+    };
+
+    let forward = group_by_date(vec![make("c"), make("a"), make("b")]);
+    let backward = group_by_date(vec![make("b"), make("a"), make("c")]);
+
+    let hashes = |grouped: &std::collections::BTreeMap<_, Vec<CommitData>>| -> Vec<String> {
+        grouped.values().flatten().map(|c| c.hash.clone()).collect()
+    };
+    assert_eq!(hashes(&forward), vec!["a", "b", "c"]);
+    assert_eq!(hashes(&forward), hashes(&backward));
+}
+
+#[test]
+fn group_by_date_keeps_time_order_before_hash() {
+    let mut early = make_sized_commit("z", 10, 0, 0);
+    let mut late = make_sized_commit("a", 10, 0, 0);
+    early.date = late.date - Duration::seconds(30);
+    // Keep both on the same day
+    if early.date_naive() != late.date_naive() {
+        late.date = early.date + Duration::seconds(1);
+    }
+
+    let grouped = group_by_date(vec![late, early]);
+    let hashes: Vec<_> = grouped.values().flatten().map(|c| c.hash.as_str()).collect();
+    assert_eq!(hashes, vec!["z", "a"]);
+}
+
+#[test]
+fn group_by_count_chunks_oldest_first() {
+    // Given newest first, as git walks them
+    let commits: Vec<CommitData> = (0..10).map(|i| make_sized_commit(&format!("c{}", i), 10, 0, i)).collect();
+
+    let chunks = group_by_count(commits.clone(), 4);
+    let sizes: Vec<usize> = chunks.iter().map(|(_, c)| c.len()).collect();
+    assert_eq!(sizes, vec![4, 4, 2]);
+    assert_eq!(chunks[0].1[0].hash, "c9");
+    assert_eq!(chunks[2].1[1].hash, "c0");
+    // Each chunk is dated by its first commit
+    for (date, chunk) in &chunks {
+        assert_eq!(*date, chunk[0].date_naive());
+    }
+
+    assert_eq!(group_by_count(commits, 0).len(), 10);
+    assert!(group_by_count(Vec::new(), 4).is_empty());
+}
+
+fn make_sized_commit(hash: &str, insertions: u32, deletions: u32, days_ago: i64) -> CommitData {
+    CommitData {
+        hash: hash.to_string(),
+        date: Utc::now() - Duration::days(days_ago),
+        message: "change".to_string(),
+        insertions,
+        deletions,
+        files_changed: 1,
+        author: "Test".to_string(),
+        is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
+    }
+}
+
+#[test]
+fn largest_commit_picks_most_lines_changed() {
+    let commits = vec![
+        make_sized_commit("small", 10, 0, 3),
+        make_sized_commit("big", 200, 150, 2),
+        make_sized_commit("medium", 100, 20, 1),
+    ];
+    assert_eq!(largest_commit(&commits).unwrap().hash, "big");
+}
+
+#[test]
+fn largest_commit_ties_go_to_earliest() {
+    let commits = vec![
+        make_sized_commit("later", 100, 0, 1),
+        make_sized_commit("earlier", 50, 50, 5),
+        make_sized_commit("middle", 0, 100, 3),
+    ];
+    assert_eq!(largest_commit(&commits).unwrap().hash, "earlier");
+}
+
+#[test]
+fn largest_commit_empty_is_none() {
+    assert!(largest_commit(&[]).is_none());
+}
+
+fn commit_touching(hash: &str, paths: &[&str]) -> CommitData {
+    let mut commit = make_sized_commit(hash, 10, 0, 0);
+    commit.file_categories.paths = paths.iter().map(|p| p.to_string()).collect();
+    commit
+}
+
+#[test]
+fn churn_counts_commits_on_each_commits_hottest_file() {
+    let mut commits = vec![
+        commit_touching("a", &["src/lib.rs", "src/lib.rs", "README.md"]),
+        commit_touching("b", &["src/lib.rs"]),
+        commit_touching("c", &["src/lib.rs", "docs/guide.md"]),
+        commit_touching("d", &["docs/guide.md"]),
+        commit_touching("e", &[]),
+    ];
+    annotate_churn(&mut commits);
+
+    let churn: Vec<u32> = commits.iter().map(|c| c.churn).collect();
+    assert_eq!(churn, vec![3, 3, 3, 2, 0]);
+}
+
+#[test]
+fn churn_bonus_grows_with_churn_up_to_a_cap() {
+    assert_eq!(churn_bonus(0), 0);
+    assert_eq!(churn_bonus(HIGH_CHURN - 1), 0);
+    assert_eq!(churn_bonus(HIGH_CHURN), 1);
+    assert_eq!(churn_bonus(HIGH_CHURN * 2), 2);
+    assert_eq!(churn_bonus(HIGH_CHURN * 100), MAX_CHURN_BONUS);
+}
+
+#[test]
+fn top_level_dir_of_paths() {
+    assert_eq!(top_level_dir("src/world/room.rs"), "src");
+    assert_eq!(top_level_dir("./docs/guide.md"), "docs");
+    assert_eq!(top_level_dir("README.md"), ROOT_DIRECTORY);
+}
+
+#[test]
+fn group_by_directory_puts_each_commit_in_its_main_directory() {
+    let commits = vec![
+        commit_touching("a", &["src/main.rs", "src/lib.rs", "tests/cli.rs"]),
+        commit_touching("b", &["tests/cli.rs"]),
+        commit_touching("c", &["docs/guide.md", "src/lib.rs"]),
+        commit_touching("d", &["Cargo.toml"]),
+        commit_touching("e", &[]),
+    ];
+
+    let grouped = group_by_directory(commits);
+    let hashes = |dir: &str| grouped[dir].iter().map(|c| c.hash.as_str()).collect::<Vec<_>>();
+
+    assert_eq!(grouped.keys().collect::<Vec<_>>(), vec![ROOT_DIRECTORY, "docs", "src", "tests"]);
+    assert_eq!(hashes("src"), vec!["a"]);
+    assert_eq!(hashes("tests"), vec!["b"]);
+    // Ties go to the directory that sorts first
+    assert_eq!(hashes("docs"), vec!["c"]);
+    assert_eq!(hashes(ROOT_DIRECTORY), vec!["d", "e"]);
+}
+
+#[test]
+fn median_commit_size_handles_odd_even_and_empty() {
+    let odd = vec![
+        make_sized_commit("a", 30, 0, 3),
+        make_sized_commit("b", 10, 0, 2),
+        make_sized_commit("c", 20, 0, 1),
+    ];
+    assert_eq!(median_commit_size(&odd), Some(20));
+
+    let even = vec![make_sized_commit("a", 10, 0, 2), make_sized_commit("b", 15, 0, 1)];
+    assert_eq!(median_commit_size(&even), Some(12));
+
+    assert_eq!(median_commit_size(&[]), None);
+}
+
+#[test]
+fn squashed_commit_stands_out_against_small_ones() {
+    let mut commits: Vec<_> = (0..6).map(|i| make_sized_commit(&format!("small{i}"), 20, 5, i)).collect();
+    commits.push(make_sized_commit("squash", 1800, 400, 0));
+    let median = median_commit_size(&commits).unwrap();
+    assert_eq!(median, 25);
+
+    let outliers: Vec<_> = commits.iter().filter(|c| is_squash_outlier(c, median)).collect();
+    assert_eq!(outliers.len(), 1);
+    assert_eq!(outliers[0].hash, "squash");
+    assert_eq!(squash_weight(outliers[0], median), MAX_SQUASH_SPLIT);
+    assert_eq!(squash_weight(&commits[0], median), 1);
+
+    // Just past the threshold counts as a few commits, not the cap
+    let modest = make_sized_commit("modest", 130, 0, 0);
+    assert!(is_squash_outlier(&modest, median));
+    assert_eq!(squash_weight(&modest, median), 5.min(MAX_SQUASH_SPLIT));
+}
+
+fn make_message_commit(message: &str) -> CommitData {
+    let mut commit = make_sized_commit("msg", 10, 0, 0);
+    commit.message = message.to_string();
+    commit
+}
+
+#[test]
+fn frustration_zero_for_calm_messages() {
+    assert_eq!(commit_frustration(&make_message_commit("Add user settings page")), 0);
+    assert_eq!(commit_frustration(&make_message_commit("Refactor the hashing module")), 0);
+}
+
+#[test]
+fn frustration_counts_frustrated_words() {
+    assert_eq!(commit_frustration(&make_message_commit("ugh, hack around the cache")), 2);
+    assert_eq!(commit_frustration(&make_message_commit("FIXME: damned parser")), 2);
+    assert_eq!(commit_frustration(&make_message_commit("fix it again!!")), 2);
+}
+
+#[test]
+fn frustration_is_capped() {
+    let commit = make_message_commit("ugh argh wtf hack fixme stupid again!!!");
+    assert_eq!(commit_frustration(&commit), MAX_FRUSTRATION);
+}
+
 #[test]
 fn commit_data_lines_changed() {
     let commit = CommitData {
@@ -163,7 +509,7 @@ fn commit_data_lines_changed() {
         deletions: 50,
         files_changed: 5,
         author: "Test".to_string(),
-        is_merge: false, file_categories: Default::default(),
+        is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
     };
     assert_eq!(commit.lines_changed(), 150);
 }
@@ -241,3 +587,155 @@ fn commit_author_preserved() {
         assert_eq!(commit.author, "Test");
     }
 }
+
+#[test]
+fn pseudonymize_is_deterministic() {
+    assert_eq!(pseudonymize("Alice Example"), pseudonymize("Alice Example"));
+    assert_eq!(pseudonymize("Alice Example"), "Brave Wombat 62");
+}
+
+#[test]
+fn pseudonymize_hides_the_name() {
+    let alias = pseudonymize("Alice Example");
+    assert!(!alias.contains("Alice"));
+    assert_ne!(alias, pseudonymize("Bob Example"));
+}
+
+// === Diff snippets ===
+
+#[test]
+fn parsing_leaves_out_diff_snippets() {
+    let repo = create_test_repo();
+    let commits = parse_repository(repo.path(), 30).unwrap();
+    assert!(commits.iter().all(|c| c.diff_snippet.is_none()));
+}
+
+#[test]
+fn commit_with_changes_has_diff_snippet() {
+    let repo = create_test_repo();
+    let mut commits = parse_repository(repo.path(), 30).unwrap();
+    attach_diff_snippets(repo.path(), &mut commits).unwrap();
+    let add_main = commits.iter().find(|c| c.message.starts_with("Add main")).unwrap();
+
+    let snippet = add_main.diff_snippet.as_deref().unwrap();
+    assert!(!snippet.is_empty());
+    assert!(snippet.contains("main.rs"));
+    assert!(snippet.contains("+fn main() {}"));
+}
+
+#[test]
+fn diff_snippet_is_capped() {
+    let repo = create_test_repo();
+    let big: String = (0..500).map(|i| format!("let value_{} = {};\n", i, i)).collect();
+    std::fs::write(repo.path().join("big.rs"), big).unwrap();
+    Command::new("git").args(["add", "."]).current_dir(repo.path()).output().unwrap();
+    Command::new("git")
+        .args(["commit", "-m", "Add big file"])
+        .current_dir(repo.path())
+        .output()
+        .unwrap();
+
+    let mut commits = parse_repository(repo.path(), 30).unwrap();
+    attach_diff_snippets(repo.path(), &mut commits).unwrap();
+    let snippet = commits.last().unwrap().diff_snippet.clone().unwrap();
+
+    assert!(snippet.lines().count() <= MAX_SNIPPET_LINES);
+    assert!(snippet.len() <= MAX_SNIPPET_BYTES);
+    assert!(snippet.contains("+let value_0 = 0;"));
+}
+
+// === Non-UTF8 messages ===
+
+#[test]
+fn non_utf8_message_is_kept_lossily() {
+    let repo = create_test_repo();
+    let path = repo.path();
+    std::fs::write(path.join("cafe.rs"), "fn cafe() {}").unwrap();
+    // "Fix café bug" in Latin-1: 0xE9 on its own is invalid UTF-8
+    std::fs::write(path.join("msg.txt"), b"Fix caf\xe9 bug\n").unwrap();
+    Command::new("git").args(["add", "cafe.rs"]).current_dir(path).output().unwrap();
+    Command::new("git")
+        .args(["-c", "i18n.commitEncoding=ISO-8859-1", "commit", "-F", "msg.txt"])
+        .current_dir(path)
+        .output()
+        .unwrap();
+
+    let mut commits = parse_repository(path, 30).unwrap();
+    attach_diff_snippets(path, &mut commits).unwrap();
+    let commit = commits.last().unwrap();
+
+    assert_eq!(commit.message.trim_end(), "Fix caf\u{FFFD} bug");
+    assert!(commit.message.to_lowercase().contains("bug"));
+    assert!(commit.diff_snippet.as_deref().unwrap().contains("cafe.rs"));
+}
+
+// === Most edited file ===
+
+fn touching(paths: &[&str]) -> CommitData {
+    let mut commit = make_message_commit("Work");
+    commit.file_categories.paths = paths.iter().map(|p| p.to_string()).collect();
+    commit
+}
+
+#[test]
+fn most_edited_file_counts_commits_touching_each_path() {
+    let commits = vec![
+        touching(&["src/parser.rs", "README.md"]),
+        touching(&["src/parser.rs"]),
+        touching(&["src/lexer.rs", "README.md"]),
+        touching(&["src/parser.rs", "src/lexer.rs"]),
+    ];
+
+    assert_eq!(most_edited_file(&commits).as_deref(), Some("src/parser.rs"));
+}
+
+#[test]
+fn most_edited_file_counts_a_path_once_per_commit() {
+    let commits = vec![touching(&["a.rs", "a.rs", "a.rs"]), touching(&["b.rs"]), touching(&["b.rs"])];
+
+    assert_eq!(most_edited_file(&commits).as_deref(), Some("b.rs"));
+}
+
+#[test]
+fn most_edited_file_ties_go_to_first_path() {
+    let commits = vec![touching(&["src/z.rs"]), touching(&["src/a.rs"])];
+
+    assert_eq!(most_edited_file(&commits).as_deref(), Some("src/a.rs"));
+}
+
+#[test]
+fn most_edited_file_none_without_paths() {
+    assert_eq!(most_edited_file(&[make_message_commit("Work")]), None);
+    assert_eq!(most_edited_file(&[]), None);
+}
+
+// === Significant words ===
+
+#[test]
+fn significant_word_takes_branch_from_merge_messages() {
+    assert_eq!(significant_word("Merge branch 'feature/x' into main").as_deref(), Some("feature/x"));
+    assert_eq!(
+        significant_word("Merge pull request #42 from alice/login-form").as_deref(),
+        Some("alice/login-form")
+    );
+    assert_eq!(
+        significant_word("Merge remote-tracking branch 'origin/release-2.0'").as_deref(),
+        Some("origin/release-2.0")
+    );
+}
+
+#[test]
+fn significant_word_skips_stopwords_and_short_words() {
+    assert_eq!(significant_word("Fix the cache for 2024").as_deref(), Some("cache"));
+    // Longest wins, earliest on ties
+    assert_eq!(significant_word("Add parser and helper").as_deref(), Some("parser"));
+    // Only the subject line counts
+    assert_eq!(significant_word("Tidy\n\nExtraordinarily long body").as_deref(), Some("Tidy"));
+}
+
+#[test]
+fn significant_word_none_without_candidates() {
+    assert_eq!(significant_word(""), None);
+    assert_eq!(significant_word("Merge branch 'main' into dev"), None);
+    assert_eq!(significant_word("wip #12 ok"), None);
+}
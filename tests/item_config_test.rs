@@ -0,0 +1,162 @@
+//! Tests for items.toml custom items.
+//!
+//! The installed registry is process-wide, so only
+//! `installed_custom_items_spawn` installs one; keep it that way.
+
+use chrono::{NaiveDate, Utc};
+use rand::SeedableRng;
+
+use penumbra::config::{install_item_registry, ItemRegistry, DEFAULT_CUSTOM_ITEM_CHANCE};
+use penumbra::git::CommitData;
+use penumbra::item::{generate_item, ItemEffect, ItemType, Rarity};
+use penumbra::rng::GameRng;
+use penumbra::world::{Room, RoomType};
+use tempfile::TempDir;
+
+const DUCK_FILE: &str = r#"
+chance = 1.0
+
+[[item]]
+name = "Rubber Duck"
+type = "Consumable"
+effect = { Heal = 15 }
+rarity = "Uncommon"
+weight = 3
+"#;
+
+fn make_commit(hash: &str, tag: Option<&str>) -> CommitData {
+    CommitData {
+        hash: hash.to_string(),
+        date: Utc::now(),
+        message: "Work".to_string(),
+        insertions: 10,
+        deletions: 0,
+        files_changed: 1,
+        author: "Test".to_string(),
+        is_merge: false,
+        file_categories: Default::default(),
+        tag: tag.map(str::to_string),
+        diff_snippet: None,
+        churn: 0,
+    }
+}
+
+#[test]
+fn installed_custom_items_spawn() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("items.toml");
+    std::fs::write(&path, DUCK_FILE).unwrap();
+    assert!(install_item_registry(ItemRegistry::load_from(&path)));
+
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 9, 9, RoomType::Treasure, date);
+    let commits = vec![make_commit("a", None), make_commit("b", Some("v1.0")), make_commit("c", None)];
+    let mut rng = GameRng::seed_from_u64(42);
+    room.spawn_items(&commits, &mut rng);
+
+    assert!(!room.items.is_empty());
+    for item in &room.items {
+        if item.source_commit.as_deref() == Some("b") {
+            // Releases keep their artifact
+            assert_eq!(item.name, "v1.0 Artifact");
+        } else {
+            assert_eq!(item.name, "Rubber Duck");
+            assert_eq!(item.rarity, Rarity::Uncommon);
+            assert!(matches!(item.effect, ItemEffect::Heal(15)));
+        }
+    }
+
+    let loot = generate_item(&make_commit("d", None), &mut rng);
+    assert_eq!(loot.name, "Rubber Duck");
+    assert_eq!(loot.source_commit.as_deref(), Some("d"));
+}
+
+#[test]
+fn parses_definitions_with_defaults() {
+    let registry = ItemRegistry::parse(
+        "[[item]]\nname = \"Stack Trace\"\ntype = \"Scroll\"\neffect = \"RevealMap\"\n",
+    );
+
+    assert_eq!(registry.chance, DEFAULT_CUSTOM_ITEM_CHANCE);
+    let def = &registry.items()[0];
+    assert_eq!(def.name, "Stack Trace");
+    assert_eq!(def.item_type, ItemType::Scroll);
+    assert!(matches!(def.effect, ItemEffect::RevealMap));
+    assert_eq!(def.rarity, Rarity::Common);
+    assert_eq!(def.weight, 1);
+}
+
+#[test]
+fn bad_definitions_are_skipped() {
+    let registry = ItemRegistry::parse(
+        r#"
+chance = 7.0
+
+[[item]]
+name = "Fireball"
+type = "Scroll"
+effect = { Explode = 99 }
+
+[[item]]
+name = ""
+type = "Scroll"
+effect = "RevealMap"
+
+[[item]]
+name = "Never"
+type = "Scroll"
+effect = "RevealMap"
+weight = 0
+
+[[item]]
+name = "Coffee"
+type = "Consumable"
+effect = { RestoreEnergy = 30 }
+"#,
+    );
+
+    assert_eq!(registry.chance, 1.0);
+    let names: Vec<_> = registry.items().iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, vec!["Coffee"]);
+}
+
+#[test]
+fn non_positive_restores_are_skipped() {
+    let registry = ItemRegistry::parse(
+        r#"
+[[item]]
+name = "Cursed Mug"
+type = "Consumable"
+effect = { Heal = -10 }
+
+[[item]]
+name = "Decaf"
+type = "Consumable"
+effect = { RestoreEnergy = 0 }
+
+[[item]]
+name = "Espresso"
+type = "Consumable"
+effect = { RestoreEnergy = 1 }
+"#,
+    );
+
+    let names: Vec<_> = registry.items().iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, vec!["Espresso"]);
+}
+
+#[test]
+fn missing_or_invalid_file_has_no_custom_items() {
+    let dir = TempDir::new().unwrap();
+    assert!(ItemRegistry::load_from(&dir.path().join("items.toml")).is_empty());
+    assert!(ItemRegistry::parse("[[item]\nname = ").is_empty());
+}
+
+#[test]
+fn empty_registry_leaves_rng_untouched() {
+    let mut rng = GameRng::seed_from_u64(7);
+    let mut untouched = GameRng::seed_from_u64(7);
+
+    assert!(ItemRegistry::default().roll(&mut rng).is_none());
+    assert_eq!(rand::RngCore::next_u64(&mut rng), rand::RngCore::next_u64(&mut untouched));
+}
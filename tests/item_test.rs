@@ -2,17 +2,27 @@
 
 use chrono::Utc;
 use rand::SeedableRng;
-use rand_chacha::ChaCha8Rng;
 
-use penumbra::entity::Player;
+use penumbra::entity::{Enemy, EnemyType, Player};
+use penumbra::game::GameState;
 use penumbra::git::CommitData;
 use penumbra::item::{
-    apply_effect, calculate_rarity, generate_item, Item, ItemEffect, ItemType, Rarity,
+    apply_effect, apply_effect_in_context, apply_effect_with, calculate_rarity, generate_item, HealMode, Item,
+    ItemEffect, ItemType, Rarity, Resource, Stat, ThrownEffect,
 };
 use penumbra::entity::PlayerClass;
+use penumbra::rng::GameRng;
+use penumbra::world::{Room, RoomType, World};
 
 // === Item Tests (Task 8) ===
 
+#[test]
+fn item_type_symbols_are_distinct() {
+    assert_eq!(ItemType::Consumable.symbol(), '!');
+    assert_eq!(ItemType::Equipment.symbol(), ')');
+    assert_eq!(ItemType::Scroll.symbol(), '?');
+}
+
 #[test]
 fn item_new_creates_item() {
     let item = Item::new("Test", ItemType::Consumable, ItemEffect::Heal(10), Rarity::Common);
@@ -94,14 +104,41 @@ fn make_commit(message: &str, lines: u32) -> CommitData {
         deletions: 0,
         files_changed: 1,
         author: "Test".to_string(),
-        is_merge: false, file_categories: Default::default(),
+        is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
     }
 }
 
+fn make_state() -> GameState {
+    GameState::new(vec![make_commit("Test", 50)], 42, std::path::PathBuf::from("/tmp/test-repo"))
+}
+
+#[test]
+fn apply_in_context_delegates_player_effects() {
+    let mut state = make_state();
+    state.player.hp = 10;
+    let msg = apply_effect_in_context(&ItemEffect::Heal(15), &mut state);
+    assert_eq!(state.player.hp, 25);
+    assert!(msg.contains("Healed"));
+}
+
+#[test]
+fn apply_in_context_blink_moves_player() {
+    let mut state = make_state();
+    let before = (state.player.x, state.player.y);
+    let msg = apply_effect_in_context(&ItemEffect::Blink, &mut state);
+
+    let after = (state.player.x, state.player.y);
+    let room = state.world.current().unwrap();
+    assert_ne!(before, after);
+    assert!(room.is_walkable(after.0, after.1));
+    assert!(room.get_enemy_at(after.0, after.1).is_none());
+    assert!(msg.contains("blink"));
+}
+
 #[test]
 fn generate_item_from_doc_commit() {
     let commit = make_commit("Update README documentation", 50);
-    let mut rng = ChaCha8Rng::seed_from_u64(12345);
+    let mut rng = GameRng::seed_from_u64(12345);
     let item = generate_item(&commit, &mut rng);
     assert!(item.name.contains("Scroll") || item.name.contains("Map"));
 }
@@ -109,7 +146,7 @@ fn generate_item_from_doc_commit() {
 #[test]
 fn generate_item_from_test_commit() {
     let commit = make_commit("Add unit test for auth", 100);
-    let mut rng = ChaCha8Rng::seed_from_u64(12345);
+    let mut rng = GameRng::seed_from_u64(12345);
     let item = generate_item(&commit, &mut rng);
     assert!(item.name.contains("Heal") || item.name.contains("Commit"));
 }
@@ -117,7 +154,7 @@ fn generate_item_from_test_commit() {
 #[test]
 fn generate_item_from_config_commit() {
     let commit = make_commit("Update config settings", 50);
-    let mut rng = ChaCha8Rng::seed_from_u64(12345);
+    let mut rng = GameRng::seed_from_u64(12345);
     let item = generate_item(&commit, &mut rng);
     assert!(item.name.contains("Config") || item.name.contains("Scroll"));
 }
@@ -125,7 +162,206 @@ fn generate_item_from_config_commit() {
 #[test]
 fn generate_item_stores_commit_hash() {
     let commit = make_commit("Any commit", 50);
-    let mut rng = ChaCha8Rng::seed_from_u64(12345);
+    let mut rng = GameRng::seed_from_u64(12345);
     let item = generate_item(&commit, &mut rng);
     assert_eq!(item.source_commit, Some("test123".to_string()));
 }
+
+// === Transmute ===
+
+fn transmute(from: Resource, to: Resource, amount: i32) -> ItemEffect {
+    ItemEffect::Transmute { from, to, amount }
+}
+
+#[test]
+fn transmute_xp_to_hp() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.xp = 50;
+    player.hp = 20;
+    let msg = apply_effect(&transmute(Resource::Xp, Resource::Hp, 25), &mut player);
+    assert_eq!(player.xp, 25);
+    assert_eq!(player.hp, 45);
+    assert!(msg.contains("Transmuted 25 XP into HP"));
+}
+
+#[test]
+fn transmute_energy_to_xp() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.energy = 60;
+    apply_effect(&transmute(Resource::Energy, Resource::Xp, 40), &mut player);
+    assert_eq!(player.energy, 20);
+    assert_eq!(player.xp, 40);
+}
+
+#[test]
+fn transmute_hp_to_energy() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.energy = 50;
+    apply_effect(&transmute(Resource::Hp, Resource::Energy, 10), &mut player);
+    assert_eq!(player.hp, player.max_hp - 10);
+    assert_eq!(player.energy, 60);
+}
+
+#[test]
+fn transmute_never_takes_xp_below_zero() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.xp = 10;
+    player.hp = 10;
+    apply_effect(&transmute(Resource::Xp, Resource::Hp, 25), &mut player);
+    assert_eq!(player.xp, 0);
+    assert_eq!(player.hp, 20);
+}
+
+#[test]
+fn transmute_never_exceeds_max_hp() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.xp = 50;
+    player.hp = player.max_hp - 5;
+    apply_effect(&transmute(Resource::Xp, Resource::Hp, 25), &mut player);
+    assert_eq!(player.hp, player.max_hp);
+    // Only what could be used is spent
+    assert_eq!(player.xp, 45);
+}
+
+#[test]
+fn transmute_keeps_player_alive() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    player.hp = 5;
+    player.energy = 0;
+    apply_effect(&transmute(Resource::Hp, Resource::Energy, 50), &mut player);
+    assert_eq!(player.hp, 1);
+    assert_eq!(player.energy, 4);
+}
+
+#[test]
+fn transmute_with_nothing_to_trade() {
+    let mut player = Player::new(PlayerClass::Wanderer);
+    let msg = apply_effect(&transmute(Resource::Xp, Resource::Hp, 25), &mut player);
+    assert_eq!(player.xp, 0);
+    assert!(msg.contains("Nothing to transmute"));
+}
+
+// === Heal modes ===
+
+#[test]
+fn percent_heals_scale_with_max_hp() {
+    let mut small = Player::new(PlayerClass::Wanderer);
+    small.max_hp = 100;
+    small.hp = 1;
+    let mut large = Player::new(PlayerClass::Wanderer);
+    large.max_hp = 300;
+    large.hp = 1;
+
+    let msg = apply_effect_with(&ItemEffect::Heal(20), &mut small, HealMode::Percent);
+    apply_effect_with(&ItemEffect::Heal(20), &mut large, HealMode::Percent);
+    assert_eq!(small.hp, 21);
+    assert_eq!(large.hp, 61);
+    assert_eq!(msg, "Healed for 20 HP");
+}
+
+#[test]
+fn absolute_heals_ignore_max_hp() {
+    let mut small = Player::new(PlayerClass::Wanderer);
+    small.max_hp = 100;
+    small.hp = 1;
+    let mut large = Player::new(PlayerClass::Wanderer);
+    large.max_hp = 300;
+    large.hp = 1;
+
+    apply_effect_with(&ItemEffect::Heal(20), &mut small, HealMode::Absolute);
+    apply_effect_with(&ItemEffect::Heal(20), &mut large, HealMode::Absolute);
+    assert_eq!(small.hp, 21);
+    assert_eq!(large.hp, 21);
+}
+
+#[test]
+fn percent_heal_always_restores_something() {
+    assert_eq!(HealMode::Percent.heal_amount(10, 5), 1);
+    assert_eq!(HealMode::Absolute.heal_amount(10, 5), 10);
+}
+
+#[test]
+fn game_state_heal_mode_applies_to_used_items() {
+    let mut state = make_state();
+    state.heal_mode = HealMode::Percent;
+    state.player.max_hp = 200;
+    state.player.hp = 1;
+    apply_effect_in_context(&ItemEffect::Heal(10), &mut state);
+    assert_eq!(state.player.hp, 21);
+}
+
+// === Refactor Scroll ===
+
+fn refactor_state(bugs: &[(i32, i32)]) -> GameState {
+    let mut state = make_state();
+    let mut room = Room::new(0, 7, 7, RoomType::Normal, Utc::now().date_naive());
+    for &(x, y) in bugs {
+        room.enemies.push(Enemy::new(EnemyType::Bug, x, y, "bug"));
+    }
+    state.world = World::new(vec![room]);
+    state.player.x = 1;
+    state.player.y = 3;
+    state
+}
+
+#[test]
+fn refactor_clears_room_and_spawns_tech_debt() {
+    let mut state = refactor_state(&[(3, 3), (4, 2), (5, 5)]);
+    let msg = apply_effect_in_context(&ItemEffect::Refactor, &mut state);
+
+    let room = state.world.current().unwrap();
+    assert_eq!(room.enemies.len(), 1);
+    let debt = &room.enemies[0];
+    assert_eq!(debt.enemy_type, EnemyType::TechDebt);
+    assert_eq!(debt.max_hp, EnemyType::TechDebt.base_hp() * 2);
+    assert!(room.is_walkable(debt.x, debt.y));
+    assert_ne!((debt.x, debt.y), (state.player.x, state.player.y));
+    // Lands as far from the player as the room allows
+    assert_eq!((debt.x - 1).abs() + (debt.y - 3).abs(), 6);
+    assert!(!room.is_cleared());
+    assert!(msg.contains("3 enemies"));
+}
+
+#[test]
+fn refactor_in_empty_room_spawns_nothing() {
+    let mut state = refactor_state(&[]);
+    let msg = apply_effect_in_context(&ItemEffect::Refactor, &mut state);
+
+    assert!(state.world.current().unwrap().enemies.is_empty());
+    assert!(msg.contains("Nothing"));
+}
+
+#[test]
+fn large_refactor_commits_drop_refactor_scrolls() {
+    let item = Room::item_from_commit(&make_commit("Refactor the parser", 400));
+    assert_eq!(item.name, "Refactor Scroll");
+    assert!(matches!(item.effect, ItemEffect::Refactor));
+
+    let item = Room::item_from_commit(&make_commit("Refactor the parser", 20));
+    assert_ne!(item.name, "Refactor Scroll");
+}
+
+// === Thrown items ===
+
+#[test]
+fn only_damage_items_hurt_when_thrown() {
+    assert_eq!(ItemEffect::Damage(7).thrown_effect(), Some(ThrownEffect::Damage(7)));
+
+    let wasted = [
+        ItemEffect::Heal(10),
+        ItemEffect::RestoreEnergy(5),
+        ItemEffect::RevealMap,
+        ItemEffect::Blink,
+        ItemEffect::Transmute { from: Resource::Xp, to: Resource::Hp, amount: 5 },
+        ItemEffect::Refactor,
+    ];
+    for effect in &wasted {
+        assert_eq!(effect.thrown_effect(), Some(ThrownEffect::Wasted), "{:?}", effect);
+    }
+}
+
+#[test]
+fn gear_and_lore_cannot_be_thrown() {
+    assert_eq!(ItemEffect::Buff(Stat::Damage, 2, 0).thrown_effect(), None);
+    assert_eq!(ItemEffect::Lore("diff --git".to_string()).thrown_effect(), None);
+}
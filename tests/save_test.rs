@@ -5,12 +5,16 @@
 
 use std::path::PathBuf;
 
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
+use penumbra::combat::PlayerAction;
+use penumbra::entity::{Enemy, EnemyType};
 use penumbra::game::{
-    save_game, load_run_history, 
-    save_exists, delete_save, GameState, RunRecord,
+    save_game, load_run_history, suggested_difficulty, Difficulty,
+    save_exists, delete_save, GameState, RunRecord, SpeedrunProgress, VictoryCondition,
+    check_slot, delete_slot, list_saves, load_game_from, save_game_to, slot_exists, slot_path, DEFAULT_SLOT,
 };
 use penumbra::git::CommitData;
+use penumbra::world::{Direction, Room, RoomType, World};
 
 fn make_commit(msg: &str, lines: u32) -> CommitData {
     CommitData {
@@ -21,7 +25,7 @@ fn make_commit(msg: &str, lines: u32) -> CommitData {
         deletions: 0,
         files_changed: 1,
         author: "Test".to_string(),
-        is_merge: false, file_categories: Default::default(),
+        is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
     }
 }
 
@@ -66,6 +70,10 @@ fn run_record_has_expected_fields() {
         enemies_killed: 10,
         final_level: 3,
         death_cause: None,
+        goal: VictoryCondition::ClearRooms(5),
+        completed: true,
+        total_lines: 0,
+        endless_score: None,
     };
     
     assert_eq!(record.turns, 100);
@@ -85,12 +93,59 @@ fn run_record_with_death_cause() {
         enemies_killed: 0,
         final_level: 1,
         death_cause: Some("MergeConflict".to_string()),
+        goal: VictoryCondition::ReachEnd,
+        completed: true,
+        total_lines: 0,
+        endless_score: None,
     };
     
     assert!(!record.victory);
     assert_eq!(record.death_cause, Some("MergeConflict".to_string()));
 }
 
+#[test]
+fn run_record_from_state_copies_stats() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.turn = 42;
+    state.enemies_killed = 6;
+    state.game_over = true;
+    state.goal = VictoryCondition::Survive(40);
+
+    let record = RunRecord::from_state(&state);
+
+    assert_eq!(record.turns, 42);
+    assert_eq!(record.enemies_killed, 6);
+    assert_eq!(record.rooms_cleared, state.rooms_cleared());
+    assert_eq!(record.goal, VictoryCondition::Survive(40));
+    assert!(!record.victory);
+    assert!(record.completed);
+    assert_eq!(record.death_cause, None);
+}
+
+#[test]
+fn run_record_names_the_killer() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
+    room.enemies.push(Enemy::new(EnemyType::MergeConflict, 2, 2, "test"));
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    (state.player.x, state.player.y) = (1, 2);
+    state.player.hp = 1;
+
+    state.process_enemies();
+    assert!(state.game_over);
+
+    let record = RunRecord::from_state(&state);
+    assert_eq!(record.death_cause, Some("MergeConflict".to_string()));
+}
+
+#[test]
+fn run_record_from_quit_state_is_incomplete() {
+    let state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    let record = RunRecord::from_state(&state);
+    assert!(!record.completed);
+}
+
 #[test]
 fn game_state_serializable() {
     let commits = vec![make_commit("Test", 50)];
@@ -123,3 +178,150 @@ fn game_state_roundtrip() {
     assert_eq!(loaded.turn, 99);
     assert_eq!(loaded.seed, 42);
 }
+
+#[test]
+fn speedrun_clock_survives_a_save() {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.speedrun = SpeedrunProgress { elapsed_ms: 61_250, clears_ms: vec![(0, 20_000)] };
+
+    let json = serde_json::to_string(&state).unwrap();
+    let loaded: GameState = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded.speedrun, state.speedrun);
+}
+
+fn fighting_state() -> GameState {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
+    room.enemies.push(Enemy::new(EnemyType::TechDebt, 3, 3, "test"));
+
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    state.world = World::new(vec![room]);
+    state.player.x = 2;
+    state.player.y = 3;
+    state.damage_variance = 20;
+    state
+}
+
+fn fight(state: &mut GameState) -> Vec<(i32, i32, u32)> {
+    (0..6)
+        .map(|_| {
+            state.process_action(PlayerAction::Attack(Direction::East));
+            let enemy_hp = state.world.current().unwrap().enemies.first().map_or(0, |e| e.hp);
+            (state.player.hp, enemy_hp, state.stats.damage_dealt)
+        })
+        .collect()
+}
+
+#[test]
+fn loading_a_save_keeps_the_same_combat_rolls() {
+    let mut played = fighting_state();
+    played.process_action(PlayerAction::Wait);
+
+    let slot = "test-slot-rng";
+    save_game_to(&played, slot).unwrap();
+    let mut loaded = load_game_from(slot).unwrap();
+    delete_slot(slot).unwrap();
+
+    assert_eq!(fight(&mut loaded), fight(&mut played));
+}
+
+// === Save slots ===
+
+#[test]
+fn default_slot_keeps_original_save_file() {
+    assert_eq!(slot_path(DEFAULT_SLOT).file_name().unwrap(), "save.json");
+    assert_eq!(slot_path("work").file_name().unwrap(), "save_work.json");
+}
+
+#[test]
+fn slots_save_and_load_without_clobbering() {
+    let (a, b) = ("test-slot-alpha", "test-slot-beta");
+    let first = GameState::new(vec![make_commit("First", 50)], 1, PathBuf::from("/tmp/repo-a"));
+    let second = GameState::new(vec![make_commit("Second", 50)], 2, PathBuf::from("/tmp/repo-b"));
+
+    save_game_to(&first, a).unwrap();
+    save_game_to(&second, b).unwrap();
+
+    let loaded_a = load_game_from(a).unwrap();
+    let loaded_b = load_game_from(b).unwrap();
+    assert_eq!((loaded_a.seed, loaded_a.git_path.clone()), (1, PathBuf::from("/tmp/repo-a")));
+    assert_eq!((loaded_b.seed, loaded_b.git_path.clone()), (2, PathBuf::from("/tmp/repo-b")));
+
+    let saves = list_saves().unwrap();
+    assert!(saves.iter().any(|s| s == a));
+    assert!(saves.iter().any(|s| s == b));
+
+    delete_slot(a).unwrap();
+    assert!(!slot_exists(a));
+    assert!(slot_exists(b));
+    delete_slot(b).unwrap();
+}
+
+#[test]
+fn slot_names_cannot_escape_save_dir() {
+    let state = GameState::new(vec![make_commit("Test", 50)], 42, test_git_path());
+    assert!(save_game_to(&state, "../escape").is_err());
+    assert!(save_game_to(&state, "").is_err());
+    assert!(load_game_from("a/b").is_err());
+    assert!(!slot_exists("../escape"));
+}
+
+#[test]
+fn slot_names_can_be_checked_before_playing() {
+    assert!(check_slot("work-repo_2").is_ok());
+    assert!(check_slot(DEFAULT_SLOT).is_ok());
+    assert!(check_slot("my repo").is_err());
+    assert!(check_slot("").is_err());
+}
+
+fn run(victory: bool) -> RunRecord {
+    RunRecord {
+        started_at: Utc::now(),
+        ended_at: Utc::now(),
+        victory,
+        turns: 50,
+        rooms_cleared: 2,
+        enemies_killed: 3,
+        final_level: 1,
+        death_cause: None,
+        goal: VictoryCondition::ReachEnd,
+        completed: true,
+        total_lines: 0,
+        endless_score: None,
+    }
+}
+
+#[test]
+fn suggested_difficulty_eases_after_losses() {
+    let history = vec![run(true), run(false), run(false), run(false), run(false)];
+    assert_eq!(suggested_difficulty(&history), Difficulty::Easy);
+}
+
+#[test]
+fn suggested_difficulty_ramps_up_after_wins() {
+    let history = vec![run(false), run(true), run(true), run(true), run(true)];
+    assert_eq!(suggested_difficulty(&history), Difficulty::Hard);
+}
+
+#[test]
+fn suggested_difficulty_uses_recent_finished_runs() {
+    // Old losses and quit runs don't count: the last five finished runs
+    // are four wins and a loss
+    let mut history = vec![run(false); 10];
+    history.extend([run(true), run(true), run(false), run(true), run(true)]);
+    let mut quit = run(false);
+    quit.completed = false;
+    history.push(quit);
+    assert_eq!(suggested_difficulty(&history), Difficulty::Hard);
+
+    // Mixed results stay Normal
+    let mixed = vec![run(true), run(false), run(true), run(false), run(true)];
+    assert_eq!(suggested_difficulty(&mixed), Difficulty::Normal);
+}
+
+#[test]
+fn suggested_difficulty_needs_a_few_runs() {
+    assert_eq!(suggested_difficulty(&[]), Difficulty::Normal);
+    assert_eq!(suggested_difficulty(&[run(false), run(false)]), Difficulty::Normal);
+}
@@ -0,0 +1,65 @@
+//! Tests for theme.toml palettes.
+//!
+//! The installed palette is process-wide, so only
+//! `installed_theme_changes_the_player_color` installs one; keep it that way.
+
+use penumbra::entity::EnemyType;
+use penumbra::item::Rarity;
+use penumbra::ui::{enemy_color, install_palette, palette, parse_color, rarity_color, Palette, PLAYER_COLOR};
+use ratatui::style::Color;
+use tempfile::TempDir;
+
+#[test]
+fn installed_theme_changes_the_player_color() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("theme.toml");
+    std::fs::write(&path, "player = \"#ff8800\"\nbug = \"light_blue\"\n").unwrap();
+
+    assert!(install_palette(Palette::load_from(&path)));
+
+    assert_eq!(palette().player, Color::Rgb(255, 136, 0));
+    assert_eq!(enemy_color(EnemyType::Bug), Color::LightBlue);
+    // Everything else keeps the built-in colors
+    assert_eq!(rarity_color(Rarity::Legendary), Palette::default().legendary);
+
+    // The palette is fixed once installed
+    assert!(!install_palette(Palette::default()));
+    assert_eq!(palette().player, Color::Rgb(255, 136, 0));
+}
+
+#[test]
+fn missing_file_keeps_builtin_palette() {
+    let dir = TempDir::new().unwrap();
+    let palette = Palette::load_from(&dir.path().join("theme.toml"));
+
+    assert_eq!(palette, Palette::default());
+    assert_eq!(palette.player, PLAYER_COLOR);
+}
+
+#[test]
+fn invalid_toml_keeps_builtin_palette() {
+    assert_eq!(Palette::parse("player = \"#ff8800"), Palette::default());
+}
+
+#[test]
+fn invalid_colors_and_unknown_keys_are_ignored() {
+    let palette = Palette::parse(
+        "player = \"chartreuse\"\nwall = \"#12345\"\nfloor = 3\ngoblin = \"red\"\nexit = \"Dark-Grey\"\n",
+    );
+
+    assert_eq!(palette.player, Palette::default().player);
+    assert_eq!(palette.wall, Palette::default().wall);
+    assert_eq!(palette.floor, Palette::default().floor);
+    assert_eq!(palette.exit, Color::DarkGray);
+}
+
+#[test]
+fn parses_color_names_and_hex() {
+    assert_eq!(parse_color("red"), Some(Color::Red));
+    assert_eq!(parse_color("Light_Magenta"), Some(Color::LightMagenta));
+    assert_eq!(parse_color("grey"), Some(Color::Gray));
+    assert_eq!(parse_color("#0a0B0c"), Some(Color::Rgb(10, 11, 12)));
+    assert_eq!(parse_color("#xyzxyz"), None);
+    assert_eq!(parse_color("ff8800"), None);
+    assert_eq!(parse_color(""), None);
+}
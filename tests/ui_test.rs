@@ -0,0 +1,1352 @@
+//! Tests for UI state and map rendering.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{NaiveDate, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use penumbra::combat::{calculate_damage, calculate_hit_chance};
+use penumbra::entity::{Enemy, EnemyType, DEATH_SYMBOL};
+use penumbra::game::{GameEvent, GameState, Progression, SpeedrunProgress};
+use penumbra::git::{pseudonymize, CommitData};
+use penumbra::item::{Item, ItemEffect, ItemType, Rarity};
+use penumbra::ui::{
+    age_color, author_color, commit_graph, enemy_color, format_time, write_event_lines, line_to, remembered_color, render, split_times, App, BlameMode, Cursor,
+    SpeedrunTimer, BANNER_TURNS, CURSOR_COLOR, FLOOR_COLOR, FOG_COLOR, HP_LOW, TARGET_VALID_COLOR,
+};
+use penumbra::world::{Room, RoomType, Tile, World};
+use ratatui::backend::TestBackend;
+use ratatui::style::Color;
+use ratatui::Terminal;
+
+fn make_commit(msg: &str, lines: u32) -> CommitData {
+    CommitData {
+        hash: format!("hash_{}", lines),
+        date: Utc::now(),
+        message: msg.to_string(),
+        insertions: lines,
+        deletions: 0,
+        files_changed: 1,
+        author: "Test".to_string(),
+        is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
+    }
+}
+
+fn make_test_room() -> Room {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
+
+    for x in 0..7 {
+        room.set_tile(x, 0, Tile::Wall);
+        room.set_tile(x, 6, Tile::Wall);
+    }
+    for y in 0..7 {
+        room.set_tile(0, y, Tile::Wall);
+        room.set_tile(6, y, Tile::Wall);
+    }
+
+    room.enemies.push(Enemy::new(EnemyType::Bug, 4, 3, "test"));
+    room
+}
+
+fn make_app() -> App {
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, PathBuf::from("/tmp/test-repo"));
+    state.world = World::new(vec![make_test_room()]);
+    state.player.x = 1;
+    state.player.y = 3;
+    state.update_fov();
+    App::new(state)
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_input(KeyEvent::new(code, KeyModifiers::NONE));
+}
+
+fn row_text(terminal: &Terminal<TestBackend>, y: u16) -> String {
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.width)
+        .map(|x| buffer.content[buffer.index_of(x, y)].symbol().to_string())
+        .collect()
+}
+
+// === Input bursts ===
+
+#[test]
+fn burst_of_keys_all_register() {
+    let mut app = make_app();
+    app.state.world.current_mut().unwrap().enemies.clear();
+    let right = KeyEvent::new(KeyCode::Right, KeyModifiers::NONE);
+    let down = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+    app.handle_inputs([down, down]);
+    assert_eq!((app.state.player.x, app.state.player.y), (1, 5));
+
+    app.handle_inputs([right, right]);
+    assert_eq!((app.state.player.x, app.state.player.y), (3, 5));
+}
+
+#[test]
+fn burst_stops_when_the_game_ends() {
+    let mut app = make_app();
+    let right = KeyEvent::new(KeyCode::Right, KeyModifiers::NONE);
+    app.state.game_over = true;
+    app.handle_inputs([right, right]);
+    assert_eq!(app.state.player.x, 1);
+
+    let mut app = make_app();
+    app.handle_inputs([KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE), right]);
+    assert!(app.quit);
+    assert_eq!(app.state.player.x, 1);
+}
+
+// === Targeting ===
+
+#[test]
+fn targeting_starts_on_nearest_enemy() {
+    let mut app = make_app();
+    press(&mut app, KeyCode::Char('t'));
+
+    assert!(app.targeting);
+    assert_eq!(app.cursor.map(|c| c.position()), Some((4, 3)));
+}
+
+#[test]
+fn targeting_starts_on_player_without_enemies() {
+    let mut app = make_app();
+    app.state.world.current_mut().unwrap().enemies.clear();
+    app.start_targeting();
+
+    assert_eq!(app.cursor.map(|c| c.position()), Some((1, 3)));
+}
+
+#[test]
+fn cursor_moves_over_visible_tiles() {
+    let mut app = make_app();
+    app.start_targeting();
+    press(&mut app, KeyCode::Left);
+    press(&mut app, KeyCode::Char('k'));
+
+    assert_eq!(app.cursor.map(|c| c.position()), Some((3, 2)));
+}
+
+#[test]
+fn cursor_stays_on_visible_tiles() {
+    let mut app = make_app();
+    app.start_targeting();
+    for _ in 0..10 {
+        press(&mut app, KeyCode::Right);
+    }
+
+    let (x, y) = app.cursor.unwrap().position();
+    assert!(app.state.visible_tiles.contains(&(x, y)));
+}
+
+#[test]
+fn confirm_sets_target_and_leaves_mode() {
+    let mut app = make_app();
+    app.start_targeting();
+    press(&mut app, KeyCode::Enter);
+
+    assert!(!app.targeting);
+    assert!(app.cursor.is_none());
+    assert_eq!(app.target, Some((4, 3)));
+}
+
+#[test]
+fn escape_cancels_targeting() {
+    let mut app = make_app();
+    app.start_targeting();
+    press(&mut app, KeyCode::Esc);
+
+    assert!(!app.targeting);
+    assert!(app.target.is_none());
+    assert!(!app.quit);
+}
+
+#[test]
+fn targeting_does_not_spend_a_turn() {
+    let mut app = make_app();
+    app.start_targeting();
+    press(&mut app, KeyCode::Left);
+    press(&mut app, KeyCode::Enter);
+
+    assert_eq!(app.state.turn, 0);
+}
+
+#[test]
+fn only_visible_enemies_are_valid_targets() {
+    let app = make_app();
+    assert!(app.is_valid_target(4, 3));
+    assert!(!app.is_valid_target(2, 3));
+}
+
+#[test]
+fn line_to_excludes_origin_and_includes_target() {
+    let line = line_to((1, 1), (4, 1));
+    assert_eq!(line, vec![(2, 1), (3, 1), (4, 1)]);
+}
+
+#[test]
+fn line_to_diagonal() {
+    let line = line_to((0, 0), (3, 3));
+    assert_eq!(line, vec![(1, 1), (2, 2), (3, 3)]);
+}
+
+#[test]
+fn line_to_same_point_is_empty() {
+    assert!(line_to((2, 2), (2, 2)).is_empty());
+}
+
+#[test]
+fn render_highlights_cursor() {
+    let mut app = make_app();
+    app.start_targeting();
+    app.move_cursor(-1, 0);
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+
+    // Map block starts at (0, 0); its inner area is offset by the border.
+    let buffer = terminal.backend().buffer();
+    let cursor = &buffer.content[buffer.index_of(1 + 3, 1 + 3)];
+    let enemy = &buffer.content[buffer.index_of(1 + 4, 1 + 3)];
+    assert_eq!(cursor.bg, CURSOR_COLOR);
+    assert_eq!(enemy.bg, TARGET_VALID_COLOR);
+    assert_eq!(enemy.symbol(), "B");
+}
+
+// === Inspect ===
+
+#[test]
+fn inspect_starts_on_player() {
+    let mut app = make_app();
+    press(&mut app, KeyCode::Char(';'));
+
+    assert!(app.inspecting);
+    assert_eq!(app.cursor.map(|c| c.position()), Some((1, 3)));
+    let details = app.inspect_details();
+    assert_eq!(details[0], "Tile: Floor");
+    assert_eq!(details[1], "You are here.");
+}
+
+#[test]
+fn inspect_shows_enemy_stats() {
+    let mut app = make_app();
+    press(&mut app, KeyCode::Char('x'));
+    for _ in 0..3 {
+        press(&mut app, KeyCode::Right);
+    }
+
+    let details = app.inspect_details();
+    assert_eq!(details[1], "Enemy: Bug (B)  HP 10/10  DMG 3");
+}
+
+fn inspect_enemy_with_author(anonymize: bool) -> Vec<String> {
+    let mut app = make_app();
+    app.anonymize_authors = anonymize;
+    let mut commit = make_commit("Fix flaky login\n\nDetails", 12);
+    commit.hash = "test".to_string();
+    commit.author = "Alice Example".to_string();
+    app.state.world.current_mut().unwrap().source_commits.push(commit);
+    app.start_inspecting();
+    app.move_cursor(3, 0);
+    app.inspect_details()
+}
+
+#[test]
+fn inspect_shows_enemy_commit_author() {
+    let details = inspect_enemy_with_author(false);
+    assert_eq!(details[2], "From \"Fix flaky login\" by Alice Example");
+}
+
+#[test]
+fn inspect_pseudonymizes_authors_when_enabled() {
+    let details = inspect_enemy_with_author(true);
+    assert_eq!(details[2], format!("From \"Fix flaky login\" by {}", pseudonymize("Alice Example")));
+    assert!(!details.iter().any(|d| d.contains("Alice")));
+}
+
+#[test]
+fn inspect_shows_item_description() {
+    let mut app = make_app();
+    let item = Item::new("Health Potion", ItemType::Consumable, ItemEffect::Heal(20), Rarity::Common).at(2, 3);
+    app.state.world.current_mut().unwrap().items.push(item);
+    app.start_inspecting();
+    app.move_cursor(1, 0);
+
+    let details = app.inspect_details();
+    assert_eq!(details[1], "Item: Health Potion (Common) - restores 20 HP");
+}
+
+#[test]
+fn inspect_hides_unseen_tiles() {
+    let mut app = make_app();
+    app.start_inspecting();
+    app.cursor = Some(Cursor::new(4, 3));
+    app.state.visible_tiles.clear();
+
+    assert_eq!(app.inspect_details(), vec!["You can't see there.".to_string()]);
+}
+
+#[test]
+fn inspect_closes_without_quitting() {
+    let mut app = make_app();
+    press(&mut app, KeyCode::Char(';'));
+    press(&mut app, KeyCode::Esc);
+
+    assert!(!app.inspecting);
+    assert!(app.cursor.is_none());
+    assert!(!app.quit);
+}
+
+#[test]
+fn render_shows_inspect_panel() {
+    let mut app = make_app();
+    app.start_inspecting();
+    app.move_cursor(3, 0);
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+
+    // The panel replaces the message log below the map.
+    assert!(row_text(&terminal, 18).contains("Inspect"));
+    assert!(row_text(&terminal, 20).contains("Enemy: Bug"));
+}
+
+// === Exit hint ===
+
+fn make_cleared_app_with_pillar() -> App {
+    let mut app = make_app();
+    let room = app.state.world.current_mut().unwrap();
+    room.enemies.clear();
+    room.set_tile(6, 3, Tile::Exit);
+    room.set_tile(3, 3, Tile::Wall);
+    app.state.update_fov();
+    app
+}
+
+#[test]
+fn exit_hint_is_off_by_default() {
+    let app = make_cleared_app_with_pillar();
+    assert!(app.exit_hint().is_empty());
+}
+
+#[test]
+fn exit_hint_paths_around_walls() {
+    let mut app = make_cleared_app_with_pillar();
+    press(&mut app, KeyCode::Char('g'));
+
+    let hint = app.exit_hint();
+    assert!(!hint.is_empty());
+    let room = app.state.world.current().unwrap();
+    assert!(hint.iter().all(|&(x, y)| room.is_walkable(x, y)));
+    assert!(!hint.contains(&(3, 3)));
+    assert!(!hint.contains(&(1, 3)));
+    assert!(!hint.contains(&(6, 3)));
+}
+
+#[test]
+fn exit_hint_waits_for_cleared_room() {
+    let mut app = make_app();
+    app.state.world.current_mut().unwrap().set_tile(6, 3, Tile::Exit);
+    press(&mut app, KeyCode::Char('g'));
+
+    assert!(app.exit_hint().is_empty());
+}
+
+#[test]
+fn exit_hint_is_drawn_on_the_map() {
+    let mut app = make_cleared_app_with_pillar();
+    press(&mut app, KeyCode::Char('g'));
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+
+    let rows: Vec<String> = (0..24).map(|y| row_text(&terminal, y)).collect();
+    assert!(rows.iter().any(|row| row.contains('·')));
+}
+
+#[test]
+fn peek_shows_next_room_beside_cleared_exit() {
+    let mut app = make_cleared_app_with_pillar();
+    let mut next = make_test_room();
+    next.room_type = RoomType::Boss;
+    app.state.world.rooms.push(next);
+
+    app.state.player.x = 5;
+    assert_eq!(app.next_room_peek(), None);
+
+    app.peek_next_room = true;
+    let peek = app.next_room_peek().unwrap();
+    assert_eq!((peek.room_type, peek.enemies, peek.items), (RoomType::Boss, 1, 0));
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    let rows: Vec<String> = (0..24).map(|y| row_text(&terminal, y)).collect();
+    assert!(rows.iter().any(|row| row.contains("Next Room")));
+    assert!(rows.iter().any(|row| row.contains("Boss Chamber")));
+
+    // Only from beside the exit
+    app.state.player.x = 1;
+    assert_eq!(app.next_room_peek(), None);
+}
+
+// === Legend ===
+
+#[test]
+fn legend_toggles_with_l() {
+    let mut app = make_app();
+    press(&mut app, KeyCode::Char('L'));
+    assert!(app.show_legend);
+
+    // Keys don't reach the game while the legend is open
+    press(&mut app, KeyCode::Right);
+    assert_eq!(app.state.player.x, 1);
+
+    press(&mut app, KeyCode::Esc);
+    assert!(!app.show_legend);
+    assert!(!app.quit);
+}
+
+#[test]
+fn legend_lists_every_enemy_and_tile() {
+    let mut app = make_app();
+    press(&mut app, KeyCode::Char('L'));
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    let screen: String = (0..24).map(|y| row_text(&terminal, y)).collect::<Vec<_>>().join("\n");
+
+    for enemy_type in EnemyType::ALL {
+        assert!(
+            screen.contains(&format!("{}  {:?}", enemy_type.symbol(), enemy_type)),
+            "missing {:?}",
+            enemy_type
+        );
+    }
+    for tile in Tile::LEGEND {
+        assert!(screen.contains(&format!("{}  {}", tile.symbol(), tile.name())), "missing {}", tile.name());
+    }
+    assert!(screen.contains("@  You"));
+    assert!(screen.contains("?  Scroll"));
+}
+
+// === Item glyphs ===
+
+#[test]
+fn map_draws_item_glyph_by_type() {
+    let mut app = make_app();
+    let scroll = Item::new("Map Scroll", ItemType::Scroll, ItemEffect::RevealMap, Rarity::Common);
+    app.state.world.current_mut().unwrap().items.push(scroll.at(2, 3));
+    app.state.update_fov();
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+
+    let rows: Vec<String> = (0..24).map(|y| row_text(&terminal, y)).collect();
+    assert!(rows.iter().any(|row| row.contains("@?")));
+    assert!(!rows.iter().any(|row| row.contains("@!")));
+}
+
+#[test]
+fn inventory_lists_item_glyphs() {
+    let mut app = make_app();
+    app.state
+        .player
+        .pickup_item(Item::new("Map Scroll", ItemType::Scroll, ItemEffect::RevealMap, Rarity::Common));
+    app.state
+        .player
+        .pickup_item(Item::new("Elixir", ItemType::Consumable, ItemEffect::Heal(30), Rarity::Rare));
+    app.show_inventory = true;
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+
+    let rows: Vec<String> = (0..24).map(|y| row_text(&terminal, y)).collect();
+    assert!(rows.iter().any(|row| row.contains("> ? Map Scroll")));
+    assert!(rows.iter().any(|row| row.contains("  ! Elixir")));
+}
+
+// === Full inventory prompt ===
+
+fn make_full_app() -> App {
+    let mut app = make_app();
+    let floor = Item::new("Elixir", ItemType::Consumable, ItemEffect::Heal(30), Rarity::Rare);
+    app.state.world.current_mut().unwrap().items.push(floor.at(2, 3));
+    app.state.player.max_inventory = 1;
+    app.state
+        .player
+        .pickup_item(Item::new("Junk", ItemType::Consumable, ItemEffect::Heal(1), Rarity::Common));
+    press(&mut app, KeyCode::Right);
+    app
+}
+
+#[test]
+fn full_inventory_prompt_is_rendered() {
+    let app = make_full_app();
+    assert!(app.state.pending_pickup);
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert!(row_text(&terminal, 23).contains("drop Junk for Elixir? (y/n)"));
+}
+
+#[test]
+fn full_inventory_prompt_accepts_swap() {
+    let mut app = make_full_app();
+    press(&mut app, KeyCode::Char('y'));
+
+    assert!(!app.state.pending_pickup);
+    assert_eq!(app.state.player.inventory[0].name, "Elixir");
+}
+
+#[test]
+fn full_inventory_prompt_declines() {
+    let mut app = make_full_app();
+    press(&mut app, KeyCode::Char('n'));
+
+    assert!(!app.state.pending_pickup);
+    assert_eq!(app.state.player.inventory[0].name, "Junk");
+}
+
+// === Game over ===
+
+#[test]
+fn game_over_shows_essence_preview() {
+    let mut app = make_app();
+    app.state.enemies_killed = 3;
+    app.state.game_over = true;
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+
+    let expected = format!("Essence earned: {}", app.state.essence_earned());
+    assert!((0..24).any(|y| row_text(&terminal, y).contains(&expected)));
+    assert!((0..24).any(|y| row_text(&terminal, y).contains("Kills: 3")));
+}
+
+#[test]
+fn game_over_lists_new_records() {
+    let mut app = make_app();
+    app.state.game_over = true;
+    app.state.victory = true;
+    app.progression = Some(Progression::new());
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert!((0..30).any(|y| row_text(&terminal, y).contains("First victory!")));
+
+    // Nothing new against an equally fast past victory
+    let mut veteran = Progression::new();
+    veteran.complete_run(true, 0, 10, 0, 0);
+    app.progression = Some(veteran);
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert!(!(0..30).any(|y| row_text(&terminal, y).contains("victory!")));
+}
+
+// === Attack preview ===
+
+#[test]
+fn attack_preview_shows_hit_chance_and_damage() {
+    let app = make_app();
+    let player = &app.state.player;
+    let hit = (calculate_hit_chance(player.focus) * 100.0).round() as i32;
+    let damage = calculate_damage(player.damage, player.level, false);
+
+    let preview = app.attack_preview();
+    assert!(preview.starts_with(&format!("Hit {}% | Dmg {} ({} on 5% crit)", hit, damage, damage * 2)));
+    // The bug is out of reach
+    assert!(!preview.contains("E:"));
+}
+
+#[test]
+fn attack_preview_flags_lethal_adjacent_enemy() {
+    let mut app = make_app();
+    app.state.player.damage = 15;
+    app.state.world.current_mut().unwrap().enemies[0].x = 2;
+
+    assert!(app.attack_preview().contains("E: B 10HP lethal"));
+
+    app.state.world.current_mut().unwrap().enemies[0].hp = 25;
+    assert!(app.attack_preview().contains("E: B 25HP lethal on crit"));
+
+    app.state.world.current_mut().unwrap().enemies[0].hp = 40;
+    assert!(app.attack_preview().ends_with("E: B 40HP"));
+}
+
+#[test]
+fn attack_preview_accounts_for_wards() {
+    let mut app = make_app();
+    app.state.player.damage = 15;
+    let enemy = &mut app.state.world.current_mut().unwrap().enemies[0];
+    enemy.x = 2;
+    enemy.warded = true;
+
+    // A quarter of 15, or of 30 on a crit, can't finish 10 HP
+    assert!(app.attack_preview().ends_with("E: B 10HP"));
+}
+
+#[test]
+fn attack_preview_accounts_for_damage_variance() {
+    let mut app = make_app();
+    app.state.player.damage = 15;
+    app.state.world.current_mut().unwrap().enemies[0].x = 2;
+    app.state.damage_variance = 50;
+
+    // A low roll may leave it standing, a high one won't
+    assert!(app.attack_preview().ends_with("E: B 10HP lethal on crit"));
+}
+
+#[test]
+fn attack_mode_renders_preview() {
+    let mut app = make_app();
+    press(&mut app, KeyCode::Char('a'));
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert!(row_text(&terminal, 23).contains("Attack - press direction | Hit "));
+}
+
+// === Turn summary ===
+
+#[test]
+fn turn_summary_shown_above_log() {
+    let mut app = make_app();
+    app.state.world.current_mut().unwrap().enemies[0].x = 2;
+    press(&mut app, KeyCode::Char('d'));
+    assert!(app.turn_summary.starts_with("You defend; Bug "));
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert!(row_text(&terminal, 19).contains("You defend; Bug "));
+}
+
+// === Bisect ===
+
+#[test]
+fn accuse_key_ignored_outside_bisect() {
+    let mut app = make_app();
+    press(&mut app, KeyCode::Char('B'));
+    assert!(!app.confirm_accuse);
+}
+
+#[test]
+fn accuse_asks_before_charging_hp() {
+    let mut app = make_app();
+    app.state.start_bisect();
+    let hp = app.state.player.hp;
+
+    press(&mut app, KeyCode::Char('B'));
+    assert!(app.confirm_accuse);
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert!(row_text(&terminal, 23).contains("Accuse this room"));
+
+    press(&mut app, KeyCode::Char('n'));
+    assert!(!app.confirm_accuse);
+    assert_eq!(app.state.player.hp, hp);
+    assert!(!app.state.game_over);
+}
+
+#[test]
+fn confirmed_accusation_of_the_bad_room_wins() {
+    let mut app = make_app();
+    app.state.start_bisect();
+
+    press(&mut app, KeyCode::Char('B'));
+    press(&mut app, KeyCode::Char('y'));
+    assert!(app.state.game_over);
+    assert!(app.state.victory);
+}
+
+// === Author colors ===
+
+#[test]
+fn author_color_is_stable() {
+    assert_eq!(author_color("Alice Example"), author_color("Alice Example"));
+    assert_eq!(author_color("Alice Example"), author_color("  Alice Example "));
+    assert_ne!(author_color("Alice Example"), author_color("Bob Example"));
+}
+
+#[test]
+fn author_colors_stay_readable() {
+    let Color::Rgb(fr, fg, fb) = FOG_COLOR else {
+        panic!("fog should be an RGB color");
+    };
+    for name in ["Alice", "Bob", "Carol", "Dave", "Eve", "Mallory", "Trent", "Peggy"] {
+        let Color::Rgb(r, g, b) = author_color(name) else {
+            panic!("author colors should be RGB");
+        };
+        assert!(r.max(g).max(b) >= 200, "{} too dark", name);
+        assert!(r > fr && g > fg && b > fb, "{} blends into the fog", name);
+    }
+}
+
+#[test]
+fn age_color_darkens_with_age() {
+    let oldest = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+    let newest = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+    let brightness = |date: NaiveDate| {
+        let Color::Rgb(r, g, b) = age_color(date, oldest, newest) else {
+            panic!("age colors should be RGB");
+        };
+        r as u32 + g as u32 + b as u32
+    };
+
+    let mut previous = None;
+    for days in (0..=364).step_by(7) {
+        let shade = brightness(oldest + chrono::Duration::days(days));
+        if let Some(previous) = previous {
+            assert!(shade >= previous, "day {} is brighter than a newer one", days);
+        }
+        previous = Some(shade);
+    }
+    assert!(brightness(oldest) < brightness(newest));
+    // A single-day dungeon shows at full brightness
+    assert_eq!(age_color(oldest, oldest, oldest), age_color(newest, oldest, newest));
+}
+
+#[test]
+fn dungeon_map_cycles_blame_modes() {
+    let mut app = make_app();
+    press(&mut app, KeyCode::Char('M'));
+    assert!(app.show_map);
+    assert_eq!(app.blame, BlameMode::Off);
+
+    press(&mut app, KeyCode::Char('b'));
+    assert_eq!(app.blame, BlameMode::Author);
+    press(&mut app, KeyCode::Char('b'));
+    assert_eq!(app.blame, BlameMode::Age);
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    let screen: String = (0..24).map(|y| row_text(&terminal, y)).collect::<Vec<_>>().join("\n");
+    assert!(screen.contains("Blame: age"));
+    assert!(screen.contains("2026-01-01"));
+
+    press(&mut app, KeyCode::Esc);
+    assert!(!app.show_map);
+    assert!(!app.quit);
+}
+
+fn author_colored_app() -> App {
+    let mut app = make_app();
+    let mut commit = make_commit("Fix flaky login", 12);
+    commit.hash = "test".to_string();
+    commit.author = "Alice Example".to_string();
+    app.state.world.current_mut().unwrap().source_commits.push(commit);
+    app
+}
+
+fn glyph_color(app: &App, x: u16, y: u16) -> Color {
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer.content[buffer.index_of(x, y)].fg
+}
+
+#[test]
+fn enemies_colored_by_author_when_enabled() {
+    let mut app = author_colored_app();
+    // Map starts one cell in from the border
+    assert_eq!(glyph_color(&app, 5, 4), enemy_color(EnemyType::Bug));
+
+    app.color_by_author = true;
+    assert_eq!(glyph_color(&app, 5, 4), author_color("Alice Example"));
+}
+
+#[test]
+fn legend_lists_room_authors_when_coloring_by_author() {
+    let mut app = author_colored_app();
+    app.color_by_author = true;
+    app.anonymize_authors = true;
+    press(&mut app, KeyCode::Char('L'));
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    let screen: String = (0..24).map(|y| row_text(&terminal, y)).collect::<Vec<_>>().join("\n");
+
+    assert!(screen.contains("Authors"));
+    assert!(screen.contains(&pseudonymize("Alice Example")));
+    assert!(!screen.contains("Alice"));
+}
+
+// === Fog memory ===
+
+/// Draw the app with only the player's own tile in view, so the rest of
+/// the room is remembered at best.
+fn draw_out_of_view(app: &mut App) -> Terminal<TestBackend> {
+    app.state.visible_tiles = [(app.state.player.x, app.state.player.y)].into_iter().collect();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, app)).unwrap();
+    terminal
+}
+
+fn cell(terminal: &Terminal<TestBackend>, x: u16, y: u16) -> (String, Color) {
+    let buffer = terminal.backend().buffer();
+    let cell = &buffer.content[buffer.index_of(x, y)];
+    (cell.symbol().to_string(), cell.fg)
+}
+
+#[test]
+fn killed_enemy_drawn_as_corpse_until_next_turn() {
+    let mut app = make_app();
+    {
+        let bug = &mut app.state.world.current_mut().unwrap().enemies[0];
+        bug.x = 2;
+        bug.hp = 1;
+    }
+    press(&mut app, KeyCode::Char('a'));
+    press(&mut app, KeyCode::Right);
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert_eq!(cell(&terminal, 3, 4).0, DEATH_SYMBOL.to_string());
+
+    press(&mut app, KeyCode::Char('.'));
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert_ne!(cell(&terminal, 3, 4).0, DEATH_SYMBOL.to_string());
+}
+
+#[test]
+fn tiles_out_of_view_are_blank_without_fog_memory() {
+    let mut app = make_app();
+    let terminal = draw_out_of_view(&mut app);
+    assert_eq!(cell(&terminal, 1 + 3, 1 + 2).0, " ");
+}
+
+#[test]
+fn remembered_tiles_fade_into_fog() {
+    let mut app = make_app();
+    app.fog_memory = Some(10);
+
+    let terminal = draw_out_of_view(&mut app);
+    assert_eq!(cell(&terminal, 1 + 3, 1 + 2), (".".to_string(), remembered_color(FLOOR_COLOR, 0, 10)));
+
+    app.state.turn = 5;
+    let terminal = draw_out_of_view(&mut app);
+    assert_eq!(cell(&terminal, 1 + 3, 1 + 2).1, remembered_color(FLOOR_COLOR, 5, 10));
+
+    app.state.turn = 40;
+    let terminal = draw_out_of_view(&mut app);
+    assert_eq!(cell(&terminal, 1 + 3, 1 + 2).1, FOG_COLOR);
+}
+
+#[test]
+fn remembered_tiles_hide_enemies() {
+    let mut app = make_app();
+    app.fog_memory = Some(10);
+    let terminal = draw_out_of_view(&mut app);
+    // The Bug at (4, 3) isn't drawn, only the floor under it
+    assert_eq!(cell(&terminal, 1 + 4, 1 + 3).0, ".");
+}
+
+#[test]
+fn remembered_color_darkens_over_time() {
+    let brightness = |color: Color| match color {
+        Color::Rgb(r, g, b) => r as u32 + g as u32 + b as u32,
+        _ => panic!("remembered colors should be RGB"),
+    };
+    let fresh = remembered_color(Color::White, 0, 10);
+    let older = remembered_color(Color::White, 5, 10);
+    assert!(brightness(fresh) < 255 * 3);
+    assert!(brightness(older) < brightness(fresh));
+    assert_eq!(remembered_color(Color::White, 10, 10), FOG_COLOR);
+}
+
+// === Speedrun timer ===
+
+#[test]
+fn split_times_from_room_clears() {
+    let secs = Duration::from_secs;
+    let clears = [secs(40), secs(55), secs(130), secs(200)];
+
+    let splits = split_times(&clears);
+
+    assert_eq!(splits, vec![secs(40), secs(15), secs(75), secs(70)]);
+    assert_eq!(splits.iter().sum::<Duration>(), secs(200));
+}
+
+#[test]
+fn split_times_empty_before_any_clear() {
+    assert!(split_times(&[]).is_empty());
+}
+
+#[test]
+fn format_time_shows_minutes_seconds_tenths() {
+    assert_eq!(format_time(Duration::from_millis(0)), "0:00.0");
+    assert_eq!(format_time(Duration::from_millis(61_250)), "1:01.2");
+    assert_eq!(format_time(Duration::from_secs(754)), "12:34.0");
+}
+
+#[test]
+fn speedrun_timer_tracks_rooms_and_freezes_on_finish() {
+    let mut timer = SpeedrunTimer::new(None);
+    timer.clear_room(0);
+    assert_eq!(timer.splits().len(), 1);
+    assert!(!timer.is_personal_best());
+
+    timer.finish();
+    let time = timer.elapsed();
+    timer.clear_room(1);
+    assert!(timer.is_finished());
+    assert_eq!(timer.elapsed(), time);
+    assert_eq!(timer.splits().len(), 1);
+    assert!(timer.is_personal_best());
+}
+
+#[test]
+fn speedrun_timer_splits_each_room_once() {
+    let mut timer = SpeedrunTimer::new(None);
+    timer.clear_room(0);
+    timer.clear_room(1);
+    // Backtracking into a cleared room and clearing it again
+    timer.clear_room(0);
+    timer.clear_room(1);
+
+    assert_eq!(timer.splits().len(), 2);
+    let rooms: Vec<usize> = timer.progress().clears_ms.iter().map(|&(room, _)| room).collect();
+    assert_eq!(rooms, vec![0, 1]);
+}
+
+#[test]
+fn speedrun_timer_resumes_from_a_save() {
+    let saved = SpeedrunProgress { elapsed_ms: 90_000, clears_ms: vec![(0, 30_000), (1, 75_000)] };
+
+    let mut timer = SpeedrunTimer::resume(None, &saved);
+    assert!(timer.elapsed() >= Duration::from_secs(90));
+    assert_eq!(timer.splits(), vec![Duration::from_secs(30), Duration::from_secs(45)]);
+    assert!(timer.current_split() >= Duration::from_secs(15));
+
+    timer.finish();
+    let progress = timer.progress();
+    assert!(progress.elapsed_ms >= 90_000);
+    assert_eq!(progress.clears_ms, saved.clears_ms);
+}
+
+#[test]
+fn speedrun_timer_not_a_pb_when_slower() {
+    let mut timer = SpeedrunTimer::new(Some(Duration::ZERO));
+    timer.finish();
+    assert!(!timer.is_personal_best());
+}
+
+#[test]
+fn speedrun_hud_only_when_enabled() {
+    let mut app = make_app();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert!(!(0..24).any(|y| row_text(&terminal, y).contains("Time: ")));
+
+    app.speedrun = Some(SpeedrunTimer::new(None));
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert!((0..24).any(|y| row_text(&terminal, y).contains("Time: 0:00")));
+    assert!((0..24).any(|y| row_text(&terminal, y).contains("Split: 0:00")));
+}
+
+// === Diff lore ===
+
+#[test]
+fn reading_lore_opens_overlay_without_using_a_turn() {
+    let mut app = make_app();
+    let snippet = "src/lib.rs:\n@@ -1 +1 @@\n-old\n+new".to_string();
+    let fragment = Item::new("Diff Fragment", ItemType::Scroll, ItemEffect::Lore(snippet), Rarity::Common)
+        .from_commit("abcdef1234");
+    app.state.player.inventory.push(fragment);
+    let turn = app.state.turn;
+
+    press(&mut app, KeyCode::Char('i'));
+    press(&mut app, KeyCode::Enter);
+
+    assert!(app.reading.is_some());
+    assert!(!app.show_inventory);
+    assert_eq!(app.state.turn, turn);
+    assert_eq!(app.state.player.inventory.len(), 1);
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert!((0..24).any(|y| row_text(&terminal, y).contains("Diff Fragment (abcdef1)")));
+    assert!((0..24).any(|y| row_text(&terminal, y).contains("+new")));
+
+    press(&mut app, KeyCode::Esc);
+    assert!(app.reading.is_none());
+}
+
+#[test]
+fn reading_scroll_stays_within_text() {
+    let mut app = make_app();
+    let fragment = Item::new("Diff Fragment", ItemType::Scroll, ItemEffect::Lore("a\nb\nc".into()), Rarity::Common);
+    app.reading = Some(fragment);
+
+    for _ in 0..5 {
+        press(&mut app, KeyCode::Down);
+    }
+    assert_eq!(app.reading_scroll, 2);
+
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.reading_scroll, 1);
+}
+
+#[test]
+fn quick_slots_use_bound_items_after_shifts() {
+    let mut app = make_app();
+    let potion = Item::new("Health Potion", ItemType::Consumable, ItemEffect::Heal(20), Rarity::Common);
+    let elixir = Item::new("Elixir", ItemType::Consumable, ItemEffect::Heal(30), Rarity::Rare);
+    app.state.player.pickup_item(potion);
+    app.state.player.pickup_item(elixir);
+
+    // Bind in the inventory: potion to 1, elixir to 2
+    press(&mut app, KeyCode::Char('i'));
+    press(&mut app, KeyCode::Char('1'));
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char('2'));
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.state.player.quick_slots[..2], [Some(0), Some(1)]);
+
+    press(&mut app, KeyCode::Char('1'));
+    let names: Vec<_> = app.state.player.inventory.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(names, vec!["Elixir"]);
+    // The elixir moved up, and its slot followed it
+    assert_eq!(app.state.player.quick_slots[..2], [None, Some(0)]);
+
+    press(&mut app, KeyCode::Char('1'));
+    assert_eq!(app.state.player.inventory.len(), 1);
+
+    press(&mut app, KeyCode::Char('2'));
+    assert!(app.state.player.inventory.is_empty());
+    assert_eq!(app.state.player.quick_slot(1), None);
+}
+
+#[test]
+fn boss_title_and_hp_bar_show_in_sidebar() {
+    let mut app = make_app();
+    let room = app.state.world.current_mut().unwrap();
+    room.room_type = RoomType::Boss;
+    room.enemies[0].promote("The auth Conflict");
+    room.enemies[0].hp = room.enemies[0].max_hp / 2;
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    let rows: Vec<String> = (0..24).map(|y| row_text(&terminal, y)).collect();
+
+    assert!(rows.iter().any(|row| row.contains("The auth Conflict")));
+    assert!(rows.iter().any(|row| row.contains("[#####-----] 10/20")));
+}
+
+// === Auto-advance ===
+
+fn clearable_app_with_next_room() -> App {
+    let mut app = make_app();
+    {
+        let room = app.state.world.current_mut().unwrap();
+        room.set_tile(6, 3, Tile::Exit);
+        room.enemies[0].x = 2;
+        room.enemies[0].hp = 1;
+    }
+    let mut next = make_test_room();
+    next.id = 1;
+    app.state.world.rooms.push(next);
+    app
+}
+
+#[test]
+fn auto_advance_moves_on_after_clearing() {
+    let mut app = clearable_app_with_next_room();
+    app.auto_advance = true;
+    press(&mut app, KeyCode::Char('a'));
+    press(&mut app, KeyCode::Right);
+    assert!(app.confirm_advance);
+
+    press(&mut app, KeyCode::Enter);
+    assert!(!app.confirm_advance);
+    assert_eq!(app.state.world.current_room, 1);
+    assert_eq!((app.state.player.x, app.state.player.y), (1, 3));
+}
+
+#[test]
+fn auto_advance_into_a_special_room_raises_its_banner() {
+    let mut app = clearable_app_with_next_room();
+    app.state.world.rooms[1].room_type = RoomType::Treasure;
+    app.auto_advance = true;
+    press(&mut app, KeyCode::Char('a'));
+    press(&mut app, KeyCode::Right);
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.state.world.current_room, 1);
+    assert!(app.banner.is_some_and(|banner| banner.contains(RoomType::Treasure.name())));
+}
+
+#[test]
+fn auto_advance_off_leaves_you_in_the_room() {
+    let mut app = clearable_app_with_next_room();
+    press(&mut app, KeyCode::Char('a'));
+    press(&mut app, KeyCode::Right);
+    assert!(!app.confirm_advance);
+    assert_eq!(app.state.world.current_room, 0);
+
+    // Declining the prompt also stays put
+    let mut app = clearable_app_with_next_room();
+    app.auto_advance = true;
+    press(&mut app, KeyCode::Char('a'));
+    press(&mut app, KeyCode::Right);
+    press(&mut app, KeyCode::Esc);
+    assert!(!app.confirm_advance);
+    assert!(!app.quit);
+    assert_eq!(app.state.world.current_room, 0);
+}
+
+// === Restart ===
+
+#[test]
+fn restart_builds_a_fresh_run() {
+    let mut app = make_app();
+    app.color_by_author = true;
+    app.new_run = Some(Box::new(|seed| {
+        GameState::new(vec![make_commit("Test", 50)], seed, PathBuf::from("/tmp/test-repo"))
+    }));
+
+    press(&mut app, KeyCode::Char('.'));
+    press(&mut app, KeyCode::Char('i'));
+    app.state.game_over = true;
+    assert!(app.state.turn > 0);
+
+    assert!(app.restart(7));
+    assert_eq!(app.state.turn, 0);
+    assert_eq!(app.state.seed, 7);
+    assert!(!app.state.game_over);
+    assert!(!app.show_inventory);
+    assert!(app.color_by_author);
+    assert!(app.new_run.is_some());
+}
+
+#[test]
+fn restart_needs_a_way_to_rebuild_the_run() {
+    let mut app = make_app();
+    app.state.game_over = true;
+    assert!(!app.restart(7));
+    assert!(app.state.game_over);
+}
+
+// === Low HP warning ===
+
+fn warning_count(app: &App) -> usize {
+    app.state.messages.iter().filter(|m| m.contains("HP is low")).count()
+}
+
+#[test]
+fn low_hp_warns_once_per_crossing() {
+    let mut app = make_app();
+    app.low_hp_warning = 25;
+    let max = app.state.player.max_hp;
+
+    app.state.player.hp = max / 2;
+    assert!(!app.check_low_hp());
+
+    app.state.player.hp = max / 5;
+    assert!(app.is_low_hp());
+    assert!(app.check_low_hp());
+    // Staying low doesn't warn again
+    assert!(!app.check_low_hp());
+    app.state.player.hp -= 1;
+    assert!(!app.check_low_hp());
+    assert_eq!(warning_count(&app), 1);
+
+    // Healing above the threshold re-arms the warning
+    app.state.player.hp = max;
+    assert!(!app.check_low_hp());
+    app.state.player.hp = max / 5;
+    assert!(app.check_low_hp());
+    assert_eq!(warning_count(&app), 2);
+}
+
+#[test]
+fn low_hp_warning_off_at_zero() {
+    let mut app = make_app();
+    app.state.player.hp = 1;
+    assert!(!app.is_low_hp());
+    assert!(!app.check_low_hp());
+    assert_eq!(warning_count(&app), 0);
+}
+
+#[test]
+fn low_hp_draws_a_red_border() {
+    let mut app = make_app();
+    app.low_hp_warning = 25;
+    app.state.player.hp = 1;
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    assert_eq!(buffer.content[buffer.index_of(0, 0)].fg, HP_LOW);
+    assert_eq!(buffer.content[buffer.index_of(79, 23)].fg, HP_LOW);
+}
+
+// === Degenerate rooms ===
+
+#[test]
+fn empty_room_does_not_panic() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut state = GameState::new(vec![make_commit("Test", 50)], 42, PathBuf::from("/tmp/test-repo"));
+    state.world = World::new(vec![Room::new(0, 0, 0, RoomType::Normal, date)]);
+    state.update_fov();
+    assert!(state.visible_tiles.is_empty());
+
+    let app = App::new(state);
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    assert!(row_text(&terminal, 1).contains("no floor"));
+}
+
+#[test]
+fn player_outside_room_is_moved_back_in() {
+    let mut app = make_app();
+    app.state.player.x = 40;
+    app.state.player.y = -3;
+    app.state.update_fov();
+
+    assert_eq!((app.state.player.x, app.state.player.y), (5, 1));
+    assert!(app.state.visible_tiles.contains(&(5, 1)));
+    assert!(app.state.messages.iter().any(|m| m.contains("outside the room")));
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+}
+
+// === Commit graph ===
+
+fn room_with_commits(id: usize, day: u32, count: u32, room_type: RoomType) -> Room {
+    let date = NaiveDate::from_ymd_opt(2026, 1, day).unwrap();
+    let mut room = Room::new(id, 7, 7, room_type, date);
+    for i in 0..count {
+        let mut commit = make_commit("Work", i + 1);
+        commit.date = date.and_hms_opt(12, 0, 0).unwrap().and_utc();
+        room.source_commits.push(commit);
+    }
+    room
+}
+
+#[test]
+fn commit_graph_scales_days_and_marks_bosses() {
+    let rooms = vec![
+        room_with_commits(0, 1, 1, RoomType::Normal),
+        room_with_commits(1, 2, 4, RoomType::Boss),
+        room_with_commits(2, 3, 2, RoomType::Normal),
+    ];
+    assert_eq!(commit_graph(&rooms, 36), vec![" B ", " . ", " ..", "..."]);
+}
+
+#[test]
+fn commit_graph_buckets_long_histories() {
+    let rooms: Vec<Room> = (1..=28).map(|day| room_with_commits(day as usize, day, 1, RoomType::Normal)).collect();
+    let graph = commit_graph(&rooms, 10);
+    assert!(graph.iter().all(|row| row.chars().count() == 10));
+}
+
+#[test]
+fn commit_graph_is_empty_without_commits() {
+    assert!(commit_graph(&[make_test_room()], 36).is_empty());
+}
+
+#[test]
+fn victory_screen_shows_commit_graph() {
+    let mut app = make_app();
+    app.state.world.rooms[0].enemies.clear();
+    app.state.world.rooms.push(room_with_commits(1, 1, 1, RoomType::Normal));
+    app.state.world.rooms.push(room_with_commits(2, 2, 3, RoomType::Boss));
+    app.state.victory = true;
+    app.state.game_over = true;
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    // The boss day's column is topped with a B, with dots down to the base.
+    let buffer = terminal.backend().buffer();
+    let symbol = |x: u16, y: u16| buffer.content[buffer.index_of(x, y)].symbol().to_string();
+    let topped = (0..80).any(|x| (0..27).any(|y| symbol(x, y) == "B" && (1..=3).all(|dy| symbol(x, y + dy) == ".")));
+    assert!(topped);
+}
+
+// === Event stream ===
+
+#[test]
+fn events_are_written_as_json_lines() {
+    let events = vec![
+        GameEvent::PlayerMoved { x: 2, y: 3 },
+        GameEvent::PlayerDefending,
+        GameEvent::EnemyKilled { enemy_type: "Bug".to_string(), xp: 10 },
+        GameEvent::Message("Hello\nworld".to_string()),
+    ];
+    let mut out = Vec::new();
+    write_event_lines(&mut out, &events).unwrap();
+
+    let text = String::from_utf8(out).unwrap();
+    let lines: Vec<serde_json::Value> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(lines.len(), events.len());
+    assert_eq!(lines[0]["PlayerMoved"]["x"], 2);
+    assert_eq!(lines[1], "PlayerDefending");
+    assert_eq!(lines[2]["EnemyKilled"]["xp"], 10);
+    assert_eq!(lines[3]["Message"], "Hello\nworld");
+}
+
+// === Room banners ===
+
+#[test]
+fn entering_a_special_room_raises_its_banner() {
+    let types = [
+        RoomType::Sanctuary,
+        RoomType::Treasure,
+        RoomType::Library,
+        RoomType::Boss,
+        RoomType::Normal,
+        RoomType::Tutorial,
+    ];
+    let mut app = make_app();
+    app.state.world = World::new(
+        types
+            .iter()
+            .map(|&t| {
+                let mut room = make_test_room();
+                room.room_type = t;
+                room
+            })
+            .collect(),
+    );
+
+    for (room_id, room_type) in types.iter().enumerate() {
+        app.update_banner(&[GameEvent::RoomEntered { room_id }]);
+        match room_type {
+            RoomType::Normal | RoomType::Tutorial => {
+                assert_eq!(app.banner, None, "{:?}", room_type);
+                assert_eq!(app.banner_turns, 0);
+            }
+            _ => {
+                let banner = app.banner.unwrap();
+                assert!(banner.contains(room_type.name()), "{}", banner);
+                assert_eq!(app.banner_turns, BANNER_TURNS);
+            }
+        }
+    }
+}
+
+#[test]
+fn room_banner_fades_after_a_couple_of_turns() {
+    let mut app = make_app();
+    app.state.world.rooms[0].room_type = RoomType::Sanctuary;
+    app.update_banner(&[GameEvent::RoomEntered { room_id: 0 }]);
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| render(frame, &app)).unwrap();
+    let rows: Vec<String> = (0..24).map(|y| row_text(&terminal, y)).collect();
+    assert!(rows.iter().any(|row| row.contains("rest and recover")));
+
+    for _ in 1..BANNER_TURNS {
+        app.update_banner(&[]);
+        assert!(app.banner.is_some());
+    }
+    app.update_banner(&[]);
+    assert_eq!(app.banner, None);
+
+    // Other events don't bring it back
+    app.update_banner(&[GameEvent::RoomCleared { room_id: 0 }]);
+    assert_eq!(app.banner, None);
+}
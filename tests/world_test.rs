@@ -3,8 +3,10 @@
 use chrono::NaiveDate;
 use penumbra::git::CommitData;
 use penumbra::world::{
-    calculate_room_size, determine_room_type, generate_dungeon, Direction, DoorState, Room, RoomType, Tile,
-    World,
+    calculate_room_size, determine_room_type, generate_room, enforce_boss_pacing, ensure_exit_reachable, generate_dungeon, generate_dungeon_by_module,
+    generate_dungeon_with_options, keep_top_days, room_seed, RoomGrouping,
+    place_shortcuts, seed_candidates, DungeonSummary, SEED_CANDIDATES, tutorial_room, GenerationOptions, Direction, DoorState, Room, RoomSummary, RoomType, Tile,
+    World, AmbienceCue, CONFLICT_MARKERS, EMPTY_COMMIT_ROOM_SIZE, MAX_DIFFICULTY_STARS, MAX_ENEMIES_PER_ROOM, TENSE_ENEMY_COUNT,
 };
 use chrono::Utc;
 
@@ -194,6 +196,7 @@ fn make_commit(lines: u32, is_merge: bool, message: &str) -> CommitData {
         author: "Test".to_string(),
         is_merge,
         file_categories: Default::default(),
+        tag: None, diff_snippet: None, churn: 0,
     }
 }
 
@@ -276,7 +279,9 @@ fn determine_room_type_library_for_docs() {
                 config_files: 0,
                 doc_files: 2,
                 other_files: 0,
+                ..Default::default()
             },
+            tag: None, diff_snippet: None, churn: 0,
         },
     ];
     assert_eq!(determine_room_type(&commits), RoomType::Library);
@@ -302,6 +307,22 @@ fn generate_dungeon_creates_rooms() {
     assert!(!world.rooms.is_empty());
 }
 
+#[test]
+fn generate_dungeon_features_largest_commit() {
+    let commits = vec![
+        make_commit(10, false, "Fix typo"),
+        make_commit(120, false, "Rewrite the parser"),
+    ];
+    let world = generate_dungeon(&commits, 12345);
+
+    let room = &world.rooms[0];
+    assert_eq!(room.featured_commit.as_deref(), Some("hash_120"));
+    let boss = room.enemies.iter().find(|e| e.is_mini_boss()).unwrap();
+    assert_eq!(boss.source_commit, "hash_120");
+    assert_eq!(boss.title.as_deref(), Some("Rewrite the parser"));
+    assert_ne!((boss.x, boss.y), (1, room.height as i32 / 2));
+}
+
 #[test]
 fn generate_dungeon_deterministic_with_seed() {
     let commits = vec![
@@ -362,9 +383,9 @@ fn world_is_last_room() {
 
 // === Enemy Spawning Tests (Task 19) ===
 
-use penumbra::entity::EnemyType;
+use penumbra::entity::{Enemy, EnemyType};
+use penumbra::rng::GameRng;
 use rand::SeedableRng;
-use rand_chacha::ChaCha8Rng;
 
 fn make_commit_typed(message: &str) -> CommitData {
     CommitData {
@@ -375,7 +396,7 @@ fn make_commit_typed(message: &str) -> CommitData {
         deletions: 0,
         files_changed: 1,
         author: "Test".to_string(),
-        is_merge: false, file_categories: Default::default(),
+        is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
     }
 }
 
@@ -388,7 +409,7 @@ fn make_small_commit(message: &str) -> CommitData {
         deletions: 0,
         files_changed: 1,
         author: "Test".to_string(),
-        is_merge: false, file_categories: Default::default(),
+        is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
     }
 }
 
@@ -403,6 +424,7 @@ fn make_merge_commit(message: &str) -> CommitData {
         author: "Test".to_string(),
         is_merge: true,
         file_categories: Default::default(),
+        tag: None, diff_snippet: None, churn: 0,
     }
 }
 
@@ -411,7 +433,7 @@ fn spawn_enemies_creates_enemies() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
     let commits = vec![make_commit_typed("Fix bug"), make_commit_typed("Another fix")];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_enemies(&commits, &mut rng);
     assert!(!room.enemies.is_empty());
 }
@@ -422,19 +444,88 @@ fn spawn_enemies_respects_count_limit() {
     let mut room = Room::new(0, 5, 5, RoomType::Normal, date);
     // Small room (5x5 = 25 tiles, /4 = 6 max enemies)
     let commits: Vec<_> = (0..20).map(|i| make_commit_typed(&format!("Commit {}", i))).collect();
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_enemies(&commits, &mut rng);
     // Should be capped by room size
     assert!(room.enemies.len() <= 6);
 }
 
+#[test]
+fn low_enemy_cap_limits_large_busy_rooms() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 9, 9, RoomType::Normal, date);
+    let commits: Vec<_> = (0..30).map(|i| make_commit_typed(&format!("Commit {}", i))).collect();
+    let mut rng = GameRng::seed_from_u64(42);
+    room.spawn_enemies_capped(&commits, 3, &mut rng);
+    assert_eq!(room.enemies.len(), 3);
+}
+
+#[test]
+fn high_enemy_cap_still_bounded_by_room_size() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 9, 9, RoomType::Normal, date);
+    let commits: Vec<_> = (0..30).map(|i| make_commit_typed(&format!("Commit {}", i))).collect();
+    let mut rng = GameRng::seed_from_u64(42);
+    room.spawn_enemies_capped(&commits, 50, &mut rng);
+    // 9x9 = 81 tiles, /4 = 20 max enemies
+    assert_eq!(room.enemies.len(), 20);
+}
+
+#[test]
+fn generation_option_caps_enemies() {
+    let commits: Vec<_> = (0..12).map(|i| make_dated_commit(&format!("c{}", i), 40, 1, "Work")).collect();
+    let options = GenerationOptions { max_enemies_per_room: Some(2), ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 42, &options);
+    assert!(world.rooms[0].enemies.len() <= 2);
+
+    let world = generate_dungeon(&commits, 42);
+    assert_eq!(world.rooms[0].enemies.len(), MAX_ENEMIES_PER_ROOM);
+}
+
+#[test]
+fn spawn_enemies_keeps_clear_of_entrance() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let commits: Vec<_> = (0..10).map(|i| make_commit_typed(&format!("Commit {}", i))).collect();
+    for seed in 0..20 {
+        let mut room = Room::new(0, 9, 9, RoomType::Normal, date);
+        let mut rng = GameRng::seed_from_u64(seed);
+        room.spawn_enemies(&commits, &mut rng);
+        let start = (1, room.height as i32 / 2);
+        for e in &room.enemies {
+            let distance = (e.x - start.0).abs() + (e.y - start.1).abs();
+            assert!(distance > 2, "enemy at ({}, {}) too close to entrance", e.x, e.y);
+        }
+    }
+}
+
+#[test]
+fn spawn_enemies_still_fills_small_rooms() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 5, 5, RoomType::Normal, date);
+    let commits = vec![make_commit_typed("Fix bug")];
+    let mut rng = GameRng::seed_from_u64(42);
+    room.spawn_enemies(&commits, &mut rng);
+    assert_eq!(room.enemies.len(), 1);
+}
+
+#[test]
+fn spawn_enemies_scope_creep_for_sprawling_commits() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
+    let mut commit = make_commit_typed("Rework everything");
+    commit.files_changed = 40;
+    let mut rng = GameRng::seed_from_u64(42);
+    room.spawn_enemies(&[commit], &mut rng);
+    assert_eq!(room.enemies[0].enemy_type, EnemyType::ScopeCreep);
+}
+
 #[test]
 fn spawn_enemies_bug_type_for_small_commits() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
     // Bug spawns from small commits (<20 lines) per spec
     let commits = vec![make_small_commit("Fix something")];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_enemies(&commits, &mut rng);
     assert_eq!(room.enemies[0].enemy_type, EnemyType::Bug);
 }
@@ -445,7 +536,7 @@ fn spawn_enemies_merge_conflict_for_merge() {
     let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
     // MergeConflict spawns from merge commits (is_merge = true)
     let commits = vec![make_merge_commit("Merge branch feature")];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_enemies(&commits, &mut rng);
     assert_eq!(room.enemies[0].enemy_type, EnemyType::MergeConflict);
 }
@@ -455,7 +546,7 @@ fn spawn_enemies_regression_for_revert() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
     let commits = vec![make_commit_typed("Revert bad change")];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_enemies(&commits, &mut rng);
     assert_eq!(room.enemies[0].enemy_type, EnemyType::Regression);
 }
@@ -465,7 +556,7 @@ fn spawn_enemies_tech_debt_for_refactor() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
     let commits = vec![make_commit_typed("Refactor auth module")];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_enemies(&commits, &mut rng);
     assert_eq!(room.enemies[0].enemy_type, EnemyType::TechDebt);
 }
@@ -475,7 +566,7 @@ fn spawn_enemies_positions_on_walkable_tiles() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
     let commits = vec![make_commit_typed("Commit 1"), make_commit_typed("Commit 2")];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_enemies(&commits, &mut rng);
     for enemy in &room.enemies {
         assert!(room.is_walkable(enemy.x, enemy.y));
@@ -487,7 +578,7 @@ fn spawn_enemies_no_overlapping_positions() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 9, 9, RoomType::Normal, date);
     let commits: Vec<_> = (0..5).map(|i| make_commit_typed(&format!("Commit {}", i))).collect();
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_enemies(&commits, &mut rng);
     
     let mut positions: Vec<_> = room.enemies.iter().map(|e| (e.x, e.y)).collect();
@@ -510,26 +601,83 @@ fn make_commit_lines(message: &str, lines: u32) -> CommitData {
         deletions: 0,
         files_changed: 1,
         author: "Test".to_string(),
-        is_merge: false, file_categories: Default::default(),
+        is_merge: false, file_categories: Default::default(), tag: None, diff_snippet: None, churn: 0,
     }
 }
 
+#[test]
+fn empty_commit_still_makes_a_room_worth_entering() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let commits = vec![make_commit_lines("Empty commit", 0)];
+    let mut rng = GameRng::seed_from_u64(42);
+
+    let room = generate_room(date, &commits, 0, &mut rng);
+
+    assert_eq!((room.width, room.height), EMPTY_COMMIT_ROOM_SIZE);
+    assert!(!room.enemies.is_empty() || !room.items.is_empty());
+}
+
+#[test]
+fn high_churn_commits_spawn_tougher_enemies() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let spawn = |churn: u32| {
+        let mut room = Room::new(0, 9, 9, RoomType::Normal, date);
+        let mut commit = make_commit_lines("Tweak the parser", 30);
+        commit.churn = churn;
+        room.spawn_enemies(&[commit], &mut GameRng::seed_from_u64(7));
+        room.enemies.remove(0)
+    };
+
+    let calm = spawn(0);
+    let churned = spawn(10);
+    assert_eq!(churned.damage, calm.damage + 2);
+    assert_eq!(churned.max_hp, calm.max_hp + 4);
+    assert_eq!(churned.hp, churned.max_hp);
+}
+
 #[test]
 fn spawn_items_creates_items() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
     let commits = vec![make_commit_typed("Some commit")];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_items(&commits, &mut rng);
     assert!(!room.items.is_empty());
 }
 
+#[test]
+fn tagged_commit_yields_release_artifact() {
+    let mut commit = make_commit_lines("Add test for login", 10);
+    commit.tag = Some("v1.2.0".to_string());
+
+    let item = Room::item_from_commit(&commit);
+
+    assert_eq!(item.name, "v1.2.0 Artifact");
+    assert_eq!(item.rarity, Rarity::Uncommon);
+    assert!(matches!(item.effect, ItemEffect::Heal(20)));
+}
+
+#[test]
+fn spawn_items_places_tagged_commit_first() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
+    let mut release = make_commit_lines("Bump version", 5);
+    release.tag = Some("v2.0.0".to_string());
+    let commits = vec![make_commit_lines("Fix typo", 5), release];
+    let mut rng = GameRng::seed_from_u64(42);
+
+    room.spawn_items(&commits, &mut rng);
+
+    assert_eq!(room.items.len(), 1);
+    assert_eq!(room.items[0].name, "v2.0.0 Artifact");
+}
+
 #[test]
 fn spawn_items_doc_commit_creates_scroll() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
     let commits = vec![make_commit_typed("Update documentation")];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_items(&commits, &mut rng);
     assert_eq!(room.items[0].item_type, ItemType::Scroll);
     assert!(matches!(room.items[0].effect, ItemEffect::RevealMap));
@@ -540,7 +688,7 @@ fn spawn_items_test_commit_creates_health() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
     let commits = vec![make_commit_typed("Add test for login")];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_items(&commits, &mut rng);
     assert!(matches!(room.items[0].effect, ItemEffect::Heal(_)));
 }
@@ -550,7 +698,7 @@ fn spawn_items_config_commit_creates_buff() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
     let commits = vec![make_commit_typed("Update config file")];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_items(&commits, &mut rng);
     assert!(matches!(room.items[0].effect, ItemEffect::Buff(_, _, _)));
 }
@@ -560,7 +708,7 @@ fn spawn_items_treasure_room_gets_more() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 9, 9, RoomType::Treasure, date);
     let commits: Vec<_> = (0..5).map(|i| make_commit_typed(&format!("Commit {}", i))).collect();
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_items(&commits, &mut rng);
     // Treasure rooms get 2-3 items
     assert!(room.items.len() >= 2);
@@ -573,14 +721,14 @@ fn spawn_items_rarity_scales_with_lines() {
     // Common: <50 lines
     let mut room1 = Room::new(0, 7, 7, RoomType::Normal, date);
     let commits1 = vec![make_commit_lines("Small fix", 30)];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room1.spawn_items(&commits1, &mut rng);
     assert_eq!(room1.items[0].rarity, Rarity::Common);
     
     // Legendary: >500 lines
     let mut room2 = Room::new(1, 7, 7, RoomType::Normal, date);
     let commits2 = vec![make_commit_lines("Huge feature", 600)];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room2.spawn_items(&commits2, &mut rng);
     assert_eq!(room2.items[0].rarity, Rarity::Legendary);
 }
@@ -590,9 +738,1072 @@ fn spawn_items_positions_on_walkable_tiles() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
     let commits = vec![make_commit_typed("Commit 1")];
-    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut rng = GameRng::seed_from_u64(42);
     room.spawn_items(&commits, &mut rng);
     for item in &room.items {
         assert!(room.is_walkable(item.x, item.y));
     }
 }
+
+// === Commit Frustration ===
+
+#[test]
+fn frustrated_commit_enemies_hit_harder() {
+    let calm = make_small_commit("Add a test");
+    let angry = make_small_commit("ugh, hack it again!!");
+    let mut room = Room::new(0, 7, 7, RoomType::Normal, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+    room.source_commits = vec![calm.clone(), angry.clone()];
+    room.enemies.push(penumbra::entity::Enemy::new(EnemyType::Bug, 2, 2, &calm.hash));
+    room.enemies.push(penumbra::entity::Enemy::new(EnemyType::Bug, 4, 4, &angry.hash));
+
+    room.apply_frustration();
+
+    let base = EnemyType::Bug.base_damage();
+    assert_eq!(room.enemies[0].damage, base);
+    assert_eq!(room.enemies[1].damage, base + 4);
+}
+
+// === Per-room Seeding ===
+
+fn make_dated_commit(hash: &str, lines: u32, days_ago: i64, message: &str) -> CommitData {
+    let mut commit = make_commit(lines, false, message);
+    commit.hash = hash.to_string();
+    commit.date = Utc::now() - chrono::Duration::days(days_ago);
+    commit
+}
+
+fn enemy_positions(room: &Room) -> Vec<(i32, i32)> {
+    room.enemies.iter().map(|e| (e.x, e.y)).collect()
+}
+
+#[test]
+fn room_contents_stable_when_other_rooms_change() {
+    let base = vec![
+        make_dated_commit("a1", 120, 3, "Add login"),
+        make_dated_commit("a2", 40, 3, "Fix login bug"),
+        make_dated_commit("b1", 60, 2, "Add signup"),
+        make_dated_commit("b2", 15, 2, "Tweak signup"),
+    ];
+    let mut extended = base.clone();
+    extended.push(make_dated_commit("c1", 30, 1, "Add logout"));
+    extended.push(make_dated_commit("c2", 10, 1, "Fix logout"));
+
+    let world1 = generate_dungeon(&base, 777);
+    let world2 = generate_dungeon(&extended, 777);
+
+    assert_eq!(world2.rooms.len(), 3);
+    for i in 0..2 {
+        assert!(!world1.rooms[i].enemies.is_empty());
+        assert_eq!(enemy_positions(&world1.rooms[i]), enemy_positions(&world2.rooms[i]));
+    }
+    // Room 1 gains an exit once it's no longer last, so only compare room 0's tiles
+    assert_eq!(world1.rooms[0].tiles, world2.rooms[0].tiles);
+}
+
+#[test]
+fn room_seed_differs_per_room() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let next = NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+    assert_eq!(room_seed(42, 0, date), room_seed(42, 0, date));
+    assert_ne!(room_seed(42, 0, date), room_seed(42, 1, date));
+    assert_ne!(room_seed(42, 0, date), room_seed(42, 0, next));
+    assert_ne!(room_seed(42, 0, date), room_seed(43, 0, date));
+}
+
+#[test]
+fn generated_rooms_keep_start_tile_clear() {
+    let commits: Vec<_> = (0..8)
+        .map(|i| make_dated_commit(&format!("h{}", i), 10, 1, "Fix bug"))
+        .collect();
+    let world = generate_dungeon(&commits, 9);
+    let room = &world.rooms[0];
+    let start = (1, room.height as i32 / 2);
+    assert!(room.enemies.iter().all(|e| (e.x, e.y) != start));
+}
+
+fn four_day_history() -> Vec<CommitData> {
+    vec![
+        make_dated_commit("d1", 30, 4, "Initial commit"),
+        make_dated_commit("d2", 40, 3, "Add parser"),
+        make_dated_commit("d3", 50, 2, "Add renderer"),
+        make_dated_commit("d4", 60, 1, "Add tests"),
+    ]
+}
+
+#[test]
+fn exit_position_finds_exit_tile() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
+    assert_eq!(room.exit_position(), None);
+
+    room.set_tile(6, 3, Tile::Exit);
+    assert_eq!(room.exit_position(), Some((6, 3)));
+}
+
+#[test]
+fn max_rooms_caps_room_count() {
+    let options = GenerationOptions { max_rooms: Some(2), ..Default::default() };
+    let world = generate_dungeon_with_options(&four_day_history(), 42, &options);
+    assert_eq!(world.rooms.len(), 2);
+}
+
+#[test]
+fn max_rooms_keeps_most_recent_days() {
+    let commits = four_day_history();
+    let options = GenerationOptions { max_rooms: Some(2), ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 42, &options);
+
+    let full = generate_dungeon(&commits, 42);
+    assert_eq!(world.rooms[0].source_date, full.rooms[2].source_date);
+    assert_eq!(world.rooms[1].source_date, full.rooms[3].source_date);
+}
+
+#[test]
+fn max_rooms_last_room_has_no_exit() {
+    let options = GenerationOptions { max_rooms: Some(2), ..Default::default() };
+    let world = generate_dungeon_with_options(&four_day_history(), 42, &options);
+    let last = world.rooms.last().unwrap();
+    assert!(!last.tiles.iter().flatten().any(|t| *t == Tile::Exit));
+    assert!(world.rooms[0].tiles.iter().flatten().any(|t| *t == Tile::Exit));
+}
+
+#[test]
+fn max_rooms_larger_than_history_keeps_everything() {
+    let options = GenerationOptions { max_rooms: Some(10), ..Default::default() };
+    let world = generate_dungeon_with_options(&four_day_history(), 42, &options);
+    assert_eq!(world.rooms.len(), 4);
+}
+
+#[test]
+fn reverse_puts_newest_day_first() {
+    let commits = four_day_history();
+    let options = GenerationOptions { reverse: true, ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 42, &options);
+
+    let newest = world.rooms.iter().map(|r| r.source_date).max().unwrap();
+    let oldest = world.rooms.iter().map(|r| r.source_date).min().unwrap();
+    assert_eq!(world.rooms[0].source_date, newest);
+    assert_eq!(world.rooms.last().unwrap().source_date, oldest);
+}
+
+#[test]
+fn reverse_keeps_connections_in_room_order() {
+    let options = GenerationOptions { reverse: true, ..Default::default() };
+    let world = generate_dungeon_with_options(&four_day_history(), 42, &options);
+
+    assert!(!world.rooms[0].tiles.iter().flatten().any(|t| *t == Tile::Entrance));
+    assert!(world.rooms[0].tiles.iter().flatten().any(|t| *t == Tile::Exit));
+    let last = world.rooms.last().unwrap();
+    assert!(!last.tiles.iter().flatten().any(|t| *t == Tile::Exit));
+}
+
+#[test]
+fn reverse_with_max_rooms_keeps_most_recent_days() {
+    let commits = four_day_history();
+    let options = GenerationOptions { max_rooms: Some(2), reverse: true, ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 42, &options);
+
+    let full = generate_dungeon(&commits, 42);
+    assert_eq!(world.rooms[0].source_date, full.rooms[3].source_date);
+    assert_eq!(world.rooms[1].source_date, full.rooms[2].source_date);
+}
+
+// === Merge Puzzle ===
+
+#[test]
+fn merge_puzzle_splits_boss_rooms() {
+    let commits = vec![make_commit(120, true, "Merge branch 'feature'")];
+    let options = GenerationOptions { merge_puzzle: true, ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 42, &options);
+    let room = &world.rooms[0];
+
+    assert_eq!(room.room_type, RoomType::Boss);
+    assert_eq!(room.merge_branches, Some([false, false]));
+    for x in 2..=4 {
+        assert_eq!(room.get_tile(x, 3), Some(&Tile::Wall));
+    }
+    // Both ends stay open
+    assert!(room.is_walkable(1, 3));
+    assert!(room.is_walkable(5, 3));
+}
+
+#[test]
+fn merge_puzzle_is_off_by_default() {
+    let commits = vec![make_commit(120, true, "Merge branch 'feature'")];
+    let world = generate_dungeon(&commits, 42);
+    assert_eq!(world.rooms[0].merge_branches, None);
+    assert!(world.rooms[0].branches_merged());
+}
+
+#[test]
+fn merge_branches_need_space() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 5, 5, RoomType::Boss, date);
+    assert!(!room.add_merge_branches());
+    assert_eq!(room.merge_branches, None);
+}
+
+#[test]
+fn merge_branch_at_splits_north_and_south() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 7, 7, RoomType::Boss, date);
+    room.add_merge_branches();
+
+    assert_eq!(room.merge_branch_at((3, 1)), Some(0));
+    assert_eq!(room.merge_branch_at((3, 5)), Some(1));
+    assert_eq!(room.merge_branch_at((1, 1)), None);
+    assert_eq!(room.merge_branch_at((5, 5)), None);
+}
+
+// === Deletion Swarms ===
+
+fn make_churn_commit(hash: &str, insertions: u32, deletions: u32) -> CommitData {
+    let mut commit = make_commit(insertions, false, "Remove legacy importer");
+    commit.hash = hash.to_string();
+    commit.deletions = deletions;
+    commit
+}
+
+fn deletion_count(room: &Room) -> usize {
+    room.enemies.iter().filter(|e| e.enemy_type == EnemyType::Deletion).count()
+}
+
+#[test]
+fn deletion_heavy_commit_spawns_swarm() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 9, 9, RoomType::Normal, date);
+    let mut rng = GameRng::seed_from_u64(42);
+    room.spawn_deletion_swarms(&[make_churn_commit("cut", 10, 150)], MAX_ENEMIES_PER_ROOM, &mut rng);
+
+    assert_eq!(deletion_count(&room), 3);
+    assert!(room.enemies.iter().all(|e| e.source_commit == "cut"));
+}
+
+#[test]
+fn insertion_heavy_commit_spawns_no_swarm() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 9, 9, RoomType::Normal, date);
+    let mut rng = GameRng::seed_from_u64(42);
+    room.spawn_deletion_swarms(&[make_churn_commit("grow", 150, 100)], MAX_ENEMIES_PER_ROOM, &mut rng);
+
+    assert_eq!(deletion_count(&room), 0);
+}
+
+#[test]
+fn deletion_swarm_respects_room_cap() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 5, 5, RoomType::Normal, date);
+    for y in 0..5 {
+        room.enemies.push(penumbra::entity::Enemy::new(EnemyType::Bug, 0, y, "old"));
+    }
+    let mut rng = GameRng::seed_from_u64(42);
+    room.spawn_deletion_swarms(&[make_churn_commit("cut", 0, 400)], MAX_ENEMIES_PER_ROOM, &mut rng);
+
+    // 5x5 rooms hold at most 6 enemies
+    assert_eq!(room.enemies.len(), 6);
+    assert_eq!(deletion_count(&room), 1);
+}
+
+#[test]
+fn deletion_swarm_respects_the_enemy_cap() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 9, 9, RoomType::Normal, date);
+    room.enemies.push(penumbra::entity::Enemy::new(EnemyType::Bug, 0, 0, "old"));
+    let mut rng = GameRng::seed_from_u64(42);
+    room.spawn_deletion_swarms(&[make_churn_commit("cut", 0, 400)], 3, &mut rng);
+
+    assert_eq!(room.enemies.len(), 3);
+    assert_eq!(deletion_count(&room), 2);
+}
+
+#[test]
+fn mini_boss_keeps_its_commits_deletion_swarm() {
+    let commit = make_churn_commit("cut", 10, 150);
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 9, 9, RoomType::Normal, date);
+    let mut rng = GameRng::seed_from_u64(42);
+    room.spawn_enemies(std::slice::from_ref(&commit), &mut rng);
+    room.spawn_deletion_swarms(std::slice::from_ref(&commit), MAX_ENEMIES_PER_ROOM, &mut rng);
+    assert_eq!(deletion_count(&room), 3);
+
+    assert!(room.spawn_mini_boss(&commit, &mut rng));
+    assert_eq!(deletion_count(&room), 3);
+    // The regular enemy was replaced by the mini-boss
+    assert_eq!(room.enemies.len(), 4);
+}
+
+#[test]
+fn mini_boss_keeps_clear_of_the_entrance() {
+    let commit = make_churn_commit("big", 300, 0);
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    for seed in 0..50 {
+        let mut room = Room::new(0, 5, 5, RoomType::Normal, date);
+        let mut rng = GameRng::seed_from_u64(seed);
+        assert!(room.spawn_mini_boss(&commit, &mut rng));
+
+        let boss = room.enemies.iter().find(|e| e.is_mini_boss()).unwrap();
+        assert!(!room.near_start((boss.x, boss.y)), "seed {} put the mini-boss by the entrance", seed);
+    }
+}
+
+#[test]
+fn deletion_swarms_only_spawn_when_enabled() {
+    let commits = vec![make_churn_commit("cut", 10, 150)];
+    let options = GenerationOptions { deletion_swarms: true, ..Default::default() };
+
+    let with = generate_dungeon_with_options(&commits, 42, &options);
+    let without = generate_dungeon(&commits, 42);
+    assert!(deletion_count(&with.rooms[0]) > 0);
+    assert_eq!(deletion_count(&without.rooms[0]), 0);
+}
+
+// === Seen Tiles ===
+
+#[test]
+fn mark_seen_records_turns() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 5, 5, RoomType::Normal, date);
+    assert_eq!(room.last_seen(2, 2), None);
+
+    room.mark_seen(&[(2, 2), (9, 9)], 3);
+    assert_eq!(room.last_seen(2, 2), Some(3));
+    assert_eq!(room.last_seen(1, 1), None);
+    assert_eq!(room.last_seen(9, 9), None);
+}
+
+// === Exit Reachability ===
+
+fn walled_room_with_exit() -> Room {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 7, 7, RoomType::Normal, date);
+    for i in 0..7 {
+        room.set_tile(i, 0, Tile::Wall);
+        room.set_tile(i, 6, Tile::Wall);
+        room.set_tile(0, i, Tile::Wall);
+        room.set_tile(6, i, Tile::Wall);
+    }
+    room.set_tile(6, 3, Tile::Exit);
+    room
+}
+
+#[test]
+fn sealed_exit_is_carved_open() {
+    let mut room = walled_room_with_exit();
+    for y in 1..6 {
+        room.set_tile(4, y, Tile::Wall);
+    }
+    assert!(penumbra::combat::find_path((1, 3), (6, 3), &room).is_none());
+
+    assert!(ensure_exit_reachable(&mut room));
+    assert!(penumbra::combat::find_path((1, 3), (6, 3), &room).is_some());
+    // Only the wall in the way was removed
+    assert_eq!(room.get_tile(4, 3), Some(&Tile::Floor));
+    assert_eq!(room.get_tile(4, 2), Some(&Tile::Wall));
+    assert_eq!(room.get_tile(6, 3), Some(&Tile::Exit));
+}
+
+#[test]
+fn reachable_exit_is_left_alone() {
+    let mut room = walled_room_with_exit();
+    room.add_merge_branches();
+    let before = room.tiles.clone();
+
+    assert!(!ensure_exit_reachable(&mut room));
+    assert_eq!(room.tiles, before);
+}
+
+#[test]
+fn generated_exits_are_reachable() {
+    let world = generate_dungeon(&four_day_history(), 42);
+    for room in &world.rooms {
+        if let Some(exit) = room.exit_position() {
+            let start = (1, room.height as i32 / 2);
+            assert!(penumbra::combat::find_path(start, exit, room).is_some(), "room {} is sealed", room.id);
+        }
+    }
+}
+
+// === Tutorial ===
+
+#[test]
+fn tutorial_room_precedes_first_git_room() {
+    let commits = four_day_history();
+    let options = GenerationOptions { tutorial: true, ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 42, &options);
+    let plain = generate_dungeon(&commits, 42);
+
+    assert_eq!(world.rooms.len(), plain.rooms.len() + 1);
+    assert_eq!(world.rooms[0].room_type, RoomType::Tutorial);
+    assert!(world.rooms[0].source_commits.is_empty());
+    assert_eq!(world.rooms[1].source_date, plain.rooms[0].source_date);
+    assert_eq!(world.first_dungeon_room(), 1);
+    assert!(world.rooms.iter().enumerate().all(|(i, room)| room.id == i));
+}
+
+#[test]
+fn tutorial_leaves_git_rooms_unchanged_apart_from_entrance() {
+    let commits = four_day_history();
+    let options = GenerationOptions { tutorial: true, ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 42, &options);
+    let plain = generate_dungeon(&commits, 42);
+
+    let first = &world.rooms[1];
+    let mid_y = first.height as i32 / 2;
+    assert_eq!(first.get_tile(0, mid_y), Some(&Tile::Entrance));
+    for (room, original) in world.rooms[2..].iter().zip(&plain.rooms[1..]) {
+        assert_eq!(room.tiles, original.tiles);
+        assert_eq!(room.enemies.len(), original.enemies.len());
+    }
+}
+
+#[test]
+fn tutorial_room_exit_is_behind_its_bug() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let room = tutorial_room(date);
+    let exit = room.exit_position().unwrap();
+    let start = (1, room.height as i32 / 2);
+
+    assert!(penumbra::combat::find_path(start, exit, &room).is_some());
+    assert_eq!(room.enemies.len(), 1);
+    assert_eq!(room.items.len(), 1);
+    // The bug stands in the only gap in the dividing wall
+    let bug = &room.enemies[0];
+    assert_eq!(room.get_tile(bug.x, bug.y - 1), Some(&Tile::Wall));
+    assert_eq!(room.get_tile(bug.x, bug.y + 1), Some(&Tile::Wall));
+}
+
+#[test]
+fn no_tutorial_by_default() {
+    let world = generate_dungeon(&four_day_history(), 42);
+    assert_eq!(world.first_dungeon_room(), 0);
+    assert!(world.rooms.iter().all(|r| r.room_type != RoomType::Tutorial));
+}
+
+// === Shortcuts ===
+
+fn touching(hash: &str, days_ago: i64, paths: &[&str]) -> CommitData {
+    let mut commit = make_dated_commit(hash, 40, days_ago, "Work");
+    commit.file_categories.paths = paths.iter().map(|p| p.to_string()).collect();
+    commit
+}
+
+#[test]
+fn rooms_sharing_files_get_a_shortcut() {
+    let commits = vec![
+        touching("a", 4, &["src/parser.rs"]),
+        touching("b", 3, &["README.md"]),
+        touching("c", 2, &["src/parser.rs", "src/lexer.rs"]),
+        touching("d", 1, &["Cargo.toml"]),
+    ];
+    let options = GenerationOptions { shortcuts: true, ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 42, &options);
+
+    assert_eq!(world.rooms[0].shortcut, Some(2));
+    assert_eq!(world.rooms[2].shortcut, Some(0));
+    assert_eq!(world.rooms[1].shortcut, None);
+    assert_eq!(world.rooms[3].shortcut, None);
+
+    let (x, y) = world.rooms[0].shortcut_door();
+    assert_eq!(world.rooms[0].get_tile(x, y), Some(&Tile::Door(Direction::North, DoorState::Closed)));
+}
+
+#[test]
+fn neighbouring_rooms_are_not_shortcut() {
+    let commits = vec![
+        touching("a", 3, &["src/parser.rs"]),
+        touching("b", 2, &["src/parser.rs"]),
+        touching("c", 1, &["README.md"]),
+    ];
+    let mut rooms = generate_dungeon(&commits, 42).rooms;
+
+    assert!(place_shortcuts(&mut rooms).is_empty());
+    assert!(rooms.iter().all(|r| r.shortcut.is_none()));
+}
+
+#[test]
+fn each_room_gets_one_shortcut_strongest_first() {
+    let commits = vec![
+        touching("a", 5, &["src/ui.rs"]),
+        touching("b", 4, &["docs/a.md"]),
+        touching("c", 3, &["src/ui.rs", "src/app.rs"]),
+        touching("d", 2, &["docs/b.md"]),
+        touching("e", 1, &["src/ui.rs", "src/app.rs"]),
+    ];
+    let mut rooms = generate_dungeon(&commits, 42).rooms;
+
+    // Rooms 2 and 4 share two files, beating room 0's single match
+    assert_eq!(place_shortcuts(&mut rooms), vec![(2, 4)]);
+    assert_eq!(rooms[0].shortcut, None);
+}
+
+#[test]
+fn shortcuts_off_by_default() {
+    let commits = vec![
+        touching("a", 3, &["src/parser.rs"]),
+        touching("b", 2, &["README.md"]),
+        touching("c", 1, &["src/parser.rs"]),
+    ];
+    let world = generate_dungeon(&commits, 42);
+    assert!(world.rooms.iter().all(|r| r.shortcut.is_none()));
+}
+
+// === Diff lore ===
+
+fn with_snippet(hash: &str, lines: u32, days_ago: i64) -> CommitData {
+    let mut commit = make_dated_commit(hash, lines, days_ago, "Work");
+    commit.diff_snippet = Some(format!("src/{}.rs:\n@@ -0,0 +1 @@\n+fn {}() {{}}", hash, hash));
+    commit
+}
+
+#[test]
+fn diff_lore_drops_fragment_from_largest_commit() {
+    let commits = vec![with_snippet("small", 10, 1), with_snippet("large", 80, 1), make_dated_commit("none", 300, 1, "Work")];
+    let options = GenerationOptions { diff_lore: true, ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 42, &options);
+
+    let fragment = world.rooms[0].items.iter().find(|i| i.name == "Diff Fragment").unwrap();
+    assert_eq!(fragment.item_type, ItemType::Scroll);
+    assert_eq!(fragment.source_commit.as_deref(), Some("large"));
+    match &fragment.effect {
+        ItemEffect::Lore(text) => assert!(text.contains("+fn large() {}")),
+        other => panic!("expected lore, got {:?}", other),
+    }
+}
+
+#[test]
+fn without_diff_lore_snippets_are_dropped() {
+    let commits = vec![with_snippet("a", 40, 1)];
+    let world = generate_dungeon(&commits, 42);
+
+    assert!(world.rooms[0].items.iter().all(|i| i.name != "Diff Fragment"));
+    assert!(world.rooms[0].source_commits.iter().all(|c| c.diff_snippet.is_none()));
+}
+
+// === Themed start ===
+
+fn themed_commits() -> Vec<CommitData> {
+    vec![
+        touching("a1", 4, &["README.md"]),
+        touching("a2", 4, &["src/parser.rs"]),
+        touching("b", 3, &["src/parser.rs"]),
+        make_dated_commit("big", 300, 2, "Work"),
+    ]
+}
+
+#[test]
+fn themed_start_names_first_room_after_most_edited_file() {
+    let options = GenerationOptions { themed_start: true, ..Default::default() };
+    let world = generate_dungeon_with_options(&themed_commits(), 42, &options);
+
+    let room = &world.rooms[0];
+    assert_eq!(room.theme_file.as_deref(), Some("src/parser.rs"));
+    assert_eq!(room.name(), "The parser.rs Halls");
+    assert!(world.rooms[1..].iter().all(|r| r.theme_file.is_none()));
+
+    // The keeper comes from the commit that touched the file
+    let keeper = room.enemies.iter().find(|e| e.is_mini_boss()).unwrap();
+    assert_eq!(keeper.title.as_deref(), Some("Keeper of parser.rs"));
+    assert_eq!(keeper.source_commit, "a2");
+}
+
+#[test]
+fn themed_start_skips_tutorial_room() {
+    let options = GenerationOptions { themed_start: true, tutorial: true, ..Default::default() };
+    let world = generate_dungeon_with_options(&themed_commits(), 42, &options);
+
+    assert_eq!(world.rooms[0].theme_file, None);
+    assert_eq!(world.rooms[1].theme_file.as_deref(), Some("src/parser.rs"));
+}
+
+#[test]
+fn unthemed_rooms_use_type_name() {
+    let world = generate_dungeon(&themed_commits(), 42);
+    assert_eq!(world.rooms[0].name(), world.rooms[0].room_type.name());
+}
+
+// === Boss Pacing Tests ===
+
+fn linear_history(days: i64) -> Vec<CommitData> {
+    (0..days)
+        .map(|d| make_dated_commit(&format!("c{}", d), 30 + d as u32 * 10, days - d, "Steady work"))
+        .collect()
+}
+
+#[test]
+fn boss_pacing_adds_periodic_bosses() {
+    let commits = linear_history(7);
+    let plain = generate_dungeon(&commits, 42);
+    assert!(plain.rooms.iter().all(|r| r.room_type != RoomType::Boss));
+
+    let options = GenerationOptions { boss_every: Some(3), ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 42, &options);
+
+    assert_eq!(world.rooms.len(), 7);
+    for window in world.rooms.chunks(3) {
+        let bosses = window.iter().filter(|r| r.room_type == RoomType::Boss).count();
+        assert_eq!(bosses, 1);
+    }
+}
+
+#[test]
+fn boss_pacing_promotes_hardest_room_and_keeps_merge_bosses() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut rooms: Vec<Room> = (0..4).map(|id| Room::new(id, 7, 7, RoomType::Normal, date)).collect();
+    rooms[0].enemies.push(penumbra::entity::Enemy::new(EnemyType::Bug, 2, 2, "a"));
+    rooms[1].enemies.push(penumbra::entity::Enemy::new(EnemyType::Bug, 2, 2, "b"));
+    rooms[1].enemies.push(penumbra::entity::Enemy::new(EnemyType::TechDebt, 3, 3, "c"));
+    rooms[3].room_type = RoomType::Boss;
+
+    enforce_boss_pacing(&mut rooms, 2);
+
+    assert_eq!(rooms[0].room_type, RoomType::Normal);
+    assert_eq!(rooms[1].room_type, RoomType::Boss);
+    // The toughest enemy leads the chamber
+    let boss = rooms[1].enemies.iter().find(|e| e.is_mini_boss()).unwrap();
+    assert_eq!(boss.enemy_type, EnemyType::TechDebt);
+    // The second window already has a merge boss
+    assert_eq!(rooms[2].room_type, RoomType::Normal);
+}
+
+// === Room Peek Tests ===
+
+#[test]
+fn peek_next_summarizes_following_room() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let first = Room::new(0, 5, 5, RoomType::Normal, date);
+    let mut second = Room::new(1, 7, 7, RoomType::Boss, date);
+    second.enemies.push(penumbra::entity::Enemy::new(EnemyType::Bug, 2, 2, "a"));
+    second.enemies.push(penumbra::entity::Enemy::new(EnemyType::MergeConflict, 3, 3, "b"));
+    second.items.push(penumbra::item::Item::new("Potion", ItemType::Consumable, ItemEffect::Heal(10), Rarity::Common));
+    let mut world = World::new(vec![first, second]);
+
+    assert_eq!(world.peek_next(), Some(RoomSummary { room_type: RoomType::Boss, enemies: 2, items: 1 }));
+
+    world.current_room = 1;
+    assert_eq!(world.peek_next(), None);
+}
+
+// === Merge boss names ===
+
+#[test]
+fn merge_rooms_name_their_boss_after_the_branch() {
+    let mut merge = make_dated_commit("m1", 40, 1, "Merge branch 'feature/auth' into main");
+    merge.is_merge = true;
+    let commits = vec![make_dated_commit("w1", 20, 1, "Small tweak"), merge, make_dated_commit("big", 400, 2, "Work")];
+    let world = generate_dungeon(&commits, 42);
+
+    let room = world.rooms.iter().find(|r| r.room_type == RoomType::Boss).unwrap();
+    let boss = room.boss().unwrap();
+    assert_eq!(boss.title.as_deref(), Some("The feature/auth Conflict"));
+    assert_eq!(boss.source_commit, "m1");
+}
+
+#[test]
+fn merge_rooms_without_a_significant_word_stay_unnamed() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 7, 7, RoomType::Boss, date);
+    let mut merge = make_commit(40, true, "Merge branch 'main' into dev");
+    merge.hash = "m".to_string();
+    room.source_commits = vec![merge];
+    room.enemies.push(penumbra::entity::Enemy::new(EnemyType::MergeConflict, 3, 3, "m"));
+
+    assert!(!room.name_merge_boss());
+    assert!(room.boss().is_none());
+}
+
+// === Simple layout ===
+
+#[test]
+fn simple_layout_has_only_perimeter_walls() {
+    let mut merge = touching("m", 3, &["src/parser.rs"]);
+    merge.is_merge = true;
+    merge.insertions = 120;
+    let mut tests = make_dated_commit("t", 60, 2, "Add tests");
+    tests.file_categories.paths = vec!["tests/parser.rs".to_string()];
+    let commits = vec![touching("a", 5, &["src/parser.rs"]), touching("b", 4, &["README.md"]), merge, tests];
+    let options = GenerationOptions {
+        simple_layout: true,
+        merge_puzzle: true,
+        shortcuts: true,
+        ..Default::default()
+    };
+    let world = generate_dungeon_with_options(&commits, 42, &options);
+    let last = world.rooms.len() - 1;
+
+    for (i, room) in world.rooms.iter().enumerate() {
+        let (w, h) = (room.width as i32, room.height as i32);
+        let mid = h / 2;
+        for y in 0..h {
+            for x in 0..w {
+                let tile = room.get_tile(x, y).unwrap();
+                let edge = x == 0 || y == 0 || x == w - 1 || y == h - 1;
+                if (x, y) == (0, mid) && i > 0 {
+                    assert_eq!(tile, &Tile::Entrance);
+                } else if (x, y) == (w - 1, mid) && i < last {
+                    assert_eq!(tile, &Tile::Exit);
+                } else if edge {
+                    assert_eq!(tile, &Tile::Wall, "room {} at ({}, {})", i, x, y);
+                } else {
+                    assert!(room.is_walkable(x, y), "room {} at ({}, {})", i, x, y);
+                }
+            }
+        }
+        assert_eq!(room.shortcut, None);
+        assert_eq!(room.merge_branches, None);
+    }
+
+    // Room types keep their gameplay
+    assert_eq!(world.rooms[2].room_type, RoomType::Boss);
+    assert_eq!(world.rooms[3].room_type, RoomType::Sanctuary);
+    assert_eq!(world.rooms[3].get_tile(1, 1), Some(&Tile::HealingZone));
+}
+
+#[test]
+fn dominant_author_has_most_commits() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 5, 5, RoomType::Normal, date);
+    assert_eq!(room.dominant_author(), None);
+
+    let authored = |lines: u32, author: &str| {
+        let mut commit = make_commit(lines, false, "Change");
+        commit.author = author.to_string();
+        commit
+    };
+    room.source_commits = vec![authored(1, "Alice"), authored(2, "Bob"), authored(3, "Bob")];
+    assert_eq!(room.dominant_author(), Some("Bob"));
+
+    // Ties go to whoever is listed first
+    room.source_commits.push(authored(4, "Alice"));
+    assert_eq!(room.dominant_author(), Some("Alice"));
+}
+
+#[test]
+fn estimated_difficulty_empty_room_is_one_star() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let room = Room::new(0, 5, 5, RoomType::Normal, date);
+    assert_eq!(room.estimated_difficulty(), 1);
+}
+
+#[test]
+fn estimated_difficulty_rises_with_a_swarm() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 9, 9, RoomType::Normal, date);
+    room.enemies.push(Enemy::new(EnemyType::Bug, 2, 2, "a"));
+    let single = room.estimated_difficulty();
+
+    for i in 0..7 {
+        room.enemies.push(Enemy::new(EnemyType::Bug, 3 + i % 4, 3 + i / 4, "a"));
+    }
+    let swarm = room.estimated_difficulty();
+    assert!(swarm > single);
+    assert!(swarm >= 3);
+    assert!(swarm <= MAX_DIFFICULTY_STARS);
+}
+
+#[test]
+fn estimated_difficulty_boss_room_is_hard() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 9, 9, RoomType::Boss, date);
+    let mut boss = Enemy::new(EnemyType::MergeConflict, 4, 4, "merge");
+    boss.promote("The auth Conflict");
+    room.enemies.push(boss);
+    room.enemies.push(Enemy::new(EnemyType::Bug, 2, 2, "a"));
+    assert!(room.estimated_difficulty() >= 4);
+
+    // The same enemies deep in the dungeon max out the rating
+    room.id = 40;
+    assert_eq!(room.estimated_difficulty(), MAX_DIFFICULTY_STARS);
+}
+
+#[test]
+fn conflict_markers_guard_merge_bosses_only() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let mut room = Room::new(0, 9, 9, RoomType::Boss, date);
+    let mut boss = Enemy::new(EnemyType::MergeConflict, 6, 4, "merge");
+    boss.promote("The auth Conflict");
+    room.enemies.push(boss);
+
+    assert!(room.spawn_conflict_markers(MAX_ENEMIES_PER_ROOM));
+    let markers: Vec<_> = room.enemies.iter().filter(|e| e.conflict_marker).collect();
+    assert_eq!(markers.len(), CONFLICT_MARKERS);
+    for marker in &markers {
+        assert_eq!(marker.source_commit, "merge");
+        assert!((marker.x - 6).abs() <= 1 && (marker.y - 4).abs() <= 1);
+    }
+    assert!(room.boss().unwrap().warded);
+
+    // Only the last marker's death lifts the ward
+    let first = room.enemies.iter().position(|e| e.conflict_marker).unwrap();
+    room.enemies.remove(first);
+    assert!(!room.lift_conflict_ward());
+    room.enemies.retain(|e| !e.conflict_marker);
+    assert!(room.lift_conflict_ward());
+    assert!(!room.boss().unwrap().warded);
+
+    // A mini-boss of another type gets no markers
+    let mut other = Room::new(1, 9, 9, RoomType::Boss, date);
+    let mut debt = Enemy::new(EnemyType::TechDebt, 6, 4, "old");
+    debt.promote("The Nameless Commit");
+    other.enemies.push(debt);
+    assert!(!other.spawn_conflict_markers(MAX_ENEMIES_PER_ROOM));
+    assert!(other.enemies.iter().all(|e| !e.conflict_marker && !e.warded));
+}
+
+#[test]
+fn conflict_markers_count_towards_the_enemy_cap() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let boss_room = |others: i32| {
+        let mut room = Room::new(0, 9, 9, RoomType::Boss, date);
+        let mut boss = Enemy::new(EnemyType::MergeConflict, 6, 4, "merge");
+        boss.promote("The auth Conflict");
+        room.enemies.push(boss);
+        for y in 0..others {
+            room.enemies.push(Enemy::new(EnemyType::Bug, 3, 2 + y, "a"));
+        }
+        room
+    };
+
+    // One slot left under the cap: one marker
+    let mut room = boss_room(1);
+    assert!(room.spawn_conflict_markers(3));
+    assert_eq!(room.enemies.len(), 3);
+    assert_eq!(room.enemies.iter().filter(|e| e.conflict_marker).count(), 1);
+
+    // No slots left: no markers, and the boss isn't warded
+    let mut full = boss_room(2);
+    assert!(!full.spawn_conflict_markers(3));
+    assert_eq!(full.enemies.len(), 3);
+    assert!(!full.boss().unwrap().warded);
+}
+
+#[test]
+fn squash_aware_dungeon_makes_an_epic_room_with_extra_enemies() {
+    let day = |days_ago: i64| Utc::now() - chrono::Duration::days(days_ago);
+    let mut commits = Vec::new();
+    for i in 0..6 {
+        let mut commit = make_commit(20 + i, false, "Tweak config");
+        commit.date = day(3 + i as i64);
+        commits.push(commit);
+    }
+    let mut squash = make_commit(400, false, "Refactor storage layer");
+    squash.date = day(1);
+    commits.push(squash);
+
+    let plain = generate_dungeon_with_options(&commits, 7, &GenerationOptions::default());
+    let options = GenerationOptions { squash_aware: true, ..Default::default() };
+    let aware = generate_dungeon_with_options(&commits, 7, &options);
+
+    let epic: Vec<_> = aware.rooms.iter().filter(|r| r.epic).collect();
+    assert_eq!(epic.len(), 1);
+    assert!(epic[0].source_commits.iter().any(|c| c.hash == "hash_400"));
+    assert!(epic[0].name().starts_with("Epic "));
+
+    let index = aware.rooms.iter().position(|r| r.epic).unwrap();
+    assert!(aware.rooms[index].enemies.len() > plain.rooms[index].enemies.len());
+    assert!(plain.rooms.iter().all(|r| !r.epic));
+    // Other rooms are untouched
+    for (a, p) in aware.rooms.iter().zip(&plain.rooms).filter(|(a, _)| !a.epic) {
+        assert_eq!(a.enemies.len(), p.enemies.len());
+    }
+}
+
+#[test]
+fn room_types_map_to_ambience_cues() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let with_enemies = |room_type: RoomType, count: usize| {
+        let mut room = Room::new(0, 9, 9, room_type, date);
+        for i in 0..count {
+            room.enemies.push(Enemy::new(EnemyType::Bug, 2 + i as i32 % 5, 2 + i as i32 / 5, "c"));
+        }
+        room
+    };
+
+    assert_eq!(with_enemies(RoomType::Sanctuary, 0).ambience(), AmbienceCue::Calm);
+    assert_eq!(with_enemies(RoomType::Tutorial, 1).ambience(), AmbienceCue::Calm);
+    assert_eq!(with_enemies(RoomType::Boss, 1).ambience(), AmbienceCue::Tense);
+    assert_eq!(with_enemies(RoomType::Normal, 2).ambience(), AmbienceCue::Uneasy);
+    assert_eq!(with_enemies(RoomType::Library, 2).ambience(), AmbienceCue::Mysterious);
+    assert_eq!(with_enemies(RoomType::Treasure, 2).ambience(), AmbienceCue::Mysterious);
+
+    // Crowds make any room tense, and clearing a room calms it
+    assert_eq!(with_enemies(RoomType::Treasure, TENSE_ENEMY_COUNT).ambience(), AmbienceCue::Tense);
+    assert_eq!(with_enemies(RoomType::Normal, 0).ambience(), AmbienceCue::Calm);
+    let mut boss = with_enemies(RoomType::Boss, 1);
+    boss.cleared = true;
+    assert_eq!(boss.ambience(), AmbienceCue::Calm);
+}
+
+#[test]
+fn grouping_by_directory_makes_a_room_per_module() {
+    let touching = |lines: u32, days_ago: i64, paths: &[&str]| {
+        let mut commit = make_commit(lines, false, "Change things");
+        commit.date = Utc::now() - chrono::Duration::days(days_ago);
+        commit.file_categories.paths = paths.iter().map(|p| p.to_string()).collect();
+        commit
+    };
+    let commits = vec![
+        touching(30, 5, &["src/main.rs"]),
+        touching(40, 4, &["tests/cli.rs"]),
+        touching(50, 3, &["src/lib.rs", "src/world.rs"]),
+        touching(60, 2, &["docs/guide.md"]),
+    ];
+
+    let options = GenerationOptions { group_by: RoomGrouping::Directory, ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 9, &options);
+
+    let modules: Vec<_> = world.rooms.iter().map(|r| r.module.clone().unwrap()).collect();
+    assert_eq!(modules, vec!["docs", "src", "tests"]);
+    assert_eq!(world.rooms[1].source_commits.len(), 2);
+    assert_eq!(world.rooms[1].name(), "The src/ Wing");
+    // Rooms are dated by their latest commit
+    assert_eq!(world.rooms[1].source_date, commits[2].date_naive());
+
+    // The dispatching entry point and the direct one agree
+    let direct = generate_dungeon_by_module(&commits, 9, &options);
+    assert_eq!(direct.rooms.len(), world.rooms.len());
+    assert!(generate_dungeon(&commits, 9).rooms.iter().all(|r| r.module.is_none()));
+}
+
+#[test]
+fn top_days_keeps_the_busiest_days_in_date_order() {
+    let on_day = |lines: u32, days_ago: i64, hash: &str| {
+        let mut commit = make_commit(lines, false, "Work");
+        commit.hash = hash.to_string();
+        commit.date = Utc::now() - chrono::Duration::days(days_ago);
+        commit
+    };
+    let commits = vec![
+        on_day(500, 6, "big_old"),
+        on_day(10, 5, "quiet"),
+        on_day(300, 4, "tie_a1"),
+        on_day(200, 3, "tie_b1"),
+        on_day(100, 3, "tie_b2"),
+        on_day(20, 2, "quiet2"),
+        on_day(400, 1, "big_new"),
+    ];
+
+    let options = GenerationOptions { top_days: Some(3), ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 5, &options);
+    let firsts: Vec<_> = world.rooms.iter().map(|r| r.source_commits[0].hash.as_str()).collect();
+    // 300 lines four days ago ties 300 three days ago, which wins on commit count
+    assert_eq!(firsts, vec!["big_old", "tie_b1", "big_new"]);
+
+    // Full ties go to the earlier day
+    let mut days: Vec<_> = vec![
+        (NaiveDate::from_ymd_opt(2026, 1, 3).unwrap(), vec![on_day(50, 0, "c")]),
+        (NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), vec![on_day(50, 0, "a")]),
+        (NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(), vec![on_day(50, 0, "b")]),
+    ];
+    keep_top_days(&mut days, 2);
+    let kept: Vec<_> = days.iter().map(|(_, c)| c[0].hash.as_str()).collect();
+    assert_eq!(kept, vec!["a", "b"]);
+}
+
+// === Seed browser ===
+
+#[test]
+fn seed_candidates_summarize_consecutive_seeds() {
+    let commits: Vec<CommitData> = (0..12)
+        .map(|i| make_dated_commit(&format!("c{}", i), 40 + i * 90, (i % 6) as i64, "Work"))
+        .collect();
+    let options = GenerationOptions::default();
+
+    let candidates = seed_candidates(&commits, 100, SEED_CANDIDATES, &options);
+    assert_eq!(candidates.len(), SEED_CANDIDATES);
+    let seeds: Vec<u64> = candidates.iter().map(|c| c.seed).collect();
+    assert_eq!(seeds, (100..100 + SEED_CANDIDATES as u64).collect::<Vec<_>>());
+
+    // Each summary matches the dungeon that seed actually builds
+    for candidate in &candidates {
+        let world = generate_dungeon_with_options(&commits, candidate.seed, &options);
+        assert_eq!(*candidate, DungeonSummary::of(&world, candidate.seed));
+        assert_eq!(candidate.rooms, 6);
+        assert!(candidate.bosses <= candidate.rooms);
+    }
+
+    // Same inputs, same list
+    assert_eq!(seed_candidates(&commits, 100, SEED_CANDIDATES, &options), candidates);
+
+    // Seeds wrap instead of overflowing
+    let wrapped = seed_candidates(&commits, u64::MAX, 2, &options);
+    assert_eq!(wrapped.iter().map(|c| c.seed).collect::<Vec<_>>(), vec![u64::MAX, 0]);
+}
+
+// === Commit chunks ===
+
+#[test]
+fn chunk_grouping_makes_rooms_of_n_commits() {
+    // Ten commits over three days would be three date rooms of other sizes
+    let commits: Vec<CommitData> = (0..10)
+        .map(|i| make_dated_commit(&format!("c{}", i), 50, (i / 4 + i % 2) as i64, "Work"))
+        .collect();
+
+    let options = GenerationOptions { group_by: RoomGrouping::Chunks(4), ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 3, &options);
+    assert_eq!(world.rooms.len(), 3);
+    let hashes: Vec<Vec<&str>> = world
+        .rooms
+        .iter()
+        .map(|r| r.source_commits.iter().map(|c| c.hash.as_str()).collect())
+        .collect();
+    assert_eq!(
+        hashes,
+        vec![vec!["c9", "c5", "c7", "c8"], vec!["c1", "c3", "c4", "c6"], vec!["c0", "c2"]]
+    );
+    for room in &world.rooms {
+        assert_eq!(room.source_date, room.source_commits[0].date_naive());
+    }
+
+    // Dates still group by default, one room per day
+    assert_eq!(generate_dungeon(&commits, 3).rooms.len(), 4);
+}
+
+#[test]
+fn chunks_from_one_day_share_its_date() {
+    let commits: Vec<CommitData> = (0..4)
+        .map(|i| make_dated_commit(&format!("d{}", i), 50, 1, "Work"))
+        .collect();
+
+    let options = GenerationOptions { group_by: RoomGrouping::Chunks(2), ..Default::default() };
+    let world = generate_dungeon_with_options(&commits, 3, &options);
+    assert_eq!(world.rooms.len(), 2);
+    assert_eq!(world.rooms[0].source_date, world.rooms[1].source_date);
+    assert_eq!(world.rooms[0].source_commits[0].hash, "d0");
+    assert_eq!(world.rooms[1].source_commits[0].hash, "d2");
+}
+
+// === Room population ===
+
+#[test]
+fn git_rooms_get_an_enemy_per_commit() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let commits: Vec<CommitData> = (0..4)
+        .map(|i| make_dated_commit(&format!("p{}", i), 100, 0, "Add feature"))
+        .collect();
+    let mut rng = GameRng::seed_from_u64(42);
+
+    let room = generate_room(date, &commits, 0, &mut rng);
+
+    assert_eq!(room.room_type, RoomType::Normal);
+    assert_eq!(room.enemies.len(), commits.len());
+    for commit in &commits {
+        assert!(room.enemies.iter().any(|e| e.source_commit == commit.hash));
+    }
+    assert!(!room.items.is_empty());
+}
+
+#[test]
+fn git_room_population_is_capped_and_seeded() {
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    let commits: Vec<CommitData> = (0..20)
+        .map(|i| make_dated_commit(&format!("p{}", i), 200, 0, "Add feature"))
+        .collect();
+
+    let room = generate_room(date, &commits, 0, &mut GameRng::seed_from_u64(42));
+    assert_eq!(room.enemies.len(), MAX_ENEMIES_PER_ROOM);
+    assert!(room.items.len() <= 3);
+
+    // The same room seed always populates the room the same way
+    let again = generate_room(date, &commits, 0, &mut GameRng::seed_from_u64(42));
+    assert_eq!(enemy_positions(&room), enemy_positions(&again));
+}